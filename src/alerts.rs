@@ -0,0 +1,182 @@
+use crate::config::AlertRule;
+use crate::history::{History, metric_key};
+use crate::http::HttpClient;
+use crate::types::Signal;
+use futures_util::future::join_all;
+use tracing::{info, warn};
+
+/// A rule that matched one collected metric.
+#[derive(Debug)]
+pub struct Alert<'a> {
+    pub rule: &'a AlertRule,
+    pub signal: &'a Signal,
+    pub value: f64,
+    /// History mean the value was compared against, for relative rules.
+    pub baseline: Option<f64>,
+}
+
+impl Alert<'_> {
+    fn message(&self) -> String {
+        let rule = self.rule;
+        match self.baseline {
+            Some(baseline) => format!(
+                "{}: {} = {:.2} is {:.1}x its baseline {:.2} (rule: {} {}x)",
+                self.signal.title,
+                rule.metric,
+                self.value,
+                self.value / baseline,
+                baseline,
+                rule.condition,
+                rule.value
+            ),
+            None => format!(
+                "{}: {} = {:.2} (rule: {} {})",
+                self.signal.title, rule.metric, self.value, rule.condition, rule.value
+            ),
+        }
+    }
+}
+
+/// Check every rule against the collected signals. Relative rules compare
+/// against the mean of the metric's last `window` runs and are skipped
+/// without history.
+pub fn evaluate<'a>(
+    rules: &'a [AlertRule],
+    signals: &'a [Signal],
+    history: Option<&History>,
+    window: usize,
+) -> Vec<Alert<'a>> {
+    let mut alerts = Vec::new();
+    for rule in rules {
+        let candidates = signals.iter().filter(|s| {
+            rule.category
+                .as_ref()
+                .is_none_or(|c| c.eq_ignore_ascii_case(&s.category))
+        });
+        for signal in candidates {
+            for metric in signal.metrics.iter().filter(|m| m.name == rule.metric) {
+                let (threshold, baseline) = if rule.relative {
+                    let series = history
+                        .map(|h| h.series(&metric_key(signal, metric), window))
+                        .unwrap_or_default();
+                    if series.is_empty() {
+                        continue;
+                    }
+                    let mean = series.iter().sum::<f64>() / series.len() as f64;
+                    if mean == 0.0 {
+                        continue;
+                    }
+                    (rule.value * mean, Some(mean))
+                } else {
+                    (rule.value, None)
+                };
+                if metric.value.is_finite() && rule.condition.holds(metric.value, threshold) {
+                    alerts.push(Alert {
+                        rule,
+                        signal,
+                        value: metric.value,
+                        baseline,
+                    });
+                }
+            }
+        }
+    }
+    alerts
+}
+
+/// POST each alert to its rule's webhook. The payload carries a Slack- and
+/// Discord-compatible `text` field plus the raw numbers. Failures are logged,
+/// never fatal.
+pub async fn fire(http: &HttpClient, alerts: &[Alert<'_>]) {
+    let sends = alerts.iter().map(|alert| async move {
+        let message = alert.message();
+        let payload = serde_json::json!({
+            "text": format!("SolScout alert: {message}"),
+            "content": format!("SolScout alert: {message}"),
+            "metric": alert.rule.metric,
+            "category": alert.signal.category,
+            "signal": alert.signal.title,
+            "value": alert.value,
+            "baseline": alert.baseline,
+            "condition": alert.rule.condition.to_string(),
+            "threshold": alert.rule.value,
+            "relative": alert.rule.relative,
+            "url": alert.signal.url,
+        });
+        let result = http
+            .post_json_raw(&alert.rule.webhook, &payload.to_string(), &[])
+            .await;
+        match result {
+            Ok(_) => info!(alert = %message, "alert fired"),
+            Err(e) => warn!(alert = %message, error = %e, "alert webhook failed"),
+        }
+    });
+    join_all(sends).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AlertCondition;
+    use crate::test_support::{self, TempDir};
+
+    fn fees(value: f64) -> Signal {
+        test_support::metric_signal(
+            "Network Performance",
+            "Priority fees",
+            "median_priority_fee",
+            value,
+            "lamports",
+        )
+    }
+
+    fn rule(condition: AlertCondition, value: f64, relative: bool) -> AlertRule {
+        AlertRule {
+            metric: "median_priority_fee".into(),
+            category: Some("network performance".into()),
+            condition,
+            value,
+            relative,
+            webhook: "http://127.0.0.1:9/hook".into(),
+        }
+    }
+
+    #[test]
+    fn absolute_threshold() {
+        let signals = [fees(12_000.0)];
+        let rules = [
+            rule(AlertCondition::Above, 10_000.0, false),
+            rule(AlertCondition::Below, 10_000.0, false),
+            AlertRule {
+                category: Some("DeFi".into()),
+                ..rule(AlertCondition::Above, 0.0, false)
+            },
+        ];
+        let alerts = evaluate(&rules, &signals, None, 10);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].value, 12_000.0);
+        assert_eq!(alerts[0].baseline, None);
+    }
+
+    #[test]
+    fn relative_threshold_uses_history_mean() {
+        let dir = TempDir::new("alerts");
+        let mut history = History::load(&dir.join("history.json")).unwrap();
+        let rules = [rule(AlertCondition::AtLeast, 5.0, true)];
+
+        assert!(
+            evaluate(&rules, &[fees(50_000.0)], Some(&history), 10).is_empty(),
+            "no baseline yet"
+        );
+        for normal in [900.0, 1_100.0, 1_000.0] {
+            history.record(&[fees(normal)], None);
+        }
+
+        assert!(evaluate(&rules, &[fees(4_000.0)], Some(&history), 10).is_empty());
+        let spike = [fees(5_000.0)];
+        let alerts = evaluate(&rules, &spike, Some(&history), 10);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].baseline, Some(1_000.0));
+        assert!(alerts[0].message().contains("5.0x"));
+    }
+}
@@ -1,5 +1,19 @@
-use crate::types::{Metric, Signal, SignalSource};
+use crate::analysis::stats::{ema, rolling_stats};
+use crate::config::SignalDetail;
+use crate::history::{History, metric_key};
+use crate::types::{Metric, Narrative, Signal, SignalSource, canonicalize_unit};
+use chrono::Utc;
 use std::collections::HashMap;
+use tracing::warn;
+
+/// z-score at or above which a signal is flagged as a genuine anomaly.
+const HIGH_NOVELTY_Z: f64 = 2.0;
+
+/// Shares of a signal's importance. Novelty dominates so an anomaly
+/// outranks any steady-state signal, however fresh or trusted.
+const NOVELTY_WEIGHT: f64 = 0.6;
+const QUALITY_WEIGHT: f64 = 0.25;
+const DIVERSITY_WEIGHT: f64 = 0.15;
 
 /// Aggregated signal group with computed velocity metrics.
 #[derive(Debug, Clone)]
@@ -8,8 +22,14 @@ pub struct SignalGroup {
     pub signals: Vec<usize>,
     pub source_diversity: usize,
     pub total_signals: usize,
-    #[allow(dead_code)]
+    /// Sum of the signals' recency weights; equals `total_signals` without
+    /// decay.
+    pub weight: f64,
+    /// Each metric name summed over the group's signals, by name, with
+    /// headline metrics first once [`mark_headlines`] has run.
     pub key_metrics: Vec<Metric>,
+    /// Names from `analysis.headline_metrics` this group carries.
+    pub headline_metrics: Vec<String>,
 }
 
 fn normalize_category(cat: &str) -> String {
@@ -24,8 +44,33 @@ fn normalize_category(cat: &str) -> String {
     }
 }
 
-/// Aggregate signals by category, compute cross-source validation.
-pub fn aggregate(signals: &[Signal]) -> Vec<SignalGroup> {
+/// Keep signals whose normalized category passes the allowlist (when set) and
+/// isn't blocked. Both lists are normalized the same way, so "defi" and
+/// "Decentralized Finance" name the same category.
+pub fn filter_categories(signals: &mut Vec<Signal>, include: &[String], exclude: &[String]) {
+    let normalize = |list: &[String]| -> Vec<String> {
+        list.iter()
+            .map(|c| normalize_category(c.trim()).to_lowercase())
+            .collect()
+    };
+    let (include, exclude) = (normalize(include), normalize(exclude));
+    signals.retain(|s| {
+        let category = normalize_category(&s.category).to_lowercase();
+        (include.is_empty() || include.contains(&category)) && !exclude.contains(&category)
+    });
+}
+
+/// Weight of a signal `age_hours` old when weight halves every
+/// `half_life_hours`. Future timestamps count in full.
+fn recency_weight(age_hours: f64, half_life_hours: f64) -> f64 {
+    0.5_f64.powf(age_hours.max(0.0) / half_life_hours)
+}
+
+/// Aggregate signals by category, compute cross-source validation. With a
+/// half-life, older signals count less toward a category's rank.
+pub fn aggregate(signals: &[Signal], half_life_hours: Option<f64>) -> Vec<SignalGroup> {
+    let now = Utc::now();
+    let half_life = half_life_hours.filter(|h| *h > 0.0);
     let mut by_category: HashMap<String, Vec<usize>> = HashMap::new();
 
     for (i, signal) in signals.iter().enumerate() {
@@ -42,43 +87,291 @@ pub fn aggregate(signals: &[Signal]) -> Vec<SignalGroup> {
             let sources: std::collections::HashSet<SignalSource> =
                 indices.iter().map(|&i| signals[i].source).collect();
 
-            // Aggregate metrics across signals in this group
-            let mut metric_sums: HashMap<String, (f64, String)> = HashMap::new();
+            // Sum metrics across signals in this group; only values with the
+            // same name and unit add up.
+            let mut metric_sums: HashMap<(String, String), f64> = HashMap::new();
             for &i in &indices {
                 for m in &signals[i].metrics {
-                    let entry = metric_sums
-                        .entry(m.name.clone())
-                        .or_insert((0.0, m.unit.clone()));
-                    entry.0 += m.value;
+                    let unit = canonicalize_unit(&m.unit).to_string();
+                    *metric_sums.entry((m.name.clone(), unit)).or_default() += m.value;
                 }
             }
-            let key_metrics: Vec<Metric> = metric_sums
+            let mut key_metrics: Vec<Metric> = metric_sums
                 .into_iter()
-                .map(|(name, (value, unit))| Metric { name, value, unit })
+                .map(|((name, unit), value)| Metric { name, value, unit })
                 .collect();
+            key_metrics.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.unit.cmp(&b.unit)));
+
+            let weight = match half_life {
+                Some(half_life) => indices
+                    .iter()
+                    .map(|&i| {
+                        let age = now - signals[i].timestamp;
+                        recency_weight(age.num_seconds() as f64 / 3600.0, half_life)
+                    })
+                    .sum(),
+                None => indices.len() as f64,
+            };
 
             SignalGroup {
                 category,
                 total_signals: indices.len(),
                 source_diversity: sources.len(),
+                weight,
                 signals: indices,
                 key_metrics,
+                headline_metrics: Vec::new(),
             }
         })
         .collect();
 
-    // Sort by source diversity (multi-source signals are more credible), then by count
-    groups.sort_by(|a, b| {
-        b.source_diversity
-            .cmp(&a.source_diversity)
-            .then(b.total_signals.cmp(&a.total_signals))
-    });
-
+    groups.sort_by(group_order);
     groups
 }
 
-/// Prepare a JSON summary of signals for Claude analysis.
-pub fn signals_to_json(signals: &[Signal], groups: &[SignalGroup]) -> String {
+/// Source diversity first (multi-source signals are more credible), then
+/// groups carrying a headline metric, then recency-weighted count. Ties go
+/// alphabetically by category so equal data always yields the same report
+/// and the same LLM input.
+fn group_order(a: &SignalGroup, b: &SignalGroup) -> std::cmp::Ordering {
+    b.source_diversity
+        .cmp(&a.source_diversity)
+        .then((!b.headline_metrics.is_empty()).cmp(&!a.headline_metrics.is_empty()))
+        .then(b.weight.total_cmp(&a.weight))
+        .then(b.total_signals.cmp(&a.total_signals))
+        .then_with(|| a.category.cmp(&b.category))
+}
+
+/// Whether `metric` is one of `category`'s headline metrics in `headlines`
+/// (`analysis.headline_metrics`). Categories match after normalization,
+/// ignoring case, like the category filters.
+pub fn is_headline(headlines: &HashMap<String, Vec<String>>, category: &str, metric: &str) -> bool {
+    let category = normalize_category(category).to_lowercase();
+    headlines.iter().any(|(listed, names)| {
+        normalize_category(listed.trim()).to_lowercase() == category
+            && names.iter().any(|n| n == metric)
+    })
+}
+
+/// Record each group's headline metrics, move them to the front of its
+/// `key_metrics`, keeping the name order otherwise, and re-sort the groups so
+/// those with one lead others of the same source diversity.
+pub fn mark_headlines(groups: &mut [SignalGroup], headlines: &HashMap<String, Vec<String>>) {
+    for group in groups.iter_mut() {
+        let mut names: Vec<String> = group
+            .key_metrics
+            .iter()
+            .filter(|m| is_headline(headlines, &group.category, &m.name))
+            .map(|m| m.name.clone())
+            .collect();
+        names.dedup();
+        group.key_metrics.sort_by_key(|m| !names.contains(&m.name));
+        group.headline_metrics = names;
+    }
+    groups.sort_by(group_order);
+}
+
+/// How far a signal's most unusual metric sits from its recent history.
+#[derive(Debug, Clone)]
+pub struct Novelty {
+    pub metric: String,
+    pub z_score: f64,
+}
+
+impl Novelty {
+    pub fn level(&self) -> &'static str {
+        match self.z_score.abs() {
+            z if z >= HIGH_NOVELTY_Z => "high",
+            z if z >= 1.0 => "notable",
+            _ => "background",
+        }
+    }
+}
+
+/// Suffix of the smoothed twin of a metric, e.g. `avg_tps_ema`.
+pub const EMA_SUFFIX: &str = "_ema";
+
+/// For each metric named in `smoothing`, add a `<name>_ema` twin: the
+/// exponential moving average of its recorded history and this run's value,
+/// with the configured smoothing factor. The raw metric stays. Returns how
+/// many were added.
+pub fn add_smoothed(
+    signals: &mut [Signal],
+    history: &History,
+    smoothing: &HashMap<String, f64>,
+) -> usize {
+    let mut added = 0;
+    for signal in signals.iter_mut() {
+        let smoothed: Vec<Metric> = signal
+            .metrics
+            .iter()
+            .filter_map(|m| {
+                let &alpha = smoothing.get(&m.name)?;
+                let mut series = history.series(&metric_key(signal, m), usize::MAX);
+                series.push(m.value);
+                Some(Metric {
+                    name: format!("{}{EMA_SUFFIX}", m.name),
+                    value: ema(&series, alpha)?,
+                    unit: m.unit.clone(),
+                })
+            })
+            .collect();
+        added += smoothed.len();
+        signal.metrics.extend(smoothed);
+    }
+    added
+}
+
+/// Score each signal against the last `window` runs. `None` means there is not
+/// enough history to judge, which is different from "not novel".
+pub fn novelty(signals: &[Signal], history: &History, window: usize) -> Vec<Option<Novelty>> {
+    signals
+        .iter()
+        .map(|s| {
+            s.metrics
+                .iter()
+                .filter_map(|m| {
+                    let series = history.series(&metric_key(s, m), window);
+                    z_score(m.value, &series).map(|z_score| Novelty {
+                        metric: m.name.clone(),
+                        z_score,
+                    })
+                })
+                .max_by(|a, b| a.z_score.abs().total_cmp(&b.z_score.abs()))
+        })
+        .collect()
+}
+
+/// How much each signal matters to the story, 0.0-1.0. Novelty counts most,
+/// saturating at the anomaly threshold; no history counts as none. Then the
+/// signal's quality, then its share of its source's signals in its
+/// category: a category's only signal from a source adds a source to the
+/// category, the tenth adds little.
+pub fn importance(signals: &[Signal], novelty: &[Option<Novelty>]) -> Vec<f64> {
+    let mut per_source: HashMap<(String, SignalSource), usize> = HashMap::new();
+    for s in signals {
+        *per_source
+            .entry((normalize_category(&s.category), s.source))
+            .or_default() += 1;
+    }
+    signals
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let novel = novelty
+                .get(i)
+                .and_then(Option::as_ref)
+                .map_or(0.0, |n| (n.z_score.abs() / HIGH_NOVELTY_Z).min(1.0));
+            let peers = per_source[&(normalize_category(&s.category), s.source)];
+            NOVELTY_WEIGHT * novel
+                + QUALITY_WEIGHT * s.quality().clamp(0.0, 1.0)
+                + DIVERSITY_WEIGHT / peers as f64
+        })
+        .collect()
+}
+
+fn z_score(value: f64, series: &[f64]) -> Option<f64> {
+    if !value.is_finite() {
+        return None;
+    }
+    let mut points = series.to_vec();
+    points.push(value);
+    let band = rolling_stats(&points, series.len()).pop().flatten()?;
+    if band.std_dev < f64::EPSILON {
+        // A flat history: any move at all is notable, but unbounded z is meaningless.
+        return Some(if (value - band.mean).abs() < f64::EPSILON {
+            0.0
+        } else {
+            HIGH_NOVELTY_Z.copysign(value - band.mean)
+        });
+    }
+    Some((value - band.mean) / band.std_dev)
+}
+
+/// Distinct sources needed for full diversity credit.
+const FULL_DIVERSITY_SOURCES: f64 = 3.0;
+/// Multiplier applied when supporting metrics move in opposite directions.
+const DISAGREEMENT_PENALTY: f64 = 0.8;
+
+/// Replace the model's self-reported confidence with one grounded in the
+/// evidence: how many distinct sources back the narrative, how trustworthy
+/// those sources are, and whether their change metrics point the same way.
+/// Unresolved signal references contribute nothing.
+pub fn recalibrate(narratives: &mut [Narrative], signals: &[Signal]) {
+    let by_id: HashMap<&str, &Signal> = signals.iter().map(|s| (s.id.as_str(), s)).collect();
+
+    for n in narratives {
+        let support: Vec<&Signal> = n
+            .supporting_signals
+            .iter()
+            .filter_map(|id| by_id.get(id.as_str()).copied())
+            .collect();
+
+        let sources: std::collections::HashSet<SignalSource> =
+            support.iter().map(|s| s.source).collect();
+        let diversity = (sources.len() as f64 / FULL_DIVERSITY_SOURCES).min(1.0);
+        let quality = if support.is_empty() {
+            0.0
+        } else {
+            support.iter().map(|s| s.quality()).sum::<f64>() / support.len() as f64
+        };
+        let agree = directions_agree(&support);
+
+        let evidence = 0.5 * diversity + 0.5 * quality;
+        let mut adjusted = n.raw_confidence * (0.25 + 0.75 * evidence);
+        if !agree {
+            adjusted *= DISAGREEMENT_PENALTY;
+        }
+
+        n.adjusted_confidence = Some(adjusted.clamp(0.0, 1.0));
+        n.confidence_note = format!(
+            "Model said {:.0}%. Adjusted for {} distinct source{} across {} resolved signal{}, average source quality {:.2}{}.",
+            n.raw_confidence * 100.0,
+            sources.len(),
+            if sources.len() == 1 { "" } else { "s" },
+            support.len(),
+            if support.len() == 1 { "" } else { "s" },
+            quality,
+            if agree {
+                ""
+            } else {
+                ", and change metrics that disagree in direction"
+            }
+        );
+        if let Some(r) = n.recurrence {
+            n.confidence_note.push_str(&format!(
+                " Found by {} of {} synthesis runs; the model's figure is their mean, raised for agreement.",
+                r.appeared, r.runs
+            ));
+        }
+    }
+}
+
+/// Whether the supporting signals' change/growth metrics share a sign.
+/// Signals without such metrics don't count against agreement.
+fn directions_agree(support: &[&Signal]) -> bool {
+    let signs: Vec<f64> = support
+        .iter()
+        .flat_map(|s| s.metrics.iter())
+        .filter(|m| m.name.contains("change") || m.name.contains("growth"))
+        .filter(|m| m.value.abs() > f64::EPSILON)
+        .map(|m| m.value.signum())
+        .collect();
+    signs.windows(2).all(|w| w[0] == w[1])
+}
+
+/// Metrics kept per signal in compact mode.
+const COMPACT_METRICS: usize = 3;
+
+/// Prepare a JSON summary of signals for Claude analysis. Compact detail keeps
+/// only what narrative linkage and ranking need: ID, source, title, novelty
+/// and the top metrics, led by the one novelty flagged.
+pub fn signals_to_json(
+    signals: &[Signal],
+    groups: &[SignalGroup],
+    novelty: &[Option<Novelty>],
+    detail: SignalDetail,
+) -> String {
     let summary: Vec<serde_json::Value> = groups
         .iter()
         .map(|g| {
@@ -87,20 +380,72 @@ pub fn signals_to_json(signals: &[Signal], groups: &[SignalGroup]) -> String {
                 .iter()
                 .map(|&i| {
                     let s = &signals[i];
-                    serde_json::json!({
+                    let novelty = novelty.get(i).and_then(Option::as_ref);
+                    let mut metrics: Vec<&Metric> = s
+                        .metrics
+                        .iter()
+                        .filter(|m| {
+                            let finite = m.value.is_finite();
+                            if !finite {
+                                warn!(signal = %s.title, metric = %m.name, "dropped non-finite metric");
+                            }
+                            finite
+                        })
+                        .collect();
+                    let headline = |m: &Metric| g.headline_metrics.contains(&m.name);
+                    // Stable sorts: headline metrics first, then source order.
+                    metrics.sort_by_key(|m| !headline(m));
+                    if detail == SignalDetail::Compact {
+                        // The flagged metric ahead of even the headline ones.
+                        metrics.sort_by_key(|m| novelty.is_none_or(|n| n.metric != m.name));
+                        metrics.truncate(COMPACT_METRICS);
+                    }
+
+                    let mut value = serde_json::json!({
+                        "id": s.id,
                         "source": s.source.to_string(),
                         "title": s.title,
-                        "description": s.description,
-                        "metrics": s.metrics.iter().map(|m| {
-                            serde_json::json!({
+                        "metrics": metrics.iter().map(|m| {
+                            let mut metric = serde_json::json!({
                                 "name": m.name,
                                 "value": m.value,
                                 "unit": m.unit,
-                            })
+                            });
+                            if headline(m) {
+                                metric["headline"] = true.into();
+                            }
+                            metric
                         }).collect::<Vec<_>>(),
-                        "url": s.url,
-                        "timestamp": s.timestamp.to_rfc3339(),
-                    })
+                        "novelty": match novelty {
+                            Some(n) => serde_json::json!({
+                                "level": n.level(),
+                                "z_score": (n.z_score * 100.0).round() / 100.0,
+                                "metric": n.metric,
+                            }),
+                            None => serde_json::json!("unknown"),
+                        },
+                    });
+                    if let Some(origin) = &s.origin {
+                        value["origin"] = origin.clone().into();
+                    }
+                    if !s.languages.is_empty() {
+                        value["languages"] = serde_json::json!(s.languages);
+                    }
+                    if let Some(note) = &s.note {
+                        value["analyst_note"] = note.clone().into();
+                    }
+                    if let Some(quality) = s.quality {
+                        value["analyst_quality"] = quality.into();
+                    }
+                    if let Some(importance) = s.importance {
+                        value["importance"] = importance.into();
+                    }
+                    if detail == SignalDetail::Full {
+                        value["description"] = s.description.clone().into();
+                        value["url"] = serde_json::json!(s.url);
+                        value["timestamp"] = s.timestamp.to_rfc3339().into();
+                    }
+                    value
                 })
                 .collect();
 
@@ -115,3 +460,383 @@ pub fn signals_to_json(signals: &[Signal], groups: &[SignalGroup]) -> String {
 
     serde_json::to_string_pretty(&summary).unwrap_or_else(|_| "[]".into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(source: SignalSource, title: &str, change: Option<f64>) -> Signal {
+        Signal::new(
+            source,
+            "DeFi".into(),
+            title.into(),
+            String::new(),
+            change
+                .map(|value| Metric {
+                    name: "tvl_change_7d".into(),
+                    value,
+                    unit: "%".into(),
+                })
+                .into_iter()
+                .collect(),
+            None,
+        )
+    }
+
+    fn narrative(supporting: &[&Signal]) -> Narrative {
+        Narrative {
+            supporting_signals: supporting.iter().map(|s| s.id.clone()).collect(),
+            ..crate::test_support::narrative("Test", 0.9)
+        }
+    }
+
+    #[test]
+    fn single_low_quality_source_drags_confidence_down() {
+        let blog = signal(SignalSource::Social, "Blog: restaking hype", None);
+        let chain = signal(SignalSource::SolanaOnchain, "Jito TVL up", Some(12.0));
+        let tvl = signal(SignalSource::DeFiLlama, "LST TVL up", Some(8.0));
+        let repos = signal(SignalSource::GitHub, "Restaking repos", None);
+
+        let mut narratives = vec![narrative(&[&blog]), narrative(&[&chain, &tvl, &repos])];
+        recalibrate(
+            &mut narratives,
+            &[blog.clone(), chain.clone(), tvl.clone(), repos.clone()],
+        );
+
+        let weak = narratives[0].adjusted_confidence.unwrap();
+        let strong = narratives[1].adjusted_confidence.unwrap();
+        assert!(weak < 0.6, "weak = {weak}");
+        assert!(strong > 0.8, "strong = {strong}");
+        assert!(narratives[0].confidence_note.contains("1 distinct source"));
+    }
+
+    #[test]
+    fn disagreeing_metrics_and_dangling_refs_lower_confidence() {
+        let up = signal(SignalSource::SolanaOnchain, "Volume up", Some(20.0));
+        let down = signal(SignalSource::DeFiLlama, "TVL down", Some(-15.0));
+        let agreeing = signal(SignalSource::DeFiLlama, "TVL up", Some(5.0));
+
+        let mut mixed = vec![narrative(&[&up, &down]), narrative(&[&up, &agreeing])];
+        recalibrate(&mut mixed, &[up.clone(), down.clone(), agreeing.clone()]);
+        assert!(mixed[0].adjusted_confidence < mixed[1].adjusted_confidence);
+
+        let mut dangling = vec![narrative(&[])];
+        dangling[0].supporting_signals = vec!["s00000000".into()];
+        recalibrate(&mut dangling, &[]);
+        assert!((dangling[0].adjusted_confidence.unwrap() - 0.225).abs() < 1e-9);
+    }
+
+    #[test]
+    fn z_score_needs_history() {
+        assert!(z_score(10.0, &[]).is_none());
+        assert!(z_score(10.0, &[9.0]).is_none());
+    }
+
+    #[test]
+    fn z_score_flags_spike() {
+        let series = [1000.0, 1100.0, 900.0, 1000.0];
+        assert!(z_score(1020.0, &series).unwrap().abs() < 1.0);
+        assert!(z_score(3000.0, &series).unwrap() >= HIGH_NOVELTY_Z);
+    }
+
+    #[test]
+    fn non_finite_metrics_are_dropped_from_json() {
+        let mut bad = signal(SignalSource::SolanaOnchain, "Epoch", Some(f64::NAN));
+        bad.metrics.push(Metric {
+            name: "epoch_progress".into(),
+            value: f64::INFINITY,
+            unit: "%".into(),
+        });
+        bad.metrics.push(Metric {
+            name: "epoch".into(),
+            value: 812.0,
+            unit: String::new(),
+        });
+        let signals = vec![bad];
+        let groups = aggregate(&signals, None);
+        let json = signals_to_json(&signals, &groups, &[None], SignalDetail::Full);
+
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let metrics = parsed[0]["signals"][0]["metrics"].as_array().unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0]["name"], "epoch");
+    }
+
+    #[test]
+    fn headline_metrics_lead_their_group_and_are_flagged() {
+        let metric = |name: &str, value: f64| Metric {
+            name: name.into(),
+            value,
+            unit: "tx/s".into(),
+        };
+        let mut tps = signal(SignalSource::SolanaOnchain, "TPS", None);
+        tps.category = "Network Performance".into();
+        tps.metrics = vec![
+            metric("avg_tps", 4000.0),
+            metric("avg_non_vote_tps", 1100.0),
+        ];
+        let signals = vec![tps, signal(SignalSource::DeFiLlama, "TVL", Some(2.0))];
+        let mut groups = aggregate(&signals, None);
+        assert_eq!(groups[0].category, "DeFi", "equal groups go alphabetically");
+        let headlines = HashMap::from([(
+            "network performance".to_string(),
+            vec!["avg_non_vote_tps".to_string()],
+        )]);
+        mark_headlines(&mut groups, &headlines);
+        assert_eq!(
+            groups[0].category, "Network Performance",
+            "a headline metric ranks its group first"
+        );
+
+        let network = groups
+            .iter()
+            .find(|g| g.category == "Network Performance")
+            .unwrap();
+        assert_eq!(network.headline_metrics, ["avg_non_vote_tps"]);
+        assert_eq!(network.key_metrics[0].name, "avg_non_vote_tps");
+        let defi = groups.iter().find(|g| g.category == "DeFi").unwrap();
+        assert!(defi.headline_metrics.is_empty());
+
+        let json = signals_to_json(&signals, &groups, &[None, None], SignalDetail::Full);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let network = parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|g| g["category"] == "Network Performance")
+            .unwrap();
+        let metrics = network["signals"][0]["metrics"].as_array().unwrap();
+        assert_eq!(metrics[0]["name"], "avg_non_vote_tps");
+        assert_eq!(metrics[0]["headline"], true);
+        assert!(metrics[1].get("headline").is_none());
+    }
+
+    #[test]
+    fn importance_weighs_novelty_then_quality_and_diversity() {
+        let mut signals = vec![
+            signal(SignalSource::DeFiLlama, "a", Some(1.0)),
+            signal(SignalSource::DeFiLlama, "b", Some(1.0)),
+            signal(SignalSource::Social, "c", Some(1.0)),
+        ];
+        let spike = Novelty {
+            metric: "tvl_change_7d".into(),
+            z_score: -3.1,
+        };
+        let scores = importance(&signals, &[Some(spike.clone()), None, None]);
+        assert!(
+            scores[0] > 0.6,
+            "a past-threshold anomaly saturates novelty"
+        );
+        assert!(scores[0] > scores[1] && scores[0] > scores[2]);
+        // The only social signal in DeFi adds a source; the DeFiLlama pair
+        // split that credit, though their quality is higher.
+        let quality_gap =
+            0.25 * (SignalSource::DeFiLlama.quality() - SignalSource::Social.quality());
+        assert!((scores[2] - scores[1] - (0.075 - quality_gap)).abs() < 1e-9);
+
+        signals[1].importance = Some(0.42);
+        let json = signals_to_json(
+            &signals,
+            &aggregate(&signals, None),
+            &[],
+            SignalDetail::Compact,
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let imported: Vec<&serde_json::Value> = parsed[0]["signals"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|s| s.get("importance"))
+            .collect();
+        assert_eq!(imported, [&serde_json::json!(0.42)]);
+    }
+
+    #[test]
+    fn group_metrics_sum_across_unit_spellings_only() {
+        let mut signals = vec![
+            signal(SignalSource::DeFiLlama, "a", Some(2.0)),
+            signal(SignalSource::Dune, "b", Some(3.0)),
+            signal(SignalSource::Social, "c", Some(5.0)),
+        ];
+        signals[1].metrics[0].unit = "percent".into();
+        signals[2].metrics[0].unit = "USD".into();
+        let groups = aggregate(&signals, None);
+        let sums: Vec<(f64, &str)> = groups[0]
+            .key_metrics
+            .iter()
+            .map(|m| (m.value, m.unit.as_str()))
+            .collect();
+        assert_eq!(sums, [(5.0, "%"), (5.0, "USD")]);
+    }
+
+    #[test]
+    fn stale_category_ranks_below_fresh_one_with_same_count() {
+        let stamped = |category: &str, title: &str, days_old: i64| {
+            let mut s = signal(SignalSource::GitHub, title, None);
+            s.category = category.into();
+            s.timestamp = Utc::now() - chrono::Duration::days(days_old);
+            s
+        };
+        // Same count and source diversity; only age differs.
+        let signals = vec![
+            stamped("DePIN", "old a", 10),
+            stamped("DePIN", "old b", 12),
+            stamped("DePIN", "old c", 14),
+            stamped("Payments", "new a", 0),
+            stamped("Payments", "new b", 1),
+            stamped("Payments", "new c", 2),
+        ];
+
+        let groups = aggregate(&signals, Some(72.0));
+        let order: Vec<&str> = groups.iter().map(|g| g.category.as_str()).collect();
+        assert_eq!(order, ["Payments", "DePIN"]);
+        assert_eq!(groups[0].total_signals, groups[1].total_signals);
+        assert!(
+            groups[0].weight > 1.5 && groups[1].weight < 0.3,
+            "{groups:?}"
+        );
+
+        let undecayed = aggregate(&signals, None);
+        assert!(undecayed.iter().all(|g| g.weight == 3.0));
+    }
+
+    #[test]
+    fn tied_categories_order_alphabetically_every_time() {
+        let categories = ["RWA", "Gaming", "DePIN", "DAO", "Payments", "AI", "Oracles"];
+        let signals: Vec<Signal> = categories
+            .iter()
+            .map(|category| {
+                let mut s = signal(SignalSource::GitHub, category, Some(1.0));
+                s.category = (*category).into();
+                s
+            })
+            .collect();
+
+        let first = aggregate(&signals, None);
+        let order: Vec<&str> = first.iter().map(|g| g.category.as_str()).collect();
+        assert_eq!(
+            order,
+            ["AI", "DAO", "DePIN", "Gaming", "Oracles", "Payments", "RWA"]
+        );
+        let json = signals_to_json(&signals, &first, &[], SignalDetail::Full);
+        for _ in 0..10 {
+            let again = aggregate(&signals, None);
+            let again_order: Vec<&str> = again.iter().map(|g| g.category.as_str()).collect();
+            assert_eq!(again_order, order);
+            assert_eq!(
+                signals_to_json(&signals, &again, &[], SignalDetail::Full),
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn compact_detail_keeps_ids_and_top_metrics() {
+        let mut s = signal(SignalSource::DeFiLlama, "Kamino TVL", Some(4.0));
+        s.description = "A long paragraph of protocol prose.".repeat(20);
+        for name in ["tvl", "fees_24h", "users", "volume"] {
+            s.metrics.push(Metric {
+                name: name.into(),
+                value: 1.0,
+                unit: String::new(),
+            });
+        }
+        let signals = vec![s];
+        let groups = aggregate(&signals, None);
+        let novelty = [Some(Novelty {
+            metric: "users".into(),
+            z_score: 2.5,
+        })];
+
+        let full = signals_to_json(&signals, &groups, &novelty, SignalDetail::Full);
+        let compact = signals_to_json(&signals, &groups, &novelty, SignalDetail::Compact);
+        assert!(compact.len() * 2 < full.len());
+
+        let parsed: serde_json::Value = serde_json::from_str(&compact).unwrap();
+        let entry = &parsed[0]["signals"][0];
+        assert_eq!(entry["id"], signals[0].id.as_str());
+        assert!(entry.get("description").is_none());
+        let names: Vec<&str> = entry["metrics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["users", "tvl_change_7d", "tvl"]);
+    }
+
+    #[test]
+    fn blocked_category_never_reaches_synthesis() {
+        let mut signals = vec![
+            Signal::new(
+                SignalSource::Nft,
+                "nfts".into(),
+                "Mad Lads floor".into(),
+                String::new(),
+                Vec::new(),
+                None,
+            ),
+            signal(SignalSource::DeFiLlama, "Kamino TVL", Some(3.0)),
+            Signal::new(
+                SignalSource::GitHub,
+                "decentralized finance".into(),
+                "New DeFi repos".into(),
+                String::new(),
+                Vec::new(),
+                None,
+            ),
+        ];
+        filter_categories(&mut signals, &[], &["NFT".into()]);
+        let json = signals_to_json(
+            &signals,
+            &aggregate(&signals, None),
+            &[None, None],
+            SignalDetail::Full,
+        );
+        assert!(!json.contains("Mad Lads"));
+        assert_eq!(signals.len(), 2);
+
+        filter_categories(&mut signals, &["defi".into()], &[]);
+        assert_eq!(signals.len(), 2, "both spellings normalize to DeFi");
+        filter_categories(&mut signals, &["Gaming".into()], &[]);
+        assert!(signals.is_empty());
+    }
+
+    #[test]
+    fn smoothed_twin_damps_a_dip_and_keeps_the_raw_value() {
+        let tps = |value: f64| {
+            Signal::new(
+                SignalSource::SolanaOnchain,
+                "Network Performance".into(),
+                "Solana TPS".into(),
+                String::new(),
+                vec![
+                    Metric {
+                        name: "avg_tps".into(),
+                        value,
+                        unit: "tx/s".into(),
+                    },
+                    Metric {
+                        name: "avg_non_vote_tps".into(),
+                        value: value / 4.0,
+                        unit: "tx/s".into(),
+                    },
+                ],
+                None,
+            )
+        };
+        let mut history = History::default();
+        for _ in 0..3 {
+            history.record(&[tps(4000.0)], None);
+        }
+        let smoothing = HashMap::from([("avg_tps".to_string(), 0.3)]);
+
+        let mut signals = vec![tps(2000.0)];
+        assert_eq!(add_smoothed(&mut signals, &history, &smoothing), 1);
+        let names: Vec<&str> = signals[0].metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["avg_tps", "avg_non_vote_tps", "avg_tps_ema"]);
+        assert_eq!(signals[0].metrics[0].value, 2000.0, "raw value kept");
+        assert!((signals[0].metrics[2].value - 3400.0).abs() < 1e-9);
+        assert_eq!(signals[0].metrics[2].unit, "tx/s");
+    }
+}
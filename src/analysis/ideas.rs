@@ -1,8 +1,11 @@
+use crate::analysis::persona::{self, Stage};
+use crate::config::Persona;
 use crate::error::Result;
 use crate::llm::LlmClient;
 use crate::types::{BuildIdea, Narrative};
+use futures_util::{StreamExt, stream};
 use serde::Deserialize;
-use tracing::info;
+use tracing::{info, warn};
 
 const SYSTEM_PROMPT: &str = r#"You are a product strategist for the Solana ecosystem. Given identified narratives with supporting data, generate concrete build ideas that an AI agent or small team could implement in one week.
 
@@ -13,7 +16,7 @@ For each build idea, provide:
 4. MVP scope (what you build in a week — list 3-5 concrete deliverables, not vague goals)
 5. Competitive landscape (name specific existing tools/products, explain what gap this fills)
 6. Timing rationale (connect directly to the narrative data — why does *this week's* signal data make this timely?)
-7. Which narrative index this idea supports (from the input)
+7. Which narrative this idea supports, by the narrative's `id` field from the input
 
 Generate 3-5 ideas per narrative. Focus on ideas that are:
 - Immediately useful (not "build a protocol" — think tools, dashboards, bots, alerts)
@@ -37,11 +40,15 @@ Respond in JSON:
       "mvp_scope": "...",
       "competitive_landscape": "...",
       "timing_rationale": "...",
-      "narrative_index": 0
+      "narrative_id": "n5d2e71aa"
     }
   ]
 }"#;
 
+/// Confidence change, on the 0-1 scale, that makes a narrative worth fresh
+/// ideas even when its trend holds.
+const MATERIAL_CONFIDENCE_SHIFT: f64 = 0.1;
+
 #[derive(Deserialize)]
 struct IdeasResponse {
     ideas: Vec<RawIdea>,
@@ -55,10 +62,69 @@ struct RawIdea {
     mvp_scope: String,
     competitive_landscape: String,
     timing_rationale: String,
-    narrative_index: usize,
+    narrative_id: String,
+}
+
+/// Generate ideas for `narratives`, `batch_size` at a time (all in one call
+/// when unset) with up to `concurrency` batches in flight. Results merge in
+/// narrative order. A failed batch is logged and skipped unless every batch
+/// fails.
+pub async fn generate_ideas(
+    llm: &LlmClient,
+    narratives: &[Narrative],
+    batch_size: Option<usize>,
+    concurrency: usize,
+    persona: Persona,
+) -> Result<Vec<BuildIdea>> {
+    let system = persona::system_prompt(persona, Stage::Ideas, SYSTEM_PROMPT);
+    let size = batch_size.filter(|&n| n > 0).unwrap_or(narratives.len());
+    if narratives.len() <= size {
+        return ideas_for_batch(llm, &system, narratives).await;
+    }
+
+    let batches: Vec<&[Narrative]> = narratives.chunks(size).collect();
+    info!(
+        narrative_count = narratives.len(),
+        batches = batches.len(),
+        batch_size = size,
+        "generating build ideas in batches"
+    );
+    // Collected first: a `Map` adapter held across the await would keep the
+    // whole pipeline future from being `Send` for spawned runs.
+    let requests: Vec<_> = batches
+        .iter()
+        .map(|b| ideas_for_batch(llm, &system, b))
+        .collect();
+    let results: Vec<Result<Vec<BuildIdea>>> = stream::iter(requests)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut ideas = Vec::new();
+    let mut first_error = None;
+    for (i, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(batch) => ideas.extend(batch),
+            Err(e) => {
+                warn!(
+                    batch = i + 1,
+                    "idea generation failed for batch, skipping: {e}"
+                );
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) if ideas.is_empty() => Err(e),
+        _ => Ok(ideas),
+    }
 }
 
-pub async fn generate_ideas(llm: &LlmClient, narratives: &[Narrative]) -> Result<Vec<BuildIdea>> {
+async fn ideas_for_batch(
+    llm: &LlmClient,
+    system: &str,
+    narratives: &[Narrative],
+) -> Result<Vec<BuildIdea>> {
     info!(narrative_count = narratives.len(), "generating build ideas");
 
     let narratives_json = serde_json::to_string_pretty(narratives).unwrap_or_else(|_| "[]".into());
@@ -66,23 +132,212 @@ pub async fn generate_ideas(llm: &LlmClient, narratives: &[Narrative]) -> Result
     let user_message =
         format!("Generate build ideas for these Solana ecosystem narratives:\n\n{narratives_json}");
 
-    let response: IdeasResponse = llm.complete_json(SYSTEM_PROMPT, &user_message).await?;
+    let response: IdeasResponse = llm.complete_json("ideas", system, &user_message).await?;
 
     let count = response.ideas.len();
-    let ideas = response
-        .ideas
-        .into_iter()
-        .map(|i| BuildIdea {
-            title: i.title,
-            description: i.description,
-            target_user: i.target_user,
-            mvp_scope: i.mvp_scope,
-            competitive_landscape: i.competitive_landscape,
-            timing_rationale: i.timing_rationale,
-            narrative_index: i.narrative_index,
-        })
-        .collect();
+    let ideas = to_build_ideas(response.ideas, narratives);
 
     info!(count, "generated build ideas");
     Ok(ideas)
 }
+
+/// Attach each raw idea to a narrative of the batch it was generated for.
+/// Models don't always echo the ID: a 1-based position or the title within
+/// the batch is mapped back to that narrative's ID, and a one-narrative batch
+/// claims every idea. Anything else keeps the reference as given.
+fn to_build_ideas(raw: Vec<RawIdea>, batch: &[Narrative]) -> Vec<BuildIdea> {
+    raw.into_iter()
+        .map(|i| {
+            let narrative_id = resolve_narrative(&i.narrative_id, batch)
+                .map(|n| n.id.clone())
+                .unwrap_or_else(|| {
+                    warn!(narrative_id = %i.narrative_id, idea = %i.title, "idea references no narrative in its batch");
+                    i.narrative_id
+                });
+            BuildIdea {
+                title: i.title,
+                description: i.description,
+                target_user: i.target_user,
+                mvp_scope: i.mvp_scope,
+                competitive_landscape: i.competitive_landscape,
+                timing_rationale: i.timing_rationale,
+                narrative_id,
+                carried_over: false,
+            }
+        })
+        .collect()
+}
+
+fn resolve_narrative<'a>(reference: &str, batch: &'a [Narrative]) -> Option<&'a Narrative> {
+    let reference = reference.trim();
+    if let Some(n) = batch.iter().find(|n| n.id == reference) {
+        return Some(n);
+    }
+    let position = reference.trim_start_matches('#').parse::<usize>().ok();
+    if let Some(n) = position
+        .and_then(|p| p.checked_sub(1))
+        .and_then(|i| batch.get(i))
+    {
+        return Some(n);
+    }
+    if let Some(n) = batch
+        .iter()
+        .find(|n| n.title.eq_ignore_ascii_case(reference))
+    {
+        return Some(n);
+    }
+    match batch {
+        [only] => Some(only),
+        _ => None,
+    }
+}
+
+/// Split `narratives` into the ones that need fresh ideas and the stored ideas
+/// reusable for the rest. A narrative keeps its previous ideas when the last
+/// run had it with the same trend and a confidence within
+/// `MATERIAL_CONFIDENCE_SHIFT`, and generated at least one idea for it. Both
+/// sides are compared by [`Narrative::confidence`], so `narratives` should be
+/// recalibrated like the stored ones.
+pub fn carry_over(
+    narratives: &[Narrative],
+    previous_narratives: &[Narrative],
+    previous_ideas: &[BuildIdea],
+) -> (Vec<Narrative>, Vec<BuildIdea>) {
+    let mut changed = Vec::new();
+    let mut carried = Vec::new();
+    for narrative in narratives {
+        let stored: Vec<&BuildIdea> = previous_ideas
+            .iter()
+            .filter(|i| i.narrative_id == narrative.id)
+            .collect();
+        let unchanged = previous_narratives
+            .iter()
+            .find(|p| p.id == narrative.id)
+            .is_some_and(|p| {
+                p.trend == narrative.trend
+                    && (p.confidence() - narrative.confidence()).abs() < MATERIAL_CONFIDENCE_SHIFT
+            });
+        if unchanged && !stored.is_empty() {
+            carried.extend(stored.into_iter().map(|i| BuildIdea {
+                carried_over: true,
+                ..i.clone()
+            }));
+        } else {
+            changed.push(narrative.clone());
+        }
+    }
+    (changed, carried)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TrendDirection;
+
+    fn narrative(title: &str, confidence: f64, trend: TrendDirection) -> Narrative {
+        Narrative {
+            trend,
+            ..crate::test_support::narrative(title, confidence)
+        }
+    }
+
+    fn idea(narrative: &Narrative) -> BuildIdea {
+        BuildIdea {
+            title: format!("Tool for {}", narrative.title),
+            description: String::new(),
+            target_user: String::new(),
+            mvp_scope: String::new(),
+            competitive_landscape: String::new(),
+            timing_rationale: String::new(),
+            narrative_id: narrative.id.clone(),
+            carried_over: false,
+        }
+    }
+
+    fn raw(title: &str, narrative_id: &str) -> RawIdea {
+        RawIdea {
+            title: title.into(),
+            description: String::new(),
+            target_user: String::new(),
+            mvp_scope: String::new(),
+            competitive_landscape: String::new(),
+            timing_rationale: String::new(),
+            narrative_id: narrative_id.into(),
+        }
+    }
+
+    #[test]
+    fn batched_ideas_point_at_their_own_batch() {
+        use TrendDirection::*;
+        let narratives: Vec<Narrative> = ["Restaking", "DePIN", "Payments", "Memecoins", "RWA"]
+            .iter()
+            .map(|t| narrative(t, 0.7, Stable))
+            .collect();
+        let batches: Vec<&[Narrative]> = narratives.chunks(2).collect();
+        let responses = [
+            vec![raw("a", &narratives[0].id), raw("b", "2")],
+            // Positions restart in every batch: "1" here is Payments, not Restaking.
+            vec![raw("c", "1"), raw("d", "memecoins"), raw("e", "n-unknown")],
+            vec![raw("f", "anything")],
+        ];
+
+        let merged: Vec<BuildIdea> = batches
+            .iter()
+            .zip(responses)
+            .flat_map(|(batch, response)| to_build_ideas(response, batch))
+            .collect();
+        let pointed: Vec<(&str, &str)> = merged
+            .iter()
+            .map(|i| {
+                let title = narratives
+                    .iter()
+                    .find(|n| n.id == i.narrative_id)
+                    .map_or(i.narrative_id.as_str(), |n| n.title.as_str());
+                (i.title.as_str(), title)
+            })
+            .collect();
+        assert_eq!(
+            pointed,
+            [
+                ("a", "Restaking"),
+                ("b", "DePIN"),
+                ("c", "Payments"),
+                ("d", "Memecoins"),
+                ("e", "n-unknown"),
+                ("f", "RWA"),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_new_or_shifted_narratives_need_ideas() {
+        use TrendDirection::*;
+        let steady = narrative("Restaking", 0.80, Accelerating);
+        let flipped = narrative("Memecoins", 0.70, Accelerating);
+        let jumped = narrative("DePIN", 0.50, Stable);
+        let previous = [
+            narrative("Restaking", 0.75, Accelerating),
+            narrative("Memecoins", 0.70, Decelerating),
+            narrative("DePIN", 0.30, Stable),
+        ];
+        let fresh = narrative("Payments", 0.60, Emerging);
+        // Same self-reported confidence, but far less evidence this run.
+        let mut previous_rwa = narrative("RWA", 0.90, Stable);
+        previous_rwa.adjusted_confidence = Some(0.85);
+        let mut thinned = narrative("RWA", 0.90, Stable);
+        thinned.adjusted_confidence = Some(0.40);
+        let previous = [previous.as_slice(), &[previous_rwa]].concat();
+        let previous_ideas: Vec<BuildIdea> = previous.iter().map(idea).collect();
+
+        let (changed, carried) = carry_over(
+            &[steady.clone(), flipped, jumped, fresh, thinned],
+            &previous,
+            &previous_ideas,
+        );
+        let changed: Vec<&str> = changed.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(changed, ["Memecoins", "DePIN", "Payments", "RWA"]);
+        assert_eq!(carried.len(), 1);
+        assert_eq!(carried[0].narrative_id, steady.id);
+        assert!(carried[0].carried_over);
+    }
+}
@@ -1,3 +1,7 @@
 pub mod aggregator;
 pub mod ideas;
+pub mod persona;
+pub mod pricing;
+pub mod sentiment;
+pub mod stats;
 pub mod synthesizer;
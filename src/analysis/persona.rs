@@ -0,0 +1,77 @@
+use crate::config::Persona;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Which system prompt a fragment frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Synthesis,
+    Ideas,
+}
+
+/// One persona's framing from `data/personas.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fragments {
+    synthesis: String,
+    ideas: String,
+}
+
+/// The bundled fragments, keyed by persona name such as "trader".
+fn registry() -> &'static BTreeMap<String, Fragments> {
+    static REGISTRY: OnceLock<BTreeMap<String, Fragments>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        toml::from_str(include_str!("../../data/personas.toml"))
+            .expect("data/personas.toml is a valid persona registry")
+    })
+}
+
+/// `base` with `persona`'s framing for `stage` in front. The generic
+/// persona, or one without fragments, gets `base` unchanged.
+pub fn system_prompt(persona: Persona, stage: Stage, base: &str) -> String {
+    let Some(fragments) = persona.key().and_then(|key| registry().get(key)) else {
+        return base.to_string();
+    };
+    let framing = match stage {
+        Stage::Synthesis => &fragments.synthesis,
+        Stage::Ideas => &fragments.ideas,
+    };
+    format!("{}\n\n{base}", framing.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_persona_frames_both_stages_and_generic_adds_nothing() {
+        let personas = [
+            Persona::Vc,
+            Persona::Researcher,
+            Persona::Trader,
+            Persona::Builder,
+        ];
+        for persona in personas {
+            let key = persona.key().unwrap();
+            assert!(registry().contains_key(key), "no fragments for {key}");
+            for stage in [Stage::Synthesis, Stage::Ideas] {
+                let prompt = system_prompt(persona, stage, "BASE");
+                assert!(prompt.ends_with("\n\nBASE"), "{key} {stage:?}");
+                assert!(prompt.len() > "\n\nBASE".len() + 40, "{key} {stage:?}");
+            }
+        }
+        assert_eq!(
+            registry().len(),
+            personas.len(),
+            "fragments without a persona"
+        );
+        let trader = system_prompt(Persona::Trader, Stage::Synthesis, "BASE");
+        assert!(trader.contains("catalysts"));
+        assert_ne!(trader, system_prompt(Persona::Trader, Stage::Ideas, "BASE"));
+        assert_eq!(
+            system_prompt(Persona::Generic, Stage::Synthesis, "BASE"),
+            "BASE"
+        );
+    }
+}
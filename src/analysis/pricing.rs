@@ -0,0 +1,126 @@
+use crate::config::PricingConfig;
+use crate::http::HttpClient;
+use crate::types::{Metric, Signal};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+/// Suffix of the USD metric added next to each SOL-denominated one.
+const USD_SUFFIX: &str = "_usd";
+
+#[derive(Deserialize)]
+struct SimplePrice {
+    solana: UsdQuote,
+}
+
+#[derive(Deserialize)]
+struct UsdQuote {
+    usd: f64,
+}
+
+/// The SOL/USD price to convert with: the configured one, else a fetched
+/// one if enabled. `None` when neither yields a usable price.
+pub async fn sol_usd(config: &PricingConfig, http: &HttpClient) -> Option<f64> {
+    if let Some(price) = config.sol_usd {
+        return usable(price);
+    }
+    if !config.fetch_sol_price {
+        return None;
+    }
+    match http.get_json::<SimplePrice>(&config.price_url).await {
+        Ok(quote) => usable(quote.solana.usd),
+        Err(e) => {
+            warn!("SOL/USD price unavailable, skipping USD normalization: {e}");
+            None
+        }
+    }
+}
+
+fn usable(price: f64) -> Option<f64> {
+    let ok = price.is_finite() && price > 0.0;
+    if !ok {
+        warn!(price, "ignoring invalid SOL/USD price");
+    }
+    ok.then_some(price)
+}
+
+/// Add `<name>_usd` next to every SOL-denominated metric, keeping the
+/// original. Nothing is added without a price, or where the signal already
+/// reports that USD metric. Returns how many metrics were added.
+pub fn add_usd_equivalents(signals: &mut [Signal], sol_usd: Option<f64>) -> usize {
+    let Some(price) = sol_usd else {
+        debug!("no SOL/USD price, metrics left in SOL");
+        return 0;
+    };
+    let mut added = 0;
+    for signal in signals.iter_mut() {
+        let converted: Vec<Metric> = signal
+            .metrics
+            .iter()
+            .filter(|m| m.unit.eq_ignore_ascii_case("SOL") && m.value.is_finite())
+            .map(|m| Metric {
+                name: format!("{}{USD_SUFFIX}", m.name),
+                value: m.value * price,
+                unit: "USD".into(),
+            })
+            .filter(|usd| !signal.metrics.iter().any(|m| m.name == usd.name))
+            .collect();
+        added += converted.len();
+        signal.metrics.extend(converted);
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SignalSource;
+
+    fn floor(value: f64) -> Signal {
+        Signal::new(
+            SignalSource::Nft,
+            "NFT".into(),
+            "Mad Lads".into(),
+            String::new(),
+            vec![
+                Metric {
+                    name: "floor_price".into(),
+                    value,
+                    unit: "SOL".into(),
+                },
+                Metric {
+                    name: "listed_count".into(),
+                    value: 400.0,
+                    unit: "listings".into(),
+                },
+            ],
+            None,
+        )
+    }
+
+    #[test]
+    fn sol_metrics_gain_usd_equivalents() {
+        let mut signals = vec![floor(42.5)];
+        assert_eq!(add_usd_equivalents(&mut signals, Some(150.0)), 1);
+        let metrics = &signals[0].metrics;
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics[0].value, 42.5, "original kept");
+        assert_eq!(metrics[2].name, "floor_price_usd");
+        assert_eq!(metrics[2].value, 6375.0);
+        assert_eq!(metrics[2].unit, "USD");
+
+        assert_eq!(
+            add_usd_equivalents(&mut signals, Some(150.0)),
+            0,
+            "never duplicated"
+        );
+    }
+
+    #[test]
+    fn no_price_adds_nothing() {
+        let mut signals = vec![floor(42.5)];
+        assert_eq!(add_usd_equivalents(&mut signals, None), 0);
+        assert_eq!(signals[0].metrics.len(), 2);
+        assert_eq!(usable(0.0), None);
+        assert_eq!(usable(f64::NAN), None);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::error::Result;
+use crate::llm::LlmClient;
+use crate::types::{Metric, Signal, SignalSource};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Tone of a source's coverage, -1.0 (bearish) to 1.0 (bullish).
+pub const METRIC: &str = "sentiment_score";
+
+const BULLISH: &[&str] = &[
+    "adoption",
+    "all-time",
+    "ath",
+    "boom",
+    "boost",
+    "boosts",
+    "breakthrough",
+    "bullish",
+    "expands",
+    "expansion",
+    "gain",
+    "gains",
+    "growing",
+    "growth",
+    "inflows",
+    "launch",
+    "launched",
+    "launches",
+    "milestone",
+    "momentum",
+    "outperforms",
+    "partnership",
+    "rallies",
+    "rally",
+    "record",
+    "soar",
+    "soaring",
+    "soars",
+    "strong",
+    "success",
+    "surge",
+    "surges",
+    "surging",
+    "upgrade",
+    "wins",
+];
+
+const BEARISH: &[&str] = &[
+    "bearish",
+    "congestion",
+    "crash",
+    "crashes",
+    "decline",
+    "declines",
+    "declining",
+    "delisted",
+    "drained",
+    "exploit",
+    "exploited",
+    "fails",
+    "failure",
+    "fear",
+    "hack",
+    "hacked",
+    "halt",
+    "halted",
+    "lawsuit",
+    "layoffs",
+    "losses",
+    "outage",
+    "outages",
+    "outflows",
+    "plunge",
+    "plunges",
+    "rug",
+    "scam",
+    "selloff",
+    "slump",
+    "sued",
+    "vulnerability",
+    "warning",
+];
+
+/// Words that flip the tone of the word right after them.
+const NEGATIONS: &[&str] = &["no", "not", "never", "without", "isn't", "wasn't", "won't"];
+
+const SENTIMENT_PROMPT: &str = r#"You rate the tone of crypto media coverage. For each source you get its recent article titles and snippets. Score how bullish or bearish the coverage is toward the projects it discusses, from -1.0 (clearly bearish: hacks, outages, declines) through 0.0 (neutral or mixed) to 1.0 (clearly bullish: launches, growth, records). Judge tone, not volume.
+
+Respond in JSON:
+{"scores": [{"id": "signal id", "score": 0.0}]}"#;
+
+/// Tone of one title or snippet: (bullish - bearish) / matched words, or
+/// 0.0 when no word matches.
+pub fn classify(text: &str) -> f64 {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let (mut bullish, mut bearish) = (0u32, 0u32);
+    for (i, word) in words.iter().enumerate() {
+        let polarity = if BULLISH.contains(word) {
+            1
+        } else if BEARISH.contains(word) {
+            -1
+        } else {
+            continue;
+        };
+        let negated = i > 0 && NEGATIONS.contains(&words[i - 1]);
+        if (polarity > 0) != negated {
+            bullish += 1;
+        } else {
+            bearish += 1;
+        }
+    }
+    let matched = bullish + bearish;
+    if matched == 0 {
+        0.0
+    } else {
+        (f64::from(bullish) - f64::from(bearish)) / f64::from(matched)
+    }
+}
+
+/// Mean tone over `texts`, so neutral titles pull the score toward 0.0.
+/// `None` with nothing to score.
+pub fn keyword_score<S: AsRef<str>>(texts: &[S]) -> Option<f64> {
+    if texts.is_empty() {
+        return None;
+    }
+    let total: f64 = texts.iter().map(|t| classify(t.as_ref())).sum();
+    Some(total / texts.len() as f64)
+}
+
+/// Append the keyword `sentiment_score` of `texts` to `signal`.
+pub fn add_keyword_score<S: AsRef<str>>(signal: &mut Signal, texts: &[S]) {
+    if let Some(score) = keyword_score(texts) {
+        signal.metrics.push(Metric {
+            name: METRIC.into(),
+            value: score,
+            unit: String::new(),
+        });
+    }
+}
+
+/// The `sentiment_score` a signal carries, if any.
+pub fn score_of(signal: &Signal) -> Option<f64> {
+    signal
+        .metrics
+        .iter()
+        .find(|m| m.name == METRIC)
+        .map(|m| m.value)
+}
+
+#[derive(Deserialize)]
+struct ScoresResponse {
+    scores: Vec<RawScore>,
+}
+
+#[derive(Deserialize)]
+struct RawScore {
+    id: String,
+    score: f64,
+}
+
+/// Re-score social signals that carry a keyword `sentiment_score` by asking
+/// the model to read their titles and snippets. Signals the model skips
+/// keep their keyword score. Returns how many were re-scored.
+pub async fn rescore_with_llm(llm: &LlmClient, signals: &mut [Signal]) -> Result<usize> {
+    let scored: Vec<serde_json::Value> = signals
+        .iter()
+        .filter(|s| s.source == SignalSource::Social && score_of(s).is_some())
+        .map(|s| {
+            serde_json::json!({
+                "id": s.id,
+                "title": s.title,
+                "text": s.description,
+            })
+        })
+        .collect();
+    if scored.is_empty() {
+        return Ok(0);
+    }
+    info!(sources = scored.len(), "asking LLM for coverage sentiment");
+    let user_message = format!(
+        "Rate the tone of these sources:\n\n{}",
+        serde_json::to_string_pretty(&scored).unwrap_or_else(|_| "[]".into())
+    );
+    let response: ScoresResponse = llm
+        .complete_json("sentiment", SENTIMENT_PROMPT, &user_message)
+        .await?;
+    Ok(apply_scores(signals, response.scores))
+}
+
+fn apply_scores(signals: &mut [Signal], scores: Vec<RawScore>) -> usize {
+    let scores: HashMap<String, f64> = scores
+        .into_iter()
+        .filter(|s| s.score.is_finite())
+        .map(|s| (s.id, s.score.clamp(-1.0, 1.0)))
+        .collect();
+    let mut applied = 0;
+    for signal in signals.iter_mut() {
+        let Some(&score) = scores.get(&signal.id) else {
+            continue;
+        };
+        match signal.metrics.iter_mut().find(|m| m.name == METRIC) {
+            Some(metric) => {
+                metric.value = score;
+                applied += 1;
+            }
+            None => warn!(id = %signal.id, "LLM scored a signal without sentiment, ignoring"),
+        }
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_classifier_reads_tone_and_negation() {
+        assert_eq!(classify("Jupiter volume surges to record high"), 1.0);
+        assert_eq!(classify("Validator outage halted block production"), -1.0);
+        assert_eq!(classify("Restaking yields explained"), 0.0);
+        assert_eq!(classify("Bridge exploit, but TVL growth continues"), 0.0);
+        assert_eq!(classify("No outage despite the congestion"), 0.0);
+        assert_eq!(classify("Mainnet launch brings no gains"), 0.0);
+        assert_eq!(classify("Protocol not hacked, rally resumes"), 1.0);
+
+        let titles = [
+            "Solana DEX volume surges",
+            "Wallet drained in phishing scam",
+            "Firedancer explained",
+            "Staking momentum grows",
+        ];
+        let score = keyword_score(&titles).unwrap();
+        assert!((score - 0.25).abs() < 1e-9, "{score}");
+        assert_eq!(keyword_score::<&str>(&[]), None);
+    }
+
+    #[test]
+    fn llm_scores_replace_keyword_scores_in_range() {
+        let blog = |name: &str| {
+            let mut signal = Signal::new(
+                SignalSource::Social,
+                format!("Blog: {name}"),
+                format!("{name}: 4 recent articles"),
+                "Recent topics: a; b".into(),
+                Vec::new(),
+                None,
+            );
+            add_keyword_score(&mut signal, &["Solana DEX volume surges"]);
+            signal
+        };
+        let mut signals = vec![blog("Helius"), blog("Solana News")];
+        let scores = vec![
+            RawScore {
+                id: signals[0].id.clone(),
+                score: -3.0,
+            },
+            RawScore {
+                id: "s00000000".into(),
+                score: 0.5,
+            },
+        ];
+
+        assert_eq!(apply_scores(&mut signals, scores), 1);
+        assert_eq!(score_of(&signals[0]), Some(-1.0), "clamped to [-1, 1]");
+        assert_eq!(score_of(&signals[1]), Some(1.0), "unscored keeps keyword");
+    }
+}
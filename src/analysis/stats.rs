@@ -0,0 +1,106 @@
+/// Spread of the values that came before a point in a series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Band {
+    pub mean: f64,
+    /// Sample standard deviation.
+    pub std_dev: f64,
+}
+
+impl Band {
+    pub fn lower(&self, sigmas: f64) -> f64 {
+        self.mean - sigmas * self.std_dev
+    }
+
+    pub fn upper(&self, sigmas: f64) -> f64 {
+        self.mean + sigmas * self.std_dev
+    }
+
+    /// Whether `value` lies more than `sigmas` deviations from the mean.
+    pub fn is_outside(&self, value: f64, sigmas: f64) -> bool {
+        value < self.lower(sigmas) || value > self.upper(sigmas)
+    }
+}
+
+/// For each point of `series`, the mean and standard deviation of up to
+/// `window` points before it. The point itself is excluded so it can be
+/// judged against its own history. `None` until two earlier points exist,
+/// the minimum for a sample deviation.
+pub fn rolling_stats(series: &[f64], window: usize) -> Vec<Option<Band>> {
+    (0..series.len())
+        .map(|i| {
+            let prior = &series[i.saturating_sub(window)..i];
+            if prior.len() < 2 {
+                return None;
+            }
+            let n = prior.len() as f64;
+            let mean = prior.iter().sum::<f64>() / n;
+            let variance = prior.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            Some(Band {
+                mean,
+                std_dev: variance.sqrt(),
+            })
+        })
+        .collect()
+}
+
+/// Exponential moving average of `series`, oldest first, seeded with the
+/// first value: each point moves the average `alpha` of the way toward it.
+/// `alpha` of 1.0 tracks the latest value exactly; smaller values smooth
+/// harder. `None` for an empty series. Non-finite points are skipped.
+pub fn ema(series: &[f64], alpha: f64) -> Option<f64> {
+    series
+        .iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .fold(None, |avg, value| {
+            Some(match avg {
+                None => value,
+                Some(avg) => alpha * value + (1.0 - alpha) * avg,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn band_covers_only_the_trailing_window() {
+        let series = [10.0, 12.0, 10.0, 12.0, 11.0, 30.0];
+        let bands = rolling_stats(&series, 4);
+        assert_eq!(bands.len(), series.len());
+        assert!(bands[0].is_none() && bands[1].is_none());
+
+        let third = bands[2].unwrap();
+        assert_eq!(third.mean, 11.0);
+        assert!((third.std_dev - 2f64.sqrt()).abs() < 1e-9);
+
+        // The spike is judged against [12, 10, 12, 11], not against itself.
+        let last = bands[5].unwrap();
+        assert_eq!(last.mean, 11.25);
+        assert!(last.is_outside(30.0, 2.0));
+        assert!(!last.is_outside(12.0, 2.0));
+    }
+
+    #[test]
+    fn flat_history_has_zero_width() {
+        let band = rolling_stats(&[5.0, 5.0, 5.0], 10)[2].unwrap();
+        assert_eq!(band.std_dev, 0.0);
+        assert!(band.is_outside(5.1, 2.0));
+        assert!(!band.is_outside(5.0, 2.0));
+    }
+
+    #[test]
+    fn ema_damps_a_momentary_dip() {
+        let steady = [4000.0, 4000.0, 4000.0];
+        assert_eq!(ema(&steady, 0.3), Some(4000.0));
+
+        let dip = [4000.0, 4000.0, 4000.0, 2000.0];
+        let smoothed = ema(&dip, 0.3).unwrap();
+        assert!((smoothed - 3400.0).abs() < 1e-9, "{smoothed}");
+        assert_eq!(ema(&dip, 1.0), Some(2000.0), "alpha 1 is the raw value");
+
+        assert_eq!(ema(&[], 0.3), None);
+        assert_eq!(ema(&[f64::NAN, 10.0, 20.0], 0.5), Some(15.0));
+    }
+}
@@ -1,8 +1,15 @@
+use crate::analysis::persona::{self, Stage};
+use crate::config::{DiversityPolicy, Persona};
 use crate::error::Result;
-use crate::llm::LlmClient;
-use crate::types::{Metric, Narrative, TrendDirection};
+use crate::llm::{LlmClient, estimate_tokens};
+use crate::narrative_history::title_similarity;
+use crate::types::{
+    Metric, Narrative, Recurrence, RelationKind, Relationship, Signal, SignalSource, TrendDirection,
+};
+use futures_util::future::join_all;
 use serde::Deserialize;
-use tracing::info;
+use std::collections::HashSet;
+use tracing::{info, warn};
 
 const SYSTEM_PROMPT: &str = r#"You are a senior Solana ecosystem analyst identifying emerging narratives from cross-source signal data.
 
@@ -12,7 +19,7 @@ For each narrative you identify, provide:
 1. A clear, specific title — name the specific protocols, tools, or primitives involved. "Concentrated Liquidity Migration on Raydium and Orca" not "DeFi growth."
 2. A 2-3 sentence summary covering: what is happening, why it matters for the Solana ecosystem, and what structural shift it represents.
 3. Confidence score (0.0-1.0) based on signal strength and source diversity.
-4. Which signal indices support this narrative (from the input data).
+4. Which signals support this narrative, by their `id` field (e.g. "s1f0c9a27"). Never use array positions.
 5. Trend direction: "Accelerating" (growing faster), "Stable" (steady), "Decelerating" (slowing), "Emerging" (too early to tell, but signals present).
 6. Key quantitative metrics that back the narrative.
 
@@ -21,6 +28,14 @@ Analysis depth requirements:
 - **Structural implications:** What does this trend enable or threaten in the ecosystem? Which protocols or categories benefit or lose?
 - **Cross-signal validation:** Do GitHub activity, onchain metrics, TVL data, and social signals agree? Explicitly flag divergences (e.g., rising developer activity but flat TVL suggests pre-launch building).
 - **Second-order effects:** What follows from this trend? If liquid staking is growing, what does that unlock for DeFi composability?
+- **Novelty:** Each signal carries a `novelty` field comparing it to recent runs. "high" marks a genuine anomaly (|z| >= 2 on the named metric) — lead with these. "background" is steady-state and should not drive a narrative on its own. "unknown" means no history yet; judge those on their own merits.
+- **Importance:** Each signal may carry an `importance` from 0.0 to 1.0, mostly its novelty with some weight on source quality and on how much it adds to its category's source mix. When signals compete for a narrative, lead with the most important.
+- **Smoothed metrics:** A metric ending in `_ema` is the exponential moving average of the same metric across recent runs, shown next to the raw value. Judge trend direction on the `_ema` value: a raw dip with a steady `_ema` is noise, not deceleration.
+- **Headline metrics:** A metric with `"headline": true` is the figure that carries its category's story, e.g. `avg_non_vote_tps` rather than `avg_tps`, since total TPS is mostly validator vote traffic. Base throughput and activity narratives on headline metrics and cite the others as context.
+- **Analyst annotations:** A signal may carry an `analyst_note` and an `analyst_quality` (0.0-1.0) from a human curator. Treat the note as context the data alone doesn't show, and weigh the signal by the quality given.
+- **External signals:** A signal with an `origin` field was merged from the user's own data feed rather than collected by this tool. Its `source` says what kind of data it is; treat it like any other signal of that source.
+- **Coverage languages:** A blog signal may list the `languages` its articles are written in. A narrative covered in several languages (say English, Chinese and Korean) has spread beyond one community and is stronger than one covered in a single language; say so when it happens.
+- **Coverage tone:** Social signals may carry a `sentiment_score` from -1.0 (bearish) to 1.0 (bullish). Rising coverage with negative tone is a contradiction worth flagging, not confirmation of a trend.
 - **Specificity:** Name specific protocols, repositories, and programs. Reference actual addresses, repo names, and TVL figures from the data.
 
 Respond in JSON:
@@ -30,7 +45,7 @@ Respond in JSON:
       "title": "...",
       "summary": "...",
       "confidence": 0.85,
-      "supporting_signals": [0, 3, 7],
+      "supporting_signals": ["s1f0c9a27", "s8b04e611"],
       "trend": "Accelerating",
       "key_metrics": [{"name": "...", "value": 123.4, "unit": "..."}]
     }
@@ -45,6 +60,39 @@ Rules:
 - Don't invent data. Only use what's in the signals.
 - When signals contradict each other, say so — contradiction is itself a signal."#;
 
+const RELATIONSHIPS_PROMPT: &str = r#"You are a senior Solana ecosystem analyst. Given a set of narratives identified from this week's signal data, find the directed relationships between them.
+
+Relationship kinds:
+- "enables": growth in A makes B possible or more likely (liquid staking growth enables LST-collateralized lending).
+- "competes_with": A and B fight for the same users, liquidity, or developer attention.
+- "depends_on": A cannot continue without B holding up.
+
+Respond in JSON:
+{
+  "relationships": [
+    {"from": "n5d2e71aa", "to": "n0b7c3e19", "kind": "enables", "rationale": "one sentence grounded in the narratives' data"}
+  ]
+}
+
+Rules:
+- Refer to narratives only by their `id` field.
+- Only include relationships you can justify from the summaries and metrics. An empty list is a valid answer.
+- At most one relationship per ordered pair."#;
+
+#[derive(Deserialize)]
+struct RelationshipsResponse {
+    relationships: Vec<RawRelationship>,
+}
+
+#[derive(Deserialize)]
+struct RawRelationship {
+    from: String,
+    to: String,
+    kind: String,
+    #[serde(default)]
+    rationale: String,
+}
+
 #[derive(Deserialize)]
 struct SynthesisResponse {
     narratives: Vec<RawNarrative>,
@@ -55,7 +103,9 @@ struct RawNarrative {
     title: String,
     summary: String,
     confidence: f64,
-    supporting_signals: Vec<usize>,
+    /// Models occasionally emit bare numbers despite the prompt; keep them as
+    /// strings so they surface as unresolved references instead of a parse error.
+    supporting_signals: Vec<serde_json::Value>,
     trend: String,
     #[serde(default)]
     key_metrics: Vec<RawMetric>,
@@ -69,24 +119,328 @@ struct RawMetric {
     unit: String,
 }
 
-pub async fn identify_narratives(llm: &LlmClient, signals_json: &str) -> Result<Vec<Narrative>> {
+pub async fn identify_narratives(
+    llm: &LlmClient,
+    signals_json: &str,
+    token_budget: usize,
+    persona: Persona,
+) -> Result<Vec<Narrative>> {
+    let system = persona::system_prompt(persona, Stage::Synthesis, SYSTEM_PROMPT);
+    let estimated = estimate_tokens(signals_json);
+    if estimated <= token_budget {
+        return synthesize(llm, &system, signals_json)
+            .await
+            .map(dedup_narratives);
+    }
+
+    let groups: Vec<serde_json::Value> = serde_json::from_str(signals_json)
+        .map_err(|e| crate::error::Error::parse(format!("signals JSON: {e}")))?;
+    let batches = batch_groups(groups, token_budget);
+    info!(
+        estimated_tokens = estimated,
+        token_budget,
+        batches = batches.len(),
+        "signal set exceeds LLM budget, synthesizing in batches"
+    );
+
+    let mut narratives = Vec::new();
+    for (i, batch) in batches.iter().enumerate() {
+        let batch_json = serde_json::to_string_pretty(batch).unwrap_or_else(|_| "[]".into());
+        info!(batch = i + 1, of = batches.len(), "synthesizing batch");
+        narratives.extend(synthesize(llm, &system, &batch_json).await?);
+    }
+    Ok(dedup_narratives(narratives))
+}
+
+/// Share of the gap to 100% confidence a narrative gains when every run of
+/// an ensemble finds it, scaled down as fewer runs agree.
+const RECURRENCE_BOOST: f64 = 0.5;
+
+/// [`identify_narratives`] run `runs` times at once, combined by
+/// [`combine_runs`]. A failed pass is logged and counts as finding nothing;
+/// the ensemble fails unless a majority of passes succeed, since nothing
+/// could reach a majority otherwise.
+pub async fn identify_narratives_ensemble(
+    llm: &LlmClient,
+    signals_json: &str,
+    token_budget: usize,
+    persona: Persona,
+    runs: usize,
+    match_threshold: f64,
+) -> Result<Vec<Narrative>> {
+    if runs <= 1 {
+        return identify_narratives(llm, signals_json, token_budget, persona).await;
+    }
+    info!(runs, "synthesizing narratives with an ensemble of runs");
+    let results =
+        join_all((0..runs).map(|_| identify_narratives(llm, signals_json, token_budget, persona)))
+            .await;
+    let mut passes = Vec::new();
+    let mut first_error = None;
+    for (i, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(narratives) => passes.push(narratives),
+            Err(e) => {
+                warn!(run = i + 1, error = %e, "synthesis run failed, leaving it out of the ensemble");
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) if passes.len() * 2 <= runs => {
+            warn!(
+                succeeded = passes.len(),
+                runs, "too few synthesis runs succeeded for a majority"
+            );
+            Err(e)
+        }
+        _ => Ok(combine_runs(passes, runs, match_threshold)),
+    }
+}
+
+/// Cluster the narratives of several synthesis runs: a narrative joins the
+/// cluster holding the most similar title (see [`title_similarity`]) when
+/// that reaches `match_threshold`. Clusters found by a majority of the
+/// `total` runs attempted, failed ones included, are kept as one narrative
+/// with the most confident member's text, every member's evidence, and their
+/// mean confidence raised by [`RECURRENCE_BOOST`] in proportion to the
+/// agreement. The rest are dropped as likely hallucinations.
+pub fn combine_runs(
+    runs: Vec<Vec<Narrative>>,
+    total: usize,
+    match_threshold: f64,
+) -> Vec<Narrative> {
+    let total = total.max(runs.len());
+    // Members of each cluster, with the run that produced them.
+    let mut clusters: Vec<Vec<(usize, Narrative)>> = Vec::new();
+    for (run, narratives) in runs.into_iter().enumerate() {
+        for n in narratives {
+            let similarity = |cluster: &Vec<(usize, Narrative)>| {
+                cluster
+                    .iter()
+                    .map(|(_, m)| match m.id == n.id {
+                        true => 1.0,
+                        false => title_similarity(&m.title, &n.title),
+                    })
+                    .fold(0.0, f64::max)
+            };
+            let best = clusters
+                .iter_mut()
+                .map(|cluster| (similarity(cluster), cluster))
+                .filter(|(score, _)| *score >= match_threshold)
+                .max_by(|a, b| a.0.total_cmp(&b.0));
+            match best {
+                Some((_, cluster)) => cluster.push((run, n)),
+                None => clusters.push(vec![(run, n)]),
+            }
+        }
+    }
+
+    let mut kept = Vec::new();
+    for mut cluster in clusters {
+        let appeared = cluster
+            .iter()
+            .map(|(run, _)| *run)
+            .collect::<HashSet<_>>()
+            .len();
+        cluster.sort_by(|a, b| b.1.raw_confidence.total_cmp(&a.1.raw_confidence));
+        if appeared * 2 <= total {
+            warn!(narrative = %cluster[0].1.title, appeared, runs = total, "dropping narrative found by a minority of synthesis runs");
+            continue;
+        }
+        let mean =
+            cluster.iter().map(|(_, n)| n.raw_confidence).sum::<f64>() / cluster.len() as f64;
+        let agreement = (appeared - 1) as f64 / (total - 1).max(1) as f64;
+        let mut members = cluster.into_iter().map(|(_, n)| n);
+        let Some(mut merged) = members.next() else {
+            continue;
+        };
+        for other in members {
+            merge_evidence(&mut merged, other);
+        }
+        merged.raw_confidence =
+            (mean + (1.0 - mean) * RECURRENCE_BOOST * agreement).clamp(0.0, 1.0);
+        merged.recurrence = Some(Recurrence {
+            appeared,
+            runs: total,
+        });
+        kept.push(merged);
+    }
+    info!(
+        kept = kept.len(),
+        runs = total,
+        "narratives agreed by a majority of synthesis runs"
+    );
+    dedup_narratives(kept)
+}
+
+/// Enforce the prompt's "2+ sources" rule after the fact: a narrative whose
+/// supporting signals span fewer than `min_sources` distinct sources is
+/// dropped, or under `Downgrade` keeps a proportionally reduced confidence.
+/// The threshold never exceeds the number of sources that produced signals,
+/// so a run with a single enabled source still yields narratives.
+pub fn enforce_source_diversity(
+    narratives: &mut Vec<Narrative>,
+    signals: &[Signal],
+    min_sources: usize,
+    policy: DiversityPolicy,
+) {
+    let available = signals
+        .iter()
+        .map(|s| s.source)
+        .collect::<HashSet<_>>()
+        .len();
+    let required = min_sources.min(available);
+    if required <= 1 {
+        return;
+    }
+
+    let sources_of = |n: &Narrative| {
+        n.supporting_signals
+            .iter()
+            .filter_map(|id| signals.iter().find(|s| &s.id == id))
+            .map(|s| s.source)
+            .collect::<HashSet<SignalSource>>()
+            .len()
+    };
+
+    match policy {
+        DiversityPolicy::Drop => narratives.retain(|n| {
+            let sources = sources_of(n);
+            if sources < required {
+                warn!(narrative = %n.title, sources, required, "dropping narrative below source diversity threshold");
+            }
+            sources >= required
+        }),
+        DiversityPolicy::Downgrade => {
+            for n in narratives.iter_mut() {
+                let sources = sources_of(n);
+                if sources < required {
+                    n.raw_confidence *= sources as f64 / required as f64;
+                    warn!(narrative = %n.title, sources, required, "downgrading narrative below source diversity threshold");
+                }
+            }
+        }
+    }
+}
+
+/// Pack category groups into batches under `token_budget`, keeping each
+/// category whole where possible. A category too large on its own is split by
+/// signal, repeating its header in every part.
+fn batch_groups(
+    groups: Vec<serde_json::Value>,
+    token_budget: usize,
+) -> Vec<Vec<serde_json::Value>> {
+    let mut batches: Vec<Vec<serde_json::Value>> = Vec::new();
+    let mut current: Vec<serde_json::Value> = Vec::new();
+    let mut current_tokens = 0;
+
+    let mut pieces = Vec::new();
+    for group in groups {
+        if estimate_tokens(&group.to_string()) <= token_budget {
+            pieces.push(group);
+            continue;
+        }
+        let signals = group["signals"].as_array().cloned().unwrap_or_default();
+        let mut part = group.clone();
+        part["signals"] = serde_json::json!([]);
+        let header_tokens = estimate_tokens(&part.to_string());
+        let mut part_signals = Vec::new();
+        let mut part_tokens = header_tokens;
+        for signal in signals {
+            let tokens = estimate_tokens(&signal.to_string());
+            if !part_signals.is_empty() && part_tokens + tokens > token_budget {
+                let mut p = part.clone();
+                p["signals"] = serde_json::Value::Array(std::mem::take(&mut part_signals));
+                pieces.push(p);
+                part_tokens = header_tokens;
+            }
+            part_tokens += tokens;
+            part_signals.push(signal);
+        }
+        if !part_signals.is_empty() {
+            part["signals"] = serde_json::Value::Array(part_signals);
+            pieces.push(part);
+        }
+    }
+
+    for piece in pieces {
+        let tokens = estimate_tokens(&piece.to_string());
+        if !current.is_empty() && current_tokens + tokens > token_budget {
+            batches.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        current_tokens += tokens;
+        current.push(piece);
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// Merge narratives sharing an ID (same normalized title), keeping the most
+/// confident one's text and the union of their evidence.
+pub fn dedup_narratives(narratives: Vec<Narrative>) -> Vec<Narrative> {
+    let mut merged: Vec<Narrative> = Vec::new();
+    for n in narratives {
+        let Some(existing) = merged.iter_mut().find(|m| m.id == n.id) else {
+            merged.push(n);
+            continue;
+        };
+        if n.raw_confidence > existing.raw_confidence {
+            let weaker = std::mem::replace(existing, n);
+            merge_evidence(existing, weaker);
+        } else {
+            merge_evidence(existing, n);
+        }
+    }
+    merged
+}
+
+fn merge_evidence(into: &mut Narrative, other: Narrative) {
+    for id in other.supporting_signals {
+        if !into.supporting_signals.contains(&id) {
+            into.supporting_signals.push(id);
+        }
+    }
+    for m in other.key_metrics {
+        if !into.key_metrics.iter().any(|k| k.name == m.name) {
+            into.key_metrics.push(m);
+        }
+    }
+}
+
+async fn synthesize(llm: &LlmClient, system: &str, signals_json: &str) -> Result<Vec<Narrative>> {
     info!("sending signals to LLM for narrative identification");
 
     let user_message = format!(
         "Analyze these aggregated signals from the Solana ecosystem and identify emerging narratives:\n\n{signals_json}"
     );
 
-    let response: SynthesisResponse = llm.complete_json(SYSTEM_PROMPT, &user_message).await?;
+    let response: SynthesisResponse = llm
+        .complete_json("synthesis", system, &user_message)
+        .await?;
 
     let count = response.narratives.len();
     let narratives = response
         .narratives
         .into_iter()
         .map(|n| Narrative {
+            id: Narrative::id_for(&n.title),
             title: n.title,
             summary: n.summary,
-            confidence: n.confidence.clamp(0.0, 1.0),
-            supporting_signals: n.supporting_signals,
+            raw_confidence: n.confidence.clamp(0.0, 1.0),
+            adjusted_confidence: None,
+            confidence_note: String::new(),
+            supporting_signals: n
+                .supporting_signals
+                .into_iter()
+                .map(|v| match v {
+                    serde_json::Value::String(id) => id,
+                    other => other.to_string(),
+                })
+                .collect(),
             trend: parse_trend(&n.trend),
             key_metrics: n
                 .key_metrics
@@ -97,6 +451,9 @@ pub async fn identify_narratives(llm: &LlmClient, signals_json: &str) -> Result<
                     unit: m.unit,
                 })
                 .collect(),
+            relationships: Vec::new(),
+            continuity: None,
+            recurrence: None,
         })
         .collect();
 
@@ -104,6 +461,76 @@ pub async fn identify_narratives(llm: &LlmClient, signals_json: &str) -> Result<
     Ok(narratives)
 }
 
+/// Ask the model how the identified narratives relate and attach the edges to
+/// their source narratives. Edges naming unknown IDs are dropped.
+pub async fn identify_relationships(llm: &LlmClient, narratives: &mut [Narrative]) -> Result<()> {
+    if narratives.len() < 2 {
+        return Ok(());
+    }
+    info!("asking LLM for relationships between narratives");
+
+    let summaries: Vec<serde_json::Value> = narratives
+        .iter()
+        .map(|n| {
+            serde_json::json!({
+                "id": n.id,
+                "title": n.title,
+                "summary": n.summary,
+                "trend": n.trend.to_string(),
+            })
+        })
+        .collect();
+    let user_message = format!(
+        "Identify relationships between these narratives:\n\n{}",
+        serde_json::to_string_pretty(&summaries).unwrap_or_else(|_| "[]".into())
+    );
+
+    let response: RelationshipsResponse = llm
+        .complete_json("relationships", RELATIONSHIPS_PROMPT, &user_message)
+        .await?;
+    let count = attach_relationships(narratives, response.relationships);
+    info!(count, "narrative relationships identified");
+    Ok(())
+}
+
+fn attach_relationships(narratives: &mut [Narrative], raw: Vec<RawRelationship>) -> usize {
+    let ids: HashSet<String> = narratives.iter().map(|n| n.id.clone()).collect();
+    let mut seen = HashSet::new();
+    let mut count = 0;
+
+    for r in raw {
+        let Some(kind) = parse_relation_kind(&r.kind) else {
+            warn!(kind = %r.kind, "unknown relationship kind, skipping");
+            continue;
+        };
+        if r.from == r.to || !ids.contains(&r.from) || !ids.contains(&r.to) {
+            warn!(from = %r.from, to = %r.to, "relationship references unknown narrative, skipping");
+            continue;
+        }
+        if !seen.insert((r.from.clone(), r.to.clone())) {
+            continue;
+        }
+        if let Some(source) = narratives.iter_mut().find(|n| n.id == r.from) {
+            source.relationships.push(Relationship {
+                kind,
+                target: r.to,
+                rationale: r.rationale,
+            });
+            count += 1;
+        }
+    }
+    count
+}
+
+fn parse_relation_kind(s: &str) -> Option<RelationKind> {
+    match s.to_lowercase().replace([' ', '-'], "_").as_str() {
+        "enables" => Some(RelationKind::Enables),
+        "competes_with" | "competes" => Some(RelationKind::CompetesWith),
+        "depends_on" | "requires" => Some(RelationKind::DependsOn),
+        _ => None,
+    }
+}
+
 fn parse_trend(s: &str) -> TrendDirection {
     match s.to_lowercase().as_str() {
         "accelerating" => TrendDirection::Accelerating,
@@ -113,3 +540,173 @@ fn parse_trend(s: &str) -> TrendDirection {
         _ => TrendDirection::Emerging,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::narrative;
+
+    fn raw(from: &str, to: &str, kind: &str) -> RawRelationship {
+        RawRelationship {
+            from: from.into(),
+            to: to.into(),
+            kind: kind.into(),
+            rationale: String::new(),
+        }
+    }
+
+    #[test]
+    fn ensemble_keeps_majority_narratives_and_boosts_agreement() {
+        let with = |title: &str, confidence: f64, signal: &str| Narrative {
+            supporting_signals: vec![signal.into()],
+            ..narrative(title, confidence)
+        };
+        let runs = vec![
+            vec![
+                with("Liquid Staking Growth", 0.6, "s1"),
+                with("Memecoin Mania", 0.9, "s9"),
+            ],
+            vec![with("Growth of Liquid Staking", 0.8, "s2")],
+            vec![
+                with("Liquid staking growth", 0.7, "s1"),
+                with("Perps Volume Surge", 0.5, "s3"),
+            ],
+            vec![with("Perps volume surge", 0.5, "s4")],
+            vec![with("Perps Volume Surge", 0.5, "s3")],
+        ];
+        let kept = combine_runs(runs, 5, 0.5);
+        let titles: Vec<&str> = kept.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, ["Growth of Liquid Staking", "Perps Volume Surge"]);
+
+        let staking = &kept[0];
+        assert_eq!(
+            staking.recurrence,
+            Some(Recurrence {
+                appeared: 3,
+                runs: 5
+            })
+        );
+        assert_eq!(staking.supporting_signals, ["s2", "s1"]);
+        // Mean 0.7, raised by half the gap times agreement (3-1)/(5-1).
+        assert!((staking.raw_confidence - (0.7 + 0.3 * 0.5 * 0.5)).abs() < 1e-9);
+
+        // Split evenly between two runs is not a majority.
+        let kept = combine_runs(vec![vec![with("A", 0.5, "s1")], Vec::new()], 2, 0.5);
+        assert!(kept.is_empty());
+
+        // Failed runs count against the majority: two of three agree, two
+        // of four (the other two failed) do not.
+        let agreed = || vec![vec![with("A", 0.5, "s1")], vec![with("A", 0.5, "s2")]];
+        let kept = combine_runs(agreed(), 3, 0.5);
+        assert_eq!(
+            kept[0].recurrence,
+            Some(Recurrence {
+                appeared: 2,
+                runs: 3
+            })
+        );
+        assert!(combine_runs(agreed(), 4, 0.5).is_empty());
+    }
+
+    #[test]
+    fn single_source_narrative_is_filtered() {
+        let signal = |source, title: &str| {
+            Signal::new(
+                source,
+                "DeFi".into(),
+                title.into(),
+                String::new(),
+                Vec::new(),
+                None,
+            )
+        };
+        let signals = vec![
+            signal(SignalSource::GitHub, "repo a"),
+            signal(SignalSource::GitHub, "repo b"),
+            signal(SignalSource::DeFiLlama, "tvl"),
+        ];
+        let mut single = narrative("GitHub Only", 0.5);
+        single.supporting_signals = vec![signals[0].id.clone(), signals[1].id.clone()];
+        let mut diverse = narrative("Cross Source", 0.5);
+        diverse.supporting_signals = vec![signals[0].id.clone(), signals[2].id.clone()];
+
+        let mut narratives = vec![single.clone(), diverse.clone()];
+        enforce_source_diversity(&mut narratives, &signals, 2, DiversityPolicy::Drop);
+        let titles: Vec<&str> = narratives.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, ["Cross Source"]);
+
+        let mut narratives = vec![single, diverse];
+        enforce_source_diversity(&mut narratives, &signals, 2, DiversityPolicy::Downgrade);
+        assert_eq!(narratives.len(), 2);
+        assert!((narratives[0].raw_confidence - 0.25).abs() < 1e-9);
+        assert!((narratives[1].raw_confidence - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn oversized_input_batches_by_category() {
+        let signal = |n: usize| serde_json::json!({"id": format!("s{n:08x}"), "description": "x".repeat(200)});
+        let groups = vec![
+            serde_json::json!({"category": "DeFi", "signals": (0..3).map(signal).collect::<Vec<_>>()}),
+            serde_json::json!({"category": "NFT", "signals": (3..5).map(signal).collect::<Vec<_>>()}),
+            serde_json::json!({"category": "DePIN", "signals": (5..20).map(signal).collect::<Vec<_>>()}),
+        ];
+        let batches = batch_groups(groups, 400);
+
+        assert!(batches.len() > 2);
+        for batch in &batches {
+            let categories: HashSet<&str> = batch
+                .iter()
+                .filter_map(|g| g["category"].as_str())
+                .collect();
+            let tokens = estimate_tokens(&serde_json::Value::Array(batch.clone()).to_string());
+            assert!(categories.len() == 1 || tokens <= 400);
+        }
+        let total: usize = batches
+            .iter()
+            .flatten()
+            .map(|g| g["signals"].as_array().unwrap().len())
+            .sum();
+        assert_eq!(total, 20);
+    }
+
+    #[test]
+    fn dedup_merges_evidence() {
+        let mut a = narrative("Liquid Staking", 0.5);
+        a.raw_confidence = 0.6;
+        a.supporting_signals = vec!["s1".into(), "s2".into()];
+        let mut b = narrative("liquid staking ", 0.5);
+        b.raw_confidence = 0.8;
+        b.supporting_signals = vec!["s2".into(), "s3".into()];
+
+        let merged = dedup_narratives(vec![a, b, narrative("Other", 0.5)]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].raw_confidence, 0.8);
+        assert_eq!(merged[0].supporting_signals, ["s2", "s3", "s1"]);
+    }
+
+    #[test]
+    fn relationships_keep_only_known_ids() {
+        let mut narratives = vec![
+            narrative("Liquid staking", 0.5),
+            narrative("LST lending", 0.5),
+        ];
+        let (a, b) = (narratives[0].id.clone(), narratives[1].id.clone());
+
+        let count = attach_relationships(
+            &mut narratives,
+            vec![
+                raw(&a, &b, "enables"),
+                raw(&a, &b, "Competes With"),
+                raw(&b, "n00000000", "depends on"),
+                raw(&b, &b, "enables"),
+                raw(&b, &a, "depends on"),
+            ],
+        );
+
+        assert_eq!(count, 2);
+        assert_eq!(narratives[0].relationships.len(), 1);
+        assert_eq!(narratives[0].relationships[0].kind, RelationKind::Enables);
+        assert_eq!(narratives[0].relationships[0].target, b);
+        assert_eq!(narratives[1].relationships[0].kind, RelationKind::DependsOn);
+    }
+}
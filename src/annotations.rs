@@ -0,0 +1,147 @@
+use crate::error::{Error, Result};
+use crate::types::Signal;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{debug, info};
+
+/// One analyst annotation, keyed by signal ID in `annotations.toml`:
+///
+/// ```toml
+/// [s1f0c9a27]
+/// drop = true
+///
+/// [s8b04e611]
+/// quality = 1.0
+/// note = "Confirmed with the team: mainnet launch next week"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Annotation {
+    /// Remove the signal before aggregation.
+    #[serde(default)]
+    pub drop: bool,
+    /// Replaces the source's baseline quality, 0.0-1.0.
+    pub quality: Option<f64>,
+    /// Context passed to the LLM alongside the signal.
+    pub note: Option<String>,
+}
+
+/// Annotations by signal ID.
+pub type Annotations = BTreeMap<String, Annotation>;
+
+/// Read annotations from `path`. A missing file is no annotations; a file
+/// that doesn't parse, or a quality outside 0.0-1.0, is an error.
+pub fn load(path: &Path) -> Result<Annotations> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!(path = %path.display(), "no annotations file");
+            return Ok(Annotations::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let annotations: Annotations = toml::from_str(&content)
+        .map_err(|e| Error::config(format!("annotations {}: {e}", path.display())))?;
+    for (id, annotation) in &annotations {
+        if let Some(q) = annotation.quality
+            && !(0.0..=1.0).contains(&q)
+        {
+            return Err(Error::config(format!(
+                "annotations {}: quality for {id} must be between 0.0 and 1.0, got {q}",
+                path.display()
+            )));
+        }
+    }
+    Ok(annotations)
+}
+
+/// Apply annotations to signals, logging each one that takes effect.
+/// Returns how many signals were annotated (dropped ones included).
+pub fn apply(signals: &mut Vec<Signal>, annotations: &Annotations) -> usize {
+    if annotations.is_empty() {
+        return 0;
+    }
+    let mut applied = 0;
+    signals.retain_mut(|signal| {
+        let Some(annotation) = annotations.get(&signal.id) else {
+            return true;
+        };
+        applied += 1;
+        if annotation.drop {
+            info!(id = %signal.id, title = %signal.title, "annotation: dropped signal");
+            return false;
+        }
+        if let Some(quality) = annotation.quality {
+            info!(
+                id = %signal.id,
+                title = %signal.title,
+                from = signal.quality(),
+                to = quality,
+                "annotation: quality overridden"
+            );
+            signal.quality = Some(quality);
+        }
+        if let Some(note) = &annotation.note {
+            info!(id = %signal.id, title = %signal.title, note = %note, "annotation: note added");
+            signal.note = Some(note.clone());
+        }
+        true
+    });
+    for id in annotations.keys() {
+        if !signals.iter().any(|s| &s.id == id) {
+            debug!(id = %id, "annotation matched no signal this run");
+        }
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::types::SignalSource;
+
+    #[test]
+    fn annotations_drop_reweight_and_note() {
+        let signal = |title: &str| {
+            Signal::new(
+                SignalSource::Social,
+                "DeFi".into(),
+                title.into(),
+                String::new(),
+                Vec::new(),
+                None,
+            )
+        };
+        let mut signals = vec![
+            signal("Spam listicle"),
+            signal("Launch post"),
+            signal("Other"),
+        ];
+        let (spam, launch) = (signals[0].id.clone(), signals[1].id.clone());
+        let annotations: Annotations = toml::from_str(&format!(
+            "[{spam}]\ndrop = true\n\n[{launch}]\nquality = 0.95\nnote = \"Confirmed launch\"\n\n[s00000000]\ndrop = true\n"
+        ))
+        .unwrap();
+
+        assert_eq!(apply(&mut signals, &annotations), 2);
+        assert_eq!(signals.len(), 2);
+        assert_eq!(signals[0].title, "Launch post");
+        assert_eq!(signals[0].quality(), 0.95);
+        assert_eq!(signals[0].note.as_deref(), Some("Confirmed launch"));
+        assert_eq!(signals[1].quality(), SignalSource::Social.quality());
+    }
+
+    #[test]
+    fn bad_quality_and_unknown_keys_are_rejected() {
+        let dir = TempDir::new("annotations");
+        let path = dir.join("annotations.toml");
+        assert!(load(&path).unwrap().is_empty(), "missing file is fine");
+
+        std::fs::write(&path, "[s1]\nquality = 1.5\n").unwrap();
+        assert!(load(&path).unwrap_err().to_string().contains("quality"));
+        std::fs::write(&path, "[s1]\ndorp = true\n").unwrap();
+        assert!(load(&path).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use crate::analysis;
+use crate::config::{Config, LlmConfig};
+use crate::error::{Error, Result};
+use crate::llm::{LlmClient, Provider, Usage};
+use crate::types::Narrative;
+use futures_util::future::join_all;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+/// Titles listed per provider in the comparison table.
+const TITLES_SHOWN: usize = 5;
+
+/// One provider to compare, written `provider` or `provider:model`, with an
+/// optional `@base_url` for a provider served from its own endpoint.
+#[derive(Debug, Clone)]
+pub struct ModelSpec {
+    pub label: String,
+    pub provider: Provider,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl std::str::FromStr for ModelSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (spec, base_url) = match s.split_once('@') {
+            Some((spec, url)) if !url.trim().is_empty() => (spec, Some(url.trim().to_string())),
+            Some((spec, _)) => (spec, None),
+            None => (s, None),
+        };
+        let (name, model) = match spec.split_once(':') {
+            Some((name, model)) if !model.trim().is_empty() => (name, Some(model.trim().into())),
+            Some((name, _)) => (name, None),
+            None => (spec, None),
+        };
+        let provider = match name.trim().to_lowercase().as_str() {
+            "anthropic" => Provider::Anthropic,
+            "openrouter" => Provider::OpenRouter,
+            "openai" => Provider::OpenAi,
+            other => {
+                return Err(format!(
+                    "unknown provider '{other}' in '{s}' (expected anthropic, openrouter or openai, optionally :model and @base_url)"
+                ));
+            }
+        };
+        Ok(Self {
+            label: s.trim().to_string(),
+            provider,
+            model,
+            base_url,
+        })
+    }
+}
+
+impl ModelSpec {
+    /// The configured LLM settings with this provider and model swapped in.
+    /// Key, endpoint and price overrides only carry over when the provider is
+    /// the configured one; otherwise the provider's defaults apply. A spec's
+    /// own base URL always wins.
+    fn llm_config(&self, base: &LlmConfig) -> LlmConfig {
+        let mut llm = base.clone();
+        // Each row should be the named model's own answer.
+        llm.fallback = None;
+        if std::mem::discriminant(&self.provider) != std::mem::discriminant(&base.provider) {
+            llm.api_key_env = None;
+            llm.api_key_envs.clear();
+            llm.base_url = None;
+            llm.input_price_per_mtok = None;
+            llm.output_price_per_mtok = None;
+        }
+        llm.provider = self.provider.clone();
+        if let Some(model) = &self.model {
+            llm.model = model.clone();
+        }
+        if let Some(base_url) = &self.base_url {
+            llm.base_url = Some(base_url.clone());
+        }
+        llm
+    }
+}
+
+/// What one provider made of the shared signals.
+pub struct ProviderRun {
+    pub label: String,
+    pub model: String,
+    pub narratives: Result<Vec<Narrative>>,
+    pub usage: Usage,
+    pub cost_usd: Option<f64>,
+    pub elapsed: Duration,
+}
+
+/// Run narrative synthesis on the same signal JSON once per provider,
+/// concurrently. A provider that fails keeps its row with the error.
+pub async fn run(cfg: &Config, specs: &[ModelSpec], signals_json: &str) -> Vec<ProviderRun> {
+    join_all(specs.iter().map(|spec| {
+        run_one(
+            cfg,
+            spec.label.clone(),
+            spec.llm_config(&cfg.llm),
+            signals_json,
+        )
+    }))
+    .await
+}
+
+async fn run_one(cfg: &Config, label: String, llm: LlmConfig, signals_json: &str) -> ProviderRun {
+    let started = Instant::now();
+    let client = match LlmClient::from_config(&llm, &cfg.http) {
+        Ok(client) => client,
+        Err(e) => {
+            error!(provider = %label, "provider unavailable: {e}");
+            return ProviderRun {
+                label,
+                model: llm.model,
+                narratives: Err(e),
+                usage: Usage::default(),
+                cost_usd: None,
+                elapsed: started.elapsed(),
+            };
+        }
+    };
+    let narratives = analysis::synthesizer::identify_narratives(
+        &client,
+        signals_json,
+        llm.input_token_budget,
+        cfg.analysis.persona,
+    )
+    .await;
+    match &narratives {
+        Ok(n) => info!(provider = %label, count = n.len(), "narratives identified"),
+        Err(e) => error!(provider = %label, "narrative synthesis failed: {e}"),
+    }
+    ProviderRun {
+        label,
+        model: llm.model,
+        narratives,
+        usage: client.usage(),
+        cost_usd: client.estimated_cost_usd(),
+        elapsed: started.elapsed(),
+    }
+}
+
+/// Side-by-side plain-text summary: one block per provider with counts,
+/// confidence spread, tokens, cost and the top narrative titles.
+pub fn table(runs: &[ProviderRun]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<28} {:>10} {:>13} {:>10} {:>10} {:>9} {:>7}",
+        "provider", "narratives", "confidence", "in tok", "out tok", "cost", "time"
+    );
+    for run in runs {
+        let tokens = (run.usage.input_tokens, run.usage.output_tokens);
+        let cost = run
+            .cost_usd
+            .map_or_else(|| "n/a".to_string(), |c| format!("${c:.4}"));
+        let time = format!("{:.1}s", run.elapsed.as_secs_f64());
+        match &run.narratives {
+            Ok(narratives) => {
+                let _ = writeln!(
+                    out,
+                    "{:<28} {:>10} {:>13} {:>10} {:>10} {:>9} {:>7}",
+                    run.label,
+                    narratives.len(),
+                    confidence_spread(narratives),
+                    tokens.0,
+                    tokens.1,
+                    cost,
+                    time
+                );
+                for n in narratives.iter().take(TITLES_SHOWN) {
+                    let _ = writeln!(
+                        out,
+                        "    {:>3}%  {}",
+                        (n.confidence() * 100.0).round() as u32,
+                        n.title
+                    );
+                }
+                if narratives.len() > TITLES_SHOWN {
+                    let _ = writeln!(out, "          … {} more", narratives.len() - TITLES_SHOWN);
+                }
+            }
+            Err(e) => {
+                let _ = writeln!(
+                    out,
+                    "{:<28} {:>10} {:>13} {:>10} {:>10} {:>9} {:>7}",
+                    run.label, "failed", "-", tokens.0, tokens.1, cost, time
+                );
+                let _ = writeln!(out, "    {e}");
+            }
+        }
+        let _ = writeln!(out, "    model: {}", run.model);
+    }
+    out
+}
+
+/// "min–max%" of the narratives' confidence, "-" when there are none.
+fn confidence_spread(narratives: &[Narrative]) -> String {
+    let pct = |c: f64| (c * 100.0).round() as u32;
+    let min = narratives
+        .iter()
+        .map(Narrative::confidence)
+        .reduce(f64::min);
+    let max = narratives
+        .iter()
+        .map(Narrative::confidence)
+        .reduce(f64::max);
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{}–{}%", pct(min), pct(max)),
+        _ => "-".into(),
+    }
+}
+
+/// Parse `--provider` values, rejecting an empty list.
+pub fn parse_specs(values: &[String]) -> Result<Vec<ModelSpec>> {
+    if values.is_empty() {
+        return Err(Error::config(
+            "compare needs at least one --provider, e.g. --provider anthropic:claude-sonnet-4-5",
+        ));
+    }
+    values
+        .iter()
+        .map(|v| v.parse().map_err(Error::config))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_parse_provider_and_optional_model() {
+        let spec: ModelSpec = "openrouter:anthropic/claude-sonnet-4.5".parse().unwrap();
+        assert!(matches!(spec.provider, Provider::OpenRouter));
+        assert_eq!(spec.model.as_deref(), Some("anthropic/claude-sonnet-4.5"));
+
+        let spec: ModelSpec = "openai".parse().unwrap();
+        assert!(matches!(spec.provider, Provider::OpenAi));
+        assert_eq!(spec.model, None);
+
+        let spec: ModelSpec = "openai:qwen2.5:14b@http://gpu-box:11434/v1"
+            .parse()
+            .unwrap();
+        assert_eq!(spec.model.as_deref(), Some("qwen2.5:14b"));
+        assert_eq!(spec.base_url.as_deref(), Some("http://gpu-box:11434/v1"));
+
+        assert!("gemini:pro".parse::<ModelSpec>().is_err());
+        assert!(parse_specs(&[]).is_err());
+    }
+
+    #[test]
+    fn endpoint_follows_the_provider_unless_the_spec_names_one() {
+        let base: LlmConfig = toml::from_str(
+            "provider = \"openai\"\nmodel = \"llama3.1\"\nbase_url = \"https://api.together.xyz/v1\"\n",
+        )
+        .unwrap();
+        let llm = |spec: &str| spec.parse::<ModelSpec>().unwrap().llm_config(&base);
+
+        assert_eq!(
+            llm("openai:qwen2.5").base_url.as_deref(),
+            Some("https://api.together.xyz/v1"),
+            "same provider keeps its endpoint"
+        );
+        assert_eq!(llm("anthropic").base_url, None);
+        assert_eq!(
+            llm("anthropic@https://gateway.internal/anthropic")
+                .base_url
+                .as_deref(),
+            Some("https://gateway.internal/anthropic")
+        );
+    }
+
+    #[test]
+    fn failed_provider_keeps_its_row() {
+        let narrative: Narrative = serde_json::from_value(serde_json::json!({
+            "id": "n1",
+            "title": "Restaking",
+            "summary": "",
+            "confidence": 0.8,
+            "trend": "Emerging",
+            "supporting_signals": [],
+            "key_metrics": []
+        }))
+        .unwrap();
+        let runs = [
+            ProviderRun {
+                label: "anthropic".into(),
+                model: "claude-sonnet-4-5".into(),
+                narratives: Ok(vec![narrative]),
+                usage: Usage {
+                    calls: 1,
+                    input_tokens: 1200,
+                    output_tokens: 300,
+                },
+                cost_usd: Some(0.0081),
+                elapsed: Duration::from_millis(2500),
+            },
+            ProviderRun {
+                label: "openai:llama3.1".into(),
+                model: "llama3.1".into(),
+                narratives: Err(Error::http("connection refused")),
+                usage: Usage::default(),
+                cost_usd: None,
+                elapsed: Duration::ZERO,
+            },
+        ];
+        let table = table(&runs);
+        assert!(table.contains("80–80%"), "{table}");
+        assert!(table.contains("Restaking"));
+        assert!(table.contains("$0.0081"));
+        assert!(table.contains("failed"));
+        assert!(table.contains("connection refused"));
+    }
+}
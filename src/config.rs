@@ -1,8 +1,13 @@
 use crate::error::{Error, Result};
-use serde::Deserialize;
-use std::path::Path;
+use crate::types::TrendDirection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Config {
     pub github: GitHubConfig,
     pub solana: SolanaConfig,
@@ -10,14 +15,42 @@ pub struct Config {
     pub social: SocialConfig,
     #[serde(default)]
     pub defi_llama: DefiLlamaConfig,
+    #[serde(default)]
+    pub nft: NftConfig,
+    #[serde(default)]
+    pub dune: DuneConfig,
+    #[serde(default)]
+    pub farcaster: FarcasterConfig,
+    #[serde(default)]
+    pub telegram: TelegramConfig,
     pub llm: LlmConfig,
     #[serde(default)]
+    pub synthesis: SynthesisConfig,
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+    #[serde(default)]
     pub output: OutputConfig,
+    #[serde(default)]
+    pub report: ReportConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub email: EmailConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    #[serde(default)]
+    pub listen: ListenConfig,
+    #[serde(default)]
+    pub external: ExternalConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct GitHubConfig {
-    #[serde(default = "default_github_token")]
+    #[serde(default = "default_github_token", skip_serializing)]
     pub token: String,
     #[serde(default = "default_topics")]
     pub topics: Vec<String>,
@@ -27,52 +60,237 @@ pub struct GitHubConfig {
     pub lookback_days: u32,
     #[serde(default = "default_max_repos")]
     pub max_repos: u32,
+    /// Cap on 100-result pages read per topic search to reach `max_repos`.
+    #[serde(default = "default_max_search_pages")]
+    pub max_search_pages: u32,
+    /// Established repos ("owner/name") whose star velocity is tracked.
+    #[serde(default = "default_tracked_repos")]
+    pub tracked_repos: Vec<String>,
+    /// Repos ("owner/name") whose issue and discussion activity is tracked
+    /// as a developer-mindshare signal.
+    #[serde(default)]
+    pub flagship_repos: Vec<String>,
+    /// Also search for young Solana repos gaining stars fastest.
+    #[serde(default)]
+    pub trending: bool,
+    /// How recently a repo must have been created to count as trending.
+    #[serde(default = "default_trending_days")]
+    pub trending_days: u32,
+    /// Longest a call waits for a spent rate budget to refill. A budget
+    /// that resets later ends that kind of call for the run.
+    #[serde(default = "default_github_max_rate_wait_secs")]
+    pub max_rate_wait_secs: u64,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SolanaConfig {
     #[serde(default = "default_rpc_url")]
     pub rpc_url: String,
     #[serde(default = "default_programs")]
     pub tracked_programs: Vec<TrackedProgram>,
+    /// SPL token mints whose 24h transfer volume and unique senders are
+    /// measured. Opt-in: each costs one RPC call per sampled transaction.
+    #[serde(default)]
+    pub tracked_mints: Vec<TrackedMint>,
+    /// AMM pools whose TVL is read from their vault balances, RPC only.
+    #[serde(default)]
+    pub tracked_pools: Vec<TrackedPool>,
+    /// Gap between a pool's onchain TVL and DeFiLlama's, in percent of
+    /// DeFiLlama's, above which the pool is flagged.
+    #[serde(default = "default_pool_tvl_tolerance_pct")]
+    pub pool_tvl_tolerance_pct: f64,
+    /// Maximum number of RPC requests in flight at once.
+    #[serde(default = "default_rpc_concurrency")]
+    pub max_concurrency: usize,
+    /// Commitment level sent with every RPC call that accepts one.
+    #[serde(default)]
+    pub commitment: Commitment,
+    /// Window over which each tracked program's transactions are counted.
+    #[serde(default = "default_activity_window_hours")]
+    pub activity_window_hours: u32,
+    /// Cap on 1000-signature pages read per program. A busy program that
+    /// fills them before the window boundary gets a lower-bound count.
+    #[serde(default = "default_max_signature_pages")]
+    pub max_signature_pages: usize,
+    /// Count Token-2022 mints and the extensions they enable. Opt-in: two
+    /// `getProgramAccounts` scans of the Token-2022 program.
+    #[serde(default)]
+    pub track_token2022: bool,
+    /// Mints with extensions read in full for the per-extension breakdown.
+    /// Past this, counts are scaled up from an evenly spaced sample.
+    #[serde(default = "default_token2022_sample")]
+    pub token2022_sample: usize,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// How settled the state an RPC node answers from must be.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    /// Latest block the node has seen; may still be rolled back.
+    Processed,
+    /// Voted on by a supermajority of stake.
+    #[default]
+    Confirmed,
+    /// Rooted: 31+ confirmed blocks built on top. Never rolled back.
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Processed => "processed",
+            Self::Confirmed => "confirmed",
+            Self::Finalized => "finalized",
+        }
+    }
+}
+
+impl std::fmt::Display for Commitment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Written as a registry key (`program = "jupiter"`) or an address; name
+/// and category default to the bundled registry's (see `data/programs.toml`).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(try_from = "crate::sources::programs::ProgramEntry")]
 pub struct TrackedProgram {
     pub name: String,
     pub address: String,
     pub category: String,
+    /// Count the program's accounts with `getProgramAccounts`. Expensive, and
+    /// many public RPC nodes disable it, so it is opt-in per program.
+    #[serde(default)]
+    pub count_accounts: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrackedMint {
+    pub name: String,
+    pub mint: String,
+    pub category: String,
+    /// Most recent transactions read per run. Busy mints reach this before
+    /// the 24h window is covered; the signal then states the hours it spans.
+    #[serde(default = "default_mint_max_transactions")]
+    pub max_transactions: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrackedPool {
+    pub name: String,
+    /// The pool's state account, not its LP mint.
+    pub address: String,
+    pub amm: AmmLayout,
+    pub category: String,
+    /// DeFiLlama yields pool ID to cross-check TVL against when the
+    /// DeFiLlama source is enabled.
+    pub defillama_pool: Option<String>,
+}
+
+/// Account layout of a pool's state, which says where its vaults are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmmLayout {
+    /// Raydium AMM v4, constant product.
+    RaydiumV4,
+    /// Orca Whirlpool, concentrated liquidity.
+    OrcaWhirlpool,
 }
 
-#[derive(Debug, Deserialize)]
+impl AmmLayout {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RaydiumV4 => "Raydium AMM v4",
+            Self::OrcaWhirlpool => "Orca Whirlpool",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SocialConfig {
     #[serde(default = "default_sources")]
     pub sources: Vec<SocialSource>,
+    /// Article titles containing any of these (case-insensitive) count as
+    /// Solana-relevant.
+    #[serde(default = "default_relevance_keywords")]
+    pub relevance_keywords: Vec<String>,
+    /// Extra relevance keywords for articles detected in other languages,
+    /// keyed by ISO 639-1 code. Articles in a language with no entry here
+    /// (and not English) are still counted, but flagged.
+    #[serde(default = "default_language_keywords")]
+    pub language_keywords: HashMap<String, Vec<String>>,
+    /// Articles whose titles contain any of these are dropped entirely.
+    #[serde(default)]
+    pub exclude_keywords: Vec<String>,
+    /// Article titles listed in each blog's summary signal.
+    #[serde(default = "default_max_titles")]
+    pub max_titles: usize,
+    /// Blogs scraped at once.
+    #[serde(default = "default_social_concurrency")]
+    pub max_concurrency: usize,
+    /// Extra attempts at a blog after a 5xx, each after a short jittered
+    /// pause. 0 disables them.
+    #[serde(default = "default_scrape_retries")]
+    pub scrape_retries: usize,
+    /// How each blog's `sentiment_score` is measured, if at all.
+    #[serde(default)]
+    pub sentiment: SentimentMode,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SentimentMode {
+    /// No sentiment score.
+    Off,
+    /// Offline keyword classifier over article titles.
+    #[default]
+    Keyword,
+    /// Keyword scores, then replaced by an LLM reading titles and snippets.
+    /// Keyword scores stay if the LLM is unavailable.
+    Llm,
 }
 
 impl Default for SocialConfig {
     fn default() -> Self {
         Self {
             sources: default_sources(),
+            relevance_keywords: default_relevance_keywords(),
+            language_keywords: default_language_keywords(),
+            exclude_keywords: Vec::new(),
+            max_titles: default_max_titles(),
+            max_concurrency: default_social_concurrency(),
+            scrape_retries: default_scrape_retries(),
+            sentiment: SentimentMode::default(),
+            max_signals: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SocialSource {
     pub name: String,
     pub url: String,
+    /// "blog" (scraped), "substack" or "mirror" (read from the feed).
+    /// Substack and Mirror hosts are detected without it.
     #[serde(default = "default_source_type")]
-    #[allow(dead_code)]
     pub source_type: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct DefiLlamaConfig {
     #[serde(default = "default_defi_llama_enabled")]
     pub enabled: bool,
     #[serde(default = "default_top_protocols")]
     pub top_protocols: usize,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
 }
 
 impl Default for DefiLlamaConfig {
@@ -80,11 +298,139 @@ impl Default for DefiLlamaConfig {
         Self {
             enabled: true,
             top_protocols: 10,
+            max_signals: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct NftConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Magic Eden collection symbols, e.g. "mad_lads".
+    #[serde(default)]
+    pub collections: Vec<String>,
+    #[serde(default = "default_nft_base_url")]
+    pub base_url: String,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DuneConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_dune_api_key_env")]
+    pub api_key_env: String,
+    #[serde(default)]
+    pub queries: Vec<DuneQuery>,
+    /// Seconds between execution status checks.
+    #[serde(default = "default_dune_poll_interval")]
+    pub poll_interval_secs: u64,
+    /// Status checks before giving up on an execution.
+    #[serde(default = "default_dune_max_polls")]
+    pub max_polls: u32,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
+}
+
+impl Default for DuneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_key_env: default_dune_api_key_env(),
+            queries: Vec::new(),
+            poll_interval_secs: default_dune_poll_interval(),
+            max_polls: default_dune_max_polls(),
+            max_signals: None,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FarcasterConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Env var holding the Neynar API key.
+    #[serde(default = "default_farcaster_api_key_env")]
+    pub api_key_env: String,
+    #[serde(default = "default_farcaster_base_url")]
+    pub base_url: String,
+    /// Channel IDs, e.g. "solana".
+    #[serde(default = "default_farcaster_channels")]
+    pub channels: Vec<String>,
+    /// Keywords searched across all casts.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Hard cap on pages (100 casts each) fetched per channel or keyword.
+    #[serde(default = "default_farcaster_max_pages")]
+    pub max_pages: u32,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
+}
+
+impl Default for FarcasterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_key_env: default_farcaster_api_key_env(),
+            base_url: default_farcaster_base_url(),
+            channels: default_farcaster_channels(),
+            keywords: Vec::new(),
+            max_pages: default_farcaster_max_pages(),
+            max_signals: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TelegramConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Env var holding the bot token from @BotFather.
+    #[serde(default = "default_telegram_bot_token_env")]
+    pub bot_token_env: String,
+    #[serde(default = "default_telegram_base_url")]
+    pub base_url: String,
+    /// Channels as `@username` or numeric chat ID. The bot must be an admin
+    /// of each one to receive its posts.
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// Hard cap on `getUpdates` pages (100 updates each) read per run.
+    #[serde(default = "default_telegram_max_pages")]
+    pub max_pages: u32,
+    /// Cap on signals this source contributes per run (default: no cap).
+    pub max_signals: Option<usize>,
+}
+
+impl Default for TelegramConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bot_token_env: default_telegram_bot_token_env(),
+            base_url: default_telegram_base_url(),
+            channels: Vec::new(),
+            max_pages: default_telegram_max_pages(),
+            max_signals: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DuneQuery {
+    /// Saved query ID, as in `dune.com/queries/<id>`.
+    pub id: u64,
+    pub name: String,
+    pub category: String,
+    /// Result columns to turn into metrics (default: every numeric column).
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Units by column name, e.g. `{ volume_usd = "USD" }`.
+    #[serde(default)]
+    pub units: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LlmConfig {
     #[serde(default)]
     pub provider: crate::llm::Provider,
@@ -92,19 +438,299 @@ pub struct LlmConfig {
     pub model: String,
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
+    /// Anthropic responses cut off at `max_tokens` are retried with double
+    /// the limit, up to this.
+    #[serde(default = "default_max_tokens_cap")]
+    pub max_tokens_cap: u32,
+    /// Estimated tokens of signal data per synthesis call. Larger inputs are
+    /// split into category batches whose narratives are merged afterwards.
+    #[serde(default = "default_input_token_budget")]
+    pub input_token_budget: usize,
+    /// Name of the env var holding the API key (default per provider).
+    pub api_key_env: Option<String>,
+    /// Env vars holding several keys for the provider, used round-robin.
+    /// Takes precedence over `api_key_env`.
+    #[serde(default)]
+    pub api_key_envs: Vec<String>,
+    /// Seconds a key that got rate limited is skipped before being reused.
+    #[serde(default = "default_key_cooldown_secs")]
+    pub key_cooldown_secs: u64,
+    /// Completions in flight at once against this provider endpoint, across
+    /// every client in the process. Further calls wait for a slot.
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Base URL override (default per provider). Point the `openai` provider at
+    /// a local server, e.g. `http://localhost:11434/v1` (Ollama) or
+    /// `http://localhost:1234/v1` (LM Studio).
+    pub base_url: Option<String>,
+    /// Request `response_format: json_object` from OpenAI-compatible APIs.
+    /// Off by default because many local models reject or ignore it.
+    #[serde(default)]
+    pub json_mode: bool,
+    /// Sampling temperature. Unset leaves the provider default; lower values
+    /// make narratives more stable across runs on the same signals.
+    pub temperature: Option<f32>,
+    /// Sampling seed for best-effort determinism. Honored by OpenAI and most
+    /// OpenAI-compatible servers (Ollama, LM Studio, some OpenRouter routes);
+    /// Anthropic has no equivalent and ignores it.
+    pub seed: Option<u64>,
+    /// Abort further LLM calls in a run once estimated spend would pass this.
+    pub max_run_cost_usd: Option<f64>,
+    /// USD per million input tokens, for models missing from the price table.
+    pub input_price_per_mtok: Option<f64>,
+    /// USD per million output tokens, for models missing from the price table.
+    pub output_price_per_mtok: Option<f64>,
+    /// Write the exact prompt of every LLM call to this directory (`-` for
+    /// stderr), API keys redacted. Also `run --explain-prompt`.
+    pub prompt_dump: Option<String>,
+    /// Secondary provider tried when a call to this one fails.
+    pub fallback: Option<FallbackLlmConfig>,
+}
+
+/// `[llm.fallback]`: where calls go once the primary provider fails. Keys,
+/// endpoint and prices are its own; sampling and token limits are shared.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FallbackLlmConfig {
+    pub provider: crate::llm::Provider,
+    pub model: String,
     /// Name of the env var holding the API key (default per provider).
     pub api_key_env: Option<String>,
     /// Base URL override (default per provider).
     pub base_url: Option<String>,
+    pub input_price_per_mtok: Option<f64>,
+    pub output_price_per_mtok: Option<f64>,
+}
+
+impl LlmConfig {
+    /// Env vars to read API keys from: `api_key_envs`, else `api_key_env`,
+    /// else the provider default.
+    pub fn key_env_vars(&self) -> Vec<String> {
+        if !self.api_key_envs.is_empty() {
+            return self.api_key_envs.clone();
+        }
+        vec![
+            self.api_key_env
+                .clone()
+                .unwrap_or_else(|| self.provider.default_api_key_env().into()),
+        ]
+    }
+
+    /// These settings with `[llm.fallback]` swapped in as the provider,
+    /// `None` when no fallback is configured.
+    pub fn fallback_config(&self) -> Option<LlmConfig> {
+        let fallback = self.fallback.as_ref()?;
+        Some(LlmConfig {
+            provider: fallback.provider.clone(),
+            model: fallback.model.clone(),
+            api_key_env: fallback.api_key_env.clone(),
+            api_key_envs: Vec::new(),
+            base_url: fallback.base_url.clone(),
+            input_price_per_mtok: fallback.input_price_per_mtok,
+            output_price_per_mtok: fallback.output_price_per_mtok,
+            fallback: None,
+            ..self.clone()
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SynthesisConfig {
+    /// How much of each signal is sent to narrative synthesis.
+    #[serde(default)]
+    pub signal_detail: SignalDetail,
+    /// Synthesis passes whose narratives are clustered, keeping those a
+    /// majority of passes found. 1 runs a single pass.
+    #[serde(default = "default_ensemble_runs")]
+    pub ensemble_runs: usize,
+}
+
+impl Default for SynthesisConfig {
+    fn default() -> Self {
+        Self {
+            signal_detail: SignalDetail::default(),
+            ensemble_runs: default_ensemble_runs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalDetail {
+    /// Description, URL, timestamp and every metric.
+    #[default]
+    Full,
+    /// ID, source, title and the top three metrics only.
+    Compact,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AnalysisConfig {
+    /// Make an extra LLM call to find how narratives relate to each other.
+    #[serde(default)]
+    pub relationships: bool,
+    /// Multipliers applied per trend direction when ranking ideas, and when
+    /// ranking narratives if `sort_by_trend` is set.
+    #[serde(default)]
+    pub trend_weights: TrendWeights,
+    /// Order report narratives by confidence times trend weight instead of
+    /// confidence alone. Superseded by `report.sort_by`, which wins if set.
+    #[serde(default)]
+    pub sort_by_trend: bool,
+    /// Distinct signal sources a narrative needs to be kept as-is.
+    #[serde(default = "default_min_source_diversity")]
+    pub min_source_diversity: usize,
+    /// What happens to narratives below `min_source_diversity`.
+    #[serde(default)]
+    pub diversity_policy: DiversityPolicy,
+    /// Generate ideas only for narratives that are new or whose trend or
+    /// confidence shifted since the last stored run; reuse the rest.
+    #[serde(default)]
+    pub ideas_on_change: bool,
+    /// Hours after which a signal counts half toward its category's rank.
+    /// Unset weighs every signal the same.
+    pub recency_half_life_hours: Option<f64>,
+    /// Narratives per idea-generation call; unset sends all in one call.
+    pub idea_batch_size: Option<usize>,
+    /// Idea batches generated at once.
+    #[serde(default = "default_idea_concurrency")]
+    pub idea_concurrency: usize,
+    /// Only keep signals in these categories (empty keeps all). Names are
+    /// normalized, so "defi" and "Decentralized Finance" both mean DeFi.
+    #[serde(default)]
+    pub include_categories: Vec<String>,
+    /// Drop signals in these categories.
+    #[serde(default)]
+    pub exclude_categories: Vec<String>,
+    /// Per-signal drops, quality overrides and notes, keyed by signal ID.
+    /// A missing file means no annotations.
+    #[serde(default = "default_annotations_path")]
+    pub annotations_path: String,
+    /// Smoothing factor (0.0-1.0] per metric name. Each named metric gets a
+    /// `<name>_ema` twin averaged over history; needs `[history]`.
+    #[serde(default)]
+    pub smoothing: HashMap<String, f64>,
+    /// Metric names that carry each category's story, keyed by category.
+    /// They lead their group's metrics, are flagged to the LLM, and stand
+    /// out in the report.
+    #[serde(default = "default_headline_metrics")]
+    pub headline_metrics: HashMap<String, Vec<String>>,
+    /// Fewer signals than this skip LLM analysis: the run renders a
+    /// signals-only report instead of paying for thin narratives. 0 disables.
+    #[serde(default = "default_min_signals")]
+    pub min_signals: usize,
+    /// Lens both LLM stages take on the data, framed by
+    /// `data/personas.toml`.
+    #[serde(default)]
+    pub persona: Persona,
+}
+
+/// Whose point of view the synthesis and ideas prompts take.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Persona {
+    /// The base prompts with no extra framing.
+    #[default]
+    Generic,
+    /// Venture scout: fundable categories forming early.
+    Vc,
+    /// Protocol researcher: structural shifts and their mechanisms.
+    Researcher,
+    /// Trader: near-term catalysts and exposed tokens.
+    Trader,
+    /// Builder: tooling gaps and what to ship next.
+    Builder,
 }
 
-#[derive(Debug, Deserialize)]
+impl Persona {
+    /// Key of this persona's fragments in `data/personas.toml`.
+    pub fn key(self) -> Option<&'static str> {
+        match self {
+            Self::Generic => None,
+            Self::Vc => Some("vc"),
+            Self::Researcher => Some("researcher"),
+            Self::Trader => Some("trader"),
+            Self::Builder => Some("builder"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiversityPolicy {
+    /// Remove the narrative.
+    #[default]
+    Drop,
+    /// Keep it with confidence scaled by sources found / sources required.
+    Downgrade,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            relationships: false,
+            trend_weights: TrendWeights::default(),
+            sort_by_trend: false,
+            min_source_diversity: default_min_source_diversity(),
+            diversity_policy: DiversityPolicy::default(),
+            ideas_on_change: false,
+            recency_half_life_hours: None,
+            idea_batch_size: None,
+            idea_concurrency: default_idea_concurrency(),
+            include_categories: Vec::new(),
+            exclude_categories: Vec::new(),
+            annotations_path: default_annotations_path(),
+            smoothing: HashMap::new(),
+            headline_metrics: default_headline_metrics(),
+            min_signals: default_min_signals(),
+            persona: Persona::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrendWeights {
+    #[serde(default = "default_trend_weight")]
+    pub accelerating: f64,
+    #[serde(default = "default_trend_weight")]
+    pub emerging: f64,
+    #[serde(default = "default_trend_weight")]
+    pub stable: f64,
+    #[serde(default = "default_trend_weight")]
+    pub decelerating: f64,
+}
+
+impl Default for TrendWeights {
+    fn default() -> Self {
+        Self {
+            accelerating: default_trend_weight(),
+            emerging: default_trend_weight(),
+            stable: default_trend_weight(),
+            decelerating: default_trend_weight(),
+        }
+    }
+}
+
+impl TrendWeights {
+    pub fn weight(&self, trend: TrendDirection) -> f64 {
+        match trend {
+            TrendDirection::Accelerating => self.accelerating,
+            TrendDirection::Emerging => self.emerging,
+            TrendDirection::Stable => self.stable,
+            TrendDirection::Decelerating => self.decelerating,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct OutputConfig {
     #[serde(default = "default_output_path")]
     pub path: String,
     #[serde(default = "default_title")]
     #[allow(dead_code)]
     pub title: String,
+    /// HEAD every signal link before rendering and drop the dead ones.
+    #[serde(default)]
+    pub check_links: bool,
 }
 
 impl Default for OutputConfig {
@@ -112,10 +738,415 @@ impl Default for OutputConfig {
         Self {
             path: default_output_path(),
             title: default_title(),
+            check_links: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportConfig {
+    /// Color palette: "dark" (default) or "light".
+    #[serde(default)]
+    pub theme: crate::types::Theme,
+    /// BCP 47 tag choosing thousands/decimal separators, e.g. "de-DE".
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// "UTC" or a fixed offset such as "+02:00" for the generated-at time.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Decimal places per metric name, e.g. `{ tvl_usd = 0 }`. Unlisted
+    /// metrics get a precision chosen by magnitude.
+    #[serde(default)]
+    pub metric_precision: HashMap<String, usize>,
+    /// Narratives below this confidence (0.0-1.0) are left out of the HTML
+    /// report, along with their ideas. JSON exports keep them.
+    #[serde(default)]
+    pub min_confidence: f64,
+    /// Embed the full signals array as JSON, with a button to download it.
+    /// Off by default since it roughly doubles the report's size.
+    #[serde(default)]
+    pub embed_raw_signals: bool,
+    /// A template file rendered at run time in place of the built-in one,
+    /// see `output::template`. Read and checked by `validate`.
+    #[serde(default)]
+    pub template_path: Option<String>,
+    /// Signals older than this, by content timestamp or else collection
+    /// time, are dimmed and marked stale.
+    #[serde(default = "default_stale_after_hours")]
+    pub stale_after_hours: u64,
+    /// Order of narratives, and of the ideas grouped under them. Unset
+    /// follows `analysis.sort_by_trend`; see [`Config::narrative_sort`].
+    #[serde(default)]
+    pub sort_by: Option<NarrativeSort>,
+}
+
+/// Key the report ranks narratives by, highest first. Ties go to
+/// confidence, then trend weight, then supporting signal count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NarrativeSort {
+    #[default]
+    Confidence,
+    /// Confidence times the narrative's `analysis.trend_weights` weight.
+    Weighted,
+    /// Trend weight alone, e.g. every Accelerating narrative first.
+    Trend,
+    /// Number of supporting signals.
+    Signals,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            theme: crate::types::Theme::default(),
+            locale: default_locale(),
+            timezone: default_timezone(),
+            metric_precision: HashMap::new(),
+            min_confidence: 0.0,
+            embed_raw_signals: false,
+            template_path: None,
+            stale_after_hours: default_stale_after_hours(),
+            sort_by: None,
         }
     }
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HistoryConfig {
+    #[serde(default = "default_history_enabled")]
+    pub enabled: bool,
+    /// JSON file holding metric values from previous runs.
+    #[serde(default = "default_history_path")]
+    pub path: String,
+    /// Number of most recent runs used as the baseline for novelty scoring.
+    #[serde(default = "default_history_window")]
+    pub window: usize,
+    /// Full signals, narratives and ideas of the latest run, read by `explain`.
+    #[serde(default = "default_last_run_path")]
+    pub last_run_path: String,
+    /// JSON file tracking narratives across runs for streaks and trajectories.
+    #[serde(default = "default_narratives_path")]
+    pub narratives_path: String,
+    /// Title similarity (0.0-1.0, share of words in common) at which a
+    /// narrative continues one from an earlier run.
+    #[serde(default = "default_narrative_match_threshold")]
+    pub narrative_match_threshold: f64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_history_enabled(),
+            path: default_history_path(),
+            window: default_history_window(),
+            last_run_path: default_last_run_path(),
+            narratives_path: default_narratives_path(),
+            narrative_match_threshold: default_narrative_match_threshold(),
+        }
+    }
+}
+
+/// SOL/USD price used to add USD equivalents of SOL-denominated metrics.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PricingConfig {
+    /// Fixed price. Takes precedence over fetching.
+    pub sol_usd: Option<f64>,
+    /// Fetch the current price from `price_url` (CoinGecko's simple price API).
+    #[serde(default)]
+    pub fetch_sol_price: bool,
+    #[serde(default = "default_sol_price_url")]
+    pub price_url: String,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            sol_usd: None,
+            fetch_sol_price: false,
+            price_url: default_sol_price_url(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AlertsConfig {
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+}
+
+/// Fires a webhook when a collected metric crosses a threshold.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlertRule {
+    /// Metric name, e.g. "avg_tps".
+    pub metric: String,
+    /// Only signals in this category (case-insensitive); any when unset.
+    pub category: Option<String>,
+    pub condition: AlertCondition,
+    pub value: f64,
+    /// Compare against `value` times the metric's history mean instead of
+    /// `value` itself, e.g. `condition = ">"`, `value = 5.0` for "5x normal".
+    #[serde(default)]
+    pub relative: bool,
+    /// URL the alert is POSTed to as JSON.
+    pub webhook: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AlertCondition {
+    #[serde(rename = ">")]
+    Above,
+    #[serde(rename = ">=")]
+    AtLeast,
+    #[serde(rename = "<")]
+    Below,
+    #[serde(rename = "<=")]
+    AtMost,
+}
+
+impl AlertCondition {
+    pub fn holds(self, observed: f64, threshold: f64) -> bool {
+        match self {
+            Self::Above => observed > threshold,
+            Self::AtLeast => observed >= threshold,
+            Self::Below => observed < threshold,
+            Self::AtMost => observed <= threshold,
+        }
+    }
+}
+
+impl std::fmt::Display for AlertCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Above => ">",
+            Self::AtLeast => ">=",
+            Self::Below => "<",
+            Self::AtMost => "<=",
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EmailConfig {
+    pub smtp_host: Option<String>,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    /// Omit for relays that don't require authentication.
+    pub username: Option<String>,
+    /// Name of the env var holding the SMTP password.
+    #[serde(default = "default_smtp_password_env")]
+    pub password_env: String,
+    #[serde(default = "default_email_from")]
+    pub from: String,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            smtp_host: None,
+            smtp_port: default_smtp_port(),
+            security: SmtpSecurity::default(),
+            username: None,
+            password_env: default_smtp_password_env(),
+            from: default_email_from(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExternalConfig {
+    /// NDJSON file of signals from your own sources, one `Signal` per line,
+    /// merged into every collection.
+    pub signals_file: Option<String>,
+    /// Origin given to each merged signal, shown as its source status name.
+    #[serde(default = "default_external_label")]
+    pub label: String,
+}
+
+impl Default for ExternalConfig {
+    fn default() -> Self {
+        Self {
+            signals_file: None,
+            label: default_external_label(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListenConfig {
+    /// Address `listen` binds when `--bind` isn't given.
+    #[serde(default = "default_listen_bind")]
+    pub bind: String,
+    /// Name of the env var holding the shared secret every trigger must
+    /// send in the `X-Trigger-Secret` header.
+    #[serde(default = "default_listen_secret_env")]
+    pub secret_env: String,
+}
+
+impl Default for ListenConfig {
+    fn default() -> Self {
+        Self {
+            bind: default_listen_bind(),
+            secret_env: default_listen_secret_env(),
+        }
+    }
+}
+
+/// How the SMTP connection is secured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (usually port 587).
+    #[default]
+    StartTls,
+    /// TLS from the first byte (usually port 465).
+    Tls,
+    /// Unencrypted; only for local relays.
+    None,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HttpConfig {
+    /// Seconds allowed to establish a connection.
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout: u64,
+    /// Seconds allowed for a whole request, including reading the body.
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout: u64,
+    /// Per-source `request_timeout` overrides, keyed by source name
+    /// (`github`, `solana`, `social`, `defi_llama`, `nft`, `dune`, `farcaster`,
+    /// `telegram`, `llm`). Entries are laid over the defaults, so setting one
+    /// source keeps `llm`'s longer default.
+    #[serde(
+        default = "default_source_timeouts",
+        deserialize_with = "merge_source_timeouts"
+    )]
+    pub timeouts: HashMap<String, u64>,
+    /// Proxy for plain-HTTP requests. When neither this nor `https_proxy` is
+    /// set, the standard `HTTP_PROXY`/`HTTPS_PROXY` env vars apply.
+    pub http_proxy: Option<String>,
+    /// Proxy for HTTPS requests.
+    pub https_proxy: Option<String>,
+    /// Per-source proxy (all schemes), keyed like `timeouts`.
+    #[serde(default)]
+    pub proxies: HashMap<String, String>,
+    /// `User-Agent` sent with every request.
+    #[serde(default = "default_user_agent")]
+    pub user_agent: String,
+    /// Headers sent with every request.
+    #[serde(default)]
+    pub default_headers: HeaderSet,
+    /// Per-source headers, keyed like `timeouts`. They are added to
+    /// `default_headers` and win on a name clash.
+    #[serde(default)]
+    pub headers: HashMap<String, HeaderSet>,
+    /// File keeping fetched pages with their `ETag`/`Last-Modified`, so the
+    /// next run asks for them conditionally. Unset always fetches in full.
+    pub cache_path: Option<String>,
+    /// Pages the cache keeps; past this, the least recently used are dropped.
+    #[serde(default = "default_cache_max_pages")]
+    pub cache_max_pages: usize,
+    /// Retries all requests of a run may spend together. Once spent, later
+    /// requests fail at once rather than hammer endpoints that are down.
+    #[serde(default = "default_retry_budget")]
+    pub retry_budget: usize,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: default_connect_timeout(),
+            request_timeout: default_request_timeout(),
+            timeouts: default_source_timeouts(),
+            http_proxy: None,
+            https_proxy: None,
+            proxies: HashMap::new(),
+            user_agent: default_user_agent(),
+            default_headers: HeaderSet::default(),
+            headers: HashMap::new(),
+            cache_path: None,
+            cache_max_pages: default_cache_max_pages(),
+            retry_budget: default_retry_budget(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Request timeout for `source`, falling back to the global one.
+    pub fn timeout_for(&self, source: &str) -> Duration {
+        Duration::from_secs(
+            self.timeouts
+                .get(source)
+                .copied()
+                .unwrap_or(self.request_timeout),
+        )
+    }
+
+    /// `default_headers` with `source`'s overrides applied.
+    pub fn headers_for(&self, source: &str) -> HeaderSet {
+        let mut merged = self.default_headers.clone();
+        if let Some(overrides) = self.headers.get(source) {
+            for (name, value) in &overrides.0 {
+                merged
+                    .0
+                    .retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+                merged.0.insert(name.clone(), value.clone());
+            }
+        }
+        merged
+    }
+}
+
+/// Header name to value. `Debug` and `Serialize` redact the values of headers
+/// that carry credentials, so a logged or fingerprinted config never leaks
+/// them.
+#[derive(Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct HeaderSet(pub BTreeMap<String, String>);
+
+impl HeaderSet {
+    /// Whether `name` looks like it carries a credential.
+    pub fn is_sensitive(name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        matches!(
+            name.as_str(),
+            "authorization" | "proxy-authorization" | "cookie"
+        ) || ["key", "token", "secret", "auth", "session", "signature"]
+            .iter()
+            .any(|word| name.contains(word))
+    }
+}
+
+impl HeaderSet {
+    /// The headers with credential values replaced by `<redacted>`.
+    fn redacted(&self) -> impl Iterator<Item = (&String, &str)> {
+        self.0.iter().map(|(name, value)| {
+            let shown = if Self::is_sensitive(name) {
+                "<redacted>"
+            } else {
+                value.as_str()
+            };
+            (name, shown)
+        })
+    }
+}
+
+impl fmt::Debug for HeaderSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.redacted()).finish()
+    }
+}
+
+impl Serialize for HeaderSet {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.redacted())
+    }
+}
+
 // Defaults
 fn default_github_token() -> String {
     std::env::var("GITHUB_TOKEN").unwrap_or_default()
@@ -129,28 +1160,68 @@ fn default_min_stars() -> u32 {
 fn default_lookback_days() -> u32 {
     30
 }
+fn default_tracked_repos() -> Vec<String> {
+    vec![
+        "anza-xyz/agave".into(),
+        "solana-foundation/anchor".into(),
+        "jito-foundation/jito-solana".into(),
+    ]
+}
+fn default_trending_days() -> u32 {
+    14
+}
 fn default_max_repos() -> u32 {
     30
 }
+fn default_max_search_pages() -> u32 {
+    5
+}
+fn default_github_max_rate_wait_secs() -> u64 {
+    60
+}
 fn default_rpc_url() -> String {
     std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".into())
 }
-fn default_programs() -> Vec<TrackedProgram> {
+fn default_activity_window_hours() -> u32 {
+    24
+}
+fn default_max_signature_pages() -> usize {
+    10
+}
+fn default_token2022_sample() -> usize {
+    500
+}
+fn default_rpc_concurrency() -> usize {
+    4
+}
+fn default_annotations_path() -> String {
+    "annotations.toml".into()
+}
+fn default_mint_max_transactions() -> usize {
+    200
+}
+fn default_pool_tvl_tolerance_pct() -> f64 {
+    25.0
+}
+pub fn default_programs() -> Vec<TrackedProgram> {
     vec![
         TrackedProgram {
             name: "Raydium AMM".into(),
             address: "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8".into(),
             category: "DeFi".into(),
+            count_accounts: false,
         },
         TrackedProgram {
             name: "Jupiter Aggregator".into(),
             address: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".into(),
             category: "DeFi".into(),
+            count_accounts: false,
         },
         TrackedProgram {
             name: "Marinade Finance".into(),
             address: "MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD".into(),
             category: "Liquid Staking".into(),
+            count_accounts: false,
         },
     ]
 }
@@ -161,6 +1232,71 @@ fn default_sources() -> Vec<SocialSource> {
         source_type: "blog".into(),
     }]
 }
+pub fn default_relevance_keywords() -> Vec<String> {
+    [
+        "solana",
+        "sol",
+        "defi",
+        "depin",
+        "token",
+        "validator",
+        "staking",
+        "nft",
+        "web3",
+        "blockchain",
+        "crypto",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+fn default_language_keywords() -> HashMap<String, Vec<String>> {
+    let list = |words: &[&str]| words.iter().map(|w| w.to_string()).collect();
+    HashMap::from([
+        (
+            "zh".to_string(),
+            list(&[
+                "索拉纳",
+                "去中心化",
+                "质押",
+                "代币",
+                "区块链",
+                "验证者",
+                "加密",
+            ]),
+        ),
+        (
+            "ko".to_string(),
+            list(&[
+                "솔라나",
+                "디파이",
+                "스테이킹",
+                "토큰",
+                "블록체인",
+                "검증인",
+                "크립토",
+            ]),
+        ),
+        (
+            "es".to_string(),
+            list(&[
+                "cripto",
+                "cadena de bloques",
+                "validador",
+                "finanzas descentralizadas",
+            ]),
+        ),
+    ])
+}
+fn default_max_titles() -> usize {
+    10
+}
+fn default_social_concurrency() -> usize {
+    4
+}
+fn default_scrape_retries() -> usize {
+    1
+}
 fn default_source_type() -> String {
     "blog".into()
 }
@@ -170,24 +1306,326 @@ fn default_defi_llama_enabled() -> bool {
 fn default_top_protocols() -> usize {
     10
 }
+fn default_nft_base_url() -> String {
+    "https://api-mainnet.magiceden.dev/v2".into()
+}
+fn default_dune_api_key_env() -> String {
+    "DUNE_API_KEY".into()
+}
+fn default_dune_poll_interval() -> u64 {
+    2
+}
+fn default_dune_max_polls() -> u32 {
+    30
+}
+fn default_min_signals() -> usize {
+    5
+}
+/// Total TPS is mostly validator votes; non-vote TPS is user activity.
+fn default_headline_metrics() -> HashMap<String, Vec<String>> {
+    HashMap::from([(
+        "Network Performance".to_string(),
+        vec!["avg_non_vote_tps".to_string()],
+    )])
+}
+fn default_min_source_diversity() -> usize {
+    2
+}
+fn default_trend_weight() -> f64 {
+    1.0
+}
+fn default_locale() -> String {
+    "en-US".into()
+}
+fn default_timezone() -> String {
+    "UTC".into()
+}
+fn default_farcaster_api_key_env() -> String {
+    "NEYNAR_API_KEY".into()
+}
+fn default_farcaster_base_url() -> String {
+    "https://api.neynar.com/v2/farcaster".into()
+}
+fn default_farcaster_channels() -> Vec<String> {
+    vec!["solana".into()]
+}
+fn default_farcaster_max_pages() -> u32 {
+    5
+}
+fn default_telegram_bot_token_env() -> String {
+    "TELEGRAM_BOT_TOKEN".into()
+}
+fn default_telegram_base_url() -> String {
+    "https://api.telegram.org".into()
+}
+fn default_telegram_max_pages() -> u32 {
+    10
+}
+fn default_key_cooldown_secs() -> u64 {
+    60
+}
+fn default_max_concurrent_requests() -> usize {
+    4
+}
 fn default_model() -> String {
     "arcee-ai/trinity-large-preview:free".into()
 }
 fn default_max_tokens() -> u32 {
     4096
 }
+fn default_sol_price_url() -> String {
+    "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd".into()
+}
+fn default_idea_concurrency() -> usize {
+    3
+}
+fn default_max_tokens_cap() -> u32 {
+    16_384
+}
+fn default_input_token_budget() -> usize {
+    60_000
+}
 fn default_output_path() -> String {
     "report.html".into()
 }
 fn default_title() -> String {
     "SolScout Narrative Report".into()
 }
+fn default_history_enabled() -> bool {
+    true
+}
+fn default_history_path() -> String {
+    "history.json".into()
+}
+fn default_history_window() -> usize {
+    10
+}
+fn default_last_run_path() -> String {
+    "last_run.json".into()
+}
+fn default_narratives_path() -> String {
+    "narrative_history.json".into()
+}
+fn default_narrative_match_threshold() -> f64 {
+    0.5
+}
+fn default_smtp_port() -> u16 {
+    587
+}
+fn default_smtp_password_env() -> String {
+    "SMTP_PASSWORD".into()
+}
+fn default_email_from() -> String {
+    "SolScout <solscout@localhost>".into()
+}
+fn default_external_label() -> String {
+    "external".into()
+}
+fn default_listen_bind() -> String {
+    "127.0.0.1:8787".into()
+}
+fn default_listen_secret_env() -> String {
+    "LISTEN_SECRET".into()
+}
+fn default_connect_timeout() -> u64 {
+    10
+}
+fn default_request_timeout() -> u64 {
+    30
+}
+fn default_stale_after_hours() -> u64 {
+    72
+}
+fn default_ensemble_runs() -> usize {
+    1
+}
+fn default_retry_budget() -> usize {
+    50
+}
+fn default_cache_max_pages() -> usize {
+    2000
+}
+fn default_user_agent() -> String {
+    "st-narrative/0.1.0 (solscout)".into()
+}
+fn default_source_timeouts() -> HashMap<String, u64> {
+    // LLM completions routinely take minutes; everything else should answer fast.
+    HashMap::from([("llm".into(), 180)])
+}
+
+fn merge_source_timeouts<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<HashMap<String, u64>, D::Error> {
+    let mut timeouts = default_source_timeouts();
+    timeouts.extend(HashMap::<String, u64>::deserialize(deserializer)?);
+    Ok(timeouts)
+}
+
+/// Env var naming the config file when `--config` isn't given.
+pub const CONFIG_ENV: &str = "ST_NARRATIVE_CONFIG";
+
+/// Config file read when nothing else names one.
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+/// Env vars an environment-only config takes the LLM provider and model from.
+const PROVIDER_ENV: &str = "ST_NARRATIVE_PROVIDER";
+const MODEL_ENV: &str = "ST_NARRATIVE_MODEL";
+
+/// How long unnamed piped stdin gets to close before it's taken as no
+/// config: a CI step or service can leave stdin open and never write to it.
+const STDIN_WAIT: Duration = Duration::from_secs(2);
+
+/// Where the config comes from, see [`ConfigSource::resolve`].
+#[derive(Debug, PartialEq)]
+pub enum ConfigSource {
+    /// A config file; `-` reads standard input.
+    Path(PathBuf),
+    /// TOML piped to standard input without naming it.
+    Stdin(String),
+    /// No file: defaults plus the environment, see [`Config::from_env`].
+    Env,
+}
+
+impl ConfigSource {
+    /// Pick the config in order of precedence: `flag` (the `--config`
+    /// option), `ST_NARRATIVE_CONFIG`, TOML piped to standard input,
+    /// `config.toml` if it exists, else the environment alone. Empty piped
+    /// input, as from `/dev/null` under cron, counts as none, and so does
+    /// input still open after [`STDIN_WAIT`]. Without `read_stdin` the piped
+    /// input is never looked at.
+    pub fn resolve(flag: Option<PathBuf>, read_stdin: bool) -> Result<Self> {
+        let env = std::env::var_os(CONFIG_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+        let piped = || -> Result<Option<String>> {
+            let stdin = std::io::stdin();
+            if !read_stdin || stdin.is_terminal() {
+                return Ok(None);
+            }
+            tracing::info!("reading config from stdin (pass -c to skip)");
+            read_piped(stdin, STDIN_WAIT)
+        };
+        Self::pick(flag, env, piped, Path::new(DEFAULT_CONFIG_PATH).exists())
+    }
+
+    /// [`resolve`](Self::resolve) with its inputs given. Standard input is
+    /// only read when no path is named.
+    fn pick(
+        flag: Option<PathBuf>,
+        env: Option<PathBuf>,
+        stdin: impl FnOnce() -> Result<Option<String>>,
+        default_exists: bool,
+    ) -> Result<Self> {
+        if let Some(path) = flag.or(env) {
+            return Ok(Self::Path(path));
+        }
+        if let Some(content) = stdin()? {
+            return Ok(Self::Stdin(content));
+        }
+        Ok(if default_exists {
+            Self::Path(DEFAULT_CONFIG_PATH.into())
+        } else {
+            Self::Env
+        })
+    }
+}
+
+/// All of `input` if it closes within `wait`, `None` if it doesn't or holds
+/// only whitespace. A reader left open is abandoned on its thread.
+fn read_piped(mut input: impl Read + Send + 'static, wait: Duration) -> Result<Option<String>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut content = String::new();
+        let _ = tx.send(input.read_to_string(&mut content).map(|_| content));
+    });
+    match rx.recv_timeout(wait) {
+        Ok(content) => Ok(Some(content?).filter(|c| !c.trim().is_empty())),
+        Err(_) => {
+            tracing::warn!(
+                "stdin still open after {}s, ignoring it (pass -c - to wait for it)",
+                wait.as_secs()
+            );
+            Ok(None)
+        }
+    }
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) if path == Path::new("-") => f.write_str("stdin"),
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Stdin(_) => f.write_str("stdin"),
+            Self::Env => f.write_str("environment (no config file)"),
+        }
+    }
+}
 
 impl Config {
+    /// Read the config file at `path`, or standard input for `-`.
     pub fn load(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| Error::config(format!("Failed to read config {}: {e}", path.display())))?;
-        toml::from_str(&content).map_err(|e| Error::config(format!("Failed to parse config: {e}")))
+        let content = if path == Path::new("-") {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|e| Error::config(format!("Failed to read config from stdin: {e}")))?;
+            content
+        } else {
+            std::fs::read_to_string(path).map_err(|e| {
+                Error::config(format!("Failed to read config {}: {e}", path.display()))
+            })?
+        };
+        Self::parse(&content)
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Self> {
+        match source {
+            ConfigSource::Path(path) => Self::load(path),
+            ConfigSource::Stdin(content) => Self::parse(content),
+            ConfigSource::Env => Self::from_env(),
+        }
+    }
+
+    /// A config for runs without a file: `ST_NARRATIVE_PROVIDER` and
+    /// `ST_NARRATIVE_MODEL` pick the LLM, and everything else takes its
+    /// default, including the ones read from `SOLANA_RPC_URL` and
+    /// `GITHUB_TOKEN`.
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut llm = toml::Table::new();
+        for (key, name) in [("provider", PROVIDER_ENV), ("model", MODEL_ENV)] {
+            if let Some(value) = var(name).filter(|v| !v.is_empty()) {
+                llm.insert(key.into(), value.into());
+            }
+        }
+        let mut table = toml::Table::new();
+        table.insert("github".into(), toml::Table::new().into());
+        table.insert("solana".into(), toml::Table::new().into());
+        table.insert("llm".into(), llm.into());
+        Self::parse(&table.to_string())
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let mut cfg: Self = toml::from_str(content)
+            .map_err(|e| Error::config(format!("Failed to parse config: {e}")))?;
+        // Here rather than in `validate`, so commands that skip validation
+        // still never fetch a program twice or a pool by a padded address.
+        cfg.dedup_tracked_programs()?;
+        cfg.trim_tracked_pools()?;
+        Ok(cfg)
+    }
+
+    /// 16 hex digits identifying the effective settings: every value after
+    /// defaults, environment and command-line overrides are applied, so a
+    /// default spelled out in the file, a comment or a reordered key doesn't
+    /// change it. The GitHub token and credential headers are left out.
+    pub fn fingerprint(&self) -> String {
+        // Through `Value`, whose maps are sorted, so `HashMap` order is moot.
+        let settings = serde_json::to_value(self).expect("config serializes to JSON");
+        format!("{:016x}", crate::types::fnv1a(&settings.to_string()))
     }
 
     pub fn validate(&self) -> Result<()> {
@@ -196,7 +1634,289 @@ impl Config {
                 "GITHUB_TOKEN not set. Export it or set github.token in config.toml",
             ));
         }
+        if !(0.0..=f64::MAX).contains(&self.solana.pool_tvl_tolerance_pct) {
+            return Err(Error::config(format!(
+                "solana.pool_tvl_tolerance_pct must be 0 or more, got {}",
+                self.solana.pool_tvl_tolerance_pct
+            )));
+        }
+        crate::output::locale::Localization::from_config(&self.report)?;
+        if let Some(path) = &self.report.template_path {
+            crate::output::template::CustomTemplate::load(Path::new(path))?;
+        }
+        if !(0.0..=1.0).contains(&self.report.min_confidence) {
+            return Err(Error::config(format!(
+                "report.min_confidence must be between 0.0 and 1.0, got {}",
+                self.report.min_confidence
+            )));
+        }
+        if self.synthesis.ensemble_runs == 0 {
+            return Err(Error::config("synthesis.ensemble_runs must be at least 1"));
+        }
+        if !(0.0..=1.0).contains(&self.history.narrative_match_threshold) {
+            return Err(Error::config(format!(
+                "history.narrative_match_threshold must be between 0.0 and 1.0, got {}",
+                self.history.narrative_match_threshold
+            )));
+        }
+        if let Some((name, alpha)) = self
+            .analysis
+            .smoothing
+            .iter()
+            .find(|(_, alpha)| !(**alpha > 0.0 && **alpha <= 1.0))
+        {
+            return Err(Error::config(format!(
+                "analysis.smoothing.{name} must be above 0.0 and at most 1.0, got {alpha}"
+            )));
+        }
         // LLM API key is validated at call time — not all providers require one
         Ok(())
     }
+
+    /// `report.sort_by`, or when unset `weighted` if the older
+    /// `analysis.sort_by_trend` is on, else `confidence`.
+    pub fn narrative_sort(&self) -> NarrativeSort {
+        self.report
+            .sort_by
+            .unwrap_or(if self.analysis.sort_by_trend {
+                NarrativeSort::Weighted
+            } else {
+                NarrativeSort::Confidence
+            })
+    }
+
+    /// Trim each tracked program's address and reject any that isn't a
+    /// base58 public key, so a bad paste fails on load rather than mid-run.
+    /// A program listed again, under any name, is dropped with a warning;
+    /// otherwise it would be fetched and counted twice.
+    fn dedup_tracked_programs(&mut self) -> Result<()> {
+        let mut seen: HashMap<String, String> = HashMap::new();
+        let mut kept = Vec::with_capacity(self.solana.tracked_programs.len());
+        for mut program in std::mem::take(&mut self.solana.tracked_programs) {
+            let address = program.address.trim();
+            if !crate::util::is_pubkey(address) {
+                return Err(Error::config(format!(
+                    "solana.tracked_programs: '{}' has malformed address {:?} (expected 32-44 base58 characters)",
+                    program.name, program.address
+                )));
+            }
+            program.address = address.to_string();
+            if let Some(first) = seen.get(&program.address) {
+                tracing::warn!(
+                    address = %program.address,
+                    kept = %first,
+                    dropped = %program.name,
+                    "program tracked twice; keeping the first entry"
+                );
+                continue;
+            }
+            seen.insert(program.address.clone(), program.name.clone());
+            kept.push(program);
+        }
+        self.solana.tracked_programs = kept;
+        Ok(())
+    }
+
+    /// Trim each tracked pool's address and reject any that isn't a base58
+    /// public key.
+    fn trim_tracked_pools(&mut self) -> Result<()> {
+        for pool in &mut self.solana.tracked_pools {
+            pool.address = pool.address.trim().to_string();
+            if !crate::util::is_pubkey(&pool.address) {
+                return Err(Error::config(format!(
+                    "solana.tracked_pools: '{}' has malformed address {:?} (expected 32-44 base58 characters)",
+                    pool.name, pool.address
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(programs: &str) -> Result<Config> {
+        Config::parse(&format!(
+            "[github]\ntoken = \"ghp_test\"\n[solana]\ntracked_programs = [{programs}]\n[llm]\n"
+        ))
+    }
+
+    #[test]
+    fn tracked_programs_dedupe_by_trimmed_address() {
+        let cfg = config(
+            r#"{ program = "jupiter" },
+            { name = "Jup", category = "DeFi", address = " JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 " },
+            { program = "orca" }"#,
+        )
+        .unwrap();
+        let kept: Vec<&str> = cfg
+            .solana
+            .tracked_programs
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(kept, ["Jupiter Aggregator v6", "Orca Whirlpools"]);
+    }
+
+    #[test]
+    fn malformed_program_addresses_are_rejected() {
+        for address in [
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4x0",
+            "JUP6LkbZbjS1 jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4",
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaVO",
+            "short",
+        ] {
+            let err = config(&format!(
+                r#"{{ name = "Bad", category = "DeFi", address = "{address}" }}"#
+            ))
+            .unwrap_err()
+            .to_string();
+            assert!(err.contains("malformed address"), "{address}: {err}");
+        }
+    }
+
+    #[test]
+    fn pool_addresses_are_trimmed_on_load() {
+        let pools = |address: &str| {
+            Config::parse(&format!(
+                "[github]\ntoken = \"ghp_test\"\n[solana]\n[[solana.tracked_pools]]\nname = \"SOL-USDC\"\naddress = \"{address}\"\namm = \"raydium_v4\"\ncategory = \"DeFi\"\n[llm]\n"
+            ))
+        };
+        let cfg = pools(" 58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2\t").unwrap();
+        assert_eq!(
+            cfg.solana.tracked_pools[0].address,
+            "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"
+        );
+        let err = pools("58oQChx4 yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2").unwrap_err();
+        assert!(err.to_string().contains("SOL-USDC"), "{err}");
+    }
+
+    #[test]
+    fn source_timeouts_are_laid_over_the_defaults() {
+        let cfg: Config = toml::from_str(
+            "[github]\ntoken = \"ghp_test\"\n[solana]\n[llm]\n[http]\nrequest_timeout = 20\n[http.timeouts]\nsocial = 5\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.http.timeout_for("social"), Duration::from_secs(5));
+        assert_eq!(cfg.http.timeout_for("llm"), Duration::from_secs(180));
+        assert_eq!(cfg.http.timeout_for("github"), Duration::from_secs(20));
+
+        let cfg: Config = toml::from_str(
+            "[github]\ntoken = \"ghp_test\"\n[solana]\n[llm]\n[http.timeouts]\nllm = 600\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.http.timeout_for("llm"), Duration::from_secs(600));
+    }
+
+    #[test]
+    fn fingerprint_ignores_layout_but_not_values() {
+        let fingerprint = |content: &str| Config::parse(content).unwrap().fingerprint();
+        let base = fingerprint("[llm]\nmodel = \"a\"\nmax_tokens = 100\n[github]\n[solana]\n");
+        let reordered = fingerprint(
+            "# comment\n[solana]\n[github]\n\n[llm]\nmax_tokens = 100  # inline\nmodel = \"a\"\n",
+        );
+        assert_eq!(base, reordered);
+        assert_eq!(base.len(), 16);
+        let changed = fingerprint("[llm]\nmodel = \"b\"\nmax_tokens = 100\n[github]\n[solana]\n");
+        assert_ne!(base, changed);
+    }
+
+    #[test]
+    fn fingerprint_follows_command_line_overrides() {
+        let content = "[github]\n[solana]\n[llm]\n";
+        let base = Config::parse(content).unwrap().fingerprint();
+        for same in [
+            "[github]\n[solana]\n[llm]\n[analysis]\nideas_on_change = false\n",
+            "[github]\nmin_stars = 5\n[solana]\n[llm]\n",
+            "[github]\ntoken = \"ghp_other\"\n[solana]\n[llm]\n",
+        ] {
+            let cfg = Config::parse(same).unwrap();
+            assert_eq!(
+                cfg.fingerprint(),
+                base,
+                "defaults and the token don't count: {same}"
+            );
+        }
+
+        let overrides: [fn(&mut Config); 7] = [
+            |cfg| cfg.llm.model = "other-model".into(),
+            |cfg| cfg.report.theme = crate::types::Theme::Light,
+            |cfg| cfg.analysis.include_categories.push("defi".into()),
+            |cfg| cfg.analysis.exclude_categories.push("nft".into()),
+            |cfg| cfg.analysis.ideas_on_change = true,
+            |cfg| cfg.output.check_links = true,
+            |cfg| cfg.external.signals_file = Some("extra.jsonl".into()),
+        ];
+        for apply in overrides {
+            let mut cfg = Config::parse(content).unwrap();
+            apply(&mut cfg);
+            assert_ne!(cfg.fingerprint(), base);
+        }
+    }
+
+    #[test]
+    fn config_source_follows_flag_env_stdin_then_default() {
+        let path = |p: &str| Some(PathBuf::from(p));
+        let unread = || -> Result<Option<String>> { panic!("stdin read with a path named") };
+        let piped = || Ok(Some("[llm]\n".to_string()));
+
+        let pick = ConfigSource::pick(path("cli.toml"), path("env.toml"), unread, true).unwrap();
+        assert_eq!(pick, ConfigSource::Path("cli.toml".into()));
+        let pick = ConfigSource::pick(None, path("env.toml"), unread, true).unwrap();
+        assert_eq!(pick, ConfigSource::Path("env.toml".into()));
+        let pick = ConfigSource::pick(None, None, piped, true).unwrap();
+        assert_eq!(pick, ConfigSource::Stdin("[llm]\n".into()));
+        let pick = ConfigSource::pick(None, None, || Ok(None), true).unwrap();
+        assert_eq!(pick, ConfigSource::Path(DEFAULT_CONFIG_PATH.into()));
+        let pick = ConfigSource::pick(None, None, || Ok(None), false).unwrap();
+        assert_eq!(pick, ConfigSource::Env);
+        assert_eq!(ConfigSource::Path("-".into()).to_string(), "stdin");
+    }
+
+    #[test]
+    fn piped_stdin_left_open_counts_as_none() {
+        /// A pipe whose writer stays open until the sender is dropped.
+        struct OpenPipe(std::sync::mpsc::Receiver<()>);
+        impl Read for OpenPipe {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                let _ = self.0.recv();
+                Ok(0)
+            }
+        }
+
+        let wait = Duration::from_millis(50);
+        let (writer, pipe) = std::sync::mpsc::channel();
+        assert_eq!(read_piped(OpenPipe(pipe), wait).unwrap(), None);
+        drop(writer);
+
+        let closed = std::io::Cursor::new("[llm]\n");
+        assert_eq!(read_piped(closed, wait).unwrap(), Some("[llm]\n".into()));
+        let blank = std::io::Cursor::new(" \n");
+        assert_eq!(read_piped(blank, wait).unwrap(), None);
+    }
+
+    #[test]
+    fn environment_alone_makes_a_minimal_config() {
+        let cfg = Config::from_vars(|name| match name {
+            "ST_NARRATIVE_PROVIDER" => Some("openai".into()),
+            "ST_NARRATIVE_MODEL" => Some("gpt-4o-mini".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert!(matches!(cfg.llm.provider, crate::llm::Provider::OpenAi));
+        assert_eq!(cfg.llm.model, "gpt-4o-mini");
+        assert_eq!(cfg.fingerprint().len(), 16);
+
+        let defaults = Config::from_vars(|_| None).unwrap();
+        assert_eq!(defaults.llm.model, default_model());
+        assert!(defaults.defi_llama.enabled);
+
+        let piped = Config::from_source(&ConfigSource::Stdin(
+            "[github]\n[solana]\n[llm]\nmodel = \"m\"\n".into(),
+        ))
+        .unwrap();
+        assert_eq!(piped.llm.model, "m");
+    }
 }
@@ -5,6 +5,9 @@ pub enum Error {
     #[error("HTTP error: {0}")]
     Http(String),
 
+    #[error("Request to {platform} timed out after {after_secs}s")]
+    Timeout { platform: String, after_secs: u64 },
+
     #[error("API error ({platform}): {message}")]
     Api {
         platform: String,
@@ -24,11 +27,22 @@ pub enum Error {
         retry_after_secs: Option<u64>,
     },
 
+    #[error("Retry budget exhausted: {limit} retries spent this run (http.retry_budget)")]
+    RetryBudget { limit: usize },
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("Template error: {0}")]
     Template(String),
+
+    #[error("SMTP error: {0}")]
+    Smtp(String),
+
+    #[error(
+        "LLM budget exceeded: ~${spent_usd:.4} spent, the next call would pass the ${cap_usd:.2} cap (llm.max_run_cost_usd)"
+    )]
+    Budget { spent_usd: f64, cap_usd: f64 },
 }
 
 impl Error {
@@ -66,7 +80,22 @@ impl Error {
 
     #[allow(dead_code)]
     pub fn is_retryable(&self) -> bool {
-        matches!(self, Error::Http(_) | Error::RateLimit { .. })
+        matches!(
+            self,
+            Error::Http(_) | Error::Timeout { .. } | Error::RateLimit { .. }
+        )
+    }
+
+    /// A 5xx from a struggling server, which may pass on a later attempt.
+    /// The HTTP client doesn't retry these itself.
+    pub fn is_server_error(&self) -> bool {
+        matches!(
+            self,
+            Error::Api {
+                status_code: Some(500..=599),
+                ..
+            }
+        )
     }
 }
 
@@ -0,0 +1,90 @@
+use crate::error::Result;
+use crate::types::Signal;
+use std::io::BufRead;
+use std::path::Path;
+use tracing::warn;
+
+/// Read signals from `path`, one JSON `Signal` per line, and mark each with
+/// `label` as its origin. See [`read`].
+pub fn load(path: &Path, label: &str) -> Result<Vec<Signal>> {
+    let file = std::fs::File::open(path)?;
+    read(
+        std::io::BufReader::new(file),
+        label,
+        &path.display().to_string(),
+    )
+}
+
+/// Parse NDJSON signals line by line. A line that isn't valid UTF-8 or a
+/// valid signal is skipped with a warning naming `name` and the line
+/// number; blank lines are ignored. IDs are left for the pipeline to fill in.
+pub fn read(mut reader: impl BufRead, label: &str, name: &str) -> Result<Vec<Signal>> {
+    let mut signals = Vec::new();
+    let mut bytes = Vec::new();
+    for i in 0.. {
+        bytes.clear();
+        if reader.read_until(b'\n', &mut bytes)? == 0 {
+            break;
+        }
+        let Ok(line) = std::str::from_utf8(&bytes) else {
+            warn!(
+                file = name,
+                line = i + 1,
+                "skipped external signal that isn't valid UTF-8"
+            );
+            continue;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut signal: Signal = match serde_json::from_str(line) {
+            Ok(signal) => signal,
+            Err(e) => {
+                warn!(file = name, line = i + 1, error = %e, "skipped malformed external signal");
+                continue;
+            }
+        };
+        if signal.title.trim().is_empty() || signal.category.trim().is_empty() {
+            warn!(
+                file = name,
+                line = i + 1,
+                "skipped external signal without a title or category"
+            );
+            continue;
+        }
+        signal
+            .metrics
+            .retain(|m| m.value.is_finite() && !m.name.is_empty());
+        signal.origin = Some(label.to_string());
+        signals.push(signal);
+    }
+    Ok(signals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SignalSource;
+
+    #[test]
+    fn malformed_lines_are_skipped_and_the_rest_labelled() {
+        let input = r#"{"source":"Dune","category":"DePIN","title":"Helium hotspots up","description":"","metrics":[{"name":"hotspots","value":1200,"unit":"devices"}],"url":null,"timestamp":"2026-10-01T00:00:00Z"}
+not json at all
+
+{"source":"Dune","category":"DePIN","title":"no timestamp","description":"","metrics":[]}
+{"source":"Astrology","category":"DePIN","title":"bad source","description":"","metrics":[],"timestamp":"2026-10-01T00:00:00Z"}
+{"source":"GitHub","category":"","title":"no category","description":"","metrics":[],"timestamp":"2026-10-01T00:00:00Z"}
+{"id":"custom1","source":"Social","category":"DeFi","title":"Desk chatter","description":"OTC flow","metrics":[],"timestamp":"2026-10-02T00:00:00Z","quality":0.4}
+"#;
+        let mut bytes = b"{\"source\":\"Dune\",\"title\":\"latin-1 \xe9t\xe9\"}\n".to_vec();
+        bytes.extend_from_slice(input.as_bytes());
+        let signals = read(bytes.as_slice(), "desk", "test.ndjson").unwrap();
+        assert_eq!(signals.len(), 2, "a non-UTF-8 line doesn't end the file");
+        assert_eq!(signals[0].source, SignalSource::Dune);
+        assert!(signals[0].id.is_empty(), "filled in by the pipeline");
+        assert_eq!(signals[0].metrics[0].value, 1200.0);
+        assert_eq!(signals[1].id, "custom1");
+        assert_eq!(signals[1].quality, Some(0.4));
+        assert!(signals.iter().all(|s| s.origin.as_deref() == Some("desk")));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::analysis::stats::{Band, rolling_stats};
+use crate::error::{Error, Result};
+use crate::types::{Metric, RunMetadata, Signal};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Metric values captured by one pipeline run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub started_at: DateTime<Utc>,
+    pub metrics: BTreeMap<String, f64>,
+    /// Provenance of the run, recorded with its metrics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RunMetadata>,
+}
+
+/// Which runs `prune` keeps in a history store.
+#[derive(Debug, Clone, Copy)]
+pub enum Retention {
+    /// Runs started at or after this time.
+    Since(DateTime<Utc>),
+    /// The most recent runs.
+    Runs(usize),
+}
+
+impl Retention {
+    /// How many leading runs of `starts` (oldest first) fall outside the
+    /// retention.
+    pub fn expired(self, starts: &[DateTime<Utc>]) -> usize {
+        match self {
+            Self::Since(cutoff) => starts.iter().take_while(|&&at| at < cutoff).count(),
+            Self::Runs(keep) => starts.len().saturating_sub(keep),
+        }
+    }
+}
+
+/// File-backed store of past runs, oldest first.
+#[derive(Debug, Default)]
+pub struct History {
+    path: PathBuf,
+    runs: Vec<RunRecord>,
+}
+
+impl History {
+    /// Load the store at `path`. A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self> {
+        let runs = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| Error::parse(format!("history {}: {e}", path.display())))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            runs,
+        })
+    }
+
+    /// Values recorded for `key` in the most recent `window` runs, oldest first.
+    pub fn series(&self, key: &str, window: usize) -> Vec<f64> {
+        let start = self.runs.len().saturating_sub(window);
+        self.runs[start..]
+            .iter()
+            .filter_map(|run| run.metrics.get(key).copied())
+            .collect()
+    }
+
+    /// The most recent value of `key` with the band of the `window` values
+    /// recorded before it. `None` without at least three recorded values.
+    pub fn latest_band(&self, key: &str, window: usize) -> Option<(f64, Band)> {
+        let series = self.series(key, window.saturating_add(1));
+        let band = rolling_stats(&series, window).pop().flatten()?;
+        Some((*series.last()?, band))
+    }
+
+    /// The latest recorded value of `key` from a run started at or before
+    /// `at`, with that run's start time.
+    pub fn value_at(&self, key: &str, at: DateTime<Utc>) -> Option<(DateTime<Utc>, f64)> {
+        self.runs
+            .iter()
+            .rev()
+            .filter(|run| run.started_at <= at)
+            .find_map(|run| run.metrics.get(key).map(|&v| (run.started_at, v)))
+    }
+
+    /// Append the metrics of `signals` as a new run, with its provenance if
+    /// known. The run starts when `metadata` says it did, or now.
+    pub fn record(&mut self, signals: &[Signal], metadata: Option<RunMetadata>) {
+        let metrics = signals
+            .iter()
+            .flat_map(|s| s.metrics.iter().map(move |m| (metric_key(s, m), m.value)))
+            .filter(|(_, v)| v.is_finite())
+            .collect();
+        self.runs.push(RunRecord {
+            started_at: metadata.as_ref().map_or_else(Utc::now, |m| m.started_at),
+            metrics,
+            metadata,
+        });
+    }
+
+    /// Drop the runs outside `retention`. Returns how many were dropped.
+    pub fn prune(&mut self, retention: Retention) -> usize {
+        let starts: Vec<DateTime<Utc>> = self.runs.iter().map(|run| run.started_at).collect();
+        let expired = retention.expired(&starts);
+        self.runs.drain(..expired);
+        expired
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(&self.runs)
+            .map_err(|e| Error::parse(format!("serialize history: {e}")))?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+}
+
+/// Key identifying the same metric across runs. Titles embed live numbers, so
+/// the signal is identified by source, category and URL instead.
+pub fn metric_key(signal: &Signal, metric: &Metric) -> String {
+    format!(
+        "{}|{}|{}|{}",
+        signal.source,
+        signal.category,
+        signal.url.as_deref().unwrap_or(""),
+        metric.name
+    )
+}
@@ -0,0 +1,148 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A page fetched on an earlier run: the validators to revalidate it with
+/// and the body to reuse when the server answers 304 Not Modified.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPage {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+/// Pages keyed by URL, persisted as JSON at `http.cache_path`. Past
+/// `max_pages`, the least recently used page is dropped.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HttpCache {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    max_pages: usize,
+    pages: BTreeMap<String, CachedPage>,
+    /// When each page was last read or stored, on `clock`.
+    #[serde(default)]
+    used: BTreeMap<String, u64>,
+    #[serde(default)]
+    clock: u64,
+}
+
+impl HttpCache {
+    pub fn empty(path: &Path, max_pages: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            max_pages,
+            pages: BTreeMap::new(),
+            used: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Read the cache at `path`; a missing file is an empty cache.
+    pub fn load(path: &Path, max_pages: usize) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::empty(path, max_pages));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut cache: Self = serde_json::from_str(&content)
+            .map_err(|e| Error::parse(format!("HTTP cache {}: {e}", path.display())))?;
+        cache.path = path.to_path_buf();
+        cache.max_pages = max_pages;
+        cache.evict();
+        Ok(cache)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(self)
+            .map_err(|e| Error::parse(format!("serialize HTTP cache: {e}")))?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    pub fn get(&mut self, url: &str) -> Option<&CachedPage> {
+        if self.pages.contains_key(url) {
+            self.touch(url);
+        }
+        self.pages.get(url)
+    }
+
+    /// Remember `page` for `url`. Pages without a validator can't be
+    /// revalidated, so they aren't kept.
+    pub fn insert(&mut self, url: &str, page: CachedPage) {
+        if page.etag.is_some() || page.last_modified.is_some() {
+            self.pages.insert(url.to_string(), page);
+            self.touch(url);
+            self.evict();
+        } else {
+            self.pages.remove(url);
+            self.used.remove(url);
+        }
+    }
+
+    fn touch(&mut self, url: &str) {
+        self.clock += 1;
+        self.used.insert(url.to_string(), self.clock);
+    }
+
+    /// Drop least recently used pages until at most `max_pages` remain.
+    /// Pages from caches written before use was tracked go first.
+    fn evict(&mut self) {
+        let excess = self.pages.len().saturating_sub(self.max_pages);
+        if excess == 0 {
+            return;
+        }
+        let mut by_use: Vec<(u64, String)> = self
+            .pages
+            .keys()
+            .map(|url| (self.used.get(url).copied().unwrap_or(0), url.clone()))
+            .collect();
+        by_use.sort();
+        for (_, url) in by_use.into_iter().take(excess) {
+            self.pages.remove(&url);
+            self.used.remove(&url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(etag: &str) -> CachedPage {
+        CachedPage {
+            etag: Some(etag.into()),
+            last_modified: None,
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn least_recently_used_pages_go_first() {
+        let mut cache = HttpCache::empty(Path::new("unused.json"), 2);
+        cache.insert("a", page("1"));
+        cache.insert("b", page("2"));
+        assert!(cache.get("a").is_some());
+        cache.insert("c", page("3"));
+
+        assert!(cache.get("b").is_none(), "b was used least recently");
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+
+        cache.insert(
+            "c",
+            CachedPage {
+                etag: None,
+                ..page("")
+            },
+        );
+        assert_eq!(cache.pages.len(), 1);
+        assert!(!cache.used.contains_key("c"));
+    }
+}
@@ -0,0 +1,415 @@
+use crate::config::{Config, TrackedProgram, default_programs};
+use crate::llm::Provider;
+use crate::sources::programs;
+use crate::util::is_pubkey;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+/// Everything `init` asks for.
+#[derive(Debug)]
+pub struct Answers {
+    pub provider: Provider,
+    pub model: String,
+    /// Only asked for the OpenAI-compatible provider.
+    pub base_url: Option<String>,
+    pub rpc_url: String,
+    pub programs: Vec<TrackedProgram>,
+    pub blogs: bool,
+    pub defi_llama: bool,
+    pub nft_collections: Vec<String>,
+    pub farcaster_channels: Vec<String>,
+    pub telegram_channels: Vec<String>,
+}
+
+/// Line-based question/answer over any reader and writer, so the wizard can
+/// be scripted in tests.
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Ask until `parse` accepts the answer; an empty answer means `default`.
+    /// Running out of input is an error rather than an endless loop.
+    fn ask<T>(
+        &mut self,
+        question: &str,
+        default: &str,
+        mut parse: impl FnMut(&str) -> Result<T, String>,
+    ) -> io::Result<T> {
+        loop {
+            if default.is_empty() {
+                write!(self.output, "{question}: ")?;
+            } else {
+                write!(self.output, "{question} [{default}]: ")?;
+            }
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "input ended before setup finished",
+                ));
+            }
+            let answer = match line.trim() {
+                "" => default,
+                answer => answer,
+            };
+            match parse(answer) {
+                Ok(value) => return Ok(value),
+                Err(e) => writeln!(self.output, "  {e}")?,
+            }
+        }
+    }
+
+    fn confirm(&mut self, question: &str, default: bool) -> io::Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        self.ask(question, hint, |a| match a.to_lowercase().as_str() {
+            _ if a == hint => Ok(default),
+            "y" | "yes" => Ok(true),
+            "n" | "no" => Ok(false),
+            _ => Err("answer y or n".into()),
+        })
+    }
+
+    fn list(&mut self, question: &str, default: &str) -> io::Result<Vec<String>> {
+        self.ask(question, default, |a| {
+            Ok(a.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect())
+        })
+    }
+
+    fn say(&mut self, text: &str) -> io::Result<()> {
+        writeln!(self.output, "{text}")
+    }
+}
+
+/// Walk through setup. `check_rpc` pings a candidate RPC URL; a failure is
+/// shown and the user decides whether to keep the URL anyway.
+pub fn ask<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    mut check_rpc: impl FnMut(&str) -> Result<(), String>,
+) -> io::Result<Answers> {
+    prompter.say("LLM")?;
+    let provider = prompter.ask(
+        "  Provider (anthropic, openrouter, openai)",
+        "openrouter",
+        parse_provider,
+    )?;
+    let model = prompter.ask("  Model", default_model(&provider), |a| {
+        if a.is_empty() {
+            Err("a model name is required".into())
+        } else {
+            Ok(a.to_string())
+        }
+    })?;
+    let base_url = match provider {
+        Provider::OpenAi => Some(prompter.ask(
+            "  Server URL (Ollama, LM Studio, ...)",
+            "http://localhost:11434/v1",
+            parse_http_url,
+        )?),
+        _ => None,
+    };
+
+    prompter.say("Solana")?;
+    let rpc_url = loop {
+        let url = prompter.ask(
+            "  RPC URL",
+            "https://api.mainnet-beta.solana.com",
+            parse_http_url,
+        )?;
+        match check_rpc(&url) {
+            Ok(()) => {
+                prompter.say("  RPC is healthy")?;
+                break url;
+            }
+            Err(e) => {
+                prompter.say(&format!("  RPC check failed: {e}"))?;
+                if prompter.confirm("  Use it anyway?", false)? {
+                    break url;
+                }
+            }
+        }
+    };
+    let mut programs = if prompter.confirm(
+        "  Track the default programs (Raydium, Jupiter, Marinade)?",
+        true,
+    )? {
+        default_programs()
+    } else {
+        Vec::new()
+    };
+    loop {
+        let address = prompter.ask(
+            "  Add a program address or registry key, e.g. orca (blank to finish)",
+            "",
+            |a| match a {
+                "" => Ok(None),
+                a if is_pubkey(a) || programs::registry().contains_key(a) => {
+                    Ok(Some(a.to_string()))
+                }
+                _ => Err("not a base58 program address or registry key".into()),
+            },
+        )?;
+        let Some(address) = address else { break };
+        let known = programs::registry()
+            .get(&address)
+            .or_else(|| programs::by_address(&address));
+        if let Some(known) = known {
+            prompter.say(&format!("    {} ({})", known.name, known.category))?;
+            programs.push(TrackedProgram {
+                name: known.name.clone(),
+                address: known.address.clone(),
+                category: known.category.clone(),
+                count_accounts: false,
+            });
+            continue;
+        }
+        let name = prompter.ask("    Name", &address[..8], |a| Ok(a.to_string()))?;
+        let category = prompter.ask("    Category", "DeFi", |a| Ok(a.to_string()))?;
+        programs.push(TrackedProgram {
+            name,
+            address,
+            category,
+            count_accounts: false,
+        });
+    }
+
+    prompter.say("Sources")?;
+    let blogs = prompter.confirm("  Scrape the default blogs?", true)?;
+    let defi_llama = prompter.confirm("  DeFiLlama TVL?", true)?;
+    let nft_collections = if prompter.confirm("  Magic Eden NFT collections?", false)? {
+        prompter.list("    Collection symbols, comma-separated", "mad_lads")?
+    } else {
+        Vec::new()
+    };
+    let farcaster_channels = if prompter.confirm("  Farcaster (needs NEYNAR_API_KEY)?", false)? {
+        prompter.list("    Channels, comma-separated", "solana")?
+    } else {
+        Vec::new()
+    };
+    let telegram_channels = if prompter.confirm("  Telegram (needs TELEGRAM_BOT_TOKEN)?", false)? {
+        prompter.list("    Channels, comma-separated (e.g. @solana)", "")?
+    } else {
+        Vec::new()
+    };
+
+    Ok(Answers {
+        provider,
+        model,
+        base_url,
+        rpc_url,
+        programs,
+        blogs,
+        defi_llama,
+        nft_collections,
+        farcaster_channels,
+        telegram_channels,
+    })
+}
+
+fn parse_provider(answer: &str) -> Result<Provider, String> {
+    match answer.to_lowercase().as_str() {
+        "anthropic" => Ok(Provider::Anthropic),
+        "openrouter" => Ok(Provider::OpenRouter),
+        "openai" => Ok(Provider::OpenAi),
+        _ => Err("choose anthropic, openrouter or openai".into()),
+    }
+}
+
+fn default_model(provider: &Provider) -> &'static str {
+    match provider {
+        Provider::Anthropic => "claude-sonnet-4-5",
+        Provider::OpenRouter => "arcee-ai/trinity-large-preview:free",
+        Provider::OpenAi => "qwen2.5:14b",
+    }
+}
+
+fn parse_http_url(answer: &str) -> Result<String, String> {
+    match reqwest::Url::parse(answer) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {
+            Ok(answer.trim_end_matches('/').to_string())
+        }
+        _ => Err("enter an http(s) URL".into()),
+    }
+}
+
+fn quoted(s: &str) -> String {
+    toml::Value::String(s.to_string()).to_string()
+}
+
+fn quoted_list(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|s| quoted(s)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// The config file for `answers`. Every section the answers don't touch is
+/// left to its defaults; see the repository's config.toml for all options.
+pub fn config_toml(answers: &Answers) -> String {
+    let mut out = String::from(
+        "# Generated by `st-narrative init`. See the repository's config.toml for every option.\n\n",
+    );
+    let w = &mut out;
+    let _ = writeln!(w, "[github]\n# token comes from GITHUB_TOKEN env var");
+    let _ = writeln!(w, "topics = [\"solana\"]\n");
+
+    let _ = writeln!(w, "[solana]\nrpc_url = {}\n", quoted(&answers.rpc_url));
+    for p in &answers.programs {
+        let _ = writeln!(
+            w,
+            "[[solana.tracked_programs]]\nname = {}\naddress = {}\ncategory = {}\n",
+            quoted(&p.name),
+            quoted(&p.address),
+            quoted(&p.category)
+        );
+    }
+
+    if !answers.blogs {
+        let _ = writeln!(w, "[social]\nsources = []\n");
+    }
+    let _ = writeln!(w, "[defi_llama]\nenabled = {}\n", answers.defi_llama);
+    let _ = writeln!(
+        w,
+        "[nft]\nenabled = {}\ncollections = {}\n",
+        !answers.nft_collections.is_empty(),
+        quoted_list(&answers.nft_collections)
+    );
+    let _ = writeln!(
+        w,
+        "[farcaster]\nenabled = {}\nchannels = {}\n",
+        !answers.farcaster_channels.is_empty(),
+        quoted_list(&answers.farcaster_channels)
+    );
+    let _ = writeln!(
+        w,
+        "[telegram]\nenabled = {}\nchannels = {}\n",
+        !answers.telegram_channels.is_empty(),
+        quoted_list(&answers.telegram_channels)
+    );
+
+    let provider = match answers.provider {
+        Provider::Anthropic => "anthropic",
+        Provider::OpenRouter => "openrouter",
+        Provider::OpenAi => "openai",
+    };
+    let _ = writeln!(
+        w,
+        "[llm]\nprovider = {}\nmodel = {}",
+        quoted(provider),
+        quoted(&answers.model)
+    );
+    if let Some(base_url) = &answers.base_url {
+        let _ = writeln!(w, "base_url = {}", quoted(base_url));
+    }
+    out
+}
+
+/// `.env.example` listing the variables this config reads.
+pub fn env_example(answers: &Answers) -> String {
+    let mut out = String::from("# Required\nGITHUB_TOKEN=ghp_your_token_here\n");
+    match answers.provider {
+        Provider::Anthropic => out.push_str("ANTHROPIC_API_KEY=sk-ant-your-key-here\n"),
+        Provider::OpenRouter => out.push_str("OPENROUTER_API_KEY=sk-or-your-key-here\n"),
+        Provider::OpenAi => {
+            out.push_str("# Only if the server needs one\n# OPENAI_API_KEY=your-key-here\n")
+        }
+    }
+    if !answers.farcaster_channels.is_empty() {
+        out.push_str("NEYNAR_API_KEY=your-neynar-key-here\n");
+    }
+    if !answers.telegram_channels.is_empty() {
+        out.push_str("TELEGRAM_BOT_TOKEN=123456:your-bot-token\n");
+    }
+    out
+}
+
+/// Check the generated file parses as a config.
+pub fn verify(toml_text: &str) -> Result<Config, String> {
+    toml::from_str(toml_text).map_err(|e| format!("generated config is invalid: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(script: &str, rpc_ok: bool) -> (io::Result<Answers>, String) {
+        let mut output = Vec::new();
+        let answers = ask(&mut Prompter::new(script.as_bytes(), &mut output), |_| {
+            if rpc_ok {
+                Ok(())
+            } else {
+                Err("connection refused".into())
+            }
+        });
+        (answers, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn scripted_setup_writes_a_loadable_config() {
+        let script = [
+            "gemini", // rejected
+            "anthropic",
+            "",          // default model
+            "not a url", // rejected
+            "https://rpc.example.com/",
+            "n", // skip default programs
+            "bad-address",
+            "orca", // registry key, no name or category asked
+            "CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhdefbAnjHG3JR",
+            "Candy Machine",
+            "NFT",
+            "",    // done with programs
+            "y",   // blogs
+            "n",   // no DeFiLlama
+            "n",   // no NFT
+            "yes", // Farcaster
+            "solana, jupiter",
+            "", // no Telegram
+        ]
+        .join("\n")
+            + "\n";
+        let (answers, transcript) = run(&script, true);
+        let answers = answers.unwrap();
+        assert!(transcript.contains("choose anthropic, openrouter or openai"));
+        assert!(transcript.contains("enter an http(s) URL"));
+        assert!(transcript.contains("not a base58 program address"));
+        assert!(transcript.contains("Orca Whirlpools (DeFi)"));
+
+        let config = verify(&config_toml(&answers)).unwrap();
+        assert!(matches!(config.llm.provider, Provider::Anthropic));
+        assert_eq!(config.llm.model, "claude-sonnet-4-5");
+        assert_eq!(config.solana.rpc_url, "https://rpc.example.com");
+        assert_eq!(config.solana.tracked_programs.len(), 2);
+        assert_eq!(
+            config.solana.tracked_programs[0].address,
+            "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"
+        );
+        assert_eq!(config.solana.tracked_programs[1].category, "NFT");
+        assert!(!config.defi_llama.enabled);
+        assert!(!config.nft.enabled);
+        assert!(config.farcaster.enabled);
+        assert_eq!(config.farcaster.channels, ["solana", "jupiter"]);
+        assert!(!config.telegram.enabled);
+
+        let env = env_example(&answers);
+        assert!(env.contains("ANTHROPIC_API_KEY="));
+        assert!(env.contains("NEYNAR_API_KEY="));
+        assert!(!env.contains("TELEGRAM_BOT_TOKEN"));
+    }
+
+    #[test]
+    fn unhealthy_rpc_needs_confirmation_and_eof_aborts() {
+        let (answers, transcript) = run("openrouter\n\n\nn\n", false);
+        assert!(transcript.contains("RPC check failed: connection refused"));
+        let err = answers.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,131 @@
+use whatlang::Lang;
+
+/// Language `relevance_keywords` are written in; other languages need their
+/// own list under `social.language_keywords`.
+pub const BASE_LANGUAGE: &str = "en";
+
+/// Below this confidence, a title's script decides rather than its words:
+/// headlines are short and half jargon, too little for the detector.
+const MIN_CONFIDENCE: f64 = 0.5;
+
+/// Language of `text` as an ISO 639-1 code, or `None` when it has no
+/// letters. When the detector isn't sure, Latin text reads as English,
+/// since crypto jargon fills most titles whatever their language, and
+/// Cyrillic as Russian.
+pub fn detect(text: &str) -> Option<&'static str> {
+    let info = whatlang::detect(text)?;
+    if info.confidence() < MIN_CONFIDENCE {
+        match info.script() {
+            whatlang::Script::Latin => return Some(BASE_LANGUAGE),
+            whatlang::Script::Cyrillic => return Some("ru"),
+            _ => {}
+        }
+    }
+    Some(iso_639_1(info.lang()))
+}
+
+/// The two-letter code `language_keywords` is keyed by.
+fn iso_639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Afr => "af",
+        Lang::Aka => "ak",
+        Lang::Amh => "am",
+        Lang::Ara => "ar",
+        Lang::Aze => "az",
+        Lang::Bel => "be",
+        Lang::Ben => "bn",
+        Lang::Bul => "bg",
+        Lang::Cat => "ca",
+        Lang::Ces => "cs",
+        Lang::Cmn => "zh",
+        Lang::Cym => "cy",
+        Lang::Dan => "da",
+        Lang::Deu => "de",
+        Lang::Ell => "el",
+        Lang::Eng => "en",
+        Lang::Epo => "eo",
+        Lang::Est => "et",
+        Lang::Fin => "fi",
+        Lang::Fra => "fr",
+        Lang::Guj => "gu",
+        Lang::Heb => "he",
+        Lang::Hin => "hi",
+        Lang::Hrv => "hr",
+        Lang::Hun => "hu",
+        Lang::Hye => "hy",
+        Lang::Ind => "id",
+        Lang::Ita => "it",
+        Lang::Jav => "jv",
+        Lang::Jpn => "ja",
+        Lang::Kan => "kn",
+        Lang::Kat => "ka",
+        Lang::Khm => "km",
+        Lang::Kor => "ko",
+        Lang::Lat => "la",
+        Lang::Lav => "lv",
+        Lang::Lit => "lt",
+        Lang::Mal => "ml",
+        Lang::Mar => "mr",
+        Lang::Mkd => "mk",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Nld => "nl",
+        Lang::Nob => "nb",
+        Lang::Ori => "or",
+        Lang::Pan => "pa",
+        Lang::Pes => "fa",
+        Lang::Pol => "pl",
+        Lang::Por => "pt",
+        Lang::Ron => "ro",
+        Lang::Rus => "ru",
+        Lang::Sin => "si",
+        Lang::Slk => "sk",
+        Lang::Slv => "sl",
+        Lang::Sna => "sn",
+        Lang::Spa => "es",
+        Lang::Srp => "sr",
+        Lang::Swe => "sv",
+        Lang::Tam => "ta",
+        Lang::Tel => "te",
+        Lang::Tgl => "tl",
+        Lang::Tha => "th",
+        Lang::Tuk => "tk",
+        Lang::Tur => "tr",
+        Lang::Ukr => "uk",
+        Lang::Urd => "ur",
+        Lang::Uzb => "uz",
+        Lang::Vie => "vi",
+        Lang::Yid => "yi",
+        Lang::Zul => "zu",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_are_told_apart_by_language() {
+        let cases = [
+            (
+                "Firedancer: What the new validator client means for Solana",
+                "en",
+            ),
+            ("Solana 生态系统的下一个增长点在哪里", "zh"),
+            ("솔라나 디파이 생태계의 성장", "ko"),
+            ("ソラナのステーキング入門", "ja"),
+            ("Cómo funciona el staking líquido en Solana", "es"),
+            ("O que são tokens de restaking e como usá-los", "pt"),
+            ("Comment fonctionne la validation sur Solana", "fr"),
+            ("Wie das Solana-Netzwerk mit Überlastung umgeht", "de"),
+            ("Hướng dẫn staking trên Solana cho người mới", "vi"),
+            ("Обзор экосистемы Solana", "ru"),
+            // Jargon alone reads as the base language.
+            ("Solana DePIN Q3 Recap", "en"),
+        ];
+        for (title, expected) in cases {
+            assert_eq!(detect(title), Some(expected), "{title}");
+        }
+        assert_eq!(detect("2026 — 42%"), None);
+    }
+}
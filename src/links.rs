@@ -0,0 +1,155 @@
+use crate::error::Error;
+use crate::http::HttpClient;
+use crate::types::{Signal, SignalSource};
+use futures_util::future::join_all;
+use reqwest::{Method, Url};
+use std::collections::{BTreeSet, HashSet};
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
+
+/// Link checks in flight at once.
+const CHECK_CONCURRENCY: usize = 8;
+
+/// Resolve `href` against the page it was found on. Anchors, `javascript:`
+/// and `mailto:` links have no page behind them and yield `None`.
+pub fn resolve(base: &str, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with('#') {
+        return None;
+    }
+    let url = match Url::parse(href) {
+        Ok(url) => url,
+        Err(_) => Url::parse(base).ok()?.join(href).ok()?,
+    };
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+/// Site that relative links from `source` are relative to, if it has one.
+fn home_url(source: SignalSource) -> Option<&'static str> {
+    match source {
+        SignalSource::GitHub => Some("https://github.com/"),
+        SignalSource::SolanaOnchain => Some("https://explorer.solana.com/"),
+        SignalSource::DeFiLlama => Some("https://defillama.com/"),
+        SignalSource::Nft => Some("https://magiceden.io/"),
+        SignalSource::Dune => Some("https://dune.com/"),
+        SignalSource::Social => None,
+    }
+}
+
+/// Make every signal URL absolute, dropping the ones that can't be.
+pub fn normalize(signals: &mut [Signal]) {
+    for signal in signals.iter_mut() {
+        let Some(url) = signal.url.take() else {
+            continue;
+        };
+        if Url::parse(&url).is_ok_and(|parsed| matches!(parsed.scheme(), "http" | "https")) {
+            signal.url = Some(url);
+            continue;
+        }
+        signal.url = home_url(signal.source).and_then(|home| resolve(home, &url));
+        if signal.url.is_none() {
+            debug!(signal = %signal.title, url = %url, "dropped unresolvable signal URL");
+        }
+    }
+}
+
+/// HEAD every distinct signal URL and remove the ones that are gone. Only
+/// definite failures count as dead: rate limits, auth walls and timeouts
+/// keep the link.
+pub async fn prune_dead(http: &HttpClient, signals: &mut [Signal]) -> usize {
+    let urls: BTreeSet<String> = signals.iter().filter_map(|s| s.url.clone()).collect();
+    let permits = Semaphore::new(CHECK_CONCURRENCY);
+
+    let checks = join_all(urls.iter().map(|url| async {
+        let url: &String = url;
+        let _permit = permits.acquire().await.ok()?;
+        let result = http.probe(Method::HEAD, url, &[], None).await;
+        is_dead(&result).then_some(url)
+    }))
+    .await;
+    let dead: HashSet<&String> = checks.into_iter().flatten().collect();
+    if dead.len() > 1 && dead.len() == urls.len() {
+        warn!("every link check failed; assuming the network is down and keeping all links");
+        return 0;
+    }
+
+    let mut pruned = 0;
+    for signal in signals.iter_mut() {
+        if signal.url.as_ref().is_some_and(|u| dead.contains(u)) {
+            warn!(signal = %signal.title, url = %signal.url.as_deref().unwrap_or_default(), "dead link removed");
+            signal.url = None;
+            pruned += 1;
+        }
+    }
+    info!(
+        checked = urls.len(),
+        dead = dead.len(),
+        "signal links checked"
+    );
+    pruned
+}
+
+fn is_dead(result: &crate::error::Result<u16>) -> bool {
+    match result {
+        Ok(_) => false,
+        Err(Error::Api { status_code, .. }) => matches!(status_code, Some(404 | 410)),
+        Err(Error::Http(_)) => true,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Metric;
+
+    #[test]
+    fn resolves_relative_hrefs_against_base() {
+        let base = "https://solana.com/news";
+        assert_eq!(
+            resolve(base, "/news/firedancer-mainnet").as_deref(),
+            Some("https://solana.com/news/firedancer-mainnet")
+        );
+        assert_eq!(
+            resolve("https://blog.example.com/posts/", "2026/restaking").as_deref(),
+            Some("https://blog.example.com/posts/2026/restaking")
+        );
+        assert_eq!(
+            resolve(base, "//cdn.example.com/a").as_deref(),
+            Some("https://cdn.example.com/a")
+        );
+        assert_eq!(
+            resolve(base, "https://other.org/x").as_deref(),
+            Some("https://other.org/x")
+        );
+        assert_eq!(resolve(base, "#comments"), None);
+        assert_eq!(resolve(base, "javascript:void(0)"), None);
+        assert_eq!(resolve(base, "mailto:hi@solana.com"), None);
+    }
+
+    #[test]
+    fn normalize_uses_source_home() {
+        let signal = |source, url: &str| {
+            Signal::new(
+                source,
+                "c".into(),
+                "t".into(),
+                String::new(),
+                Vec::<Metric>::new(),
+                Some(url.into()),
+            )
+        };
+        let mut signals = vec![
+            signal(SignalSource::DeFiLlama, "/protocol/jito"),
+            signal(SignalSource::Social, "/blog/post"),
+            signal(SignalSource::GitHub, "https://github.com/a/b"),
+        ];
+        normalize(&mut signals);
+        assert_eq!(
+            signals[0].url.as_deref(),
+            Some("https://defillama.com/protocol/jito")
+        );
+        assert_eq!(signals[1].url, None);
+        assert_eq!(signals[2].url.as_deref(), Some("https://github.com/a/b"));
+    }
+}
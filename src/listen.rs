@@ -0,0 +1,436 @@
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+
+/// Header carrying the shared secret on every request.
+pub const SECRET_HEADER: &str = "x-trigger-secret";
+
+/// Finished jobs kept for polling; older ones answer 404.
+const KEPT_JOBS: usize = 20;
+
+/// Largest request head read; bodies are ignored.
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// A client that sends nothing for this long is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs the pipeline once and returns what a finished job reports.
+pub type Runner =
+    Arc<dyn Fn() -> BoxFuture<'static, anyhow::Result<serde_json::Value>> + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+/// One triggered run, as returned by `GET /runs/<id>`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    pub id: String,
+    pub status: JobStatus,
+    pub status_url: String,
+    pub requested_at: DateTime<Utc>,
+    /// Further triggers folded into this job while it was queued.
+    pub coalesced: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+}
+
+/// Triggered runs, oldest first. At most one runs at a time and at most one
+/// waits behind it: a trigger while a job is queued joins that job.
+#[derive(Default)]
+struct Jobs {
+    jobs: Vec<Job>,
+    queued: Option<String>,
+    next: u64,
+}
+
+impl Jobs {
+    /// Queue a run, or join the one already queued. Returns the job.
+    fn trigger(&mut self, now: DateTime<Utc>) -> (Job, bool) {
+        if let Some(id) = &self.queued
+            && let Some(job) = self.jobs.iter_mut().find(|j| &j.id == id)
+        {
+            job.coalesced += 1;
+            return (job.clone(), false);
+        }
+        self.next += 1;
+        let id = format!("{}-{}", now.format("%Y%m%dT%H%M%S"), self.next);
+        let job = Job {
+            status_url: format!("/runs/{id}"),
+            id: id.clone(),
+            status: JobStatus::Queued,
+            requested_at: now,
+            coalesced: 0,
+            started_at: None,
+            finished_at: None,
+            error: None,
+            result: None,
+        };
+        self.jobs.push(job.clone());
+        self.queued = Some(id);
+        (job, true)
+    }
+
+    /// Move the queued job to running, if there is one.
+    fn start(&mut self, now: DateTime<Utc>) -> Option<String> {
+        let id = self.queued.take()?;
+        let job = self.jobs.iter_mut().find(|j| j.id == id)?;
+        job.status = JobStatus::Running;
+        job.started_at = Some(now);
+        Some(id)
+    }
+
+    fn finish(&mut self, id: &str, outcome: anyhow::Result<serde_json::Value>, now: DateTime<Utc>) {
+        if let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) {
+            job.finished_at = Some(now);
+            match outcome {
+                Ok(result) => {
+                    job.status = JobStatus::Done;
+                    job.result = Some(result);
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(format!("{e:#}"));
+                }
+            }
+        }
+        let finished = self
+            .jobs
+            .iter()
+            .filter(|j| matches!(j.status, JobStatus::Done | JobStatus::Failed))
+            .count();
+        if finished > KEPT_JOBS {
+            let drop = finished - KEPT_JOBS;
+            let mut dropped = 0;
+            self.jobs.retain(|j| {
+                let old = dropped < drop && matches!(j.status, JobStatus::Done | JobStatus::Failed);
+                dropped += usize::from(old);
+                !old
+            });
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<&Job> {
+        self.jobs.iter().find(|j| j.id == id)
+    }
+}
+
+struct Server {
+    secret: String,
+    jobs: Mutex<Jobs>,
+    wake: Notify,
+}
+
+impl Server {
+    /// The job list, even after a panic while it was held: each update
+    /// leaves it consistent, and triggers must keep being answered.
+    fn jobs(&self) -> MutexGuard<'_, Jobs> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Accept triggers on `listener` until the process ends. `POST /runs`
+/// queues a run and answers 202 with the job; `GET /runs/<id>` reports it,
+/// with the runner's result once done. Every request must carry `secret`
+/// in [`SECRET_HEADER`].
+pub async fn serve(listener: TcpListener, secret: String, runner: Runner) -> Result<()> {
+    let server = Arc::new(Server {
+        secret,
+        jobs: Mutex::new(Jobs::default()),
+        wake: Notify::new(),
+    });
+    tokio::spawn(worker(server.clone(), runner));
+    info!(addr = %listener.local_addr()?, "listening for run triggers");
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let server = server.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle(&server, stream).await {
+                warn!(%peer, error = %e, "trigger connection failed");
+            }
+        });
+    }
+}
+
+/// Runs queued jobs one at a time, each in its own task so a run that
+/// panics fails its job rather than the worker.
+async fn worker(server: Arc<Server>, runner: Runner) {
+    loop {
+        let started = server.jobs().start(Utc::now());
+        let Some(id) = started else {
+            server.wake.notified().await;
+            continue;
+        };
+        info!(job = %id, "triggered run started");
+        let outcome = match tokio::spawn(runner()).await {
+            Ok(outcome) => outcome,
+            Err(e) => Err(anyhow::anyhow!("run panicked: {e}")),
+        };
+        match &outcome {
+            Ok(_) => info!(job = %id, "triggered run finished"),
+            Err(e) => error!(job = %id, error = %e, "triggered run failed"),
+        }
+        server.jobs().finish(&id, outcome, Utc::now());
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    secret: Option<String>,
+}
+
+async fn handle(server: &Server, mut stream: TcpStream) -> Result<()> {
+    let request = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(request) => request?,
+        Err(_) => return Ok(()),
+    };
+    let (status, body, location) = match request {
+        None => (400, error_body("malformed request"), None),
+        Some(request) => respond(server, &request),
+    };
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Method Not Allowed",
+    };
+    let location = location
+        .map(|l| format!("location: {l}\r\n"))
+        .unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n{location}connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await.ok();
+    Ok(())
+}
+
+/// Status, JSON body and `Location` header for one request.
+fn respond(server: &Server, request: &Request) -> (u16, String, Option<String>) {
+    if !request
+        .secret
+        .as_deref()
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), server.secret.as_bytes()))
+    {
+        return (401, error_body("missing or wrong secret"), None);
+    }
+    let path = request.path.split('?').next().unwrap_or_default();
+    match (request.method.as_str(), path) {
+        ("POST", "/runs") => {
+            let (job, created) = server.jobs().trigger(Utc::now());
+            if created {
+                server.wake.notify_one();
+                info!(job = %job.id, "run triggered");
+            } else {
+                info!(job = %job.id, "trigger joined the queued run");
+            }
+            let location = job.status_url.clone();
+            (202, to_json(&job), Some(location))
+        }
+        (_, "/runs") => (405, error_body("use POST /runs"), None),
+        ("GET", path) if path.starts_with("/runs/") => {
+            let id = &path["/runs/".len()..];
+            match server.jobs().get(id) {
+                Some(job) => (200, to_json(job), None),
+                None => (404, error_body("no such run"), None),
+            }
+        }
+        _ => (404, error_body("not found"), None),
+    }
+}
+
+/// Read the request line and headers. `None` if they don't parse.
+async fn read_request(stream: &mut TcpStream) -> Result<Option<Request>> {
+    let mut head = Vec::new();
+    let mut buf = [0; 4096];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(parse_head(&String::from_utf8_lossy(&head)))
+}
+
+fn parse_head(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let secret = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(SECRET_HEADER))
+        .map(|(_, value)| value.trim().to_string());
+    Some(Request {
+        method,
+        path,
+        secret,
+    })
+}
+
+/// Compare without exiting at the first differing byte, so response timing
+/// doesn't reveal how much of a guessed secret was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn to_json(job: &Job) -> String {
+    serde_json::to_string(job).unwrap_or_else(|_| "{}".into())
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn send(addr: std::net::SocketAddr, request: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn triggers_coalesce_into_one_queued_run_and_report_results() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let release = Arc::new(Notify::new());
+        let (counter, gate) = (runs.clone(), release.clone());
+        let runner: Runner = Arc::new(move || {
+            let (counter, gate) = (counter.clone(), gate.clone());
+            Box::pin(async move {
+                let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                gate.notified().await;
+                Ok(serde_json::json!({ "run": n }))
+            })
+        });
+        let (listener, addr) = test_support::listener().await;
+        tokio::spawn(serve(listener, "s3cret".into(), runner));
+
+        let trigger = "POST /runs HTTP/1.1\r\nhost: x\r\nX-Trigger-Secret: s3cret\r\ncontent-length: 0\r\n\r\n";
+        let poll =
+            |id: &str| format!("GET /runs/{id} HTTP/1.1\r\nx-trigger-secret: s3cret\r\n\r\n");
+
+        let (status, _) = send(
+            addr,
+            "POST /runs HTTP/1.1\r\nx-trigger-secret: nope\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 401);
+
+        let (status, first) = send(addr, trigger).await;
+        assert_eq!(status, 202);
+        let first_id = first["id"].as_str().unwrap().to_string();
+        while runs.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(send(addr, &poll(&first_id)).await.1["status"], "running");
+
+        // While the first run is busy, three triggers share one queued run.
+        let (_, second) = send(addr, trigger).await;
+        let (_, third) = send(addr, trigger).await;
+        let (_, fourth) = send(addr, trigger).await;
+        assert_eq!(second["status"], "queued");
+        assert_eq!(second["id"], fourth["id"]);
+        assert_eq!(third["id"], second["id"]);
+        assert_eq!(fourth["coalesced"], 2);
+        assert_eq!(
+            fourth["status_url"],
+            format!("/runs/{}", fourth["id"].as_str().unwrap())
+        );
+
+        release.notify_one();
+        while runs.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+        let (status, done) = send(addr, &poll(&first_id)).await;
+        assert_eq!(status, 200);
+        assert_eq!(done["status"], "done");
+        assert_eq!(done["result"]["run"], 1);
+
+        release.notify_one();
+        let second_id = second["id"].as_str().unwrap();
+        loop {
+            let (_, job) = send(addr, &poll(second_id)).await;
+            if job["status"] == "done" {
+                assert_eq!(job["result"]["run"], 2);
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 2, "four triggers, two runs");
+        assert_eq!(send(addr, &poll("missing")).await.0, 404);
+    }
+
+    #[tokio::test]
+    async fn a_panicking_run_fails_its_job_and_the_next_still_runs() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let runner: Runner = Arc::new(move || {
+            let counter = counter.clone();
+            Box::pin(async move {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("collector blew up");
+                }
+                Ok(serde_json::json!({ "ok": true }))
+            })
+        });
+        let (listener, addr) = test_support::listener().await;
+        tokio::spawn(serve(listener, "s3cret".into(), runner));
+
+        let trigger = "POST /runs HTTP/1.1\r\nx-trigger-secret: s3cret\r\n\r\n";
+        let finished = |id: String| async move {
+            let poll = format!("GET /runs/{id} HTTP/1.1\r\nx-trigger-secret: s3cret\r\n\r\n");
+            loop {
+                let (_, job) = send(addr, &poll).await;
+                if job["status"] == "done" || job["status"] == "failed" {
+                    return job;
+                }
+                tokio::task::yield_now().await;
+            }
+        };
+
+        let (_, first) = send(addr, trigger).await;
+        let failed = finished(first["id"].as_str().unwrap().into()).await;
+        assert_eq!(failed["status"], "failed");
+        assert!(
+            failed["error"].as_str().unwrap().contains("panicked"),
+            "{failed}"
+        );
+
+        let (_, second) = send(addr, trigger).await;
+        let done = finished(second["id"].as_str().unwrap().into()).await;
+        assert_eq!(done["status"], "done", "the worker survived");
+    }
+}
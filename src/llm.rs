@@ -1,10 +1,16 @@
 use crate::error::{Error, Result};
 use crate::http::HttpClient;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
 
 /// LLM provider — determines API format and endpoint.
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Provider {
     Anthropic,
@@ -24,7 +30,7 @@ impl Provider {
         }
     }
 
-    fn default_api_key_env(&self) -> &'static str {
+    pub fn default_api_key_env(&self) -> &'static str {
         match self {
             Self::Anthropic => "ANTHROPIC_API_KEY",
             Self::OpenRouter => "OPENROUTER_API_KEY",
@@ -35,13 +41,206 @@ impl Provider {
 
 pub struct LlmClient {
     provider: Provider,
-    api_key: String,
+    keys: Arc<KeyRing>,
     model: String,
     max_tokens: u32,
+    /// Ceiling for retrying a response truncated at `max_tokens`.
+    max_tokens_cap: u32,
     base_url: String,
+    json_mode: bool,
+    temperature: Option<f32>,
+    seed: Option<u64>,
+    /// USD per million (input, output) tokens, if known for this model.
+    prices: Option<(f64, f64)>,
+    max_run_cost_usd: Option<f64>,
+    usage: Mutex<Usage>,
+    /// Every call made through this client, in order, fallback included.
+    calls: Mutex<Vec<LlmCall>>,
+    /// Where each call's exact prompt is written, if anywhere.
+    prompt_dump: Option<PromptDump>,
+    /// Slots for in-flight completions, shared by every client of the same
+    /// endpoint.
+    slots: Option<Arc<Semaphore>>,
+    /// Takes over, for the rest of the run, once a call here fails.
+    fallback: Option<Box<LlmClient>>,
+    failed_over: AtomicBool,
     http: HttpClient,
 }
 
+/// Destination for `--explain-prompt`: numbered files in a directory, or
+/// stderr when the directory is `-`, so stdout stays free for the report.
+struct PromptDump {
+    dir: Option<PathBuf>,
+    calls: AtomicUsize,
+}
+
+/// A provider's API keys, handed out round-robin. A key that gets rate limited
+/// sits out `cooldown` before it is offered again.
+pub struct KeyRing {
+    keys: Vec<String>,
+    cooldown: Duration,
+    state: Mutex<RingState>,
+}
+
+#[derive(Default)]
+struct RingState {
+    next: usize,
+    cooling_until: HashMap<usize, Instant>,
+}
+
+impl KeyRing {
+    pub fn new(keys: Vec<String>, cooldown: Duration) -> Self {
+        Self {
+            keys,
+            cooldown,
+            state: Mutex::new(RingState::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// The next key that isn't cooling down, with its index. `None` when
+    /// every key is.
+    fn next(&self) -> Option<(usize, &str)> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        state.cooling_until.retain(|_, until| *until > now);
+        let index = (0..self.keys.len())
+            .map(|offset| (state.next + offset) % self.keys.len())
+            .find(|i| !state.cooling_until.contains_key(i))?;
+        state.next = index + 1;
+        Some((index, &self.keys[index]))
+    }
+
+    fn cool_down(&self, index: usize) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .cooling_until
+            .insert(index, Instant::now() + self.cooldown);
+    }
+}
+
+/// The key ring for a set of env vars, created on first use. Clients built
+/// later in the same process, such as on a TUI rerun, share its rotation and
+/// cooldowns instead of starting over with a key that was just rate limited.
+fn shared_key_ring(env_vars: &[String], cooldown: Duration) -> Result<Arc<KeyRing>> {
+    static RINGS: OnceLock<Mutex<HashMap<Vec<String>, Arc<KeyRing>>>> = OnceLock::new();
+    let mut rings = RINGS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(ring) = rings.get(env_vars) {
+        return Ok(ring.clone());
+    }
+    let keys: Vec<String> = env_vars
+        .iter()
+        .map(|var| std::env::var(var).unwrap_or_default())
+        .filter(|key| !key.is_empty())
+        .collect();
+    if keys.is_empty() {
+        return Err(Error::config(format!(
+            "LLM API key not set: ${}",
+            env_vars.join(", $")
+        )));
+    }
+    let ring = Arc::new(KeyRing::new(keys, cooldown));
+    rings.insert(env_vars.to_vec(), ring.clone());
+    Ok(ring)
+}
+
+/// The completion slots for an endpoint, created on first use with `limit`
+/// permits. Later clients for the same endpoint, such as the rows of
+/// `compare` or a TUI rerun, wait on the same slots whatever their own limit.
+fn shared_slots(base_url: &str, limit: usize) -> Arc<Semaphore> {
+    static SLOTS: OnceLock<Mutex<HashMap<String, Arc<Semaphore>>>> = OnceLock::new();
+    SLOTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(base_url.to_string())
+        .or_insert_with(|| Arc::new(Semaphore::new(limit.max(1))))
+        .clone()
+}
+
+/// `…` plus the last four characters, for logs.
+pub fn redact_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 4 {
+        return "****".into();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("…{tail}")
+}
+
+/// Tokens consumed by one client across all of its calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+    pub calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// One LLM call's wall-clock time, for `run --profile`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmCall {
+    /// Pipeline stage that made the call, e.g. "synthesis".
+    pub stage: String,
+    pub elapsed_ms: u64,
+    pub ok: bool,
+}
+
+/// List prices in USD per million (input, output) tokens, matched on the
+/// model name so provider prefixes (`anthropic/...`) don't matter.
+pub fn price_per_mtok(model: &str) -> Option<(f64, f64)> {
+    let model = model.to_lowercase();
+    PRICES
+        .iter()
+        .find(|(pattern, _)| model.contains(pattern))
+        .map(|&(_, prices)| prices)
+}
+
+/// The highest listed (input, output) prices, for counting a model whose
+/// price is unknown without underestimating it.
+fn highest_price_per_mtok() -> (f64, f64) {
+    PRICES
+        .iter()
+        .fold((0.0, 0.0), |(input, output), &(_, (i, o))| {
+            (f64::max(input, i), f64::max(output, o))
+        })
+}
+
+const PRICES: &[(&str, (f64, f64))] = &[
+    (":free", (0.0, 0.0)),
+    ("opus-4-5", (5.0, 25.0)),
+    ("opus-4-6", (5.0, 25.0)),
+    ("opus", (15.0, 75.0)),
+    ("sonnet", (3.0, 15.0)),
+    ("haiku", (1.0, 5.0)),
+    ("gpt-4o-mini", (0.15, 0.6)),
+    ("gpt-4o", (2.5, 10.0)),
+    ("gpt-4.1-mini", (0.4, 1.6)),
+    ("gpt-4.1", (2.0, 8.0)),
+];
+
+/// Rough token estimate for prompts (~4 characters per token).
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+#[derive(Deserialize)]
+struct AnthropicUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+#[derive(Deserialize)]
+struct OpenAiUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
 // -- Anthropic format --
 
 #[derive(Serialize)]
@@ -50,11 +249,16 @@ struct AnthropicRequest<'a> {
     max_tokens: u32,
     system: &'a str,
     messages: Vec<Msg<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
 }
 
 #[derive(Deserialize)]
 struct AnthropicResponse {
     content: Vec<AnthropicBlock>,
+    usage: Option<AnthropicUsage>,
+    /// "end_turn", "max_tokens", "stop_sequence", ...
+    stop_reason: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -69,11 +273,24 @@ struct OpenAiRequest<'a> {
     model: &'a str,
     max_tokens: u32,
     messages: Vec<Msg<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct ResponseFormat {
+    #[serde(rename = "type")]
+    kind: &'static str,
 }
 
 #[derive(Deserialize)]
 struct OpenAiResponse {
     choices: Vec<OpenAiChoice>,
+    usage: Option<OpenAiUsage>,
 }
 
 #[derive(Deserialize)]
@@ -101,67 +318,421 @@ impl LlmClient {
         model: String,
         max_tokens: u32,
         base_url: Option<String>,
+        http: HttpClient,
     ) -> Result<Self> {
-        let http = HttpClient::new("st-narrative/0.1.0")?;
         let base_url = base_url.unwrap_or_else(|| provider.default_base_url().into());
+        let prices = price_per_mtok(&model);
         Ok(Self {
             provider,
-            api_key,
+            keys: Arc::new(KeyRing::new(vec![api_key], Duration::ZERO)),
             model,
             max_tokens,
+            max_tokens_cap: max_tokens,
             base_url,
+            json_mode: false,
+            temperature: None,
+            seed: None,
+            prices,
+            max_run_cost_usd: None,
+            usage: Mutex::new(Usage::default()),
+            calls: Mutex::new(Vec::new()),
+            prompt_dump: None,
+            slots: None,
+            fallback: None,
+            failed_over: AtomicBool::new(false),
             http,
         })
     }
 
-    /// Build from config, reading the API key from the specified env var.
+    /// Build from config, reading API keys from the configured env vars.
+    /// The OpenAI-compatible provider may run without a key (local Ollama/LM Studio).
     pub fn from_config(
-        provider: Provider,
-        model: String,
-        max_tokens: u32,
-        api_key_env: Option<String>,
-        base_url: Option<String>,
+        cfg: &crate::config::LlmConfig,
+        http_cfg: &crate::config::HttpConfig,
     ) -> Result<Self> {
-        let env_var = api_key_env.unwrap_or_else(|| provider.default_api_key_env().into());
-        let api_key = std::env::var(&env_var).unwrap_or_default();
-        if api_key.is_empty() {
-            return Err(Error::config(format!("LLM API key not set: ${env_var}")));
+        let env_vars = cfg.key_env_vars();
+        let cooldown = Duration::from_secs(cfg.key_cooldown_secs);
+        let keys = match shared_key_ring(&env_vars, cooldown) {
+            Ok(keys) => keys,
+            Err(_) if matches!(cfg.provider, Provider::OpenAi) => {
+                Arc::new(KeyRing::new(vec![String::new()], cooldown))
+            }
+            Err(e) => return Err(e),
+        };
+        let mut http = HttpClient::new(http_cfg)?.for_source(http_cfg, "llm");
+        if keys.len() > 1 {
+            // Switching keys beats waiting out one key's limit.
+            http = http.without_rate_limit_retries();
+            info!(keys = keys.len(), "rotating LLM API keys");
+        }
+        let mut client = Self::new(
+            cfg.provider.clone(),
+            String::new(),
+            cfg.model.clone(),
+            cfg.max_tokens,
+            cfg.base_url.clone(),
+            http,
+        )?;
+        client.keys = keys;
+        client.slots = Some(shared_slots(&client.base_url, cfg.max_concurrent_requests));
+        client.max_tokens_cap = cfg.max_tokens_cap.max(cfg.max_tokens);
+        client.json_mode = cfg.json_mode;
+        client.temperature = cfg.temperature;
+        client.seed = cfg.seed;
+        if cfg.seed.is_some() && matches!(cfg.provider, Provider::Anthropic) {
+            warn!("the Anthropic API has no seed parameter; llm.seed is ignored");
+        }
+        if let (Some(input), Some(output)) = (cfg.input_price_per_mtok, cfg.output_price_per_mtok) {
+            client.prices = Some((input, output));
+        }
+        client.max_run_cost_usd = cfg.max_run_cost_usd;
+        if let Some(target) = &cfg.prompt_dump {
+            client.dump_prompts(target);
         }
-        Self::new(provider, api_key, model, max_tokens, base_url)
+        if client.max_run_cost_usd.is_some() && client.prices.is_none() {
+            warn!(model = %cfg.model, "no price known for this model; set input_price_per_mtok/output_price_per_mtok to enforce max_run_cost_usd");
+        }
+        if let Some(fallback_cfg) = cfg.fallback_config() {
+            // Prompts and the spend cap are handled here, before handing off.
+            let fallback_cfg = crate::config::LlmConfig {
+                prompt_dump: None,
+                max_run_cost_usd: None,
+                ..fallback_cfg
+            };
+            match Self::from_config(&fallback_cfg, http_cfg) {
+                Ok(fallback) => client.fallback = Some(Box::new(fallback)),
+                Err(e) => warn!(
+                    provider = ?fallback_cfg.provider,
+                    model = %fallback_cfg.model,
+                    "fallback LLM provider unavailable: {e}"
+                ),
+            }
+            client.price_unknown_fallback();
+        }
+        Ok(client)
+    }
+
+    /// Under a spend cap, count a fallback whose price is unknown at the
+    /// highest listed price, so failing over can't switch the cap off.
+    fn price_unknown_fallback(&mut self) {
+        if self.max_run_cost_usd.is_none() {
+            return;
+        }
+        if let Some(fallback) = &mut self.fallback
+            && fallback.prices.is_none()
+        {
+            let prices = highest_price_per_mtok();
+            warn!(
+                model = %fallback.model,
+                input_price_per_mtok = prices.0,
+                output_price_per_mtok = prices.1,
+                "no price known for the fallback model; counting it at the highest listed price for max_run_cost_usd"
+            );
+            fallback.prices = Some(prices);
+        }
+    }
+
+    /// Calls and tokens across this client and its fallback.
+    pub fn usage(&self) -> Usage {
+        let mut usage = self.own_usage();
+        if let Some(fallback) = &self.fallback {
+            let theirs = fallback.usage();
+            usage.calls += theirs.calls;
+            usage.input_tokens += theirs.input_tokens;
+            usage.output_tokens += theirs.output_tokens;
+        }
+        usage
+    }
+
+    fn own_usage(&self) -> Usage {
+        *self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Estimated spend so far, including the fallback's, if the prices of
+    /// every model that was called are known.
+    pub fn estimated_cost_usd(&self) -> Option<f64> {
+        let usage = self.own_usage();
+        let own = match self.prices {
+            Some((input, output)) => {
+                (usage.input_tokens as f64 * input + usage.output_tokens as f64 * output) / 1e6
+            }
+            None if usage.calls == 0 => 0.0,
+            None => return None,
+        };
+        match &self.fallback {
+            Some(fallback) if fallback.usage().calls > 0 => {
+                Some(own + fallback.estimated_cost_usd()?)
+            }
+            _ if self.prices.is_none() => None,
+            _ => Some(own),
+        }
+    }
+
+    /// `provider/model` of whatever answered this run's calls, naming the
+    /// fallback when it took over.
+    pub fn produced_by(&self) -> String {
+        let primary = self.label();
+        match &self.fallback {
+            Some(fallback) if self.failed_over.load(Ordering::SeqCst) => {
+                if self.own_usage().calls > 0 {
+                    format!("{primary}, then {} (fallback)", fallback.label())
+                } else {
+                    format!("{} (fallback)", fallback.label())
+                }
+            }
+            _ => primary,
+        }
+    }
+
+    fn label(&self) -> String {
+        format!("{:?}/{}", self.provider, self.model).to_lowercase()
+    }
+
+    /// One-line summary of calls, tokens and estimated spend.
+    pub fn log_usage(&self) {
+        let usage = self.usage();
+        info!(
+            calls = usage.calls,
+            input_tokens = usage.input_tokens,
+            output_tokens = usage.output_tokens,
+            estimated_cost_usd = self.estimated_cost_usd().map(|c| format!("{c:.4}")),
+            "LLM usage"
+        );
+    }
+
+    /// Refuse a call once spend so far plus this prompt's input would pass
+    /// the cap. Output tokens aren't known in advance and aren't counted.
+    fn check_budget(&self, prompt_chars: usize) -> Result<()> {
+        let prices = match &self.fallback {
+            Some(fallback) if self.failed_over.load(Ordering::SeqCst) => fallback.prices,
+            _ => self.prices,
+        };
+        let (Some(cap), Some((input_price, _)), Some(spent)) =
+            (self.max_run_cost_usd, prices, self.estimated_cost_usd())
+        else {
+            return Ok(());
+        };
+        let next = prompt_chars.div_ceil(4) as f64 * input_price / 1e6; // ~4 chars per token
+        if spent + next > cap {
+            return Err(Error::Budget {
+                spent_usd: spent,
+                cap_usd: cap,
+            });
+        }
+        Ok(())
+    }
+
+    fn record_usage(&self, input_tokens: u64, output_tokens: u64) {
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.calls += 1;
+        usage.input_tokens += input_tokens;
+        usage.output_tokens += output_tokens;
+    }
+
+    /// Write the system prompt and user message of every call to `target`:
+    /// `NN-<stage>.txt` files in that directory, or stderr for `-`. API keys
+    /// are redacted.
+    pub fn dump_prompts(&mut self, target: &str) {
+        self.prompt_dump = Some(PromptDump {
+            dir: (target != "-").then(|| PathBuf::from(target)),
+            calls: AtomicUsize::new(0),
+        });
+    }
+
+    fn record_prompt(&self, stage: &str, system: &str, user_message: &str) {
+        let Some(dump) = &self.prompt_dump else {
+            return;
+        };
+        let call = dump.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut text = format!(
+            "# call {call}: {stage}\n# provider: {:?}, model: {}\n\n=== SYSTEM ===\n{system}\n\n=== USER ===\n{user_message}\n",
+            self.provider, self.model
+        );
+        for key in self.keys.keys.iter().filter(|k| !k.is_empty()) {
+            text = text.replace(key.as_str(), &redact_key(key));
+        }
+        match &dump.dir {
+            None => eprintln!("{text}"),
+            Some(dir) => {
+                let path = dir.join(format!("{call:02}-{stage}.txt"));
+                let written =
+                    std::fs::create_dir_all(dir).and_then(|()| std::fs::write(&path, text));
+                match written {
+                    Ok(()) => info!(path = %path.display(), "prompt written"),
+                    Err(e) => warn!(path = %path.display(), "failed to write prompt: {e}"),
+                }
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        stage: &str,
+        system: &str,
+        user_message: &str,
+        want_json: bool,
+    ) -> Result<String> {
+        self.record_prompt(stage, system, user_message);
+        self.check_budget(system.len() + user_message.len())?;
+
+        let started = Instant::now();
+        let result = self
+            .send_with_fallback(system, user_message, want_json)
+            .await;
+        self.calls.lock().unwrap().push(LlmCall {
+            stage: stage.to_string(),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            ok: result.is_ok(),
+        });
+        result
     }
 
-    pub async fn complete(&self, system: &str, user_message: &str) -> Result<String> {
-        debug!(provider = ?self.provider, model = %self.model, "sending LLM request");
+    /// Calls made so far, in order.
+    pub fn calls(&self) -> Vec<LlmCall> {
+        self.calls.lock().unwrap().clone()
+    }
 
-        match self.provider {
-            Provider::Anthropic => self.complete_anthropic(system, user_message).await,
-            Provider::OpenRouter | Provider::OpenAi => {
-                self.complete_openai(system, user_message).await
+    async fn send_with_fallback(
+        &self,
+        system: &str,
+        user_message: &str,
+        want_json: bool,
+    ) -> Result<String> {
+        let Some(fallback) = &self.fallback else {
+            return self.send_here(system, user_message, want_json).await;
+        };
+        if !self.failed_over.load(Ordering::SeqCst) {
+            match self.send_here(system, user_message, want_json).await {
+                Err(e) if !matches!(e, Error::Budget { .. }) => {
+                    warn!(
+                        from = %self.label(),
+                        to = %fallback.label(),
+                        "LLM provider failed ({e}); failing over for the rest of the run"
+                    );
+                    self.failed_over.store(true, Ordering::SeqCst);
+                }
+                result => return result,
+            }
+        }
+        fallback.send_here(system, user_message, want_json).await
+    }
+
+    /// One call to this client's own provider, rotating keys on rate limits.
+    async fn send_here(&self, system: &str, user_message: &str, want_json: bool) -> Result<String> {
+        let _slot = match &self.slots {
+            Some(slots) => {
+                if slots.available_permits() == 0 {
+                    debug!(provider = ?self.provider, "all LLM request slots busy; waiting");
+                }
+                Some(
+                    slots
+                        .acquire()
+                        .await
+                        .map_err(|_| Error::http("LLM request slots closed"))?,
+                )
+            }
+            None => None,
+        };
+        loop {
+            let Some((index, key)) = self.keys.next() else {
+                return Err(Error::RateLimit {
+                    platform: format!(
+                        "LLM provider (all {} API keys cooling down)",
+                        self.keys.len()
+                    ),
+                    retry_after_secs: Some(self.keys.cooldown.as_secs()),
+                });
+            };
+            debug!(provider = ?self.provider, model = %self.model, key = %redact_key(key), "sending LLM request");
+
+            let result = match self.provider {
+                Provider::Anthropic => self.complete_anthropic(key, system, user_message).await,
+                Provider::OpenRouter | Provider::OpenAi => {
+                    self.complete_openai(key, system, user_message, want_json && self.json_mode)
+                        .await
+                }
+            };
+            match result {
+                Err(Error::RateLimit { .. }) if self.keys.len() > 1 => {
+                    self.keys.cool_down(index);
+                    warn!(
+                        key = %redact_key(key),
+                        cooldown_secs = self.keys.cooldown.as_secs(),
+                        "LLM API key rate limited; rotating to the next key"
+                    );
+                }
+                result => return result,
             }
         }
     }
 
     /// Send a prompt and parse the response as JSON, stripping markdown fences if present.
+    /// `stage` names the call in prompt dumps, e.g. "synthesis".
     pub async fn complete_json<T: serde::de::DeserializeOwned>(
         &self,
+        stage: &str,
         system: &str,
         user_message: &str,
     ) -> Result<T> {
-        let text = self.complete(system, user_message).await?;
+        // Even in JSON mode, many local models ignore the hint, so always extract.
+        let text = self.send(stage, system, user_message, true).await?;
         let json_str = extract_json(&text);
         serde_json::from_str(json_str)
             .map_err(|e| Error::parse(format!("parse LLM JSON: {e}\nraw: {text}")))
     }
 
-    async fn complete_anthropic(&self, system: &str, user_message: &str) -> Result<String> {
+    /// A response cut off at `max_tokens` is retried with double the limit,
+    /// up to `max_tokens_cap`. Still truncated at the cap is an error: the
+    /// partial JSON would only fail to parse later with a less useful message.
+    async fn complete_anthropic(
+        &self,
+        api_key: &str,
+        system: &str,
+        user_message: &str,
+    ) -> Result<String> {
+        let mut max_tokens = self.max_tokens;
+        loop {
+            let (text, stop_reason) = self
+                .anthropic_once(api_key, system, user_message, max_tokens)
+                .await?;
+            debug!(model = %self.model, max_tokens, stop_reason = stop_reason.as_deref().unwrap_or("none"), "Anthropic response");
+            if stop_reason.as_deref() != Some("max_tokens") {
+                return Ok(text);
+            }
+            if max_tokens >= self.max_tokens_cap {
+                return Err(Error::parse(format!(
+                    "Anthropic response truncated at max_tokens = {max_tokens} (stop_reason: max_tokens); \
+                     raise [llm] max_tokens_cap or shrink the input (input_token_budget, signal_detail = \"compact\")"
+                )));
+            }
+            let next = max_tokens.saturating_mul(2).min(self.max_tokens_cap);
+            warn!(
+                max_tokens,
+                retry_max_tokens = next,
+                "Anthropic response truncated at max_tokens; retrying with a higher limit"
+            );
+            self.check_budget(system.len() + user_message.len())?;
+            max_tokens = next;
+        }
+    }
+
+    /// One Messages API call: the joined text blocks and the stop reason.
+    async fn anthropic_once(
+        &self,
+        api_key: &str,
+        system: &str,
+        user_message: &str,
+        max_tokens: u32,
+    ) -> Result<(String, Option<String>)> {
         let request = AnthropicRequest {
             model: &self.model,
-            max_tokens: self.max_tokens,
+            max_tokens,
             system,
             messages: vec![Msg {
                 role: "user",
                 content: user_message,
             }],
+            temperature: self.temperature,
         };
 
         let body = serde_json::to_string(&request)
@@ -173,10 +744,7 @@ impl LlmClient {
             .post_json_raw(
                 &url,
                 &body,
-                &[
-                    ("x-api-key", &self.api_key),
-                    ("anthropic-version", "2023-06-01"),
-                ],
+                &[("x-api-key", api_key), ("anthropic-version", "2023-06-01")],
             )
             .await
             .map_err(|e| {
@@ -187,15 +755,26 @@ impl LlmClient {
         let resp: AnthropicResponse = serde_json::from_str(&response_text)
             .map_err(|e| Error::parse(format!("parse Anthropic response: {e}")))?;
 
-        Ok(resp
+        let text = resp
             .content
             .into_iter()
             .filter_map(|b| b.text)
             .collect::<Vec<_>>()
-            .join("\n"))
+            .join("\n");
+        match resp.usage {
+            Some(u) => self.record_usage(u.input_tokens, u.output_tokens),
+            None => self.record_usage(estimate_tokens(&body) as u64, estimate_tokens(&text) as u64),
+        }
+        Ok((text, resp.stop_reason))
     }
 
-    async fn complete_openai(&self, system: &str, user_message: &str) -> Result<String> {
+    async fn complete_openai(
+        &self,
+        api_key: &str,
+        system: &str,
+        user_message: &str,
+        json_mode: bool,
+    ) -> Result<String> {
         let request = OpenAiRequest {
             model: &self.model,
             max_tokens: self.max_tokens,
@@ -209,19 +788,26 @@ impl LlmClient {
                     content: user_message,
                 },
             ],
+            response_format: json_mode.then_some(ResponseFormat {
+                kind: "json_object",
+            }),
+            temperature: self.temperature,
+            seed: self.seed,
         };
 
         let body = serde_json::to_string(&request)
             .map_err(|e| Error::parse(format!("serialize request: {e}")))?;
 
         let url = format!("{}/chat/completions", self.base_url);
+        let auth = format!("Bearer {api_key}");
+        let headers: &[(&str, &str)] = if api_key.is_empty() {
+            &[]
+        } else {
+            &[("Authorization", &auth)]
+        };
         let response_text = self
             .http
-            .post_json_raw(
-                &url,
-                &body,
-                &[("Authorization", &format!("Bearer {}", self.api_key))],
-            )
+            .post_json_raw(&url, &body, headers)
             .await
             .map_err(|e| {
                 warn!("LLM API error: {e}");
@@ -231,11 +817,17 @@ impl LlmClient {
         let resp: OpenAiResponse = serde_json::from_str(&response_text)
             .map_err(|e| Error::parse(format!("parse LLM response: {e}")))?;
 
-        resp.choices
+        let text = resp
+            .choices
             .into_iter()
             .next()
             .map(|c| c.message.content)
-            .ok_or_else(|| Error::parse("empty response from LLM"))
+            .ok_or_else(|| Error::parse("empty response from LLM"))?;
+        match resp.usage {
+            Some(u) => self.record_usage(u.prompt_tokens, u.completion_tokens),
+            None => self.record_usage(estimate_tokens(&body) as u64, estimate_tokens(&text) as u64),
+        }
+        Ok(text)
     }
 }
 
@@ -256,6 +848,15 @@ fn extract_json(text: &str) -> &str {
             }
         }
     }
+    // Prose may contain braces of its own ("fill in {name}"), so take the
+    // largest balanced region that is valid JSON.
+    if let Some(region) = balanced_regions(text)
+        .into_iter()
+        .filter(|r| serde_json::from_str::<serde::de::IgnoredAny>(r).is_ok())
+        .max_by_key(|r| r.len())
+    {
+        return region;
+    }
     if let Some(start) = text.find('{')
         && let Some(end) = text.rfind('}')
     {
@@ -264,9 +865,56 @@ fn extract_json(text: &str) -> &str {
     text
 }
 
+/// Every `{...}` span whose braces balance, ignoring braces inside JSON
+/// strings. An opening brace that never closes is skipped, not fatal.
+fn balanced_regions(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut regions = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = text[start..].find('{') {
+        let open = start + offset;
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut close = None;
+        for (i, &b) in bytes.iter().enumerate().skip(open) {
+            if in_string {
+                match b {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match b {
+                b'"' => in_string = true,
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        match close {
+            Some(end) => {
+                regions.push(&text[open..=end]);
+                start = end + 1;
+            }
+            None => start = open + 1,
+        }
+    }
+    regions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{self, Reply, TempDir};
 
     #[test]
     fn extract_json_from_markdown() {
@@ -280,6 +928,403 @@ mod tests {
         assert_eq!(extract_json(input), "{\"key\": \"value\"}");
     }
 
+    #[test]
+    fn unset_sampling_fields_are_omitted() {
+        let messages = || {
+            vec![Msg {
+                role: "user",
+                content: "hi",
+            }]
+        };
+        let body = serde_json::to_value(OpenAiRequest {
+            model: "m",
+            max_tokens: 10,
+            messages: messages(),
+            response_format: None,
+            temperature: None,
+            seed: None,
+        })
+        .unwrap();
+        assert!(body.get("temperature").is_none());
+        assert!(body.get("seed").is_none());
+
+        let body = serde_json::to_value(OpenAiRequest {
+            model: "m",
+            max_tokens: 10,
+            messages: messages(),
+            response_format: None,
+            temperature: Some(0.0),
+            seed: Some(42),
+        })
+        .unwrap();
+        assert_eq!(body["temperature"], 0.0);
+        assert_eq!(body["seed"], 42);
+    }
+
+    #[test]
+    fn extract_json_skips_prose_braces() {
+        let input =
+            "Use {placeholder} like this: {\"ideas\": [{\"title\": \"a {b}\"}]} Hope {that} helps.";
+        let json = extract_json(input);
+        assert_eq!(json, "{\"ideas\": [{\"title\": \"a {b}\"}]}");
+        assert!(serde_json::from_str::<serde_json::Value>(json).is_ok());
+    }
+
+    #[test]
+    fn extract_json_survives_unclosed_prose_brace() {
+        let input = "Note: the { character opens objects. {\"key\": \"value\"}";
+        assert_eq!(extract_json(input), "{\"key\": \"value\"}");
+    }
+
+    #[tokio::test]
+    async fn low_budget_stops_the_ideas_call() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Each call reports 100k input + 10k output tokens: $0.45 at Sonnet prices.
+        let requests = Arc::new(AtomicUsize::new(0));
+        let seen = requests.clone();
+        let addr = test_support::mock_http(move |_| {
+            seen.fetch_add(1, Ordering::SeqCst);
+            Reply::json(
+                r#"{"choices":[{"message":{"content":"{\"narratives\": []}"}}],"usage":{"prompt_tokens":100000,"completion_tokens":10000}}"#,
+            )
+        })
+        .await;
+
+        let mut client = LlmClient::new(
+            Provider::OpenRouter,
+            "key".into(),
+            "anthropic/claude-sonnet-4".into(),
+            1000,
+            Some(format!("http://{addr}")),
+            HttpClient::new(&Default::default()).unwrap(),
+        )
+        .unwrap();
+        client.max_run_cost_usd = Some(0.40);
+
+        client
+            .send("test", "system", "synthesize", false)
+            .await
+            .unwrap();
+        assert!((client.estimated_cost_usd().unwrap() - 0.45).abs() < 1e-9);
+
+        let ideas = crate::analysis::ideas::generate_ideas(
+            &client,
+            &[],
+            None,
+            1,
+            crate::config::Persona::Generic,
+        )
+        .await;
+        assert!(matches!(ideas, Err(Error::Budget { .. })));
+        assert_eq!(requests.load(Ordering::SeqCst), 1, "ideas call never sent");
+    }
+
+    #[tokio::test]
+    async fn rate_limited_key_sits_out_its_cooldown() {
+        let used = Arc::new(Mutex::new(Vec::new()));
+        let seen = used.clone();
+        let addr = test_support::mock_http(move |request| {
+            let key = if request.header("authorization") == Some("Bearer sk-first-aaaa") {
+                "first"
+            } else {
+                "second"
+            };
+            seen.lock().unwrap().push(key);
+            if key == "first" {
+                Reply::status(429)
+            } else {
+                Reply::json(r#"{"choices":[{"message":{"content":"ok"}}]}"#)
+            }
+        })
+        .await;
+
+        let mut client = LlmClient::new(
+            Provider::OpenRouter,
+            String::new(),
+            "model".into(),
+            100,
+            Some(format!("http://{addr}")),
+            HttpClient::new(&Default::default())
+                .unwrap()
+                .without_rate_limit_retries(),
+        )
+        .unwrap();
+        client.keys = Arc::new(KeyRing::new(
+            vec!["sk-first-aaaa".into(), "sk-second-bbbb".into()],
+            Duration::from_secs(60),
+        ));
+
+        assert_eq!(client.send("test", "s", "one", false).await.unwrap(), "ok");
+        assert_eq!(client.send("test", "s", "two", false).await.unwrap(), "ok");
+        assert_eq!(*used.lock().unwrap(), ["first", "second", "second"]);
+
+        client.keys.cool_down(1);
+        let err = client.send("test", "s", "three", false).await.unwrap_err();
+        assert!(matches!(err, Error::RateLimit { .. }), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn truncated_anthropic_response_retries_with_more_tokens() {
+        let limits = Arc::new(Mutex::new(Vec::new()));
+        let seen = limits.clone();
+        let addr = test_support::mock_http(move |request| {
+            let max_tokens = request.json()["max_tokens"].as_u64().unwrap();
+            seen.lock().unwrap().push(max_tokens);
+            Reply::json(if max_tokens < 400 {
+                r#"{"content":[{"text":"{\"narratives\": [{\"ti"}],"stop_reason":"max_tokens"}"#
+            } else {
+                r#"{"content":[{"text":"{\"narratives\": []}"}],"stop_reason":"end_turn"}"#
+            })
+        })
+        .await;
+
+        let mut client = LlmClient::new(
+            Provider::Anthropic,
+            "key".into(),
+            "claude-sonnet-4-5".into(),
+            100,
+            Some(format!("http://{addr}")),
+            HttpClient::new(&Default::default()).unwrap(),
+        )
+        .unwrap();
+        client.max_tokens_cap = 1000;
+        assert_eq!(
+            client.send("test", "s", "u", false).await.unwrap(),
+            "{\"narratives\": []}"
+        );
+        assert_eq!(*limits.lock().unwrap(), [100, 200, 400]);
+
+        client.max_tokens_cap = 200;
+        let err = client.send("test", "s", "u", false).await.unwrap_err();
+        assert!(err.to_string().contains("max_tokens_cap"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn failed_primary_hands_off_to_fallback() {
+        // Primary and fallback share one server and are told apart by path.
+        let hits = Arc::new(Mutex::new(Vec::new()));
+        let seen = hits.clone();
+        let addr = test_support::mock_http(move |request| {
+            if request.path.starts_with("/primary/") {
+                seen.lock().unwrap().push("primary");
+                Reply::status(401)
+                    .header("content-type", "application/json")
+                    .body(r#"{"error":{"message":"invalid x-api-key"}}"#)
+            } else {
+                seen.lock().unwrap().push("fallback");
+                Reply::json(
+                    r#"{"choices":[{"message":{"content":"{\"narratives\": []}"}}],"usage":{"prompt_tokens":10,"completion_tokens":5}}"#,
+                )
+            }
+        })
+        .await;
+
+        let http = || HttpClient::new(&Default::default()).unwrap();
+        let mut client = LlmClient::new(
+            Provider::Anthropic,
+            "key".into(),
+            "claude-sonnet-4-5".into(),
+            100,
+            Some(format!("http://{addr}/primary")),
+            http(),
+        )
+        .unwrap();
+        client.fallback = Some(Box::new(
+            LlmClient::new(
+                Provider::OpenRouter,
+                "other-key".into(),
+                "deepseek/deepseek-chat".into(),
+                100,
+                Some(format!("http://{addr}/fallback")),
+                http(),
+            )
+            .unwrap(),
+        ));
+
+        let out: serde_json::Value = client.complete_json("synthesis", "s", "u").await.unwrap();
+        assert_eq!(out, serde_json::json!({"narratives": []}));
+        client.send("test", "s", "again", false).await.unwrap();
+        assert_eq!(
+            *hits.lock().unwrap(),
+            ["primary", "fallback", "fallback"],
+            "primary is not retried once failed over"
+        );
+        assert_eq!(
+            client.produced_by(),
+            "openrouter/deepseek/deepseek-chat (fallback)"
+        );
+        assert_eq!(client.usage().calls, 2);
+    }
+
+    #[tokio::test]
+    async fn calls_past_the_slot_limit_wait_their_turn() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (now, max) = (in_flight.clone(), peak.clone());
+        let addr = test_support::mock_http_async(move |_| {
+            let (now, max) = (now.clone(), max.clone());
+            async move {
+                let current = now.fetch_add(1, Ordering::SeqCst) + 1;
+                max.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                now.fetch_sub(1, Ordering::SeqCst);
+                Reply::json(r#"{"choices":[{"message":{"content":"ok"}}]}"#)
+            }
+        })
+        .await;
+
+        let mut client = LlmClient::new(
+            Provider::OpenRouter,
+            "key".into(),
+            "model".into(),
+            100,
+            Some(format!("http://{addr}")),
+            HttpClient::new(&Default::default()).unwrap(),
+        )
+        .unwrap();
+        let slots = Arc::new(Semaphore::new(2));
+        client.slots = Some(slots.clone());
+
+        let started = Instant::now();
+        let calls = (0..3).map(|i| {
+            let client = &client;
+            async move { client.send("test", "s", &format!("call {i}"), false).await }
+        });
+        for result in futures_util::future::join_all(calls).await {
+            assert_eq!(result.unwrap(), "ok");
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert!(
+            started.elapsed() >= Duration::from_millis(200),
+            "third call waited for a slot"
+        );
+        assert_eq!(slots.available_permits(), 2, "slots are returned");
+    }
+
+    #[test]
+    fn prompt_dump_is_numbered_and_redacted() {
+        let dir = TempDir::new("prompts");
+        let mut client = LlmClient::new(
+            Provider::Anthropic,
+            "sk-ant-secret-9f3e".into(),
+            "claude-sonnet-4-5".into(),
+            100,
+            None,
+            HttpClient::new(&Default::default()).unwrap(),
+        )
+        .unwrap();
+        client.dump_prompts(dir.path().to_str().unwrap());
+
+        client.record_prompt("synthesis", "You are an analyst.", "signals: [...]");
+        client.record_prompt(
+            "ideas",
+            "You are a strategist.",
+            "leaked sk-ant-secret-9f3e here",
+        );
+
+        let synthesis = std::fs::read_to_string(dir.join("01-synthesis.txt")).unwrap();
+        assert!(synthesis.contains("=== SYSTEM ===\nYou are an analyst."));
+        assert!(synthesis.contains("=== USER ===\nsignals: [...]"));
+        let ideas = std::fs::read_to_string(dir.join("02-ideas.txt")).unwrap();
+        assert!(!ideas.contains("sk-ant-secret"), "{ideas}");
+        assert!(ideas.contains("leaked …9f3e here"));
+    }
+
+    #[tokio::test]
+    async fn prompt_dump_records_each_call_sent() {
+        let addr = test_support::mock_http(|_| {
+            Reply::json(r#"{"choices":[{"message":{"content":"ok"}}]}"#)
+        })
+        .await;
+        let dir = TempDir::new("prompts-sent");
+        let mut client = LlmClient::new(
+            Provider::OpenRouter,
+            "key".into(),
+            "model".into(),
+            100,
+            Some(format!("http://{addr}")),
+            HttpClient::new(&Default::default()).unwrap(),
+        )
+        .unwrap();
+        client.dump_prompts(dir.path().to_str().unwrap());
+
+        client
+            .send("synthesis", "system one", "user one", true)
+            .await
+            .unwrap();
+        client
+            .send("ideas", "system two", "user two", false)
+            .await
+            .unwrap();
+
+        let first = std::fs::read_to_string(dir.join("01-synthesis.txt")).unwrap();
+        assert!(first.starts_with("# call 1: synthesis\n"), "{first}");
+        assert!(first.contains("user one"));
+        let second = std::fs::read_to_string(dir.join("02-ideas.txt")).unwrap();
+        assert!(second.contains("=== SYSTEM ===\nsystem two"), "{second}");
+
+        client.dump_prompts("-");
+        assert!(
+            client.prompt_dump.as_ref().unwrap().dir.is_none(),
+            "`-` goes to stderr, not a directory named -"
+        );
+    }
+
+    #[test]
+    fn unpriced_fallback_still_counts_against_the_cap() {
+        let http = || HttpClient::new(&Default::default()).unwrap();
+        let mut client = LlmClient::new(
+            Provider::Anthropic,
+            "key".into(),
+            "claude-sonnet-4-5".into(),
+            100,
+            None,
+            http(),
+        )
+        .unwrap();
+        client.max_run_cost_usd = Some(1.0);
+        client.fallback = Some(Box::new(
+            LlmClient::new(
+                Provider::OpenRouter,
+                "other-key".into(),
+                "deepseek/deepseek-chat".into(),
+                100,
+                None,
+                http(),
+            )
+            .unwrap(),
+        ));
+        client.price_unknown_fallback();
+
+        let fallback = client.fallback.as_ref().unwrap();
+        assert_eq!(fallback.prices, Some(highest_price_per_mtok()));
+        fallback.record_usage(100_000, 0);
+        assert_eq!(client.estimated_cost_usd(), Some(1.5));
+        client.failed_over.store(true, Ordering::SeqCst);
+        assert!(matches!(client.check_budget(4), Err(Error::Budget { .. })));
+    }
+
+    #[test]
+    fn keys_are_redacted_to_last_four() {
+        assert_eq!(redact_key("sk-ant-REDACTED"), "…x9Zq");
+        assert_eq!(redact_key("abc"), "****");
+    }
+
+    #[test]
+    fn prices_match_provider_prefixed_models() {
+        assert_eq!(
+            price_per_mtok("anthropic/claude-sonnet-4.5"),
+            Some((3.0, 15.0))
+        );
+        assert_eq!(
+            price_per_mtok("arcee-ai/trinity-large-preview:free"),
+            Some((0.0, 0.0))
+        );
+        assert_eq!(price_per_mtok("qwen2.5:14b"), None);
+    }
+
     #[test]
     fn extract_json_plain() {
         let input = "{\"key\": \"value\"}";
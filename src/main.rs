@@ -1,17 +1,38 @@
+mod alerts;
 mod analysis;
+mod annotations;
+mod compare;
 mod config;
 mod error;
+mod external;
+mod history;
 mod http;
+mod http_cache;
+mod init;
+mod language;
+mod links;
+mod listen;
 mod llm;
+mod logging;
+mod narrative_history;
 mod output;
+mod pipeline;
 mod sources;
+#[cfg(test)]
+mod test_support;
+mod tui;
 mod types;
+mod util;
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use tracing::info;
 
+/// Deviations from a metric's rolling mean at which `explain` flags it.
+const OUTLIER_SIGMAS: f64 = 2.0;
+
 #[derive(Parser)]
 #[command(
     name = "st-narrative",
@@ -20,34 +41,130 @@ use tracing::info;
 struct Cli {
     #[command(subcommand)]
     command: Command,
+
+    /// Log format: text (default) or json, one object per line for log
+    /// pipelines. Falls back to $LOG_FORMAT.
+    #[arg(long, global = true)]
+    log_format: Option<String>,
 }
 
 #[derive(clap::Subcommand)]
 enum Command {
     /// Run the full narrative detection pipeline and generate a report
-    Run {
-        /// Path to config file
+    Run(RunArgs),
+
+    /// Interactively write a config.toml and .env.example
+    Init {
+        /// Path of the config file to write
         #[arg(short, long, default_value = "config.toml")]
         config: PathBuf,
 
+        /// Overwrite an existing config
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Collect signals only (no Claude analysis), output as JSON
+    Signals {
+        #[command(flatten)]
+        config: ConfigArg,
+
+        /// Single-line JSON instead of indented
+        #[arg(long, conflicts_with = "ndjson")]
+        compact: bool,
+
+        /// One signal per line (NDJSON), written as each is serialized
+        #[arg(long)]
+        ndjson: bool,
+
+        /// Also write the aggregated category groups (source diversity, counts, summed metrics) to PATH
+        #[arg(long, value_name = "PATH")]
+        groups_json: Option<PathBuf>,
+    },
+
+    /// Analyze a saved signal snapshot (from `signals`) without collecting: aggregation + LLM only
+    Analyze {
+        #[command(flatten)]
+        config: ConfigArg,
+
+        /// Signal snapshot JSON, as written by `signals` (or a stored run)
+        #[arg(long)]
+        signals: PathBuf,
+
         /// Output path for the HTML report
         #[arg(short, long)]
         output: Option<PathBuf>,
+    },
 
-        /// LLM provider override: anthropic, openrouter, openai
-        #[arg(long)]
-        provider: Option<String>,
+    /// Run the pipeline and browse narratives, evidence and ideas in the terminal
+    Tui {
+        #[command(flatten)]
+        config: ConfigArg,
+    },
 
-        /// LLM model override
-        #[arg(long)]
-        model: Option<String>,
+    /// Print the full evidence behind a narrative from the last stored run
+    Explain {
+        #[command(flatten)]
+        config: ConfigArg,
+
+        /// Narrative number (1-based, as listed) or narrative ID
+        narrative: String,
     },
 
-    /// Collect signals only (no Claude analysis), output as JSON
-    Signals {
-        /// Path to config file
-        #[arg(short, long, default_value = "config.toml")]
-        config: PathBuf,
+    /// Drop old runs from the history stores and compact the files
+    Prune {
+        #[command(flatten)]
+        config: ConfigArg,
+
+        /// Keep runs started within the last N days
+        #[arg(
+            long,
+            value_name = "N",
+            required_unless_present = "keep_runs",
+            conflicts_with = "keep_runs"
+        )]
+        keep_days: Option<u32>,
+
+        /// Keep the N most recent runs
+        #[arg(long, value_name = "N")]
+        keep_runs: Option<usize>,
+    },
+
+    /// Collect signals once and compare narrative synthesis across providers/models
+    Compare {
+        #[command(flatten)]
+        config: ConfigArg,
+
+        /// Provider to compare, as provider or provider:model, optionally followed by @base_url, e.g. anthropic:claude-sonnet-4-5 (repeatable)
+        #[arg(
+            short,
+            long = "provider",
+            value_name = "PROVIDER[:MODEL]",
+            required = true
+        )]
+        providers: Vec<String>,
+    },
+
+    /// Check config and probe connectivity to each enabled source (no collection, no LLM calls)
+    Validate {
+        #[command(flatten)]
+        config: ConfigArg,
+    },
+
+    /// List every source: enabled or not, API key present or not, items tracked (no network)
+    Sources {
+        #[command(flatten)]
+        config: ConfigArg,
+    },
+
+    /// Serve an authenticated webhook that triggers pipeline runs on demand
+    Listen {
+        #[command(flatten)]
+        config: ConfigArg,
+
+        /// Address to listen on [default: listen.bind from config]
+        #[arg(long)]
+        bind: Option<String>,
     },
 
     /// Render a report from pre-computed analysis files (no LLM calls)
@@ -67,48 +184,189 @@ enum Command {
         /// Output path for the HTML report
         #[arg(short, long, default_value = "report.html")]
         output: PathBuf,
+
+        /// Report theme: dark, light
+        #[arg(long, default_value = "dark")]
+        theme: String,
     },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "st_narrative=info".parse().unwrap()),
-        )
-        .init();
+/// `--config`, shared by every command that loads one.
+#[derive(clap::Args)]
+struct ConfigArg {
+    /// Path to config file, `-` for stdin [default: $ST_NARRATIVE_CONFIG, piped stdin, config.toml, else env vars]
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+}
+
+impl ConfigArg {
+    /// Where the config comes from: `--config`, else the first of the
+    /// fallbacks in [`config::ConfigSource::resolve`].
+    fn source(self, read_stdin: bool) -> Result<config::ConfigSource> {
+        Ok(config::ConfigSource::resolve(self.config, read_stdin)?)
+    }
+
+    fn load(self) -> Result<config::Config> {
+        load_config(&self.source(true)?)
+    }
+
+    /// [`load`](Self::load) without the piped stdin fallback, for commands
+    /// that keep running: a service's stdin may be a pipe that never closes,
+    /// and the dashboard needs stdin for the terminal.
+    fn load_without_stdin(self) -> Result<config::Config> {
+        load_config(&self.source(false)?)
+    }
+}
+
+#[derive(clap::Args)]
+struct RunArgs {
+    #[command(flatten)]
+    config: ConfigArg,
+
+    /// Output path for the HTML report
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Write the report to DIR/report-YYYYMMDD-HHMMSS.html and rebuild DIR/index.html
+    #[arg(long, value_name = "DIR", conflicts_with = "output")]
+    output_dir: Option<PathBuf>,
+
+    /// LLM provider override: anthropic, openrouter, openai
+    #[arg(long)]
+    provider: Option<String>,
+
+    /// LLM model override
+    #[arg(long)]
+    model: Option<String>,
+
+    /// Report theme override: dark, light
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// Output format: html (full report) or csv (one row per signal metric, no LLM calls)
+    #[arg(long, default_value = "html")]
+    format: String,
+
+    /// Email a digest of changes since the last run to these addresses (comma-separated)
+    #[arg(long)]
+    email: Option<String>,
+
+    /// HEAD every signal link and drop dead ones before rendering
+    #[arg(long)]
+    check_links: bool,
+
+    /// Only generate ideas for new or changed narratives; reuse the last run's ideas for the rest
+    #[arg(long)]
+    ideas_on_change: bool,
+
+    /// Only analyze signals in this category (repeatable)
+    #[arg(long = "include-category", value_name = "CATEGORY")]
+    include_categories: Vec<String>,
+
+    /// Drop signals in this category (repeatable)
+    #[arg(long = "exclude-category", value_name = "CATEGORY")]
+    exclude_categories: Vec<String>,
 
+    /// Write the exact system prompt and user message of every LLM call to DIR (`-` for stderr)
+    #[arg(long, value_name = "DIR")]
+    explain_prompt: Option<String>,
+
+    /// Print wall-clock time and request count per source collection and LLM call, slowest first
+    #[arg(long)]
+    profile: bool,
+
+    /// Merge signals from this NDJSON file (one signal per line) into the collected set [default: external.signals_file]
+    #[arg(long, value_name = "FILE")]
+    merge_signals: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<ExitCode> {
     dotenvy::from_path("../.env").ok();
     dotenvy::dotenv().ok();
 
     let cli = Cli::parse();
+    let log_format: logging::LogFormat = match cli.log_format.as_deref() {
+        Some(format) => format.parse().map_err(anyhow::Error::msg)?,
+        None => match std::env::var("LOG_FORMAT") {
+            Ok(format) => format.parse().map_err(anyhow::Error::msg)?,
+            Err(_) => logging::LogFormat::default(),
+        },
+    };
+    logging::init(log_format);
 
-    match cli.command {
-        Command::Run {
+    let status = match cli.command {
+        Command::Run(args) => return run(args).await,
+        Command::Init { config, force } => init_config(config, force).await,
+        Command::Signals {
+            config,
+            compact,
+            ndjson,
+            groups_json,
+        } => {
+            let style = if ndjson {
+                output::json::JsonStyle::Ndjson
+            } else if compact {
+                output::json::JsonStyle::Compact
+            } else {
+                output::json::JsonStyle::Pretty
+            };
+            signals_only(config, style, groups_json).await
+        }
+        Command::Analyze {
             config,
+            signals,
             output,
-            provider,
-            model,
-        } => run(config, output, provider, model).await,
-        Command::Signals { config } => signals_only(config).await,
+        } => analyze_snapshot(config, signals, output).await,
+        Command::Validate { config } => validate(config).await,
+        Command::Sources { config } => list_sources(config),
+        Command::Listen { config, bind } => listen(config, bind).await,
+        Command::Compare { config, providers } => compare_providers(config, providers).await,
+        Command::Tui { config } => dashboard(config).await,
+        Command::Explain { config, narrative } => explain(config, narrative),
+        Command::Prune {
+            config,
+            keep_days,
+            keep_runs,
+        } => prune(config, keep_days, keep_runs),
         Command::Render {
             signals,
             narratives,
             ideas,
             output,
-        } => render_from_files(signals, narratives, ideas, output),
-    }
+            theme,
+        } => render_from_files(signals, narratives, ideas, output, theme),
+    };
+    status.map(|()| ExitCode::SUCCESS)
 }
 
-async fn run(
-    config_path: PathBuf,
-    output_override: Option<PathBuf>,
-    provider_override: Option<String>,
-    model_override: Option<String>,
-) -> Result<()> {
-    let mut cfg = config::Config::load(&config_path)
-        .context(format!("loading config from {}", config_path.display()))?;
+fn load_config(source: &config::ConfigSource) -> Result<config::Config> {
+    config::Config::from_source(source).context(format!("loading config from {source}"))
+}
+
+/// Exits 0 on a complete run, 2 when some sources failed, 3 when the report
+/// is signals only (see [`pipeline::RunStatus`]); hard failures exit 1.
+async fn run(args: RunArgs) -> Result<ExitCode> {
+    let RunArgs {
+        config,
+        output: output_override,
+        output_dir,
+        provider: provider_override,
+        model: model_override,
+        theme: theme_override,
+        format,
+        email: email_to,
+        check_links,
+        ideas_on_change,
+        include_categories,
+        exclude_categories,
+        explain_prompt,
+        merge_signals,
+        profile,
+    } = args;
+    let started = std::time::Instant::now();
+    let format: output::Format = format.parse().map_err(anyhow::Error::msg)?;
+    let mut cfg = config.load()?;
     cfg.validate()?;
 
     // Apply CLI overrides
@@ -122,97 +380,162 @@ async fn run(
     if let Some(m) = model_override {
         cfg.llm.model = m;
     }
-
-    let output_path = output_override.unwrap_or_else(|| PathBuf::from(&cfg.output.path));
-    let http_client = http::HttpClient::new("st-narrative/0.1.0 (solscout)")?;
-
-    // Collect signals from all sources in parallel
-    info!("collecting signals from all sources...");
-    let (github_result, solana_result, social_result, defi_llama_result) = tokio::join!(
-        sources::github::collect(&cfg.github, &http_client),
-        sources::solana_rpc::collect(&cfg.solana, &http_client),
-        sources::social::collect(&cfg.social, &http_client),
-        sources::defi_llama::collect(&cfg.defi_llama, &http_client),
-    );
-
-    let mut signals = Vec::new();
-
-    match github_result {
-        Ok(s) => {
-            info!(count = s.len(), "GitHub signals collected");
-            signals.extend(s);
-        }
-        Err(e) => tracing::error!("GitHub collection failed: {e}"),
+    if let Some(t) = theme_override {
+        cfg.report.theme = t.parse().map_err(anyhow::Error::msg)?;
     }
-
-    match solana_result {
-        Ok(s) => {
-            info!(count = s.len(), "Solana onchain signals collected");
-            signals.extend(s);
-        }
-        Err(e) => tracing::error!("Solana RPC collection failed: {e}"),
+    cfg.output.check_links |= check_links;
+    cfg.analysis.ideas_on_change |= ideas_on_change;
+    cfg.analysis.include_categories.extend(include_categories);
+    cfg.analysis.exclude_categories.extend(exclude_categories);
+    if explain_prompt.is_some() {
+        cfg.llm.prompt_dump = explain_prompt;
     }
-
-    match social_result {
-        Ok(s) => {
-            info!(count = s.len(), "Social signals collected");
-            signals.extend(s);
-        }
-        Err(e) => tracing::error!("Social collection failed: {e}"),
+    if let Some(path) = merge_signals {
+        cfg.external.signals_file = Some(path.display().to_string());
     }
 
-    match defi_llama_result {
-        Ok(s) => {
-            info!(count = s.len(), "DeFiLlama TVL signals collected");
-            signals.extend(s);
-        }
-        Err(e) => tracing::error!("DeFiLlama collection failed: {e}"),
+    if output_dir.is_some() && format != output::Format::Html {
+        anyhow::bail!("--output-dir only applies to HTML reports");
     }
+    let output_path = match (&output_dir, output_override) {
+        (Some(dir), _) => output::index::stamped_path(dir, chrono::Utc::now()),
+        (None, Some(path)) => path,
+        (None, None) => PathBuf::from(&cfg.output.path).with_extension(format.extension()),
+    };
+    let http_client = http::HttpClient::new(&cfg.http)?;
 
-    if signals.is_empty() {
-        anyhow::bail!(
-            "No signals collected from any source. Check API keys and network connectivity."
+    if format == output::Format::Csv {
+        let (signals, sources) = pipeline::collect_signals_with_status(&cfg, &http_client).await;
+        output::csv::write_signals(&output_path, &signals)?;
+        info!(path = %output_path.display(), "CSV written");
+        println!(
+            "CSV written: {} ({} signals)",
+            output_path.display(),
+            signals.len()
         );
+        if profile {
+            print!(
+                "\n{}",
+                output::profile::table(&sources, &[], started.elapsed())
+            );
+        }
+        let status = pipeline::RunStatus::of(&sources, None, None);
+        return Ok(exit_with(status, status.summary(&sources, None, None)));
     }
 
-    info!(total = signals.len(), "total signals collected");
-
-    // Aggregate signals
-    let groups = analysis::aggregator::aggregate(&signals);
-    let signals_json = analysis::aggregator::signals_to_json(&signals, &groups);
-
-    info!(groups = groups.len(), "signal groups formed");
-
-    // LLM analysis: identify narratives
-    let llm_client = llm::LlmClient::from_config(
-        cfg.llm.provider.clone(),
-        cfg.llm.model.clone(),
-        cfg.llm.max_tokens,
-        cfg.llm.api_key_env.clone(),
-        cfg.llm.base_url.clone(),
-    )?;
-
-    let narratives = analysis::synthesizer::identify_narratives(&llm_client, &signals_json).await?;
-    info!(count = narratives.len(), "narratives identified");
+    // Read before the pipeline overwrites it with this run.
+    let previous = email_to.as_ref().and_then(|_| {
+        pipeline::Analysis::load(std::path::Path::new(&cfg.history.last_run_path)).ok()
+    });
 
-    // LLM analysis: generate build ideas
-    let build_ideas = analysis::ideas::generate_ideas(&llm_client, &narratives).await?;
-    info!(count = build_ideas.len(), "build ideas generated");
+    let analysis = pipeline::run(&cfg, &http_client).await?;
+    let pipeline::Analysis {
+        signals,
+        narratives,
+        build_ideas,
+        analysis_skipped,
+        ideas_skipped,
+        produced_by,
+        sources: source_statuses,
+        llm_calls,
+        ..
+    } = &analysis;
 
-    // Render HTML report
-    let html = output::report::render(&signals, &narratives, &build_ideas)?;
+    let locale = output::locale::Localization::from_config(&cfg.report)?;
+    let html = render_analysis(&cfg, &analysis, &locale)?;
     output::report::write_report(&output_path, &html)?;
 
     info!(path = %output_path.display(), "report written");
+    if let Some(dir) = &output_dir {
+        let listed = output::index::write_index(dir, cfg.report.theme, &locale)?;
+        info!(dir = %dir.display(), reports = listed, "report index rebuilt");
+    }
+
+    // The report is already on disk; a mail failure must not fail the run.
+    if let Some(to) = &email_to {
+        let subject = format!(
+            "SolScout digest — {}",
+            chrono::Utc::now().format("%Y-%m-%d")
+        );
+        let sent = match output::email::render_digest(&analysis, previous.as_ref()) {
+            Ok(digest) => output::email::send(&cfg.email, to, &subject, &digest).await,
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(()) => info!(to = %to, "email digest sent"),
+            Err(e) => tracing::warn!("email digest not sent: {e}"),
+        }
+    }
     println!("Report generated: {}", output_path.display());
     println!("  {} signals from {} sources", signals.len(), {
         let sources: std::collections::HashSet<_> = signals.iter().map(|s| s.source).collect();
         sources.len()
     });
-    println!("  {} narratives identified", narratives.len());
-    println!("  {} build ideas generated", build_ideas.len());
+    if let Some(reason) = &analysis_skipped {
+        println!("  LLM analysis skipped: {reason}");
+    } else if narratives.is_empty() {
+        println!("  Insufficient signal for narratives this run (no ideas generated)");
+    } else {
+        println!("  {} narratives identified", narratives.len());
+        if let Some(model) = produced_by {
+            println!("  Analysis by {model}");
+        }
+        match ideas_skipped {
+            Some(reason) => println!("  Build ideas skipped: {reason}"),
+            None => println!("  {} build ideas generated", build_ideas.len()),
+        }
+    }
+    if profile {
+        print!(
+            "\n{}",
+            output::profile::table(source_statuses, llm_calls, started.elapsed())
+        );
+    }
 
-    Ok(())
+    Ok(exit_with(analysis.status(), analysis.summary()))
+}
+
+/// Render a pipeline run's HTML report with the configured report options.
+fn render_analysis(
+    cfg: &config::Config,
+    analysis: &pipeline::Analysis,
+    locale: &output::locale::Localization,
+) -> Result<String> {
+    Ok(output::report::render(
+        &analysis.signals,
+        &analysis.narratives,
+        &analysis.build_ideas,
+        &output::report::RenderOptions {
+            theme: cfg.report.theme,
+            locale: locale.clone(),
+            trend_weights: cfg.analysis.trend_weights.clone(),
+            sort_by: cfg.narrative_sort(),
+            unchanged_sources: analysis
+                .sources
+                .iter()
+                .filter(|s| s.unchanged)
+                .map(|s| s.name.clone())
+                .collect(),
+            min_confidence: cfg.report.min_confidence,
+            headline_metrics: cfg.analysis.headline_metrics.clone(),
+            embed_raw_signals: cfg.report.embed_raw_signals,
+            template_path: cfg.report.template_path.clone(),
+            stale_after: (cfg.report.stale_after_hours > 0)
+                .then(|| chrono::Duration::hours(cfg.report.stale_after_hours as i64)),
+            run_metadata: analysis.metadata.clone(),
+        },
+        analysis.analysis_skipped.as_deref(),
+        analysis.produced_by.as_deref(),
+    )?)
+}
+
+/// Print what degraded, if anything, to stderr and turn the status into
+/// the process exit code.
+fn exit_with(status: pipeline::RunStatus, summary: Option<String>) -> ExitCode {
+    if let Some(summary) = summary {
+        eprintln!("{summary}");
+    }
+    ExitCode::from(status.exit_code())
 }
 
 fn render_from_files(
@@ -220,15 +543,28 @@ fn render_from_files(
     narratives_path: PathBuf,
     ideas_path: PathBuf,
     output_path: PathBuf,
+    theme: String,
 ) -> Result<()> {
-    let signals: Vec<types::Signal> =
+    let theme: types::Theme = theme.parse().map_err(anyhow::Error::msg)?;
+    let mut signals: Vec<types::Signal> =
         serde_json::from_str(&std::fs::read_to_string(&signals_path)?)?;
+    types::fill_missing_ids(&mut signals);
     let narratives: Vec<types::Narrative> =
         serde_json::from_str(&std::fs::read_to_string(&narratives_path)?)?;
     let build_ideas: Vec<types::BuildIdea> =
         serde_json::from_str(&std::fs::read_to_string(&ideas_path)?)?;
 
-    let html = output::report::render(&signals, &narratives, &build_ideas)?;
+    let html = output::report::render(
+        &signals,
+        &narratives,
+        &build_ideas,
+        &output::report::RenderOptions {
+            theme,
+            ..Default::default()
+        },
+        None,
+        None,
+    )?;
     output::report::write_report(&output_path, &html)?;
 
     info!(path = %output_path.display(), "report rendered from analysis files");
@@ -242,35 +578,596 @@ fn render_from_files(
     Ok(())
 }
 
-async fn signals_only(config_path: PathBuf) -> Result<()> {
-    let cfg = config::Config::load(&config_path)
-        .context(format!("loading config from {}", config_path.display()))?;
+async fn compare_providers(config: ConfigArg, providers: Vec<String>) -> Result<()> {
+    let specs = compare::parse_specs(&providers)?;
+    let cfg = config.load()?;
+    cfg.validate()?;
+    let http_client = http::HttpClient::new(&cfg.http)?;
 
-    let http_client = http::HttpClient::new("st-narrative/0.1.0 (solscout)")?;
+    let signals = pipeline::collect_signals(&cfg, &http_client).await;
+    if signals.is_empty() {
+        anyhow::bail!(
+            "No signals collected from any source. Check API keys and network connectivity."
+        );
+    }
+    // An evaluation, not a run: score against history without recording.
+    let signals_json = pipeline::prepare(&cfg, &signals)?;
 
-    let (github_result, solana_result, social_result, defi_llama_result) = tokio::join!(
-        sources::github::collect(&cfg.github, &http_client),
-        sources::solana_rpc::collect(&cfg.solana, &http_client),
-        sources::social::collect(&cfg.social, &http_client),
-        sources::defi_llama::collect(&cfg.defi_llama, &http_client),
+    let runs = compare::run(&cfg, &specs, &signals_json).await;
+    println!("{} signals, same input for every provider\n", signals.len());
+    print!("{}", compare::table(&runs));
+    if runs.iter().all(|r| r.narratives.is_err()) {
+        anyhow::bail!("every provider failed");
+    }
+    Ok(())
+}
+
+async fn init_config(config_path: PathBuf, force: bool) -> Result<()> {
+    if config_path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite it",
+            config_path.display()
+        );
+    }
+    let http_client = http::HttpClient::new(&config::HttpConfig::default())?;
+    let rpc_health = r#"{"jsonrpc":"2.0","id":1,"method":"getHealth"}"#;
+    let check_rpc = |url: &str| {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(http_client.probe(
+                reqwest::Method::POST,
+                url,
+                &[],
+                Some(rpc_health),
+            ))
+        })
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    };
+
+    println!("SolScout setup. Press Enter to accept the [default].\n");
+    let stdin = std::io::stdin();
+    let answers = init::ask(
+        &mut init::Prompter::new(stdin.lock(), std::io::stdout()),
+        check_rpc,
+    )?;
+
+    let toml_text = init::config_toml(&answers);
+    init::verify(&toml_text).map_err(anyhow::Error::msg)?;
+    std::fs::write(&config_path, toml_text)?;
+    println!("\nWrote {}", config_path.display());
+
+    let env_path = config_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(".env.example");
+    if env_path.exists() && !force {
+        println!(
+            "Kept existing {} (--force overwrites it)",
+            env_path.display()
+        );
+    } else {
+        std::fs::write(&env_path, init::env_example(&answers))?;
+        println!(
+            "Wrote {}; copy it to .env and fill in the keys",
+            env_path.display()
+        );
+    }
+    println!("Next: cargo run -- validate -c {}", config_path.display());
+    Ok(())
+}
+
+fn prune(config: ConfigArg, keep_days: Option<u32>, keep_runs: Option<usize>) -> Result<()> {
+    let cfg = config.load()?;
+    let retention = match (keep_days, keep_runs) {
+        (Some(days), _) => {
+            history::Retention::Since(chrono::Utc::now() - chrono::Duration::days(i64::from(days)))
+        }
+        (None, Some(runs)) => history::Retention::Runs(runs),
+        (None, None) => anyhow::bail!("pass --keep-days or --keep-runs"),
+    };
+    let size = |path: &Path| std::fs::metadata(path).map(|m| m.len()).ok();
+    let mut reclaimed = 0;
+
+    let path = Path::new(&cfg.history.path);
+    if let Some(before) = size(path) {
+        let mut store = history::History::load(path)?;
+        let runs = store.prune(retention);
+        // Rewriting the file is the compaction: it is stored without padding.
+        store.save()?;
+        let freed = before.saturating_sub(size(path).unwrap_or(0));
+        reclaimed += freed;
+        println!(
+            "{}: removed {runs} run(s), {} reclaimed",
+            path.display(),
+            bytes(freed)
+        );
+    } else {
+        println!("{}: no metric history", path.display());
+    }
+
+    let path = Path::new(&cfg.history.narratives_path);
+    if let Some(before) = size(path) {
+        let mut store = narrative_history::NarrativeHistory::load(path)?;
+        let pruned = store.prune(retention);
+        store.save()?;
+        let freed = before.saturating_sub(size(path).unwrap_or(0));
+        reclaimed += freed;
+        println!(
+            "{}: removed {} run(s) and {} narrative(s) seen only in them, {} reclaimed",
+            path.display(),
+            pruned.runs,
+            pruned.narratives,
+            bytes(freed)
+        );
+        if pruned.kept_for_streaks > 0 {
+            println!(
+                "  kept {} older run(s): narratives in the latest run have streaks reaching back into them",
+                pruned.kept_for_streaks
+            );
+        }
+    } else {
+        println!("{}: no narrative history", path.display());
+    }
+
+    println!("Reclaimed {} in total", bytes(reclaimed));
+    Ok(())
+}
+
+fn bytes(n: u64) -> String {
+    match n {
+        0..1024 => format!("{n} B"),
+        1024..1_048_576 => format!("{:.1} KiB", n as f64 / 1024.0),
+        _ => format!("{:.1} MiB", n as f64 / 1_048_576.0),
+    }
+}
+
+async fn dashboard(config: ConfigArg) -> Result<()> {
+    let cfg = config.load_without_stdin()?;
+    cfg.validate()?;
+    let http_client = http::HttpClient::new(&cfg.http)?;
+
+    loop {
+        let analysis = pipeline::run(&cfg, &http_client).await?;
+        match tokio::task::block_in_place(|| tui::browse(&analysis))? {
+            tui::Action::Quit => return Ok(()),
+            tui::Action::Rerun => info!("rerunning collection"),
+        }
+    }
+}
+
+fn explain(config: ConfigArg, narrative: String) -> Result<()> {
+    let cfg = config.load()?;
+    let path = PathBuf::from(&cfg.history.last_run_path);
+    let run = pipeline::Analysis::load(&path).context(format!(
+        "reading last run from {} (run the pipeline with history enabled first)",
+        path.display()
+    ))?;
+
+    let history = cfg
+        .history
+        .enabled
+        .then(|| history::History::load(Path::new(&cfg.history.path)).ok())
+        .flatten();
+
+    let found = match narrative.parse::<usize>() {
+        Ok(n) if n >= 1 => run.narratives.get(n - 1),
+        _ => run.narratives.iter().find(|n| n.id == narrative),
+    };
+    let Some(n) = found else {
+        eprintln!("No narrative '{narrative}' in the last run. Available:");
+        for (i, n) in run.narratives.iter().enumerate() {
+            eprintln!("  {:>2}. {}  {}", i + 1, n.id, n.title);
+        }
+        anyhow::bail!("narrative not found");
+    };
+
+    println!("{} [{}]", n.title, n.id);
+    println!(
+        "{} · {}% confidence · {} supporting signals",
+        n.trend,
+        (n.confidence() * 100.0) as u32,
+        n.supporting_signals.len()
+    );
+    if let Some(c) = &n.continuity {
+        let trajectory: Vec<String> = c
+            .confidence_trajectory
+            .iter()
+            .map(|v| format!("{:.0}%", v * 100.0))
+            .collect();
+        println!(
+            "{} run(s) in a row, first seen {} · confidence by run: {}",
+            c.streak,
+            c.first_seen.format("%Y-%m-%d"),
+            trajectory.join(" → ")
+        );
+    }
+    if let Some(r) = n.recurrence {
+        println!("found by {} of {} synthesis runs", r.appeared, r.runs);
+    }
+    println!("\n{}\n", n.summary);
+
+    let mut dangling = 0;
+    for id in &n.supporting_signals {
+        let Some(s) = run.signals.iter().find(|s| &s.id == id) else {
+            dangling += 1;
+            println!("✗ {id}  not in this run's signals (hallucinated or dropped reference)\n");
+            continue;
+        };
+        println!("✓ {}  [{}] {}", s.id, s.source, s.category);
+        println!("  {}", s.title);
+        if !s.description.is_empty() {
+            for line in s.description.lines() {
+                println!("  {line}");
+            }
+        }
+        for m in &s.metrics {
+            println!("    {m}");
+            let band = history
+                .as_ref()
+                .and_then(|h| h.latest_band(&history::metric_key(s, m), cfg.history.window));
+            if let Some((latest, band)) = band {
+                println!(
+                    "      history: mean {:.1}, 2σ band {:.1}..{:.1}{}",
+                    band.mean,
+                    band.lower(OUTLIER_SIGMAS),
+                    band.upper(OUTLIER_SIGMAS),
+                    if band.is_outside(latest, OUTLIER_SIGMAS) {
+                        "  ⚠ outside 2σ, worth investigating"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+        if let Some(url) = &s.url {
+            println!("  {url}");
+        }
+        println!();
+    }
+    if dangling > 0 {
+        println!(
+            "{dangling} of {} references could not be resolved.",
+            n.supporting_signals.len()
+        );
+    }
+    Ok(())
+}
+
+async fn signals_only(
+    config: ConfigArg,
+    style: output::json::JsonStyle,
+    groups_path: Option<PathBuf>,
+) -> Result<()> {
+    let cfg = config.load()?;
+
+    let http_client = http::HttpClient::new(&cfg.http)?;
+
+    let signals = pipeline::collect_signals(&cfg, &http_client).await;
+
+    if let Some(path) = groups_path {
+        let mut groups =
+            analysis::aggregator::aggregate(&signals, cfg.analysis.recency_half_life_hours);
+        analysis::aggregator::mark_headlines(&mut groups, &cfg.analysis.headline_metrics);
+        let file = std::fs::File::create(&path)
+            .context(format!("creating groups file {}", path.display()))?;
+        output::json::write_groups(std::io::BufWriter::new(file), &signals, &groups)?;
+        info!(groups = groups.len(), path = %path.display(), "signal groups written");
+    }
+
+    let stdout = std::io::stdout().lock();
+    output::json::write_signals(std::io::BufWriter::new(stdout), &signals, style)?;
+
+    Ok(())
+}
+
+async fn analyze_snapshot(
+    config: ConfigArg,
+    signals_path: PathBuf,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let cfg = config.load()?;
+    let mut signals = pipeline::load_signals(&signals_path)?;
+    pipeline::annotate(&cfg, &mut signals)?;
+    if signals.is_empty() {
+        anyhow::bail!("snapshot {} has no signals", signals_path.display());
+    }
+    info!(count = signals.len(), path = %signals_path.display(), "signal snapshot loaded");
+
+    // A replay, not a new run: scored against history without recording.
+    let signals_json = pipeline::prepare(&cfg, &signals)?;
+    let pipeline::Analyzed {
+        narratives,
+        build_ideas,
+        produced_by,
+        ..
+    } = pipeline::analyze(&cfg, &signals, &signals_json).await?;
+
+    let output_path = output.unwrap_or_else(|| PathBuf::from(&cfg.output.path));
+    let html = output::report::render(
+        &signals,
+        &narratives,
+        &build_ideas,
+        &output::report::RenderOptions {
+            theme: cfg.report.theme,
+            locale: output::locale::Localization::from_config(&cfg.report)?,
+            trend_weights: cfg.analysis.trend_weights.clone(),
+            sort_by: cfg.narrative_sort(),
+            min_confidence: cfg.report.min_confidence,
+            headline_metrics: cfg.analysis.headline_metrics.clone(),
+            embed_raw_signals: cfg.report.embed_raw_signals,
+            template_path: cfg.report.template_path.clone(),
+            stale_after: (cfg.report.stale_after_hours > 0)
+                .then(|| chrono::Duration::hours(cfg.report.stale_after_hours as i64)),
+            ..Default::default()
+        },
+        None,
+        Some(&produced_by),
+    )?;
+    output::report::write_report(&output_path, &html)?;
+
+    info!(path = %output_path.display(), "report written from snapshot");
+    println!(
+        "Report generated: {} ({} signals from snapshot, {} narratives, {} ideas)",
+        output_path.display(),
+        signals.len(),
+        narratives.len(),
+        build_ideas.len()
     );
+    Ok(())
+}
 
-    let mut signals = Vec::new();
-    if let Ok(s) = github_result {
-        signals.extend(s);
+async fn listen(config: ConfigArg, bind: Option<String>) -> Result<()> {
+    let cfg = config.load_without_stdin()?;
+    cfg.validate()?;
+    let secret = std::env::var(&cfg.listen.secret_env).unwrap_or_default();
+    if secret.is_empty() {
+        anyhow::bail!(
+            "{} is not set; refusing to accept unauthenticated triggers",
+            cfg.listen.secret_env
+        );
     }
-    if let Ok(s) = solana_result {
-        signals.extend(s);
+    let bind = bind.unwrap_or_else(|| cfg.listen.bind.clone());
+    let listener = tokio::net::TcpListener::bind(&bind)
+        .await
+        .context(format!("binding {bind}"))?;
+    println!("Listening on {bind}: POST /runs to trigger, GET /runs/<id> to poll");
+
+    let cfg = std::sync::Arc::new(cfg);
+    let http_client = http::HttpClient::new(&cfg.http)?;
+    let runner: listen::Runner = std::sync::Arc::new(move || {
+        let (cfg, http_client) = (cfg.clone(), http_client.clone());
+        Box::pin(async move {
+            let analysis = pipeline::run(&cfg, &http_client).await?;
+            let locale = output::locale::Localization::from_config(&cfg.report)?;
+            let output_path = PathBuf::from(&cfg.output.path);
+            output::report::write_report(
+                &output_path,
+                &render_analysis(&cfg, &analysis, &locale)?,
+            )?;
+            info!(path = %output_path.display(), "report written");
+            Ok(serde_json::json!({
+                "report_path": output_path,
+                "degraded": analysis.summary(),
+                "analysis": analysis,
+            }))
+        })
+    });
+    listen::serve(listener, secret, runner).await?;
+    Ok(())
+}
+
+/// One row of the `validate` report.
+struct Check {
+    name: String,
+    required: bool,
+    outcome: std::result::Result<String, String>,
+}
+
+async fn validate(config: ConfigArg) -> Result<()> {
+    let source = config.source(true)?;
+    let cfg = load_config(&source)?;
+    let http_client = http::HttpClient::new(&cfg.http)?;
+
+    let mut checks = vec![Check {
+        name: "Config".into(),
+        required: true,
+        outcome: cfg
+            .validate()
+            .map(|_| source.to_string())
+            .map_err(|e| e.to_string()),
+    }];
+
+    for env_var in cfg.llm.key_env_vars() {
+        checks.push(Check {
+            name: "LLM API key".into(),
+            required: false,
+            outcome: match std::env::var(&env_var) {
+                Ok(v) if !v.is_empty() => {
+                    Ok(format!("${env_var} is set ({})", llm::redact_key(&v)))
+                }
+                _ => Err(format!("${env_var} is not set")),
+            },
+        });
+    }
+
+    let github_auth = format!("Bearer {}", cfg.github.token);
+    let github_headers = [
+        ("Authorization", github_auth.as_str()),
+        ("Accept", "application/vnd.github+json"),
+    ];
+    let rpc_health = r#"{"jsonrpc":"2.0","id":1,"method":"getHealth"}"#;
+    let (github, solana) = tokio::join!(
+        http_client.probe(
+            reqwest::Method::GET,
+            "https://api.github.com/rate_limit",
+            &github_headers,
+            None,
+        ),
+        http_client.probe(
+            reqwest::Method::POST,
+            &cfg.solana.rpc_url,
+            &[],
+            Some(rpc_health),
+        ),
+    );
+    checks.push(probe_check("GitHub API", true, github));
+    checks.push(probe_check("Solana RPC", true, solana));
+
+    if cfg.defi_llama.enabled {
+        let result = http_client
+            .probe(
+                reqwest::Method::HEAD,
+                "https://api.llama.fi/v2/chains",
+                &[],
+                None,
+            )
+            .await;
+        checks.push(probe_check("DeFiLlama", false, result));
+    }
+
+    if cfg.nft.enabled {
+        for symbol in &cfg.nft.collections {
+            let url = format!("{}/collections/{symbol}/stats", cfg.nft.base_url);
+            let result = http_client
+                .probe(reqwest::Method::GET, &url, &[], None)
+                .await;
+            checks.push(probe_check(&format!("NFT: {symbol}"), false, result));
+        }
+    }
+
+    if cfg.farcaster.enabled {
+        match sources::farcaster::probe_url(&cfg.farcaster) {
+            Some(url) => {
+                let key = std::env::var(&cfg.farcaster.api_key_env).unwrap_or_default();
+                let result = http_client
+                    .probe(
+                        reqwest::Method::GET,
+                        &url,
+                        &[("x-api-key", key.as_str()), ("accept", "application/json")],
+                        None,
+                    )
+                    .await;
+                checks.push(probe_check("Farcaster (Neynar)", false, result));
+            }
+            None => checks.push(Check {
+                name: "Farcaster (Neynar)".into(),
+                required: false,
+                outcome: Err("no channels or keywords configured".into()),
+            }),
+        }
+    }
+
+    if cfg.telegram.enabled {
+        checks.push(Check {
+            name: "Telegram bot token".into(),
+            required: false,
+            outcome: match std::env::var(&cfg.telegram.bot_token_env) {
+                Ok(v) if !v.is_empty() => Ok(format!(
+                    "${} is set, {} channels",
+                    cfg.telegram.bot_token_env,
+                    cfg.telegram.channels.len()
+                )),
+                _ => Err(format!("${} is not set", cfg.telegram.bot_token_env)),
+            },
+        });
+    }
+
+    if cfg.dune.enabled {
+        checks.push(Check {
+            name: "Dune API key".into(),
+            required: false,
+            outcome: match std::env::var(&cfg.dune.api_key_env) {
+                Ok(v) if !v.is_empty() => Ok(format!(
+                    "${} is set, {} queries",
+                    cfg.dune.api_key_env,
+                    cfg.dune.queries.len()
+                )),
+                _ => Err(format!("${} is not set", cfg.dune.api_key_env)),
+            },
+        });
     }
-    if let Ok(s) = social_result {
-        signals.extend(s);
+
+    for source in &cfg.social.sources {
+        let result = http_client
+            .probe(reqwest::Method::HEAD, &source.url, &[], None)
+            .await;
+        checks.push(probe_check(
+            &format!("Blog: {}", source.name),
+            false,
+            result,
+        ));
+    }
+
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in &checks {
+        let (mark, detail) = match &check.outcome {
+            Ok(d) => ("✓", d.as_str()),
+            Err(d) => ("✗", d.as_str()),
+        };
+        let optional = if check.required { "" } else { " (optional)" };
+        println!("{mark} {:<width$}  {detail}{optional}", check.name);
     }
-    if let Ok(s) = defi_llama_result {
-        signals.extend(s);
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.required && c.outcome.is_err())
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{failed} required check(s) failed");
     }
+    Ok(())
+}
+
+fn list_sources(config: ConfigArg) -> Result<()> {
+    let cfg = config.load()?;
 
-    let json = serde_json::to_string_pretty(&signals)?;
-    println!("{json}");
+    let rows: Vec<[String; 4]> = sources::registry(&cfg)
+        .into_iter()
+        .map(|source| {
+            let status = if source.enabled() {
+                "enabled"
+            } else {
+                "disabled"
+            };
+            let key = match source.key_env() {
+                Some(env) if source.key_set() => format!("${env} set"),
+                Some(env) => format!("${env} missing"),
+                None => "-".into(),
+            };
+            let (count, items) = source.tracked();
+            [
+                source.name().into(),
+                status.into(),
+                key,
+                format!("{count} {items}"),
+            ]
+        })
+        .collect();
 
+    let header = ["SOURCE", "STATUS", "API KEY", "TRACKED"].map(String::from);
+    let mut widths = [0; 3];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for [name, status, key, tracked] in std::iter::once(&header).chain(&rows) {
+        println!(
+            "{name:<w0$}  {status:<w1$}  {key:<w2$}  {tracked}",
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+        );
+    }
     Ok(())
 }
+
+fn probe_check(name: &str, required: bool, result: error::Result<u16>) -> Check {
+    Check {
+        name: name.into(),
+        required,
+        outcome: result
+            .map(|status| format!("HTTP {status}"))
+            .map_err(|e| e.to_string()),
+    }
+}
@@ -0,0 +1,318 @@
+use crate::error::{Error, Result};
+use crate::history::Retention;
+use crate::types::{Continuity, Narrative};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Most recent confidences kept on a narrative's [`Continuity`].
+const TRAJECTORY_LEN: usize = 10;
+
+/// Words that say nothing about which narrative a title describes.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "by", "for", "from", "in", "into", "is", "of", "on", "or", "the",
+    "to", "with",
+];
+
+/// One run in which a tracked narrative was identified.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Appearance {
+    /// Index into the store's runs.
+    pub run: usize,
+    pub confidence: f64,
+}
+
+/// A narrative followed across runs under the ID it first appeared with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedNarrative {
+    pub id: String,
+    /// Title from the latest run it appeared in.
+    pub title: String,
+    pub first_seen: DateTime<Utc>,
+    pub appearances: Vec<Appearance>,
+}
+
+/// What [`NarrativeHistory::prune`] dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pruned {
+    pub runs: usize,
+    /// Runs outside the retention kept because a live streak covers them.
+    pub kept_for_streaks: usize,
+    /// Tracked narratives dropped with their last appearance.
+    pub narratives: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Store {
+    runs: Vec<DateTime<Utc>>,
+    narratives: Vec<TrackedNarrative>,
+}
+
+/// File-backed store of narratives from past runs, persisted as JSON at
+/// `history.narratives_path`.
+#[derive(Debug, Default)]
+pub struct NarrativeHistory {
+    path: PathBuf,
+    store: Store,
+}
+
+impl NarrativeHistory {
+    /// Load the store at `path`. A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self> {
+        let store = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| Error::parse(format!("narrative history {}: {e}", path.display())))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Store::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            store,
+        })
+    }
+
+    /// Drop the runs outside `retention`, with the appearances in them and
+    /// the narratives left without any. Runs holding the streak of a
+    /// narrative seen in the latest run are kept regardless, so pruning
+    /// never shortens a streak that can still continue.
+    pub fn prune(&mut self, retention: Retention) -> Pruned {
+        let requested = retention.expired(&self.store.runs);
+        let latest = self.store.runs.len().checked_sub(1);
+        let streaks_from = self
+            .store
+            .narratives
+            .iter()
+            .filter(|tracked| tracked.appearances.last().map(|a| a.run) == latest)
+            .map(|tracked| latest.unwrap_or(0) + 1 - continuity(tracked).streak as usize)
+            .min()
+            .unwrap_or(requested);
+        let expired = requested.min(streaks_from);
+
+        self.store.runs.drain(..expired);
+        let before = self.store.narratives.len();
+        self.store.narratives.retain_mut(|tracked| {
+            tracked.appearances.retain(|a| a.run >= expired);
+            for appearance in &mut tracked.appearances {
+                appearance.run -= expired;
+            }
+            !tracked.appearances.is_empty()
+        });
+        Pruned {
+            runs: expired,
+            kept_for_streaks: requested - expired,
+            narratives: before - self.store.narratives.len(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(&self.store)
+            .map_err(|e| Error::parse(format!("serialize narrative history: {e}")))?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    /// Record `narratives` as a new run started at `at` and set each one's
+    /// [`Continuity`]. A narrative continues the tracked narrative whose
+    /// latest title is most similar to its own, if that similarity reaches
+    /// `threshold`; each tracked narrative continues at most once per run.
+    /// Returns how many narratives continued an earlier one.
+    pub fn track(
+        &mut self,
+        narratives: &mut [Narrative],
+        at: DateTime<Utc>,
+        threshold: f64,
+    ) -> usize {
+        let run = self.store.runs.len();
+        self.store.runs.push(at);
+
+        let mut candidates = Vec::new();
+        for (n, narrative) in narratives.iter().enumerate() {
+            for (t, tracked) in self.store.narratives.iter().enumerate() {
+                let score = if tracked.id == narrative.id {
+                    1.0
+                } else {
+                    title_similarity(&tracked.title, &narrative.title)
+                };
+                if score >= threshold {
+                    candidates.push((score, n, t));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut matched: Vec<Option<usize>> = vec![None; narratives.len()];
+        let mut taken = vec![false; self.store.narratives.len()];
+        for (_, n, t) in candidates {
+            if matched[n].is_none() && !taken[t] {
+                matched[n] = Some(t);
+                taken[t] = true;
+            }
+        }
+
+        let mut continued = 0;
+        for (narrative, matched) in narratives.iter_mut().zip(matched) {
+            let appearance = Appearance {
+                run,
+                confidence: narrative.confidence(),
+            };
+            let tracked = match matched {
+                Some(t) => {
+                    continued += 1;
+                    let tracked = &mut self.store.narratives[t];
+                    tracked.title.clone_from(&narrative.title);
+                    tracked.appearances.push(appearance);
+                    tracked
+                }
+                None => {
+                    self.store.narratives.push(TrackedNarrative {
+                        id: narrative.id.clone(),
+                        title: narrative.title.clone(),
+                        first_seen: at,
+                        appearances: vec![appearance],
+                    });
+                    self.store.narratives.last_mut().expect("just pushed")
+                }
+            };
+            narrative.continuity = Some(continuity(tracked));
+        }
+        continued
+    }
+}
+
+fn continuity(tracked: &TrackedNarrative) -> Continuity {
+    let runs: Vec<usize> = tracked.appearances.iter().map(|a| a.run).collect();
+    let streak = 1 + runs
+        .windows(2)
+        .rev()
+        .take_while(|pair| pair[1] == pair[0] + 1)
+        .count();
+    let start = tracked.appearances.len().saturating_sub(TRAJECTORY_LEN);
+    Continuity {
+        streak: streak as u32,
+        first_seen: tracked.first_seen,
+        confidence_trajectory: tracked.appearances[start..]
+            .iter()
+            .map(|a| a.confidence)
+            .collect(),
+    }
+}
+
+/// Jaccard similarity of the titles' word sets, ignoring case and stopwords:
+/// 1.0 for the same words in any order, 0.0 for no word in common.
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let words = |title: &str| -> BTreeSet<String> {
+        title
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty() && !STOPWORDS.contains(w))
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::narrative;
+    use chrono::Duration;
+
+    #[test]
+    fn titles_match_by_shared_words() {
+        assert_eq!(
+            title_similarity("Restaking on Solana", "Solana restaking"),
+            1.0
+        );
+        let reworded = title_similarity(
+            "Liquid restaking goes mainstream",
+            "Liquid restaking adoption accelerates",
+        );
+        assert!((reworded - 1.0 / 3.0).abs() < 1e-9, "{reworded}");
+        assert_eq!(title_similarity("DePIN growth", "Memecoin mania"), 0.0);
+    }
+
+    #[test]
+    fn streak_follows_reworded_titles_and_resets_after_a_gap() {
+        let mut store = NarrativeHistory::default();
+        let start = Utc::now();
+        let at = |run: i64| start + Duration::days(14 * run);
+
+        let mut first = vec![
+            narrative("Liquid restaking on Solana", 0.6),
+            narrative("DePIN hardware rollout", 0.5),
+        ];
+        assert_eq!(store.track(&mut first, at(0), 0.5), 0);
+        assert_eq!(first[0].continuity.as_ref().unwrap().streak, 1);
+
+        let mut second = vec![narrative("Solana liquid restaking", 0.7)];
+        assert_eq!(store.track(&mut second, at(1), 0.5), 1);
+        let continuity = second[0].continuity.as_ref().unwrap();
+        assert_eq!(continuity.streak, 2);
+        assert_eq!(continuity.first_seen, at(0));
+        assert_eq!(continuity.confidence_trajectory, [0.6, 0.7]);
+
+        let mut third = vec![
+            narrative("Solana liquid restaking", 0.8),
+            narrative("DePIN hardware rollout", 0.5),
+        ];
+        store.track(&mut third, at(2), 0.5);
+        assert_eq!(third[0].continuity.as_ref().unwrap().streak, 3);
+        let depin = third[1].continuity.as_ref().unwrap();
+        assert_eq!(depin.streak, 1, "missed run 1");
+        assert_eq!(depin.confidence_trajectory, [0.5, 0.5]);
+
+        let mut strict = vec![narrative("Restaking yields on Solana", 0.8)];
+        assert_eq!(store.track(&mut strict, at(3), 0.9), 0);
+        assert_eq!(strict[0].continuity.as_ref().unwrap().streak, 1);
+    }
+
+    #[test]
+    fn prune_keeps_runs_under_a_live_streak() {
+        let mut store = NarrativeHistory::default();
+        let start = Utc::now();
+        let at = |run: i64| start + Duration::days(run);
+        let runs: [&[&str]; 5] = [
+            &["DePIN hardware rollout", "Memecoin mania"],
+            &["DePIN hardware rollout"],
+            &["Solana liquid restaking"],
+            &["Solana liquid restaking"],
+            &["Solana liquid restaking", "Memecoin mania"],
+        ];
+        for (i, titles) in runs.iter().enumerate() {
+            let mut batch: Vec<Narrative> = titles.iter().map(|t| narrative(t, 0.5)).collect();
+            store.track(&mut batch, at(i as i64), 0.5);
+        }
+        assert_eq!(Retention::Since(at(3)).expired(&store.store.runs), 3);
+
+        let pruned = store.prune(Retention::Runs(1));
+        assert_eq!(
+            pruned,
+            Pruned {
+                runs: 2,
+                kept_for_streaks: 2,
+                narratives: 1,
+            }
+        );
+        assert_eq!(store.store.runs, [at(2), at(3), at(4)]);
+        let titles: Vec<&str> = store
+            .store
+            .narratives
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Memecoin mania", "Solana liquid restaking"]);
+
+        let mut next = vec![narrative("Solana liquid restaking", 0.5)];
+        store.track(&mut next, at(5), 0.5);
+        assert_eq!(next[0].continuity.as_ref().unwrap().streak, 4);
+    }
+}
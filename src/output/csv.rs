@@ -0,0 +1,132 @@
+use crate::error::Result;
+use crate::types::Signal;
+use std::path::Path;
+
+const HEADER: [&str; 8] = [
+    "source",
+    "origin",
+    "category",
+    "title",
+    "metric_name",
+    "value",
+    "unit",
+    "timestamp",
+];
+
+/// Write one row per (signal, metric). Signals without metrics still get a
+/// single row with the metric columns left empty.
+pub fn write_signals(path: &Path, signals: &[Signal]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, to_csv(signals))?;
+    Ok(())
+}
+
+fn to_csv(signals: &[Signal]) -> String {
+    let mut out = String::new();
+    push_row(&mut out, HEADER.iter().map(|h| h.to_string()));
+
+    for s in signals {
+        let source = s.source.to_string();
+        let origin = s.url.clone().unwrap_or_default();
+        let timestamp = s.timestamp.to_rfc3339();
+        let base = [source, origin, s.category.clone(), s.title.clone()];
+
+        if s.metrics.is_empty() {
+            push_row(
+                &mut out,
+                base.iter().cloned().chain([
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    timestamp,
+                ]),
+            );
+            continue;
+        }
+        for m in &s.metrics {
+            push_row(
+                &mut out,
+                base.iter().cloned().chain([
+                    m.name.clone(),
+                    m.value.to_string(),
+                    m.unit.clone(),
+                    timestamp.clone(),
+                ]),
+            );
+        }
+    }
+    out
+}
+
+fn push_row(out: &mut String, fields: impl Iterator<Item = String>) {
+    let fields: Vec<String> = fields.map(|f| escape(&f)).collect();
+    out.push_str(&fields.join(","));
+    out.push_str("\r\n");
+}
+
+/// RFC 4180 quoting: wrap fields containing separators, quotes or line breaks
+/// in double quotes, doubling any embedded quotes.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Metric, SignalSource};
+
+    #[test]
+    fn escapes_and_keeps_metricless_signals() {
+        let with_metrics = Signal::new(
+            SignalSource::GitHub,
+            "DeFi".into(),
+            r#"DeFi: 3 new repos, "hot" week"#.into(),
+            String::new(),
+            vec![
+                Metric {
+                    name: "repo_count".into(),
+                    value: 3.0,
+                    unit: "repos".into(),
+                },
+                Metric {
+                    name: "total_stars".into(),
+                    value: 120.0,
+                    unit: "stars".into(),
+                },
+            ],
+            None,
+        );
+        let bare = Signal::new(
+            SignalSource::Social,
+            "Blog".into(),
+            "Helius: Firedancer update".into(),
+            String::new(),
+            Vec::new(),
+            Some("https://www.helius.dev/blog".into()),
+        );
+
+        let csv = to_csv(&[with_metrics, bare]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            "source,origin,category,title,metric_name,value,unit,timestamp"
+        );
+        assert!(
+            lines[1].starts_with(
+                r#"GitHub,,DeFi,"DeFi: 3 new repos, ""hot"" week",repo_count,3,repos,"#
+            )
+        );
+        assert!(
+            lines[3].starts_with(
+                "Social,https://www.helius.dev/blog,Blog,Helius: Firedancer update,,,,"
+            )
+        );
+    }
+}
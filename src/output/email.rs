@@ -0,0 +1,342 @@
+use crate::config::{EmailConfig, SmtpSecurity};
+use crate::error::{Error, Result};
+use crate::pipeline::Analysis;
+use askama::Template;
+use chrono::Utc;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::extension::ClientId;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const TOP_NARRATIVES: usize = 5;
+const SMTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Template)]
+#[template(path = "email.html")]
+struct DigestTemplate {
+    generated_at: String,
+    previous_at: Option<String>,
+    total_signals: usize,
+    analysis_skipped: Option<String>,
+    narratives: Vec<DigestNarrative>,
+    dropped: Vec<String>,
+    new_ideas: Vec<DigestIdea>,
+}
+
+struct DigestNarrative {
+    title: String,
+    summary: String,
+    trend: String,
+    confidence_pct: u32,
+    is_new: bool,
+}
+
+struct DigestIdea {
+    title: String,
+    description: String,
+    narrative_title: String,
+}
+
+/// Render a compact digest: the top narratives (flagging ones the previous run
+/// didn't have), narratives that disappeared, and build ideas that are new
+/// since `previous`. Without a previous run everything counts as new.
+pub fn render_digest(current: &Analysis, previous: Option<&Analysis>) -> Result<String> {
+    let previous_narratives: HashSet<&str> = previous
+        .map(|p| p.narratives.iter().map(|n| n.id.as_str()).collect())
+        .unwrap_or_default();
+    let current_narratives: HashSet<&str> =
+        current.narratives.iter().map(|n| n.id.as_str()).collect();
+    let previous_ideas: HashSet<String> = previous
+        .map(|p| {
+            p.build_ideas
+                .iter()
+                .map(|i| i.title.trim().to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut ranked: Vec<_> = current.narratives.iter().collect();
+    ranked.sort_by(|a, b| b.confidence().total_cmp(&a.confidence()));
+    let narratives = ranked
+        .into_iter()
+        .take(TOP_NARRATIVES)
+        .map(|n| DigestNarrative {
+            title: n.title.clone(),
+            summary: n.summary.clone(),
+            trend: n.trend.to_string(),
+            confidence_pct: (n.confidence() * 100.0) as u32,
+            is_new: previous.is_some() && !previous_narratives.contains(n.id.as_str()),
+        })
+        .collect();
+
+    let dropped = previous
+        .map(|p| {
+            p.narratives
+                .iter()
+                .filter(|n| !current_narratives.contains(n.id.as_str()))
+                .map(|n| n.title.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let new_ideas = current
+        .build_ideas
+        .iter()
+        .filter(|i| !previous_ideas.contains(&i.title.trim().to_lowercase()))
+        .map(|i| DigestIdea {
+            title: i.title.clone(),
+            description: i.description.clone(),
+            narrative_title: current
+                .narratives
+                .iter()
+                .find(|n| n.id == i.narrative_id)
+                .map(|n| n.title.clone())
+                .unwrap_or_else(|| "Unknown".into()),
+        })
+        .collect();
+
+    DigestTemplate {
+        generated_at: Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+        previous_at: previous.and_then(|p| {
+            p.signals
+                .iter()
+                .map(|s| s.timestamp)
+                .max()
+                .map(|t| t.format("%Y-%m-%d").to_string())
+        }),
+        total_signals: current.signals.len(),
+        analysis_skipped: current.analysis_skipped.clone(),
+        narratives,
+        dropped,
+        new_ideas,
+    }
+    .render()
+    .map_err(|e| Error::Template(e.to_string()))
+}
+
+/// Send `html` to the comma-separated `to` list over SMTP. Credentials are
+/// only ever sent over TLS, and only with a mechanism the server advertises.
+pub async fn send(config: &EmailConfig, to: &str, subject: &str, html: &str) -> Result<()> {
+    let host = config
+        .smtp_host
+        .as_deref()
+        .ok_or_else(|| Error::config("email.smtp_host is not set"))?;
+    let recipients: Vec<&str> = to
+        .split(',')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .collect();
+    if recipients.is_empty() {
+        return Err(Error::config("no email recipients given"));
+    }
+    let credentials = match &config.username {
+        Some(_) if config.security == SmtpSecurity::None => {
+            return Err(Error::config(
+                "email.username needs email.security = \"starttls\" or \"tls\"; \
+                 credentials are never sent over an unencrypted connection",
+            ));
+        }
+        Some(user) => {
+            let password = std::env::var(&config.password_env).map_err(|_| {
+                Error::config(format!("SMTP password not set: ${}", config.password_env))
+            })?;
+            Some(Credentials::new(user.clone(), password))
+        }
+        None => None,
+    };
+    let message = build_message(&config.from, &recipients, subject, html)?;
+
+    let smtp = |e: lettre::transport::smtp::Error| Error::Smtp(e.to_string());
+    let mut transport = match config.security {
+        SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(smtp)?,
+        SmtpSecurity::StartTls => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(smtp)?
+        }
+    }
+    .port(config.smtp_port)
+    .hello_name(ClientId::Domain("st-narrative".into()))
+    .timeout(Some(SMTP_TIMEOUT));
+    if let Some(credentials) = credentials {
+        transport = transport.credentials(credentials);
+    }
+
+    tokio::time::timeout(SMTP_TIMEOUT, transport.build().send(message))
+        .await
+        .map_err(|_| Error::Timeout {
+            platform: host.to_string(),
+            after_secs: SMTP_TIMEOUT.as_secs(),
+        })?
+        .map_err(smtp)?;
+    Ok(())
+}
+
+fn build_message(from: &str, to: &[&str], subject: &str, html: &str) -> Result<Message> {
+    let mailbox = |address: &str| -> Result<Mailbox> {
+        address
+            .parse()
+            .map_err(|e| Error::config(format!("email address '{address}': {e}")))
+    };
+    let mut builder = Message::builder()
+        .from(mailbox(from)?)
+        .subject(subject)
+        .header(ContentType::TEXT_HTML);
+    for recipient in to {
+        builder = builder.to(mailbox(recipient)?);
+    }
+    builder
+        .body(html.to_string())
+        .map_err(|e| Error::Smtp(format!("building message: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, narrative};
+    use crate::types::{BuildIdea, Narrative};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    fn idea(title: &str, narrative: &Narrative) -> BuildIdea {
+        BuildIdea {
+            title: title.into(),
+            description: String::new(),
+            target_user: String::new(),
+            mvp_scope: String::new(),
+            competitive_landscape: String::new(),
+            timing_rationale: String::new(),
+            narrative_id: narrative.id.clone(),
+            carried_over: false,
+        }
+    }
+
+    fn analysis(narratives: Vec<Narrative>, build_ideas: Vec<BuildIdea>) -> Analysis {
+        Analysis {
+            signals: Vec::new(),
+            narratives,
+            build_ideas,
+            analysis_skipped: None,
+            ideas_skipped: None,
+            produced_by: None,
+            sources: Vec::new(),
+            llm_calls: Vec::new(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn digest_diffs_against_previous_run() {
+        let staking = narrative("Liquid Staking", 0.8);
+        let perps = narrative("Perps Volume", 0.7);
+        let memes = narrative("Memecoin Launchpads", 0.6);
+
+        let previous = analysis(
+            vec![staking.clone(), memes.clone()],
+            vec![idea("LST Dashboard", &staking)],
+        );
+        let current = analysis(
+            vec![staking.clone(), perps.clone()],
+            vec![
+                idea("LST dashboard ", &staking),
+                idea("Funding Rate Bot", &perps),
+            ],
+        );
+
+        let html = render_digest(&current, Some(&previous)).unwrap();
+        assert_eq!(html.matches(">NEW<").count(), 1);
+        assert!(html.contains("Memecoin Launchpads"));
+        assert!(html.contains("Funding Rate Bot"));
+        assert!(!html.contains("LST dashboard"));
+    }
+
+    #[tokio::test]
+    async fn sends_over_plain_smtp() {
+        let transcript = Arc::new(Mutex::new(String::new()));
+        let seen = transcript.clone();
+        let addr = test_support::serve(move |socket| {
+            let transcript = seen.clone();
+            async move {
+                let mut stream = BufReader::new(socket);
+                stream
+                    .get_mut()
+                    .write_all(b"220 mock ready\r\n")
+                    .await
+                    .unwrap();
+                let mut in_data = false;
+                loop {
+                    let mut line = String::new();
+                    if stream.read_line(&mut line).await.unwrap() == 0 {
+                        break;
+                    }
+                    transcript.lock().unwrap().push_str(&line);
+                    let reply: &[u8] = if in_data {
+                        if line != ".\r\n" {
+                            continue;
+                        }
+                        in_data = false;
+                        b"250 queued\r\n"
+                    } else if line.starts_with("EHLO") {
+                        b"250-mock\r\n250 AUTH PLAIN\r\n"
+                    } else if line.starts_with("AUTH") {
+                        b"235 ok\r\n"
+                    } else if line.starts_with("DATA") {
+                        in_data = true;
+                        b"354 go\r\n"
+                    } else if line.starts_with("QUIT") {
+                        stream.get_mut().write_all(b"221 bye\r\n").await.unwrap();
+                        break;
+                    } else {
+                        b"250 ok\r\n"
+                    };
+                    stream.get_mut().write_all(reply).await.unwrap();
+                }
+            }
+        })
+        .await;
+
+        let config = EmailConfig {
+            smtp_host: Some("127.0.0.1".into()),
+            smtp_port: addr.port(),
+            security: SmtpSecurity::None,
+            username: None,
+            password_env: "ST_NARRATIVE_TEST_SMTP_PASSWORD".into(),
+            from: "SolScout <bot@example.com>".into(),
+        };
+        send(
+            &config,
+            "a@example.com, b@example.com",
+            "Digest — week 42",
+            "<p>hi</p>",
+        )
+        .await
+        .unwrap();
+
+        let transcript = transcript.lock().unwrap().clone();
+        assert!(transcript.contains("MAIL FROM:<bot@example.com>"));
+        assert!(transcript.contains("RCPT TO:<b@example.com>"));
+        assert!(!transcript.contains("AUTH"));
+        assert!(transcript.contains("Subject: Digest =?utf-8?b?4oCU?= week 42"));
+    }
+
+    #[tokio::test]
+    async fn credentials_are_refused_over_plain_smtp() {
+        let config = EmailConfig {
+            smtp_host: Some("127.0.0.1".into()),
+            smtp_port: test_support::unused_addr().port(),
+            security: SmtpSecurity::None,
+            username: Some("bot".into()),
+            password_env: "ST_NARRATIVE_TEST_SMTP_PASSWORD".into(),
+            from: "bot@example.com".into(),
+        };
+        let err = send(&config, "a@example.com", "Digest", "<p>hi</p>")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("never sent over an unencrypted"),
+            "{err}"
+        );
+    }
+}
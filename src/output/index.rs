@@ -0,0 +1,138 @@
+use super::locale::Localization;
+use crate::error::{Error, Result};
+use crate::types::Theme;
+use askama::Template;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use scraper::{Html, Selector};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const PREFIX: &str = "report-";
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Template)]
+#[template(path = "index.html")]
+struct IndexTemplate {
+    theme_class: String,
+    reports: Vec<ReportEntry>,
+}
+
+struct ReportEntry {
+    file_name: String,
+    generated_at: String,
+    headline: String,
+}
+
+/// `dir/report-YYYYMMDD-HHMMSS.html` for a run started at `at` (UTC).
+pub fn stamped_path(dir: &Path, at: DateTime<Utc>) -> PathBuf {
+    dir.join(format!("{PREFIX}{}.html", at.format(STAMP_FORMAT)))
+}
+
+/// Regenerate `dir/index.html` from the stamped reports currently in `dir`,
+/// newest first. Nothing else is consulted, so deleting a report removes it
+/// from the next index. Returns how many reports were listed.
+pub fn write_index(dir: &Path, theme: Theme, locale: &Localization) -> Result<usize> {
+    let mut found: Vec<(DateTime<Utc>, String, String)> = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(generated) = parse_stamp(file_name) else {
+            continue;
+        };
+        let headline = match std::fs::read_to_string(&path) {
+            Ok(html) => headline(&html),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "unreadable report left out of index");
+                continue;
+            }
+        };
+        found.push((generated, file_name.to_string(), headline));
+    }
+    found.sort_by_key(|(generated, ..)| std::cmp::Reverse(*generated));
+
+    let count = found.len();
+    let template = IndexTemplate {
+        theme_class: theme.html_class().to_string(),
+        reports: found
+            .into_iter()
+            .map(|(generated, file_name, headline)| ReportEntry {
+                file_name,
+                generated_at: locale.timestamp(generated),
+                headline,
+            })
+            .collect(),
+    };
+    let html = template
+        .render()
+        .map_err(|e| Error::Template(e.to_string()))?;
+    std::fs::write(dir.join("index.html"), html)?;
+    Ok(count)
+}
+
+/// Run time from a `report-YYYYMMDD-HHMMSS.html` file name.
+fn parse_stamp(file_name: &str) -> Option<DateTime<Utc>> {
+    let stamp = file_name.strip_prefix(PREFIX)?.strip_suffix(".html")?;
+    NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// The headline narrative a report recorded in its `solscout-headline` meta
+/// tag. Empty for signals-only reports and ones predating the tag.
+fn headline(html: &str) -> String {
+    let selector = Selector::parse(r#"meta[name="solscout-headline"]"#).expect("valid selector");
+    Html::parse_document(html)
+        .select(&selector)
+        .next()
+        .and_then(|meta| meta.value().attr("content"))
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use chrono::TimeZone;
+
+    #[test]
+    fn index_lists_reports_on_disk_newest_first() {
+        let dir = TempDir::new("index");
+        let report = |at: DateTime<Utc>, headline: &str| {
+            let path = stamped_path(dir.path(), at);
+            let html = format!(
+                r#"<html><head><meta name="solscout-headline" content="{headline}"></head></html>"#
+            );
+            std::fs::write(&path, html).unwrap();
+            path
+        };
+        let older = report(
+            Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap(),
+            "Restaking &amp; LSTs",
+        );
+        report(
+            Utc.with_ymd_and_hms(2026, 10, 15, 8, 30, 5).unwrap(),
+            "Firedancer rollout",
+        );
+        std::fs::write(dir.join("notes.html"), "not a report").unwrap();
+
+        let count = write_index(dir.path(), Theme::Dark, &Localization::default()).unwrap();
+        assert_eq!(count, 2);
+        let index = std::fs::read_to_string(dir.join("index.html")).unwrap();
+        let newest = index.find("report-20261015-083005.html").unwrap();
+        let oldest = index.find("report-20261001-000000.html").unwrap();
+        assert!(newest < oldest);
+        assert!(index.contains("2026-10-15 08:30 UTC"));
+        // Decoded from the meta tag, then escaped exactly once.
+        assert!(index.contains("Restaking &#38; LSTs"), "{index}");
+        assert!(!index.contains("notes.html"));
+
+        std::fs::remove_file(older).unwrap();
+        assert_eq!(
+            write_index(dir.path(), Theme::Dark, &Localization::default()).unwrap(),
+            1
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::analysis::aggregator::SignalGroup;
+use crate::types::{Metric, Signal};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+/// How `signals` prints its JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonStyle {
+    /// Indented array, for reading.
+    Pretty,
+    /// Single-line array.
+    Compact,
+    /// One signal object per line, for `jq -c`, log pipelines and data lakes.
+    Ndjson,
+}
+
+/// Write `signals` to `out` in `style`. NDJSON serializes and flushes one
+/// line at a time, so a consumer sees signals as they are written and the
+/// whole document is never held in memory.
+pub fn write_signals(mut out: impl Write, signals: &[Signal], style: JsonStyle) -> io::Result<()> {
+    match style {
+        JsonStyle::Pretty => serde_json::to_writer_pretty(&mut out, signals)?,
+        JsonStyle::Compact => serde_json::to_writer(&mut out, signals)?,
+        JsonStyle::Ndjson => {
+            for signal in signals {
+                serde_json::to_writer(&mut out, signal)?;
+                out.write_all(b"\n")?;
+                out.flush()?;
+            }
+            return Ok(());
+        }
+    }
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+/// One aggregated category as written by `signals --groups-json`.
+#[derive(Serialize)]
+struct GroupJson<'a> {
+    category: &'a str,
+    source_diversity: usize,
+    sources: BTreeSet<String>,
+    total_signals: usize,
+    weight: f64,
+    signal_ids: Vec<&'a str>,
+    key_metrics: &'a [Metric],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    headline_metrics: &'a [String],
+}
+
+/// Write `groups` as an indented JSON array, in aggregation order, with
+/// signal indices resolved to IDs and the sources behind each group named.
+pub fn write_groups(
+    mut out: impl Write,
+    signals: &[Signal],
+    groups: &[SignalGroup],
+) -> io::Result<()> {
+    let groups: Vec<GroupJson> = groups
+        .iter()
+        .map(|g| GroupJson {
+            category: &g.category,
+            source_diversity: g.source_diversity,
+            sources: g
+                .signals
+                .iter()
+                .map(|&i| signals[i].source.to_string())
+                .collect(),
+            total_signals: g.total_signals,
+            weight: g.weight,
+            signal_ids: g.signals.iter().map(|&i| signals[i].id.as_str()).collect(),
+            key_metrics: &g.key_metrics,
+            headline_metrics: &g.headline_metrics,
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut out, &groups)?;
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Metric, SignalSource};
+
+    fn signals() -> Vec<Signal> {
+        ["Restaking", "DePIN"]
+            .into_iter()
+            .map(|category| {
+                Signal::new(
+                    SignalSource::SolanaOnchain,
+                    category.into(),
+                    format!("{category} activity"),
+                    String::new(),
+                    vec![Metric {
+                        name: "tx_count".into(),
+                        value: 42.0,
+                        unit: "tx".into(),
+                    }],
+                    None,
+                )
+            })
+            .collect()
+    }
+
+    fn render(signals: &[Signal], style: JsonStyle) -> String {
+        let mut out = Vec::new();
+        write_signals(&mut out, signals, style).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn ndjson_is_one_parseable_signal_per_line() {
+        let ndjson = render(&signals(), JsonStyle::Ndjson);
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, category) in lines.iter().zip(["Restaking", "DePIN"]) {
+            let signal: Signal = serde_json::from_str(line).unwrap();
+            assert_eq!(signal.category, category);
+        }
+    }
+
+    #[test]
+    fn compact_is_a_single_line_and_pretty_is_not() {
+        let signals = signals();
+        let compact = render(&signals, JsonStyle::Compact);
+        assert_eq!(compact.lines().count(), 1);
+        let pretty = render(&signals, JsonStyle::Pretty);
+        assert!(pretty.lines().count() > 2);
+        let a: serde_json::Value = serde_json::from_str(&compact).unwrap();
+        let b: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn groups_name_their_sources_and_signals() {
+        let mut signals = signals();
+        signals.push(Signal::new(
+            SignalSource::GitHub,
+            "depin".into(),
+            "DePIN repos".into(),
+            String::new(),
+            vec![Metric {
+                name: "tx_count".into(),
+                value: 8.0,
+                unit: "tx".into(),
+            }],
+            None,
+        ));
+        let groups = crate::analysis::aggregator::aggregate(&signals, None);
+        let mut out = Vec::new();
+        write_groups(&mut out, &signals, &groups).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        let depin = &json[0];
+        assert_eq!(depin["category"], "DePIN", "cross-source group first");
+        assert_eq!(depin["source_diversity"], 2);
+        assert_eq!(
+            depin["sources"],
+            serde_json::json!(["GitHub", "Solana Onchain"])
+        );
+        assert_eq!(
+            depin["signal_ids"],
+            serde_json::json!([signals[1].id, signals[2].id])
+        );
+        assert_eq!(depin["key_metrics"][0]["value"], 50.0);
+        assert_eq!(json[1]["category"], "Restaking");
+    }
+}
@@ -0,0 +1,246 @@
+use crate::config::ReportConfig;
+use crate::error::{Error, Result};
+use crate::types::Metric;
+use crate::util::metric_value;
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::HashMap;
+
+/// Number and timestamp conventions for the report, from `[report] locale`
+/// and `timezone`.
+#[derive(Debug, Clone)]
+pub struct Localization {
+    group: &'static str,
+    decimal: char,
+    offset: FixedOffset,
+    /// Decimal places by metric name, from `[report] metric_precision`.
+    precision: HashMap<String, usize>,
+}
+
+impl Default for Localization {
+    /// en-US in UTC, the report's historical formatting.
+    fn default() -> Self {
+        Self {
+            group: ",",
+            decimal: '.',
+            offset: FixedOffset::east_opt(0).unwrap(),
+            precision: HashMap::new(),
+        }
+    }
+}
+
+impl Localization {
+    pub fn from_config(config: &ReportConfig) -> Result<Self> {
+        let (group, decimal) = separators(&config.locale)?;
+        Ok(Self {
+            group,
+            decimal,
+            offset: parse_offset(&config.timezone)?,
+            precision: config.metric_precision.clone(),
+        })
+    }
+
+    /// Group thousands and swap in the decimal separator on a plain number
+    /// such as `-1234.5` or `4.56e-7`. A negative value that rounded to zero
+    /// loses its sign.
+    fn localize(&self, plain: &str) -> String {
+        if plain.contains(['e', 'N', 'i']) {
+            return plain.replace('.', &self.decimal.to_string());
+        }
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(rest) if rest.chars().any(|c| c.is_ascii_digit() && c != '0') => ("-", rest),
+            Some(rest) => ("", rest),
+            None => ("", plain),
+        };
+        let (int, frac) = match digits.split_once('.') {
+            Some((int, frac)) => (int, Some(frac)),
+            None => (digits, None),
+        };
+
+        let mut grouped = String::new();
+        for (i, digit) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                grouped.push_str(self.group);
+            }
+            grouped.push(digit);
+        }
+        match frac {
+            Some(frac) => format!("{sign}{grouped}{}{frac}", self.decimal),
+            None => format!("{sign}{grouped}"),
+        }
+    }
+
+    /// Same shape as `Metric`'s `Display`, with a localized value at the
+    /// metric's configured precision.
+    pub fn metric(&self, metric: &Metric) -> String {
+        let decimals = self.precision.get(&metric.name).copied();
+        let value = self.localize(&metric_value(metric.value, decimals));
+        if metric.unit.is_empty() {
+            format!("{}: {value}", metric.name)
+        } else {
+            format!("{}: {value} {}", metric.name, metric.unit)
+        }
+    }
+
+    pub fn timestamp(&self, at: DateTime<Utc>) -> String {
+        let local = at.with_timezone(&self.offset);
+        let seconds = self.offset.local_minus_utc();
+        if seconds == 0 {
+            return local.format("%Y-%m-%d %H:%M UTC").to_string();
+        }
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let minutes = seconds.abs() / 60;
+        format!(
+            "{} UTC{sign}{:02}:{:02}",
+            local.format("%Y-%m-%d %H:%M"),
+            minutes / 60,
+            minutes % 60
+        )
+    }
+}
+
+/// Thousands and decimal separators for a BCP 47 tag such as `de-DE`.
+fn separators(locale: &str) -> Result<(&'static str, char)> {
+    let tag = locale.replace('_', "-").to_lowercase();
+    let language = tag.split('-').next().unwrap_or_default();
+    match (tag.as_str(), language) {
+        ("de-ch" | "it-ch", _) => Ok(("'", '.')),
+        ("es-mx" | "es-us", _) => Ok((",", '.')),
+        (_, "en" | "ja" | "zh" | "ko" | "th" | "he") => Ok((",", '.')),
+        (_, "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el") => Ok((".", ',')),
+        (_, "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "nb" | "no" | "fi" | "uk" | "hu") => {
+            Ok(("\u{a0}", ','))
+        }
+        _ => Err(Error::config(format!(
+            "unsupported report locale '{locale}' (e.g. en-US, de-DE, fr-FR)"
+        ))),
+    }
+}
+
+/// `UTC`, or a fixed offset such as `+02:00`, `UTC-5` or `UTC+05:30`. Named
+/// zones are not supported: there is no tz database to resolve DST against.
+fn parse_offset(timezone: &str) -> Result<FixedOffset> {
+    let invalid = || {
+        Error::config(format!(
+            "invalid report timezone '{timezone}' (expected UTC or an offset like +02:00)"
+        ))
+    };
+    let trimmed = timezone.trim();
+    let rest = trimmed
+        .strip_prefix("UTC")
+        .or_else(|| trimmed.strip_prefix("GMT"))
+        .unwrap_or(trimmed);
+    if rest.is_empty() || rest == "Z" {
+        return Ok(FixedOffset::east_opt(0).unwrap());
+    }
+
+    let (sign, rest) = match rest.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes >= 60 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn localization(locale: &str, timezone: &str) -> Localization {
+        Localization::from_config(&ReportConfig {
+            locale: locale.into(),
+            timezone: timezone.into(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    /// `supply` formatted by `locale`'s report, pinned to one decimal.
+    fn supply(locale: &str, value: f64) -> String {
+        let localization = Localization::from_config(&ReportConfig {
+            locale: locale.into(),
+            metric_precision: [("supply".to_string(), 1)].into(),
+            ..Default::default()
+        })
+        .unwrap();
+        localization.metric(&Metric {
+            name: "supply".into(),
+            value,
+            unit: String::new(),
+        })
+    }
+
+    #[test]
+    fn groups_thousands_per_locale() {
+        let supply_sol = 581_234_567.25;
+        assert_eq!(supply("en-US", supply_sol), "supply: 581,234,567.2");
+        assert_eq!(supply("de-DE", supply_sol), "supply: 581.234.567,2");
+        assert_eq!(supply("fr_FR", -1234.0), "supply: -1\u{a0}234,0");
+        assert_eq!(supply("en-US", 999.96), "supply: 1,000.0");
+        assert!(
+            Localization::from_config(&ReportConfig {
+                locale: "xx-YY".into(),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn metrics_keep_small_values_and_round_large_ones() {
+        let metric = |name: &str, value: f64| Metric {
+            name: name.into(),
+            value,
+            unit: "SOL".into(),
+        };
+        let de = localization("de-DE", "UTC");
+        assert_eq!(de.metric(&metric("fee", 0.03)), "fee: 0,03 SOL");
+        assert_eq!(
+            de.metric(&metric("circulating_sol", 581_234_567.25)),
+            "circulating_sol: 581.234.567 SOL"
+        );
+        assert_eq!(
+            Localization::default().metric(&metric("dust", 0.000_000_45)),
+            "dust: 4.50e-7 SOL"
+        );
+
+        let pinned = Localization::from_config(&ReportConfig {
+            metric_precision: [("circulating_sol".to_string(), 2)].into(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            pinned.metric(&metric("circulating_sol", 581_234_567.25)),
+            "circulating_sol: 581,234,567.25 SOL"
+        );
+        assert_eq!(
+            pinned.metric(&metric("fee", -0.001)),
+            "fee: -0.001 SOL",
+            "unlisted metrics stay automatic"
+        );
+    }
+
+    #[test]
+    fn timestamp_uses_configured_offset() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 15, 23, 30, 0).unwrap();
+        assert_eq!(
+            Localization::default().timestamp(at),
+            "2026-10-15 23:30 UTC"
+        );
+        assert_eq!(
+            localization("en-US", "UTC+05:30").timestamp(at),
+            "2026-10-16 05:00 UTC+05:30"
+        );
+        assert_eq!(
+            localization("en-US", "-04:00").timestamp(at),
+            "2026-10-15 19:30 UTC-04:00"
+        );
+        assert!(parse_offset("Europe/Berlin").is_err());
+    }
+}
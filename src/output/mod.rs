@@ -1 +1,36 @@
+pub mod csv;
+pub mod email;
+pub mod index;
+pub mod json;
+pub mod locale;
+pub mod profile;
 pub mod report;
+pub mod template;
+
+/// What `run` writes to the output path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Html,
+    Csv,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "html" => Ok(Self::Html),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!("unknown format '{other}' (expected html or csv)")),
+        }
+    }
+}
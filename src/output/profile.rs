@@ -0,0 +1,104 @@
+use crate::llm::LlmCall;
+use crate::pipeline::SourceStatus;
+use std::time::Duration;
+
+/// The `run --profile` table: every source collection and LLM call, slowest
+/// first, with the run's total wall-clock time last. Sources run in
+/// parallel, so their times overlap and don't add up to the total.
+pub fn table(sources: &[SourceStatus], llm_calls: &[LlmCall], total: Duration) -> String {
+    let mut rows: Vec<(u64, [String; 4])> = sources
+        .iter()
+        .map(|s| {
+            let name = match &s.error {
+                Some(_) => format!("{} (failed)", s.name),
+                None => s.name.clone(),
+            };
+            let row = [
+                "source".into(),
+                name,
+                seconds(s.elapsed_ms),
+                s.requests.to_string(),
+            ];
+            (s.elapsed_ms, row)
+        })
+        .chain(llm_calls.iter().map(|c| {
+            let name = if c.ok {
+                c.stage.clone()
+            } else {
+                format!("{} (failed)", c.stage)
+            };
+            let row = ["llm".into(), name, seconds(c.elapsed_ms), "1".into()];
+            (c.elapsed_ms, row)
+        }))
+        .collect();
+    rows.sort_by_key(|(elapsed_ms, _)| std::cmp::Reverse(*elapsed_ms));
+
+    let header = ["KIND", "NAME", "TIME", "REQUESTS"].map(String::from);
+    let footer = [
+        "total".into(),
+        String::new(),
+        seconds(total.as_millis() as u64),
+        String::new(),
+    ];
+    let lines: Vec<&[String; 4]> = std::iter::once(&header)
+        .chain(rows.iter().map(|(_, row)| row))
+        .chain(std::iter::once(&footer))
+        .collect();
+    let mut widths = [0; 3];
+    for row in &lines {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    for [kind, name, time, requests] in lines {
+        let line = format!(
+            "{kind:<w0$}  {name:<w1$}  {time:>w2$}  {requests}",
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.2}s", ms as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slowest_first_with_total_last() {
+        let source = |name: &str, elapsed_ms, requests, error: Option<&str>| SourceStatus {
+            name: name.into(),
+            signals: 0,
+            error: error.map(str::to_string),
+            unchanged: false,
+            elapsed_ms,
+            requests,
+        };
+        let sources = [
+            source("GitHub", 2_500, 12, None),
+            source("Solana RPC", 9_120, 214, None),
+            source("Dune", 30_000, 4, Some("HTTP error: timeout")),
+        ];
+        let calls = [LlmCall {
+            stage: "synthesis".into(),
+            elapsed_ms: 8_004,
+            ok: true,
+        }];
+        let table = table(&sources, &calls, Duration::from_millis(41_250));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "KIND    NAME             TIME  REQUESTS");
+        assert_eq!(lines[1], "source  Dune (failed)  30.00s  4");
+        assert_eq!(lines[2], "source  Solana RPC      9.12s  214");
+        assert_eq!(lines[3], "llm     synthesis       8.00s  1");
+        assert_eq!(lines[4], "source  GitHub          2.50s  12");
+        assert_eq!(lines[5], "total                  41.25s");
+    }
+}
@@ -1,32 +1,129 @@
+use super::locale::Localization;
+use super::template::CustomTemplate;
+use crate::analysis::aggregator::is_headline;
+use crate::config::{NarrativeSort, TrendWeights};
 use crate::error::{Error, Result};
-use crate::types::{BuildIdea, Narrative, Signal};
+use crate::types::{BuildIdea, Metric, Narrative, RunMetadata, Signal, Theme, TrendDirection};
 use askama::Template;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path::Path;
 
-#[derive(Template)]
+/// Reports with at most this many signals open every category section.
+const SIGNALS_EXPANDED: usize = 25;
+
+/// Confidence badges turn yellow at this confidence and green above the next.
+const MEDIUM_CONFIDENCE: f64 = 0.5;
+const HIGH_CONFIDENCE: f64 = 0.75;
+
+/// The report's view model. Custom templates (`report.template_path`) see
+/// these fields, and those of the views below, as their variables.
+#[derive(Template, Serialize)]
 #[template(path = "report.html")]
 pub struct ReportTemplate {
+    pub theme_class: String,
     pub generated_at: String,
+    /// Top-ranked narrative title, read back by the report index.
+    pub headline: String,
+    /// Why LLM analysis was skipped, if it was. Renders a signals-only banner.
+    pub analysis_skipped: Option<String>,
+    /// Analysis ran but found no narratives; explained instead of left blank.
+    pub no_narratives: bool,
+    /// `provider/model` that answered the LLM calls, fallback included.
+    pub produced_by: Option<String>,
     pub total_signals: usize,
     pub source_count: usize,
     pub narratives: Vec<NarrativeView>,
+    /// Narratives left out by `min_confidence`, and that threshold.
+    pub hidden_narratives: usize,
+    pub min_confidence_pct: u32,
+    pub relationships: Vec<RelationshipView>,
     pub build_ideas: Vec<BuildIdeaView>,
-    pub signals: Vec<SignalView>,
+    /// Some ideas were reused from the last run, so fresh ones get a badge too.
+    pub any_carried_over: bool,
+    /// Raw signals grouped by category, largest group first.
+    pub signal_groups: Vec<SignalGroupView>,
+    /// Distinct sources and categories, for the filter chips.
+    pub signal_sources: Vec<String>,
+    pub signal_categories: Vec<String>,
+    /// Few enough signals to show every group expanded.
+    pub signals_expanded: bool,
+    /// Sources served entirely from the HTTP cache this run.
+    pub unchanged_sources: Vec<String>,
+    /// Effective trend weights, e.g. "Accelerating ×1.5", for the footer.
+    pub trend_weights: Vec<String>,
+    /// The signals as JSON, escaped for a `<script>` block; see
+    /// [`script_json`].
+    pub raw_signals_json: Option<String>,
+    /// Config hash and the footer's provenance line, for `run` reports.
+    pub config_hash: Option<String>,
+    pub provenance: Option<String>,
+}
+
+/// Presentation choices for one rendered report.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    pub theme: Theme,
+    pub locale: Localization,
+    pub trend_weights: TrendWeights,
+    /// Key narratives are ranked by; ideas follow their narrative.
+    pub sort_by: NarrativeSort,
+    /// Sources whose pages were all unchanged since the last run, noted as
+    /// cached rather than fresh.
+    pub unchanged_sources: Vec<String>,
+    /// Hide narratives below this confidence, and ideas built on them.
+    pub min_confidence: f64,
+    /// `analysis.headline_metrics`: metrics shown first and emphasized.
+    pub headline_metrics: HashMap<String, Vec<String>>,
+    /// Embed the signals as downloadable JSON.
+    pub embed_raw_signals: bool,
+    /// What produced the run, shown in the footer.
+    pub run_metadata: Option<RunMetadata>,
+    /// Template file to render instead of the built-in report.
+    pub template_path: Option<String>,
+    /// Age beyond which signals are marked stale; `None` never marks them.
+    pub stale_after: Option<chrono::Duration>,
 }
 
+#[derive(Serialize)]
 pub struct NarrativeView {
+    pub id: String,
     pub title: String,
     pub summary: String,
     pub confidence_pct: u32,
+    /// Confidence band, `high`, `medium` or `low`, which picks the badge
+    /// colors.
+    pub confidence_level: String,
+    /// Tooltip explaining how the shown confidence was derived.
+    pub confidence_note: String,
     pub trend: String,
     pub trend_class: String,
     pub signal_count: usize,
-    pub metrics: Vec<String>,
+    pub metrics: Vec<MetricView>,
     pub source_diversity: usize,
     pub total_sources: usize,
+    pub supporting_ids: Vec<String>,
+    /// Consecutive runs that identified this narrative; 0 when untracked.
+    pub streak: u32,
+    /// Confidence per tracked run, oldest first, e.g. "60% → 72%".
+    pub trajectory: String,
+    /// Ensemble runs that found it, e.g. "4 of 5"; empty without an ensemble.
+    pub recurrence: String,
+}
+
+/// One edge of the narrative graph, with both ends resolved to titles.
+#[derive(Serialize)]
+pub struct RelationshipView {
+    pub from_id: String,
+    pub from_title: String,
+    pub kind: String,
+    pub to_id: String,
+    pub to_title: String,
+    pub rationale: String,
 }
 
+#[derive(Serialize)]
 pub struct BuildIdeaView {
     pub title: String,
     pub description: String,
@@ -35,52 +132,186 @@ pub struct BuildIdeaView {
     pub competitive_landscape: String,
     pub timing_rationale: String,
     pub narrative_title: String,
+    pub carried_over: bool,
 }
 
+/// One collapsible category section of the raw signals table.
+#[derive(Serialize)]
+pub struct SignalGroupView {
+    pub category: String,
+    pub signals: Vec<SignalView>,
+}
+
+#[derive(Serialize)]
 #[allow(dead_code)] // fields used by Askama template
 pub struct SignalView {
+    pub id: String,
     pub source: String,
     pub category: String,
     pub title: String,
     pub description: String,
-    pub metrics: Vec<String>,
+    pub metrics: Vec<MetricView>,
     pub url: String,
+    /// Age at `generated_at`, by content timestamp when the source gives one,
+    /// e.g. "3d old"; otherwise marked as collection time.
+    pub age: String,
+    /// Tooltip with the exact time the age is measured from.
+    pub age_note: String,
+    pub stale: bool,
+    /// Row classes dimming stale signals.
+    pub freshness_class: String,
+}
+
+/// A formatted metric, and whether it's a headline metric of its category.
+#[derive(Serialize)]
+pub struct MetricView {
+    pub text: String,
+    pub headline: bool,
 }
 
 pub fn render(
     signals: &[Signal],
     narratives: &[Narrative],
     build_ideas: &[BuildIdea],
+    options: &RenderOptions,
+    analysis_skipped: Option<&str>,
+    produced_by: Option<&str>,
+) -> Result<String> {
+    render_at(
+        signals,
+        narratives,
+        build_ideas,
+        options,
+        analysis_skipped,
+        produced_by,
+        Utc::now(),
+    )
+}
+
+/// [`render`] with the generation time fixed, so output is reproducible.
+fn render_at(
+    signals: &[Signal],
+    narratives: &[Narrative],
+    build_ideas: &[BuildIdea],
+    options: &RenderOptions,
+    analysis_skipped: Option<&str>,
+    produced_by: Option<&str>,
+    generated_at: DateTime<Utc>,
 ) -> Result<String> {
+    let RenderOptions {
+        theme,
+        locale,
+        trend_weights,
+        sort_by,
+        unchanged_sources,
+        min_confidence,
+        headline_metrics,
+        embed_raw_signals,
+        run_metadata,
+        template_path,
+        stale_after,
+    } = options;
+    let theme = *theme;
     let sources: std::collections::HashSet<_> = signals.iter().map(|s| s.source).collect();
     let total_sources = sources.len();
+    let by_id: std::collections::HashMap<&str, &Signal> =
+        signals.iter().map(|s| (s.id.as_str(), s)).collect();
 
-    let mut narrative_views: Vec<NarrativeView> = narratives
+    let mut ranked: Vec<&Narrative> = narratives.iter().collect();
+    rank_narratives(&mut ranked, *sort_by, trend_weights);
+    let (ranked, hidden): (Vec<&Narrative>, Vec<&Narrative>) = ranked
+        .into_iter()
+        .partition(|n| n.confidence() >= *min_confidence);
+    let hidden_ids: std::collections::HashSet<&str> =
+        hidden.iter().map(|n| n.id.as_str()).collect();
+
+    let narrative_views: Vec<NarrativeView> = ranked
         .iter()
         .map(|n| {
             let source_diversity = n
                 .supporting_signals
                 .iter()
-                .filter_map(|&idx| signals.get(idx))
+                .filter_map(|id| by_id.get(id.as_str()))
                 .map(|s| s.source)
                 .collect::<std::collections::HashSet<_>>()
                 .len();
             NarrativeView {
+                id: n.id.clone(),
                 title: n.title.clone(),
                 summary: n.summary.clone(),
-                confidence_pct: (n.confidence * 100.0) as u32,
+                confidence_pct: (n.confidence() * 100.0) as u32,
+                confidence_level: confidence_level(n.confidence()).to_string(),
+                confidence_note: if n.confidence_note.is_empty() {
+                    "Model-reported confidence (not recalibrated).".into()
+                } else {
+                    n.confidence_note.clone()
+                },
                 trend: n.trend.to_string(),
-                trend_class: n.trend.css_class().to_string(),
+                trend_class: n.trend.css_class(theme).to_string(),
                 signal_count: n.supporting_signals.len(),
-                metrics: n.key_metrics.iter().map(|m| m.to_string()).collect(),
+                // A narrative's metric is a headline one if it is for the
+                // category of any signal behind it.
+                metrics: metric_views(&n.key_metrics, locale, |name| {
+                    n.supporting_signals
+                        .iter()
+                        .filter_map(|id| by_id.get(id.as_str()))
+                        .any(|s| is_headline(headline_metrics, &s.category, name))
+                }),
                 source_diversity,
                 total_sources,
+                supporting_ids: n.supporting_signals.clone(),
+                streak: n.continuity.as_ref().map_or(0, |c| c.streak),
+                recurrence: n
+                    .recurrence
+                    .map(|r| format!("{} of {}", r.appeared, r.runs))
+                    .unwrap_or_default(),
+                trajectory: n
+                    .continuity
+                    .as_ref()
+                    .map(|c| {
+                        c.confidence_trajectory
+                            .iter()
+                            .map(|v| format!("{:.0}%", v * 100.0))
+                            .collect::<Vec<_>>()
+                            .join(" → ")
+                    })
+                    .unwrap_or_default(),
             }
         })
         .collect();
-    narrative_views.sort_by(|a, b| b.confidence_pct.cmp(&a.confidence_pct));
 
-    let idea_views: Vec<BuildIdeaView> = build_ideas
+    let relationship_views: Vec<RelationshipView> = ranked
+        .iter()
+        .flat_map(|from| {
+            let ranked = &ranked;
+            from.relationships.iter().filter_map(move |r| {
+                let to = ranked.iter().find(|n| n.id == r.target)?;
+                Some(RelationshipView {
+                    from_id: from.id.clone(),
+                    from_title: from.title.clone(),
+                    kind: r.kind.to_string(),
+                    to_id: to.id.clone(),
+                    to_title: to.title.clone(),
+                    rationale: r.rationale.clone(),
+                })
+            })
+        })
+        .collect();
+
+    // Ideas follow the narrative they serve, keeping their own order under
+    // it; ideas for an unknown narrative go last.
+    let mut ranked_ideas: Vec<&BuildIdea> = build_ideas
+        .iter()
+        .filter(|i| !hidden_ids.contains(i.narrative_id.as_str()))
+        .collect();
+    ranked_ideas.sort_by_key(|i| {
+        ranked
+            .iter()
+            .position(|n| n.id == i.narrative_id)
+            .unwrap_or(usize::MAX)
+    });
+
+    let idea_views: Vec<BuildIdeaView> = ranked_ideas
         .iter()
         .map(|i| BuildIdeaView {
             title: i.title.clone(),
@@ -90,36 +321,222 @@ pub fn render(
             competitive_landscape: i.competitive_landscape.clone(),
             timing_rationale: i.timing_rationale.clone(),
             narrative_title: narratives
-                .get(i.narrative_index)
+                .iter()
+                .find(|n| n.id == i.narrative_id)
                 .map(|n| n.title.clone())
                 .unwrap_or_else(|| "Unknown".into()),
+            carried_over: i.carried_over,
         })
         .collect();
 
     let signal_views: Vec<SignalView> = signals
         .iter()
-        .map(|s| SignalView {
-            source: s.source.to_string(),
-            category: s.category.clone(),
-            title: s.title.clone(),
-            description: s.description.clone(),
-            metrics: s.metrics.iter().map(|m| m.to_string()).collect(),
-            url: s.url.clone().unwrap_or_default(),
+        .map(|s| {
+            let (age, age_note, stale) = freshness(s, generated_at, *stale_after, locale);
+            SignalView {
+                id: s.id.clone(),
+                source: s.source.to_string(),
+                category: s.category.clone(),
+                title: s.title.clone(),
+                description: s.description.clone(),
+                metrics: metric_views(&s.metrics, locale, |name| {
+                    is_headline(headline_metrics, &s.category, name)
+                }),
+                url: s.url.clone().unwrap_or_default(),
+                age,
+                age_note,
+                stale,
+                freshness_class: if stale { "stale opacity-60" } else { "" }.to_string(),
+            }
         })
         .collect();
 
+    let mut signal_groups: Vec<SignalGroupView> = Vec::new();
+    for view in signal_views {
+        match signal_groups
+            .iter_mut()
+            .find(|g| g.category == view.category)
+        {
+            Some(group) => group.signals.push(view),
+            None => signal_groups.push(SignalGroupView {
+                category: view.category.clone(),
+                signals: vec![view],
+            }),
+        }
+    }
+    signal_groups.sort_by(|a, b| {
+        b.signals
+            .len()
+            .cmp(&a.signals.len())
+            .then_with(|| a.category.cmp(&b.category))
+    });
+    let mut signal_sources: Vec<String> = sources.iter().map(|s| s.to_string()).collect();
+    signal_sources.sort();
+    let mut signal_categories: Vec<String> =
+        signal_groups.iter().map(|g| g.category.clone()).collect();
+    signal_categories.sort();
+
     let template = ReportTemplate {
-        generated_at: Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+        theme_class: theme.html_class().to_string(),
+        analysis_skipped: analysis_skipped.map(str::to_string),
+        no_narratives: analysis_skipped.is_none() && narratives.is_empty(),
+        produced_by: produced_by.map(str::to_string),
+        generated_at: locale.timestamp(generated_at),
+        headline: ranked.first().map(|n| n.title.clone()).unwrap_or_default(),
         total_signals: signals.len(),
         source_count: sources.len(),
         narratives: narrative_views,
+        hidden_narratives: hidden.len(),
+        min_confidence_pct: (min_confidence * 100.0).round() as u32,
+        relationships: relationship_views,
+        any_carried_over: ranked_ideas.iter().any(|i| i.carried_over),
         build_ideas: idea_views,
-        signals: signal_views,
+        signal_groups,
+        signal_sources,
+        signal_categories,
+        signals_expanded: signals.len() <= SIGNALS_EXPANDED,
+        unchanged_sources: unchanged_sources.clone(),
+        trend_weights: [
+            TrendDirection::Accelerating,
+            TrendDirection::Emerging,
+            TrendDirection::Stable,
+            TrendDirection::Decelerating,
+        ]
+        .iter()
+        .map(|&t| format!("{t} ×{}", trend_weights.weight(t)))
+        .collect(),
+        raw_signals_json: if *embed_raw_signals {
+            Some(script_json(signals)?)
+        } else {
+            None
+        },
+        config_hash: run_metadata.as_ref().map(|m| m.config_hash.clone()),
+        provenance: run_metadata.as_ref().map(|m| provenance(m, locale)),
+    };
+
+    match template_path {
+        Some(path) => CustomTemplate::load(Path::new(path))?.render(&template),
+        None => template
+            .render()
+            .map_err(|e| Error::Template(e.to_string())),
+    }
+}
+
+/// A signal's age label at `now`, its tooltip, and whether it's older than
+/// `stale_after`. Without a content timestamp the age is that of collection,
+/// and the label says so rather than implying the content is fresh.
+fn freshness(
+    signal: &Signal,
+    now: DateTime<Utc>,
+    stale_after: Option<chrono::Duration>,
+    locale: &Localization,
+) -> (String, String, bool) {
+    let (at, label, note) = match signal.content_timestamp {
+        Some(at) => (at, format!("{} old", compact_age(now - at)), "Content from"),
+        None => (
+            signal.timestamp,
+            format!(
+                "collection time, {} before report",
+                compact_age(now - signal.timestamp)
+            ),
+            "No content timestamp from this source; collected",
+        ),
+    };
+    let stale = stale_after.is_some_and(|limit| now - at > limit);
+    (label, format!("{note} {}", locale.timestamp(at)), stale)
+}
+
+/// "<1m", "45m", "5h" under two days, then "3d".
+fn compact_age(age: chrono::Duration) -> String {
+    match age.num_minutes().max(0) {
+        0 => "<1m".into(),
+        m if m < 60 => format!("{m}m"),
+        m if m < 48 * 60 => format!("{}h", m / 60),
+        m => format!("{}d", m / (24 * 60)),
+    }
+}
+
+/// One footer line on what produced the run.
+fn provenance(metadata: &RunMetadata, locale: &Localization) -> String {
+    format!(
+        "Run: config {} · {}/{} · {} sources ({}) · st-narrative v{} · started {} · took {:.1}s",
+        metadata.config_hash,
+        metadata.provider,
+        metadata.model,
+        metadata.source_set.len(),
+        metadata.source_set.join(", "),
+        metadata.tool_version,
+        locale.timestamp(metadata.started_at),
+        metadata.duration_ms as f64 / 1000.0,
+    )
+}
+
+/// `value` as JSON that can sit inside a `<script>` element: `<`, `>` and
+/// `&` become `\u` escapes so no `</script>` or `<!--` can end the block
+/// early, and U+2028/U+2029 are escaped for older JavaScript parsers. The
+/// result still parses as the same JSON.
+fn script_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String> {
+    let json = serde_json::to_string(value).map_err(|e| Error::Template(e.to_string()))?;
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            c => escaped.push(c),
+        }
+    }
+    Ok(escaped)
+}
+
+/// Sort `narratives` by `key`, highest first, breaking ties by confidence,
+/// trend weight and supporting signal count in turn. The sort is stable, so
+/// full ties keep the model's order.
+fn rank_narratives(narratives: &mut [&Narrative], key: NarrativeSort, weights: &TrendWeights) {
+    let primary = |n: &Narrative| match key {
+        NarrativeSort::Confidence => n.confidence(),
+        NarrativeSort::Weighted => n.confidence() * weights.weight(n.trend),
+        NarrativeSort::Trend => weights.weight(n.trend),
+        NarrativeSort::Signals => n.supporting_signals.len() as f64,
     };
+    narratives.sort_by(|a, b| {
+        primary(b)
+            .total_cmp(&primary(a))
+            .then_with(|| b.confidence().total_cmp(&a.confidence()))
+            .then_with(|| weights.weight(b.trend).total_cmp(&weights.weight(a.trend)))
+            .then_with(|| b.supporting_signals.len().cmp(&a.supporting_signals.len()))
+    });
+}
 
-    template
-        .render()
-        .map_err(|e| Error::Template(e.to_string()))
+/// Format `metrics`, headline ones first and otherwise in their order.
+fn metric_views(
+    metrics: &[Metric],
+    locale: &Localization,
+    headline: impl Fn(&str) -> bool,
+) -> Vec<MetricView> {
+    let mut views: Vec<MetricView> = metrics
+        .iter()
+        .map(|m| MetricView {
+            text: locale.metric(m),
+            headline: headline(&m.name),
+        })
+        .collect();
+    views.sort_by_key(|v| !v.headline);
+    views
+}
+
+/// `low` below [`MEDIUM_CONFIDENCE`], `medium` up to [`HIGH_CONFIDENCE`],
+/// `high` above it. The template colors each band for both themes.
+fn confidence_level(confidence: f64) -> &'static str {
+    if confidence > HIGH_CONFIDENCE {
+        "high"
+    } else if confidence >= MEDIUM_CONFIDENCE {
+        "medium"
+    } else {
+        "low"
+    }
 }
 
 pub fn write_report(path: &Path, html: &str) -> Result<()> {
@@ -129,3 +546,600 @@ pub fn write_report(path: &Path, html: &str) -> Result<()> {
     std::fs::write(path, html)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, TempDir};
+    use crate::types::{Continuity, Metric, Recurrence, SignalSource};
+
+    fn signal() -> Signal {
+        Signal::new(
+            SignalSource::SolanaOnchain,
+            "Network Performance".into(),
+            "Solana TPS: 4000 total".into(),
+            "Average over 10 samples".into(),
+            vec![Metric {
+                name: "avg_tps".into(),
+                value: 4000.0,
+                unit: "tx/s".into(),
+            }],
+            None,
+        )
+    }
+
+    #[test]
+    fn headline_metrics_are_shown_first_and_emphasized() {
+        let mut s = signal();
+        s.metrics.push(Metric {
+            name: "avg_non_vote_tps".into(),
+            value: 1100.0,
+            unit: "tx/s".into(),
+        });
+        let options = RenderOptions {
+            headline_metrics: HashMap::from([(
+                "Network Performance".to_string(),
+                vec!["avg_non_vote_tps".to_string()],
+            )]),
+            ..Default::default()
+        };
+        let html = render(&[s.clone()], &[], &[], &options, None, None).unwrap();
+        let headline = html.find("headline-metric").unwrap();
+        let non_vote = html.find("avg_non_vote_tps: 1,100").unwrap();
+        let total = html.find("avg_tps: 4,000").unwrap();
+        assert!(headline < non_vote && non_vote < total, "headline first");
+        assert_eq!(html.matches("class=\"headline-metric").count(), 1);
+
+        let plain = render(&[s], &[], &[], &RenderOptions::default(), None, None).unwrap();
+        assert!(!plain.contains("headline-metric"));
+    }
+
+    #[test]
+    fn signals_only_report_renders_banner() {
+        let html = render(
+            &[signal()],
+            &[],
+            &[],
+            &RenderOptions::default(),
+            Some("LLM API key not set"),
+            None,
+        )
+        .unwrap();
+        assert!(html.contains("LLM analysis was skipped"));
+        assert!(html.contains("LLM API key not set"));
+        assert!(html.contains("Solana TPS: 4000 total"));
+    }
+
+    #[test]
+    fn signals_group_by_category_largest_first() {
+        let mut defi = signal();
+        defi.category = "DeFi".into();
+        defi.id = "sdefi0001".into();
+        let mut other = defi.clone();
+        other.id = "sdefi0002".into();
+        other.source = SignalSource::GitHub;
+        let signals = [signal(), defi, other];
+
+        let html = render(&signals, &[], &[], &RenderOptions::default(), None, None).unwrap();
+        let first = &html[html.find("<summary").unwrap()..];
+        let first = &first[..first.find("</summary>").unwrap()];
+        assert!(first.contains("DeFi"), "{first}");
+        assert_eq!(html.matches("<details class=\"signal-group").count(), 2);
+        assert!(html.contains(r#"data-kind="source" data-value="GitHub""#));
+        assert!(html.contains(r#"data-category="DeFi""#));
+        assert!(html.contains("open>"), "small reports start expanded");
+    }
+
+    #[test]
+    fn empty_analysis_explains_itself() {
+        let html = render(&[signal()], &[], &[], &RenderOptions::default(), None, None).unwrap();
+        assert!(html.contains("Insufficient signal for narratives this run"));
+        assert!(!html.contains("LLM analysis was skipped"));
+        assert!(
+            html.contains("Solana TPS: 4000 total"),
+            "raw signals still listed"
+        );
+    }
+
+    #[test]
+    fn relationships_render_between_known_narratives() {
+        let narrative = |title: &str| test_support::narrative(title, 0.7);
+        let mut staking = narrative("Liquid Staking Growth");
+        let lending = narrative("LST-Collateralized Lending");
+        staking.relationships = vec![
+            crate::types::Relationship {
+                kind: crate::types::RelationKind::Enables,
+                target: lending.id.clone(),
+                rationale: "LSTs become collateral".into(),
+            },
+            crate::types::Relationship {
+                kind: crate::types::RelationKind::CompetesWith,
+                target: "n00000000".into(),
+                rationale: String::new(),
+            },
+        ];
+
+        let html = render(
+            &[signal()],
+            &[staking, lending],
+            &[],
+            &RenderOptions::default(),
+            None,
+            Some("anthropic/claude-sonnet-4-5"),
+        )
+        .unwrap();
+        assert!(html.contains("Analysis by anthropic/claude-sonnet-4-5"));
+        assert!(html.contains("Narrative Map"));
+        assert!(html.contains("LSTs become collateral"));
+        assert!(!html.contains("competes with"));
+    }
+
+    #[test]
+    fn trend_weights_reorder_narratives() {
+        let narrative = |title: &str, trend, confidence| Narrative {
+            trend,
+            ..test_support::narrative(title, confidence)
+        };
+        let narratives = [
+            narrative("Perps Volume Surge", TrendDirection::Accelerating, 0.7),
+            narrative("Onchain AI Agents", TrendDirection::Emerging, 0.6),
+        ];
+        let order = |accelerating, emerging| {
+            let options = RenderOptions {
+                trend_weights: TrendWeights {
+                    accelerating,
+                    emerging,
+                    ..Default::default()
+                },
+                sort_by: NarrativeSort::Weighted,
+                ..Default::default()
+            };
+            let html = render(&[signal()], &narratives, &[], &options, None, None).unwrap();
+            html.find("Perps Volume Surge").unwrap() < html.find("Onchain AI Agents").unwrap()
+        };
+
+        assert!(order(1.5, 1.0), "trader weights keep Accelerating first");
+        assert!(!order(1.0, 1.5), "researcher weights put Emerging first");
+    }
+
+    #[test]
+    fn narratives_rank_by_sort_key_and_ideas_follow_them() {
+        let narrative = |title: &str, trend, confidence, signals: usize| Narrative {
+            supporting_signals: (0..signals).map(|i| format!("s{i}")).collect(),
+            trend,
+            ..test_support::narrative(title, confidence)
+        };
+        let perps = narrative("Perps Volume Surge", TrendDirection::Stable, 0.7, 2);
+        let agents = narrative("Onchain AI Agents", TrendDirection::Accelerating, 0.7, 1);
+        let depin = narrative("DePIN Buildout", TrendDirection::Decelerating, 0.6, 4);
+        let narratives = [perps.clone(), depin.clone(), agents.clone()];
+        let weights = TrendWeights {
+            accelerating: 1.5,
+            ..Default::default()
+        };
+        let order = |key| {
+            let mut ranked: Vec<&Narrative> = narratives.iter().collect();
+            rank_narratives(&mut ranked, key, &weights);
+            ranked.iter().map(|n| n.title.as_str()).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            order(NarrativeSort::Confidence),
+            ["Onchain AI Agents", "Perps Volume Surge", "DePIN Buildout"],
+            "equal confidence falls to the trend weight"
+        );
+        assert_eq!(
+            order(NarrativeSort::Signals),
+            ["DePIN Buildout", "Perps Volume Surge", "Onchain AI Agents"]
+        );
+        assert_eq!(
+            order(NarrativeSort::Trend),
+            ["Onchain AI Agents", "Perps Volume Surge", "DePIN Buildout"]
+        );
+
+        let idea = |title: &str, narrative: &Narrative| BuildIdea {
+            title: title.into(),
+            description: String::new(),
+            target_user: String::new(),
+            mvp_scope: String::new(),
+            competitive_landscape: String::new(),
+            timing_rationale: String::new(),
+            narrative_id: narrative.id.clone(),
+            carried_over: false,
+        };
+        let ideas = [
+            idea("Funding Monitor", &perps),
+            idea("Coverage Map", &depin),
+            idea("Agent Wallet", &agents),
+            idea("Agent Registry", &agents),
+        ];
+        let options = RenderOptions {
+            trend_weights: weights.clone(),
+            sort_by: NarrativeSort::Signals,
+            ..Default::default()
+        };
+        let html = render(&[signal()], &narratives, &ideas, &options, None, None).unwrap();
+        let at = |title: &str| html.find(&format!(">{title}<")).unwrap();
+        assert!(at("Coverage Map") < at("Funding Monitor"));
+        assert!(at("Funding Monitor") < at("Agent Wallet"));
+        assert!(
+            at("Agent Wallet") < at("Agent Registry"),
+            "ideas keep their order under a narrative"
+        );
+    }
+
+    #[test]
+    fn low_confidence_narratives_hide_with_their_ideas() {
+        let narrative = |title: &str, confidence| Narrative {
+            trend: TrendDirection::Stable,
+            ..test_support::narrative(title, confidence)
+        };
+        let strong = narrative("Validator Client Diversity", 0.8);
+        let middling = narrative("Stablecoin Payments", 0.6);
+        let weak = narrative("Onchain Gaming Revival", 0.3);
+        let idea = |title: &str, narrative: &Narrative| BuildIdea {
+            title: title.into(),
+            description: String::new(),
+            target_user: String::new(),
+            mvp_scope: String::new(),
+            competitive_landscape: String::new(),
+            timing_rationale: String::new(),
+            narrative_id: narrative.id.clone(),
+            carried_over: false,
+        };
+        let ideas = [
+            idea("Client Dashboard", &strong),
+            idea("Game Launcher", &weak),
+        ];
+        let narratives = [strong, middling, weak];
+
+        let all = render(
+            &[signal()],
+            &narratives,
+            &ideas,
+            &RenderOptions::default(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(all.contains("Onchain Gaming Revival"));
+        assert!(
+            all.contains(
+                "bg-green-100 text-green-800 dark:bg-green-900/40 dark:text-green-300 px-2"
+            )
+        );
+        assert!(all.contains("dark:bg-yellow-900/40"));
+        assert!(all.contains("dark:bg-red-900/40"));
+        assert!(!all.contains("confidence hidden"));
+
+        let options = RenderOptions {
+            min_confidence: 0.5,
+            ..Default::default()
+        };
+        let filtered = render(&[signal()], &narratives, &ideas, &options, None, None).unwrap();
+        assert!(!filtered.contains("Onchain Gaming Revival"));
+        assert!(!filtered.contains("Game Launcher"));
+        assert!(filtered.contains("Client Dashboard"));
+        assert!(filtered.contains("Stablecoin Payments"));
+        assert!(filtered.contains("1 narrative(s) below 50% confidence hidden"));
+    }
+
+    #[test]
+    fn unchanged_sources_are_noted() {
+        let options = RenderOptions {
+            unchanged_sources: vec!["Social".into()],
+            ..Default::default()
+        };
+        let html = render(&[signal()], &[], &[], &options, None, None).unwrap();
+        assert!(html.contains("Unchanged since the last run"));
+        assert!(html.contains("Social"));
+
+        let fresh = render(&[signal()], &[], &[], &RenderOptions::default(), None, None).unwrap();
+        assert!(!fresh.contains("Unchanged since the last run"));
+    }
+
+    #[test]
+    fn embedded_signals_cannot_break_out_of_their_script() {
+        let mut s = signal();
+        s.title = "</script><script>alert(1)</script> & <!-- \u{2028}".into();
+        let options = RenderOptions {
+            embed_raw_signals: true,
+            ..Default::default()
+        };
+        let html = render(&[s.clone()], &[], &[], &options, None, None).unwrap();
+        let marker = "id=\"raw-signals-data\">";
+        let start = html.find(marker).unwrap() + marker.len();
+        let json = &html[start..start + html[start..].find("</script>").unwrap()];
+        assert!(!json.contains(['<', '>', '&', '\u{2028}']), "{json}");
+        let parsed: Vec<Signal> = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed[0].title, s.title);
+        assert!(html.contains("Download raw data"));
+
+        let plain = render(&[s], &[], &[], &RenderOptions::default(), None, None).unwrap();
+        assert!(!plain.contains(marker));
+        assert!(!plain.contains("Download raw data"));
+    }
+
+    #[test]
+    fn run_metadata_is_noted_in_the_footer() {
+        let options = RenderOptions {
+            run_metadata: Some(RunMetadata {
+                config_hash: "0123456789abcdef".into(),
+                provider: "anthropic".into(),
+                model: "claude-test".into(),
+                source_set: vec!["GitHub".into(), "Solana RPC".into()],
+                tool_version: "0.1.0".into(),
+                started_at: DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+                    .unwrap()
+                    .to_utc(),
+                duration_ms: 42_350,
+            }),
+            ..Default::default()
+        };
+        let html = render(&[signal()], &[], &[], &options, None, None).unwrap();
+        assert!(html.contains(r#"<meta name="solscout-config-hash" content="0123456789abcdef">"#));
+        assert!(html.contains(
+            "Run: config 0123456789abcdef · anthropic/claude-test · 2 sources (GitHub, Solana RPC) · st-narrative v0.1.0"
+        ));
+        assert!(html.contains("took 42.4s"), "{html}");
+
+        let plain = render(&[signal()], &[], &[], &RenderOptions::default(), None, None).unwrap();
+        assert!(!plain.contains("solscout-config-hash"));
+        assert!(!plain.contains("Run: config"));
+    }
+
+    #[test]
+    fn custom_template_renders_the_same_view_model() {
+        let dir = TempDir::new("template");
+        let path = dir.join("report.html");
+        std::fs::write(
+            &path,
+            "<h1>Acme Research</h1>{{ total_signals }} signals\n\
+             {% for group in signal_groups %}{% for s in group.signals %}\
+             <li id=\"{{ s.id }}\">{{ s.title }}: {{ s.metrics.0.text }}</li>{% endfor %}{% endfor %}\
+             {% if no_narratives %}<p>No narratives</p>{% endif %}",
+        )
+        .unwrap();
+        let options = RenderOptions {
+            template_path: Some(path.display().to_string()),
+            ..Default::default()
+        };
+        let s = signal();
+        let html = render(std::slice::from_ref(&s), &[], &[], &options, None, None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            html,
+            format!(
+                "<h1>Acme Research</h1>1 signals\n<li id=\"{}\">Solana TPS: 4000 total: avg_tps: 4,000 tx&#x2f;s</li><p>No narratives</p>",
+                s.id
+            )
+        );
+
+        let err = render(&[s], &[], &[], &options, None, None).unwrap_err();
+        assert!(err.to_string().contains("report.template_path"), "{err}");
+    }
+
+    #[test]
+    fn signals_show_their_age_and_stale_ones_fade() {
+        let now = DateTime::from_timestamp(1_767_268_800, 0).unwrap();
+        let mut old_post = signal();
+        old_post.title = "Old post".into();
+        old_post.timestamp = now;
+        old_post.content_timestamp = Some(now - chrono::Duration::days(9));
+        let mut collected = signal();
+        collected.timestamp = now - chrono::Duration::minutes(5);
+        let options = RenderOptions {
+            stale_after: Some(chrono::Duration::hours(72)),
+            ..Default::default()
+        };
+        let html = render_at(&[old_post, collected], &[], &[], &options, None, None, now).unwrap();
+        assert!(html.contains("9d old · <span"), "content age, marked stale");
+        assert!(html.contains("collection time, 5m before report</div>"));
+        assert_eq!(html.matches("signal-row stale opacity-60").count(), 1);
+        assert!(html.contains("title=\"No content timestamp from this source; collected"));
+
+        assert_eq!(compact_age(chrono::Duration::seconds(-30)), "<1m");
+        assert_eq!(compact_age(chrono::Duration::hours(47)), "47h");
+        assert_eq!(compact_age(chrono::Duration::hours(49)), "2d");
+    }
+
+    #[test]
+    fn full_report_has_no_banner() {
+        let html = render(&[signal()], &[], &[], &RenderOptions::default(), None, None).unwrap();
+        assert!(!html.contains("LLM analysis was skipped"));
+    }
+
+    /// Compares `html` against `testdata/report/{name}.html`. Run with
+    /// `UPDATE_GOLDEN=1` to rewrite the snapshot after an intended change.
+    fn assert_golden(name: &str, html: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/report")
+            .join(format!("{name}.html"));
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            write_report(&path, html).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "{}: {e} (run with UPDATE_GOLDEN=1 to create it)",
+                path.display()
+            )
+        });
+        if html != expected {
+            let line = html
+                .lines()
+                .zip(expected.lines())
+                .position(|(a, b)| a != b)
+                .unwrap_or_else(|| html.lines().count().min(expected.lines().count()));
+            panic!(
+                "{} differs from the rendered report at line {}; \
+                 rerun with UPDATE_GOLDEN=1 if the change is intended",
+                path.display(),
+                line + 1
+            );
+        }
+    }
+
+    /// Fixed signals covering every source shape the template handles.
+    fn golden_signals() -> Vec<Signal> {
+        let fixed = |mut s: Signal| {
+            s.timestamp = DateTime::from_timestamp(1_767_225_600, 0).unwrap();
+            s
+        };
+        vec![
+            fixed(signal()),
+            fixed(Signal::new(
+                SignalSource::GitHub,
+                "DeFi".into(),
+                "New DeFi repos: 9".into(),
+                "Repos created in the last 30 days".into(),
+                vec![
+                    Metric {
+                        name: "new_repos".into(),
+                        value: 9.0,
+                        unit: String::new(),
+                    },
+                    Metric {
+                        name: "fee_share".into(),
+                        value: 0.0042,
+                        unit: String::new(),
+                    },
+                ],
+                Some("https://github.com/topics/solana".into()),
+            )),
+            fixed(Signal::new(
+                SignalSource::DeFiLlama,
+                "DeFi".into(),
+                "Solana DEX volume up 18%".into(),
+                String::new(),
+                Vec::new(),
+                None,
+            )),
+        ]
+    }
+
+    fn golden_narrative(signals: &[Signal]) -> Narrative {
+        Narrative {
+            summary: "Volume consolidates on a few venues.".into(),
+            adjusted_confidence: Some(0.74),
+            confidence_note: "Two sources agree.".into(),
+            supporting_signals: signals.iter().map(|s| s.id.clone()).collect(),
+            key_metrics: vec![Metric {
+                name: "dex_share".into(),
+                value: 61.5,
+                unit: String::new(),
+            }],
+            ..test_support::narrative("DEX Volume Concentration", 0.82)
+        }
+    }
+
+    fn golden_idea(narrative_id: &str) -> BuildIdea {
+        BuildIdea {
+            title: "Route Splitter".into(),
+            description: "Split large swaps across venues.".into(),
+            target_user: "Treasury managers".into(),
+            mvp_scope: "CLI quoting two DEXes".into(),
+            competitive_landscape: "Aggregators cover retail sizes.".into(),
+            timing_rationale: "Venue concentration raises slippage.".into(),
+            narrative_id: narrative_id.into(),
+            carried_over: false,
+        }
+    }
+
+    fn render_golden(signals: &[Signal], narratives: &[Narrative], ideas: &[BuildIdea]) -> String {
+        render_at(
+            signals,
+            narratives,
+            ideas,
+            &RenderOptions::default(),
+            None,
+            Some("anthropic/claude-sonnet-4-5"),
+            DateTime::from_timestamp(1_767_268_800, 0).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn golden_full_report() {
+        let signals = golden_signals();
+        let narrative = Narrative {
+            continuity: Some(Continuity {
+                streak: 3,
+                first_seen: "2026-01-01T00:00:00Z".parse().unwrap(),
+                confidence_trajectory: vec![0.6, 0.68, 0.74],
+            }),
+            recurrence: Some(Recurrence {
+                appeared: 4,
+                runs: 5,
+            }),
+            ..golden_narrative(&signals)
+        };
+        let ideas = [
+            golden_idea(&narrative.id),
+            BuildIdea {
+                title: "Orphaned Idea".into(),
+                ..golden_idea("n00000000")
+            },
+        ];
+        let html = render_golden(&signals, &[narrative], &ideas);
+
+        // Ideas of unknown narratives rank last, their label before the title.
+        let before = &html[..html.find("Orphaned Idea").unwrap()];
+        let card = &before[before.rfind("Route Splitter").unwrap()..];
+        assert!(
+            card.contains(">Unknown</span>"),
+            "an idea whose narrative is gone is labelled Unknown"
+        );
+        assert!(
+            html.contains("new_repos: 9.0<"),
+            "empty units leave no trailing space"
+        );
+        assert!(html.contains("fee_share: 0.0042<"));
+        assert!(html.contains(">Found in 4 of 5 synthesis runs</span>"));
+        assert!(html.contains("dex_share: 61.5<"));
+        assert!(html.contains("oldest first: 60% → 68% → 74%\">3 runs in a row<"));
+        assert!(html.contains("aria-label=\"Trend: Accelerating\""));
+        assert_golden("full", &html);
+    }
+
+    #[test]
+    fn report_has_landmarks_and_a_heading_outline() {
+        let signals = golden_signals();
+        let narrative = golden_narrative(&signals);
+        let html = render_golden(&signals, &[narrative], &[golden_idea("n00000000")]);
+
+        for landmark in ["<header", "<main", "<footer"] {
+            assert_eq!(html.matches(landmark).count(), 1, "one {landmark}");
+        }
+        assert_eq!(html.matches("<h1").count(), 1);
+        for heading in [
+            "summary-heading",
+            "narratives-heading",
+            "ideas-heading",
+            "signals-heading",
+        ] {
+            assert!(html.contains(&format!("aria-labelledby=\"{heading}\"")));
+            assert!(html.contains(&format!("<h2 id=\"{heading}\"")));
+        }
+        assert_eq!(
+            html.matches("<article").count(),
+            2,
+            "one per narrative and idea"
+        );
+        assert!(html.contains("% confidence: "), "confidence badge says why");
+        assert!(html.contains("<th scope=\"col\""));
+        assert!(html.contains("@media print"));
+        assert!(html.contains("class=\"print-hidden"));
+    }
+
+    #[test]
+    fn golden_report_without_narratives() {
+        let html = render_golden(&golden_signals(), &[], &[]);
+        assert!(html.contains("Insufficient signal for narratives this run"));
+        assert_golden("no_narratives", &html);
+    }
+
+    #[test]
+    fn golden_report_without_signals() {
+        assert_golden("empty", &render_golden(&[], &[], &[]));
+    }
+}
@@ -0,0 +1,131 @@
+//! Report templates loaded at runtime from `report.template_path`, rendered
+//! with MiniJinja against the report's view model, so a branded report needs
+//! no rebuild.
+//!
+//! Output is HTML-escaped unless the file is Markdown or plain text, and
+//! unknown variables are errors, so typos surface at once.
+
+use crate::error::{Error, Result};
+use minijinja::value::{Serde, Value};
+use minijinja::{AutoEscape, Environment, UndefinedBehavior};
+use serde::Serialize;
+use std::path::Path;
+
+/// A parsed template, ready to render any number of times.
+#[derive(Debug)]
+pub struct CustomTemplate {
+    name: String,
+    env: Environment<'static>,
+}
+
+impl CustomTemplate {
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("report.template_path {}: {e}", path.display())))?;
+        Self::parse(&path.display().to_string(), &source)
+    }
+
+    pub fn parse(name: &str, source: &str) -> Result<Self> {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.set_auto_escape_callback(auto_escape);
+        env.add_template_owned(name.to_string(), source.to_string())
+            .map_err(template_error)?;
+        Ok(Self {
+            name: name.to_string(),
+            env,
+        })
+    }
+
+    pub fn render(&self, context: &impl Serialize) -> Result<String> {
+        self.env
+            .get_template(&self.name)
+            .and_then(|template| template.render(Value::from(Serde(context))))
+            .map_err(template_error)
+    }
+}
+
+/// No escaping for `.md` and `.txt` templates, where `&amp;` would corrupt
+/// links and text; HTML escaping for anything else, as reports are HTML.
+fn auto_escape(name: &str) -> AutoEscape {
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("md" | "markdown" | "txt" | "text") => AutoEscape::None,
+        _ => AutoEscape::Html,
+    }
+}
+
+fn template_error(e: minijinja::Error) -> Error {
+    Error::Template(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(source: &str, context: serde_json::Value) -> Result<String> {
+        CustomTemplate::parse("test.html", source)?.render(&context)
+    }
+
+    #[test]
+    fn prints_loops_and_branches_like_jinja() {
+        let context = json!({
+            "title": "Fish & <Chips>",
+            "items": [{"name": "a", "tags": ["x", "y"]}, {"name": "b", "tags": []}],
+            "trend": "Accelerating",
+            "note": null,
+        });
+        let source = "{# header #}<h1>{{ title }}</h1>{{ title|safe }}\n\
+            {% for item in items %}{{ loop.index }}.{{ item.name|upper }}\
+            {% if item.tags %}[{{ item.tags|join(\", \") }}]{% else %}[-]{% endif %}\
+            {% if not loop.last %}, {% endif %}{% endfor %}\n\
+            {% if trend == \"Stable\" %}flat{% elif trend != 'Stable' %}moving{% endif %} \
+            {{ items|length }}{% if note %}!{% endif %} {{ items.0.tags.1 }}";
+        assert_eq!(
+            render(source, context).unwrap(),
+            "<h1>Fish &amp; &lt;Chips&gt;</h1>Fish & <Chips>\n1.A[x, y], 2.B[-]\nmoving 2 y"
+        );
+    }
+
+    #[test]
+    fn escaping_follows_the_file_extension() {
+        let url = json!({"url": "https://example.com/pools?a=1&b=<2>"});
+        let render = |name: &str| {
+            CustomTemplate::parse(name, "[pool]({{ url }})")
+                .unwrap()
+                .render(&url)
+                .unwrap()
+        };
+        let plain = "[pool](https://example.com/pools?a=1&b=<2>)";
+        assert_eq!(render("digest.md"), plain);
+        assert_eq!(render("notes/DIGEST.TXT"), plain);
+        assert_eq!(
+            render("report.html"),
+            "[pool](https:&#x2f;&#x2f;example.com&#x2f;pools?a=1&amp;b=&lt;2&gt;)"
+        );
+        assert_eq!(render("report.tmpl"), render("report.html"));
+    }
+
+    #[test]
+    fn mistakes_are_reported_with_their_line() {
+        let err = |source: &str| {
+            render(source, json!({"items": []}))
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(err("a\n{{ missing }}").contains("`missing` is undefined (in test.html:2)"));
+        assert!(err("\n{% if items.0.name %}{% endif %}").contains("(in test.html:2)"));
+        assert!(
+            err("{% for x in items %}\n{{ x.name }}")
+                .contains("unexpected end of input, expected end of block (in test.html:2)")
+        );
+        assert!(err("\n\n{% endif %}").contains("unknown statement endif (in test.html:3)"));
+        assert!(err("{{ items|shout }}").contains("filter shout is unknown"));
+        assert!(err("{% while x %}").contains("unknown statement while"));
+        assert!(err("{{ items").contains("expected end of variable block"));
+    }
+}
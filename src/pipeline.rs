@@ -0,0 +1,1207 @@
+use crate::alerts;
+use crate::analysis;
+use crate::annotations;
+use crate::config::{Config, SentimentMode, SignalDetail};
+use crate::error::{Error, Result};
+use crate::history::History;
+use crate::http::HttpClient;
+use crate::links;
+use crate::llm::{LlmCall, LlmClient, estimate_tokens};
+use crate::narrative_history::NarrativeHistory;
+use crate::sources::{self, Source};
+use crate::types::{self, BuildIdea, Narrative, RunMetadata, Signal};
+use crate::util::safe_div;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{Instrument, debug, error, info, info_span, warn};
+
+/// Everything a pipeline run produces, ready for rendering or browsing.
+#[derive(Serialize, Deserialize)]
+pub struct Analysis {
+    pub signals: Vec<Signal>,
+    pub narratives: Vec<Narrative>,
+    pub build_ideas: Vec<BuildIdea>,
+    /// Why LLM analysis was skipped, if it was.
+    #[serde(default)]
+    pub analysis_skipped: Option<String>,
+    /// Why idea generation failed, if it did; the narratives still stand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ideas_skipped: Option<String>,
+    /// `provider/model` that answered the LLM calls, naming the fallback if
+    /// it took over.
+    #[serde(default)]
+    pub produced_by: Option<String>,
+    /// How each enabled source fared.
+    #[serde(default)]
+    pub sources: Vec<SourceStatus>,
+    /// Every LLM call of the run with its wall-clock time.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub llm_calls: Vec<LlmCall>,
+    /// Provenance of the run; absent for runs stored before it was kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RunMetadata>,
+}
+
+/// One source's outcome in a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceStatus {
+    pub name: String,
+    /// Signals kept, after `max_signals`.
+    pub signals: usize,
+    /// Why collection failed, if it did.
+    pub error: Option<String>,
+    /// Every page it read was unchanged since the last run and came from
+    /// the HTTP cache: stale, but still valid.
+    #[serde(default)]
+    pub unchanged: bool,
+    /// Wall-clock time its collection took.
+    #[serde(default)]
+    pub elapsed_ms: u64,
+    /// HTTP requests it sent, retries included.
+    #[serde(default)]
+    pub requests: usize,
+}
+
+/// How completely a run went. Its exit code lets scripts tell a degraded
+/// run from a broken one; hard failures exit 1 through the error path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    /// Every source answered and the LLM analysis ran: exit 0.
+    Complete,
+    /// Some sources or idea generation failed but a report with narratives
+    /// was produced: exit 2.
+    Partial,
+    /// LLM analysis was skipped; the report is signals only: exit 3.
+    SignalsOnly,
+}
+
+impl RunStatus {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Complete => 0,
+            Self::Partial => 2,
+            Self::SignalsOnly => 3,
+        }
+    }
+
+    /// From source outcomes and the reasons analysis or idea generation was
+    /// skipped, if they were. Signals-only outranks partial.
+    pub fn of(
+        sources: &[SourceStatus],
+        analysis_skipped: Option<&str>,
+        ideas_skipped: Option<&str>,
+    ) -> Self {
+        if analysis_skipped.is_some() {
+            Self::SignalsOnly
+        } else if ideas_skipped.is_some() || sources.iter().any(|s| s.error.is_some()) {
+            Self::Partial
+        } else {
+            Self::Complete
+        }
+    }
+
+    /// One line on what degraded, `None` for a complete run.
+    pub fn summary(
+        self,
+        sources: &[SourceStatus],
+        analysis_skipped: Option<&str>,
+        ideas_skipped: Option<&str>,
+    ) -> Option<String> {
+        let failed: Vec<String> = sources
+            .iter()
+            .filter_map(|s| Some(format!("{} ({})", s.name, s.error.as_ref()?)))
+            .collect();
+        let failed = (!failed.is_empty()).then(|| {
+            format!(
+                "{} of {} sources failed: {}",
+                failed.len(),
+                sources.len(),
+                failed.join("; ")
+            )
+        });
+        match (self, analysis_skipped) {
+            (Self::Complete, _) => None,
+            (Self::Partial, _) => {
+                let ideas = ideas_skipped.map(|reason| format!("build ideas skipped ({reason})"));
+                let parts: Vec<String> = [failed, ideas].into_iter().flatten().collect();
+                Some(format!("partial: {}", parts.join("; ")))
+            }
+            (Self::SignalsOnly, reason) => {
+                let mut line = format!(
+                    "signals only: LLM analysis skipped ({})",
+                    reason.unwrap_or("unknown reason")
+                );
+                if let Some(failed) = failed {
+                    line.push_str(&format!("; {failed}"));
+                }
+                Some(line)
+            }
+        }
+    }
+}
+
+impl Analysis {
+    pub fn status(&self) -> RunStatus {
+        RunStatus::of(
+            &self.sources,
+            self.analysis_skipped.as_deref(),
+            self.ideas_skipped.as_deref(),
+        )
+    }
+
+    /// One line on what degraded, `None` for a complete run.
+    pub fn summary(&self) -> Option<String> {
+        self.status().summary(
+            &self.sources,
+            self.analysis_skipped.as_deref(),
+            self.ideas_skipped.as_deref(),
+        )
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| Error::parse(format!("stored run {}: {e}", path.display())))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::parse(format!("serializing run: {e}")))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// Fields a stored signal can't be read without. `id` is derived when
+/// missing and an absent `url` is read as none.
+const REQUIRED_SIGNAL_FIELDS: [&str; 6] = [
+    "source",
+    "category",
+    "title",
+    "description",
+    "metrics",
+    "timestamp",
+];
+
+/// Read a signal snapshot: the JSON array printed by `signals`, or a stored
+/// run's `signals`. Snapshots from older versions are reported by which
+/// fields they lack rather than by the first serde error.
+pub fn load_signals(path: &Path) -> Result<Vec<Signal>> {
+    let content = std::fs::read_to_string(path)?;
+    let context = |msg: String| Error::parse(format!("signal snapshot {}: {msg}", path.display()));
+    let value: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| context(e.to_string()))?;
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(mut run) => match run.remove("signals") {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => return Err(context("expected a JSON array of signals".into())),
+        },
+        _ => return Err(context("expected a JSON array of signals".into())),
+    };
+
+    let mut missing: Vec<(&str, usize)> = REQUIRED_SIGNAL_FIELDS.iter().map(|f| (*f, 0)).collect();
+    for item in &items {
+        for (field, count) in &mut missing {
+            if item.get(*field).is_none() {
+                *count += 1;
+            }
+        }
+    }
+    missing.retain(|(_, count)| *count > 0);
+    if !missing.is_empty() {
+        let fields: Vec<String> = missing
+            .iter()
+            .map(|(field, count)| format!("{field} (in {count})"))
+            .collect();
+        return Err(context(format!(
+            "{} signals; missing required fields: {}. Re-collect with `signals` to refresh an old snapshot",
+            items.len(),
+            fields.join(", ")
+        )));
+    }
+
+    let mut signals = items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            serde_json::from_value(item).map_err(|e| context(format!("signal {}: {e}", i + 1)))
+        })
+        .collect::<Result<Vec<Signal>>>()?;
+    types::fill_missing_ids(&mut signals);
+    types::canonicalize_units(&mut signals);
+    Ok(signals)
+}
+
+/// Collect signals from all sources in parallel. A failing source is logged
+/// and skipped; the rest still contribute.
+pub async fn collect_signals(cfg: &Config, http: &HttpClient) -> Vec<Signal> {
+    collect_signals_with_status(cfg, http).await.0
+}
+
+/// [`collect_signals`], also reporting how each enabled source fared.
+pub async fn collect_signals_with_status(
+    cfg: &Config,
+    http: &HttpClient,
+) -> (Vec<Signal>, Vec<SourceStatus>) {
+    let llm_client = (cfg.social.sentiment == SentimentMode::Llm)
+        .then(|| LlmClient::from_config(&cfg.llm, &cfg.http));
+    let llm = match &llm_client {
+        Some(Ok(client)) => Some(client),
+        Some(Err(e)) => {
+            warn!("LLM sentiment unavailable, keeping keyword scores: {e}");
+            None
+        }
+        None => None,
+    };
+    let collected = collect_with_llm(cfg, http, llm).await;
+    if let Some(client) = llm {
+        client.log_usage();
+    }
+    collected
+}
+
+/// Collection with `llm` scoring sentiment when `social.sentiment` asks for
+/// it, so a run's sentiment calls share its client, usage and spend cap.
+async fn collect_with_llm(
+    cfg: &Config,
+    http: &HttpClient,
+    llm: Option<&LlmClient>,
+) -> (Vec<Signal>, Vec<SourceStatus>) {
+    info!("collecting signals from all sources...");
+    http.reset_retry_budget();
+    let registry: Vec<&dyn Source> = sources::registry(cfg)
+        .into_iter()
+        .filter(|source| {
+            let enabled = source.enabled();
+            if !enabled {
+                debug!(source = source.name(), "source disabled, skipping");
+            }
+            enabled
+        })
+        .collect();
+
+    let results = join_all(registry.iter().map(|source| async move {
+        let source_http = http.for_source(&cfg.http, source.key());
+        let started = std::time::Instant::now();
+        let result = source
+            .collect(&source_http)
+            .instrument(info_span!("collect", source = source.name()))
+            .await;
+        let timing = (
+            started.elapsed().as_millis() as u64,
+            source_http.request_count(),
+        );
+        (result, source_http.all_unchanged(), timing)
+    }))
+    .await;
+    if let Err(e) = http.save_cache() {
+        warn!("failed to save HTTP cache: {e}");
+    }
+
+    let store = if cfg.history.enabled {
+        History::load(Path::new(&cfg.history.path))
+            .map_err(|e| warn!("failed to load history for growth metrics and importance: {e}"))
+            .ok()
+    } else {
+        None
+    };
+    let window = cfg.history.window;
+
+    let mut signals = Vec::new();
+    let mut statuses = Vec::new();
+    for (source, (result, unchanged, (elapsed_ms, requests))) in registry.iter().zip(results) {
+        match result {
+            Ok(s) => {
+                let s = cap_signals(
+                    source.name(),
+                    s,
+                    source.max_signals(),
+                    store.as_ref().map(|h| (h, window)),
+                );
+                info!(
+                    source = source.name(),
+                    signal_count = s.len(),
+                    unchanged,
+                    elapsed_ms,
+                    requests,
+                    "signals collected"
+                );
+                statuses.push(SourceStatus {
+                    name: source.name().into(),
+                    signals: s.len(),
+                    error: None,
+                    unchanged,
+                    elapsed_ms,
+                    requests,
+                });
+                signals.extend(s);
+            }
+            Err(e) => {
+                error!(source = source.name(), error = %e, elapsed_ms, "collection failed");
+                statuses.push(SourceStatus {
+                    name: source.name().into(),
+                    signals: 0,
+                    error: Some(e.to_string()),
+                    unchanged: false,
+                    elapsed_ms,
+                    requests,
+                });
+            }
+        }
+    }
+
+    if let Some(path) = &cfg.external.signals_file {
+        let label = &cfg.external.label;
+        let started = std::time::Instant::now();
+        let loaded = crate::external::load(Path::new(path), label);
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match loaded {
+            Ok(external) => {
+                info!(path = %path, signal_count = external.len(), "external signals merged");
+                statuses.push(SourceStatus {
+                    name: label.clone(),
+                    signals: external.len(),
+                    error: None,
+                    unchanged: false,
+                    elapsed_ms,
+                    requests: 0,
+                });
+                signals.extend(external);
+            }
+            Err(e) => {
+                error!(path = %path, error = %e, "external signals not merged");
+                statuses.push(SourceStatus {
+                    name: label.clone(),
+                    signals: 0,
+                    error: Some(e.to_string()),
+                    unchanged: false,
+                    elapsed_ms,
+                    requests: 0,
+                });
+            }
+        }
+    }
+
+    let collected = signals.len();
+    analysis::aggregator::filter_categories(
+        &mut signals,
+        &cfg.analysis.include_categories,
+        &cfg.analysis.exclude_categories,
+    );
+    if signals.len() < collected {
+        info!(
+            dropped = collected - signals.len(),
+            "signals filtered out by category"
+        );
+    }
+
+    if let Some(store) = &store {
+        sources::solana_rpc::add_growth(&mut signals, store);
+        let smoothed =
+            analysis::aggregator::add_smoothed(&mut signals, store, &cfg.analysis.smoothing);
+        if smoothed > 0 {
+            info!(smoothed, "added smoothed metrics");
+        }
+    }
+    if cfg.github.enabled() {
+        let github_http = http.for_source(&cfg.http, cfg.github.key());
+        let github = sources::github::client(&cfg.github, &github_http);
+        sources::github::add_star_velocity(&github, store.as_ref(), &mut signals).await;
+    }
+
+    if cfg.social.sentiment == SentimentMode::Llm {
+        match llm {
+            Some(llm) => rescore_sentiment(llm, &mut signals).await,
+            None => warn!("LLM sentiment unavailable, keeping keyword scores"),
+        }
+    }
+
+    let sol_usd = analysis::pricing::sol_usd(&cfg.pricing, http).await;
+    let converted = analysis::pricing::add_usd_equivalents(&mut signals, sol_usd);
+    if converted > 0 {
+        info!(converted, sol_usd, "added USD equivalents of SOL metrics");
+    }
+    if cfg.defi_llama.enabled
+        && cfg
+            .solana
+            .tracked_pools
+            .iter()
+            .any(|p| p.defillama_pool.is_some())
+    {
+        let llama_http = http.for_source(&cfg.http, cfg.defi_llama.key());
+        let flagged =
+            sources::solana_rpc::cross_check_pool_tvl(&cfg.solana, &llama_http, &mut signals).await;
+        if flagged > 0 {
+            warn!(
+                flagged,
+                "pool TVL differs from DeFiLlama's beyond tolerance"
+            );
+        }
+    }
+
+    links::normalize(&mut signals);
+    if cfg.output.check_links {
+        let pruned = links::prune_dead(http, &mut signals).await;
+        if pruned > 0 {
+            warn!(pruned, "removed dead links from signals");
+        }
+    }
+    types::fill_missing_ids(&mut signals);
+    let renamed = types::canonicalize_units(&mut signals);
+    if renamed > 0 {
+        debug!(renamed, "metric units canonicalized");
+    }
+    if let Err(e) = annotate(cfg, &mut signals) {
+        error!("annotations not applied: {e}");
+    }
+    score_importance(&mut signals, store.as_ref().map(|h| (h, window)));
+    (signals, statuses)
+}
+
+/// Set each signal's `importance`, scoring novelty against `history` (the
+/// store and its window) when there is one. Returns whether any signal had
+/// enough history to be scored for novelty.
+fn score_importance(signals: &mut [Signal], history: Option<(&History, usize)>) -> bool {
+    let novelty = match history {
+        Some((store, window)) => analysis::aggregator::novelty(signals, store, window),
+        None => vec![None; signals.len()],
+    };
+    let novel = novelty.iter().any(Option::is_some);
+    let scores = analysis::aggregator::importance(signals, &novelty);
+    for (signal, score) in signals.iter_mut().zip(scores) {
+        signal.importance = Some((score * 1000.0).round() / 1000.0);
+    }
+    novel
+}
+
+/// Replace keyword sentiment scores with the LLM's. Any failure keeps the
+/// keyword scores, so collection never depends on the LLM.
+async fn rescore_sentiment(llm_client: &LlmClient, signals: &mut [Signal]) {
+    match analysis::sentiment::rescore_with_llm(llm_client, signals).await {
+        Ok(rescored) => info!(rescored, "sentiment scored by LLM"),
+        Err(e) => warn!("LLM sentiment failed, keeping keyword scores: {e}"),
+    }
+}
+
+/// Apply `analysis.annotations_path` to signals with final IDs, before
+/// aggregation.
+pub fn annotate(cfg: &Config, signals: &mut Vec<Signal>) -> Result<()> {
+    let path = Path::new(&cfg.analysis.annotations_path);
+    let annotations = annotations::load(path)?;
+    let applied = annotations::apply(signals, &annotations);
+    if applied > 0 {
+        info!(applied, path = %path.display(), "signal annotations applied");
+    }
+    Ok(())
+}
+
+/// Keep at most `limit` signals from one source so a noisy source can't
+/// dominate aggregation. When history scores any signal for novelty, the
+/// most important are kept, so an old anomaly outlasts fresh steady-state
+/// readings. Then, or without novelty only, those carrying metrics and a
+/// link, then the most recent. Ties keep the source's own order.
+fn cap_signals(
+    source: &str,
+    mut signals: Vec<Signal>,
+    limit: Option<usize>,
+    history: Option<(&History, usize)>,
+) -> Vec<Signal> {
+    let Some(limit) = limit else {
+        return signals;
+    };
+    if signals.len() <= limit {
+        return signals;
+    }
+    warn!(
+        source,
+        collected = signals.len(),
+        kept = limit,
+        "source truncated by max_signals; raise the limit to keep more"
+    );
+    // Without novelty, importance is only quality and category spread,
+    // which would rank a lone bare signal over rich, recent ones.
+    let novel = score_importance(&mut signals, history);
+    let importance = |s: &Signal| {
+        if novel {
+            s.importance.unwrap_or(0.0)
+        } else {
+            0.0
+        }
+    };
+    signals.sort_by(|a, b| {
+        let richness = |s: &Signal| (!s.metrics.is_empty(), s.url.is_some());
+        importance(b)
+            .total_cmp(&importance(a))
+            .then(richness(b).cmp(&richness(a)))
+            .then(b.timestamp.cmp(&a.timestamp))
+    });
+    signals.truncate(limit);
+    signals
+}
+
+/// Aggregate signals, score them against history, and build the LLM input.
+pub fn prepare(cfg: &Config, signals: &[Signal]) -> Result<String> {
+    let mut groups = analysis::aggregator::aggregate(signals, cfg.analysis.recency_half_life_hours);
+    analysis::aggregator::mark_headlines(&mut groups, &cfg.analysis.headline_metrics);
+    let novelty = if cfg.history.enabled {
+        let store = History::load(Path::new(&cfg.history.path))?;
+        analysis::aggregator::novelty(signals, &store, cfg.history.window)
+    } else {
+        vec![None; signals.len()]
+    };
+
+    info!(groups = groups.len(), "signal groups formed");
+    let detail = cfg.synthesis.signal_detail;
+    let json = analysis::aggregator::signals_to_json(signals, &groups, &novelty, detail);
+    if detail == SignalDetail::Compact {
+        let full =
+            analysis::aggregator::signals_to_json(signals, &groups, &novelty, SignalDetail::Full);
+        let (full_tokens, compact_tokens) = (estimate_tokens(&full), estimate_tokens(&json));
+        info!(
+            full_tokens,
+            compact_tokens,
+            saved_pct = format!(
+                "{:.0}",
+                safe_div(
+                    full_tokens.saturating_sub(compact_tokens) as f64,
+                    full_tokens as f64,
+                    "compact_savings"
+                ) * 100.0
+            ),
+            "compact signal detail"
+        );
+    }
+    Ok(json)
+}
+
+/// What the LLM stages produced.
+pub struct Analyzed {
+    pub narratives: Vec<Narrative>,
+    pub build_ideas: Vec<BuildIdea>,
+    /// Why idea generation failed, if it did. The narratives are kept.
+    pub ideas_skipped: Option<String>,
+    /// `provider/model` that answered the calls.
+    pub produced_by: String,
+}
+
+/// Run both LLM stages: narrative synthesis, then build ideas. Narratives
+/// come back recalibrated against `signals`. Also returns which provider
+/// and model produced them.
+pub async fn analyze(cfg: &Config, signals: &[Signal], signals_json: &str) -> Result<Analyzed> {
+    let llm_client = LlmClient::from_config(&cfg.llm, &cfg.http)?;
+    let analyzed = analyze_with(cfg, &llm_client, signals, signals_json).await;
+    llm_client.log_usage();
+    analyzed
+}
+
+/// [`analyze`] through a client the caller owns.
+async fn analyze_with(
+    cfg: &Config,
+    llm_client: &LlmClient,
+    signals: &[Signal],
+    signals_json: &str,
+) -> Result<Analyzed> {
+    let result = llm_stages(cfg, llm_client, signals, signals_json).await;
+    result.map(|(narratives, build_ideas, ideas_error)| Analyzed {
+        narratives,
+        build_ideas,
+        ideas_skipped: ideas_error.map(|e| e.to_string()),
+        produced_by: llm_client.produced_by(),
+    })
+}
+
+/// Narratives, then ideas for them. Only a narrative failure fails the
+/// stages: failed idea generation is logged and comes back as the ideas'
+/// error so the run keeps its narratives, along with any ideas carried over
+/// from the last run.
+async fn llm_stages(
+    cfg: &Config,
+    llm_client: &LlmClient,
+    signals: &[Signal],
+    signals_json: &str,
+) -> Result<(Vec<Narrative>, Vec<BuildIdea>, Option<Error>)> {
+    let mut narratives = analysis::synthesizer::identify_narratives_ensemble(
+        llm_client,
+        signals_json,
+        cfg.llm.input_token_budget,
+        cfg.analysis.persona,
+        cfg.synthesis.ensemble_runs,
+        cfg.history.narrative_match_threshold,
+    )
+    .await?;
+    analysis::synthesizer::enforce_source_diversity(
+        &mut narratives,
+        signals,
+        cfg.analysis.min_source_diversity,
+        cfg.analysis.diversity_policy,
+    );
+    analysis::aggregator::recalibrate(&mut narratives, signals);
+    info!(count = narratives.len(), "narratives identified");
+    if narratives.is_empty() {
+        warn!("no narratives identified; skipping idea generation");
+        return Ok((narratives, Vec::new(), None));
+    }
+
+    if cfg.analysis.relationships
+        && let Err(e) =
+            analysis::synthesizer::identify_relationships(llm_client, &mut narratives).await
+    {
+        warn!("relationship extraction failed, continuing without it: {e}");
+    }
+
+    let previous = cfg
+        .analysis
+        .ideas_on_change
+        .then(|| Analysis::load(Path::new(&cfg.history.last_run_path)).ok())
+        .flatten();
+    let (build_ideas, carried) = match previous {
+        Some(previous) => {
+            let (changed, carried) = analysis::ideas::carry_over(
+                &narratives,
+                &previous.narratives,
+                &previous.build_ideas,
+            );
+            info!(
+                changed = changed.len(),
+                reused_ideas = carried.len(),
+                "generating ideas only for new or changed narratives"
+            );
+            let ideas = if changed.is_empty() {
+                Ok(Vec::new())
+            } else {
+                analysis::ideas::generate_ideas(
+                    llm_client,
+                    &changed,
+                    cfg.analysis.idea_batch_size,
+                    cfg.analysis.idea_concurrency,
+                    cfg.analysis.persona,
+                )
+                .await
+            };
+            (ideas, carried)
+        }
+        None => {
+            let ideas = analysis::ideas::generate_ideas(
+                llm_client,
+                &narratives,
+                cfg.analysis.idea_batch_size,
+                cfg.analysis.idea_concurrency,
+                cfg.analysis.persona,
+            )
+            .await;
+            (ideas, Vec::new())
+        }
+    };
+    match build_ideas {
+        Ok(mut ideas) => {
+            info!(count = ideas.len(), "build ideas generated");
+            ideas.extend(carried);
+            Ok((narratives, ideas, None))
+        }
+        Err(e) => {
+            error!(
+                carried = carried.len(),
+                "idea generation failed, keeping the narratives and carried-over ideas: {e}"
+            );
+            Ok((narratives, carried, Some(e)))
+        }
+    }
+}
+
+/// Link this run's narratives to earlier runs in `history.narratives_path`,
+/// setting their streaks. A store that can't be read or written is logged
+/// and the narratives go untracked.
+fn track_narratives(cfg: &Config, narratives: &mut [Narrative]) {
+    let path = Path::new(&cfg.history.narratives_path);
+    let mut store = match NarrativeHistory::load(path) {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("failed to load narrative history: {e}");
+            return;
+        }
+    };
+    let continued = store.track(
+        narratives,
+        chrono::Utc::now(),
+        cfg.history.narrative_match_threshold,
+    );
+    info!(continued, "narratives linked to earlier runs");
+    if let Err(e) = store.save() {
+        warn!("failed to save narrative history: {e}");
+    }
+}
+
+/// Collect, aggregate and analyze. If the LLM is unreachable the signals are
+/// still valuable, so analysis failure yields a signals-only result.
+pub async fn run(cfg: &Config, http: &HttpClient) -> anyhow::Result<Analysis> {
+    let (started_at, clock) = (chrono::Utc::now(), std::time::Instant::now());
+    // One client for the whole run, so sentiment and analysis calls share
+    // its usage, call log and spend cap.
+    let llm_client = LlmClient::from_config(&cfg.llm, &cfg.http);
+    if let Err(e) = &llm_client
+        && cfg.social.sentiment == SentimentMode::Llm
+    {
+        warn!("LLM sentiment unavailable, keeping keyword scores: {e}");
+    }
+    let (signals, sources) = collect_with_llm(cfg, http, llm_client.as_ref().ok()).await;
+
+    if signals.is_empty() {
+        anyhow::bail!(
+            "No signals collected from any source. Check API keys and network connectivity."
+        );
+    }
+
+    info!(total = signals.len(), "total signals collected");
+
+    if !cfg.alerts.rules.is_empty() {
+        // Loaded before this run is recorded, so relative rules compare
+        // against past runs only.
+        let history = cfg
+            .history
+            .enabled
+            .then(|| History::load(Path::new(&cfg.history.path)).ok())
+            .flatten();
+        let fired = alerts::evaluate(
+            &cfg.alerts.rules,
+            &signals,
+            history.as_ref(),
+            cfg.history.window,
+        );
+        info!(count = fired.len(), "alert rules matched");
+        alerts::fire(http, &fired).await;
+    }
+
+    let signals_json = prepare(cfg, &signals)?;
+
+    let llm = llm_client.as_ref().map_err(|e| e.to_string());
+    let mut analysis = analyze_signals(cfg, llm, signals, sources, &signals_json).await;
+    if let Ok(client) = &llm_client {
+        client.log_usage();
+    }
+    let metadata = run_metadata(cfg, &analysis.sources, started_at, clock.elapsed());
+    analysis.metadata = Some(metadata.clone());
+    if cfg.history.enabled {
+        if let Err(e) = analysis.save(Path::new(&cfg.history.last_run_path)) {
+            warn!("failed to save last run: {e}");
+        }
+        let saved = History::load(Path::new(&cfg.history.path)).and_then(|mut store| {
+            store.record(&analysis.signals, Some(metadata));
+            store.save()
+        });
+        if let Err(e) = saved {
+            warn!("failed to save history: {e}");
+        }
+    }
+    Ok(analysis)
+}
+
+/// Provenance of a run that started at `started_at` and took `duration`.
+fn run_metadata(
+    cfg: &Config,
+    sources: &[SourceStatus],
+    started_at: chrono::DateTime<chrono::Utc>,
+    duration: std::time::Duration,
+) -> RunMetadata {
+    let mut source_set: Vec<String> = sources.iter().map(|s| s.name.clone()).collect();
+    source_set.sort();
+    RunMetadata {
+        config_hash: cfg.fingerprint(),
+        provider: format!("{:?}", cfg.llm.provider).to_lowercase(),
+        model: cfg.llm.model.clone(),
+        source_set,
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        started_at,
+        duration_ms: duration.as_millis() as u64,
+    }
+}
+
+/// Run the LLM stages over a collected run, or skip them when there are
+/// fewer than `analysis.min_signals` signals. Either way the result renders;
+/// a skipped or failed analysis leaves a signals-only report.
+async fn analyze_signals(
+    cfg: &Config,
+    llm: std::result::Result<&LlmClient, String>,
+    signals: Vec<Signal>,
+    sources: Vec<SourceStatus>,
+    signals_json: &str,
+) -> Analysis {
+    let (analyzed, llm_calls) = if signals.len() < cfg.analysis.min_signals {
+        let reason = format!(
+            "too few signals for meaningful analysis ({} collected, analysis.min_signals = {})",
+            signals.len(),
+            cfg.analysis.min_signals
+        );
+        warn!("{reason}; skipping LLM analysis");
+        (Err(reason), llm.map(LlmClient::calls).unwrap_or_default())
+    } else {
+        let analyzed = match llm {
+            Ok(client) => {
+                let analyzed = analyze_with(cfg, client, &signals, signals_json).await;
+                (analyzed.map_err(|e| e.to_string()), client.calls())
+            }
+            Err(e) => (Err(e), Vec::new()),
+        };
+        if let Err(e) = &analyzed.0 {
+            error!("LLM analysis failed, rendering signals-only report: {e}");
+        }
+        analyzed
+    };
+    let (narratives, build_ideas, analysis_skipped, ideas_skipped, produced_by) = match analyzed {
+        Ok(mut analyzed) => {
+            if cfg.history.enabled {
+                track_narratives(cfg, &mut analyzed.narratives);
+            }
+            (
+                analyzed.narratives,
+                analyzed.build_ideas,
+                None,
+                analyzed.ideas_skipped,
+                Some(analyzed.produced_by),
+            )
+        }
+        Err(reason) => (Vec::new(), Vec::new(), Some(reason), None, None),
+    };
+
+    Analysis {
+        signals,
+        narratives,
+        build_ideas,
+        analysis_skipped,
+        ideas_skipped,
+        produced_by,
+        sources,
+        llm_calls,
+        metadata: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history;
+    use crate::test_support::{self, Reply, TempDir};
+    use crate::types::{Metric, SignalSource};
+
+    fn signal(title: &str, with_metric: bool) -> Signal {
+        Signal::new(
+            SignalSource::SolanaOnchain,
+            "DeFi".into(),
+            title.into(),
+            String::new(),
+            if with_metric {
+                vec![Metric {
+                    name: "tx_per_hour".into(),
+                    value: 1.0,
+                    unit: "tx/hr".into(),
+                }]
+            } else {
+                Vec::new()
+            },
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn too_few_signals_skip_both_llm_stages() {
+        let requests = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let seen = requests.clone();
+        let addr = test_support::mock_http(move |_| {
+            seen.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Reply::status(500)
+        })
+        .await;
+        let cfg: Config = toml::from_str(&format!(
+            "[github]\ntoken = \"ghp_test\"\n[solana]\n[llm]\nprovider = \"openai\"\nbase_url = \"http://{addr}/v1\"\n[history]\nenabled = false\n[analysis]\nmin_signals = 3\n"
+        ))
+        .unwrap();
+        let signals = vec![signal("a", true), signal("b", true)];
+
+        let llm = LlmClient::from_config(&cfg.llm, &cfg.http).unwrap();
+        let analysis = analyze_signals(&cfg, Ok(&llm), signals, Vec::new(), "[]").await;
+        let reason = analysis.analysis_skipped.as_deref().unwrap();
+        assert!(reason.starts_with("too few signals"), "{reason}");
+        assert!(reason.contains("2 collected"));
+        assert!(analysis.llm_calls.is_empty());
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(analysis.signals.len(), 2, "still rendered");
+        assert_eq!(analysis.status().exit_code(), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_ideas_keep_the_narratives() {
+        let signals = vec![signal("a", true), signal("b", true)];
+        let ids: Vec<String> = signals.iter().map(|s| s.id.clone()).collect();
+        let narratives = serde_json::json!({"narratives": [{
+            "title": "Validator upgrades",
+            "summary": "Clients ship.",
+            "confidence": 0.8,
+            "supporting_signals": ids,
+            "trend": "Emerging",
+        }]})
+        .to_string();
+        let addr = test_support::mock_http(move |request| {
+            if request.body.contains("product strategist") {
+                Reply::status(400).body("bad request")
+            } else {
+                let content =
+                    serde_json::json!({"choices": [{"message": {"content": narratives}}]});
+                Reply::json(content.to_string())
+            }
+        })
+        .await;
+        let cfg: Config = toml::from_str(&format!(
+            "[github]\ntoken = \"ghp_test\"\n[solana]\n[llm]\nprovider = \"openai\"\napi_key = \"sk-test\"\nbase_url = \"http://{addr}/v1\"\n[history]\nenabled = false\n[analysis]\nmin_signals = 1\nmin_source_diversity = 1\n"
+        ))
+        .unwrap();
+
+        let llm = LlmClient::from_config(&cfg.llm, &cfg.http).unwrap();
+        let analysis = analyze_signals(&cfg, Ok(&llm), signals, Vec::new(), "[]").await;
+        assert_eq!(analysis.analysis_skipped, None);
+        assert_eq!(analysis.narratives.len(), 1, "narratives survive");
+        assert!(analysis.build_ideas.is_empty());
+        assert!(analysis.ideas_skipped.is_some());
+        assert_eq!(analysis.status(), RunStatus::Partial);
+        let summary = analysis.summary().unwrap();
+        assert!(
+            summary.starts_with("partial: build ideas skipped ("),
+            "{summary}"
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_ideas_keep_the_carried_over_ones() {
+        let signals = vec![signal("a", true), signal("b", true)];
+        let ids: Vec<String> = signals.iter().map(|s| s.id.clone()).collect();
+        let narrative = |title: &str| {
+            serde_json::json!({
+                "title": title,
+                "summary": "Clients ship.",
+                "confidence": 0.8,
+                "supporting_signals": ids,
+                "trend": "Emerging",
+            })
+        };
+        let first = serde_json::json!({"narratives": [narrative("Validator upgrades")]});
+        let second = serde_json::json!({"narratives": [
+            narrative("Validator upgrades"),
+            narrative("Restaking yields"),
+        ]});
+        let idea = serde_json::json!({"ideas": [{
+            "title": "Upgrade Tracker",
+            "description": "d",
+            "target_user": "u",
+            "mvp_scope": "m",
+            "competitive_landscape": "c",
+            "timing_rationale": "t",
+            "narrative_id": Narrative::id_for("Validator upgrades"),
+        }]});
+        let rerun = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let seen = rerun.clone();
+        let addr = test_support::mock_http(move |request| {
+            let rerun = seen.load(std::sync::atomic::Ordering::SeqCst);
+            let content = match (request.body.contains("product strategist"), rerun) {
+                (true, true) => return Reply::status(400).body("bad request"),
+                (true, false) => idea.to_string(),
+                (false, true) => second.to_string(),
+                (false, false) => first.to_string(),
+            };
+            Reply::json(
+                serde_json::json!({"choices": [{"message": {"content": content}}]}).to_string(),
+            )
+        })
+        .await;
+        let dir = TempDir::new("carried-ideas");
+        let last_run = dir.join("last_run.json");
+        let cfg: Config = toml::from_str(&format!(
+            "[github]\ntoken = \"ghp_test\"\n[solana]\n[llm]\nprovider = \"openai\"\napi_key = \"sk-test\"\nbase_url = \"http://{addr}/v1\"\n[history]\nenabled = false\nlast_run_path = {:?}\n[analysis]\nmin_signals = 1\nmin_source_diversity = 1\nideas_on_change = true\n",
+            last_run.display().to_string()
+        ))
+        .unwrap();
+        let llm = LlmClient::from_config(&cfg.llm, &cfg.http).unwrap();
+
+        let analysis = analyze_signals(&cfg, Ok(&llm), signals.clone(), Vec::new(), "[]").await;
+        assert_eq!(analysis.build_ideas.len(), 1);
+        analysis.save(&last_run).unwrap();
+
+        rerun.store(true, std::sync::atomic::Ordering::SeqCst);
+        let analysis = analyze_signals(&cfg, Ok(&llm), signals, Vec::new(), "[]").await;
+        assert_eq!(analysis.narratives.len(), 2);
+        let titles: Vec<&str> = analysis
+            .build_ideas
+            .iter()
+            .map(|i| i.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Upgrade Tracker"], "carried-over idea kept");
+        assert!(analysis.build_ideas[0].carried_over);
+        assert!(analysis.ideas_skipped.is_some(), "the failure still shows");
+    }
+
+    #[test]
+    fn run_status_ranks_signals_only_over_partial() {
+        let status = |name: &str, error: Option<&str>| SourceStatus {
+            name: name.into(),
+            signals: if error.is_some() { 0 } else { 4 },
+            error: error.map(str::to_string),
+            unchanged: false,
+            elapsed_ms: 0,
+            requests: 0,
+        };
+        let healthy = [status("GitHub", None), status("Solana RPC", None)];
+        let degraded = [
+            status("GitHub", Some("HTTP error: timeout")),
+            status("Solana RPC", None),
+        ];
+
+        assert_eq!(RunStatus::of(&healthy, None, None), RunStatus::Complete);
+        assert_eq!(RunStatus::Complete.exit_code(), 0);
+        assert_eq!(RunStatus::Complete.summary(&healthy, None, None), None);
+
+        let partial = RunStatus::of(&degraded, None, None);
+        assert_eq!(partial.exit_code(), 2);
+        assert_eq!(
+            partial.summary(&degraded, None, None).unwrap(),
+            "partial: 1 of 2 sources failed: GitHub (HTTP error: timeout)"
+        );
+
+        let no_ideas = RunStatus::of(&degraded, None, Some("LLM budget exceeded"));
+        assert_eq!(no_ideas, RunStatus::Partial);
+        assert_eq!(
+            no_ideas
+                .summary(&degraded, None, Some("LLM budget exceeded"))
+                .unwrap(),
+            "partial: 1 of 2 sources failed: GitHub (HTTP error: timeout); build ideas skipped (LLM budget exceeded)"
+        );
+        assert_eq!(
+            RunStatus::of(&healthy, None, Some("timeout")),
+            RunStatus::Partial
+        );
+
+        let skipped = RunStatus::of(&degraded, Some("LLM API key not set"), None);
+        assert_eq!(skipped.exit_code(), 3);
+        let line = skipped
+            .summary(&degraded, Some("LLM API key not set"), None)
+            .unwrap();
+        assert!(line.starts_with("signals only: LLM analysis skipped (LLM API key not set)"));
+        assert!(line.contains("GitHub (HTTP error: timeout)"));
+    }
+
+    #[test]
+    fn old_snapshot_reports_missing_fields() {
+        let dir = TempDir::new("snapshot");
+        let path = dir.join("signals.json");
+
+        let current = serde_json::to_string(&[signal("a", true), signal("b", false)]).unwrap();
+        std::fs::write(&path, &current).unwrap();
+        let loaded = load_signals(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.iter().all(|s| !s.id.is_empty()));
+
+        let mut old: Vec<serde_json::Value> = serde_json::from_str(&current).unwrap();
+        for item in &mut old {
+            let item = item.as_object_mut().unwrap();
+            item.remove("id");
+            item.remove("timestamp");
+        }
+        old[0].as_object_mut().unwrap().remove("description");
+        std::fs::write(&path, serde_json::to_string(&old).unwrap()).unwrap();
+        let err = load_signals(&path).unwrap_err().to_string();
+        assert!(err.contains("description (in 1)"), "{err}");
+        assert!(err.contains("timestamp (in 2)"), "{err}");
+        assert!(!err.contains("id ("), "{err}");
+    }
+
+    #[test]
+    fn old_anomaly_survives_the_cap_over_fresh_steady_signals() {
+        let reading = |title: &str, url: &str, value: f64, hours_old: i64| {
+            let mut s = signal(title, true);
+            s.url = Some(url.into());
+            s.metrics[0].value = value;
+            s.timestamp -= chrono::Duration::hours(hours_old);
+            s
+        };
+        // Each program's tx_per_hour has hovered around 100 for five runs.
+        let runs: Vec<serde_json::Value> = [100.0, 102.0, 98.0, 101.0, 99.0]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let metrics: serde_json::Map<String, serde_json::Value> = ["a", "b", "c"]
+                    .iter()
+                    .map(|p| {
+                        let s = reading("", &format!("https://x/{p}"), *value, 0);
+                        (history::metric_key(&s, &s.metrics[0]), (*value).into())
+                    })
+                    .collect();
+                serde_json::json!({
+                    "started_at": (chrono::Utc::now() - chrono::Duration::days(5 - i as i64)).to_rfc3339(),
+                    "metrics": metrics,
+                })
+            })
+            .collect();
+        let dir = TempDir::new("importance");
+        let path = dir.join("history.json");
+        std::fs::write(&path, serde_json::Value::from(runs).to_string()).unwrap();
+        let store = History::load(&path).unwrap();
+
+        let signals = vec![
+            reading("fresh steady b", "https://x/b", 100.5, 0),
+            reading("old spike a", "https://x/a", 450.0, 36),
+            reading("fresh steady c", "https://x/c", 99.5, 0),
+        ];
+        let kept = cap_signals("test", signals.clone(), Some(1), Some((&store, 10)));
+        assert_eq!(kept[0].title, "old spike a");
+        assert!(kept[0].importance.unwrap() > 0.6);
+
+        // Without history there's no novelty, and all three carry metrics
+        // and a link, so recency decides.
+        let kept = cap_signals("test", signals, Some(1), None);
+        assert!(kept[0].title.starts_with("fresh"));
+    }
+
+    #[test]
+    fn cap_keeps_richest_signals() {
+        let signals = vec![
+            signal("bare a", false),
+            signal("rich a", true),
+            signal("bare b", false),
+            signal("rich b", true),
+        ];
+        let kept: Vec<String> = cap_signals("test", signals.clone(), Some(2), None)
+            .into_iter()
+            .map(|s| s.title)
+            .collect();
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|t| t.starts_with("rich")));
+        assert_eq!(cap_signals("test", signals, None, None).len(), 4);
+    }
+
+    #[test]
+    fn cap_without_novelty_ignores_category_spread() {
+        let now = chrono::Utc::now();
+        let in_category = |title: &str, category: &str, with_metric: bool| {
+            let mut s = signal(title, with_metric);
+            s.category = category.into();
+            s.timestamp = now;
+            s
+        };
+        let signals = vec![
+            in_category("lone bare gaming", "Gaming", false),
+            in_category("rich defi a", "DeFi", true),
+            in_category("rich defi b", "DeFi", true),
+            in_category("lone rich payments", "Payments", true),
+        ];
+        let kept: Vec<String> = cap_signals("test", signals, Some(3), None)
+            .into_iter()
+            .map(|s| s.title)
+            .collect();
+        assert_eq!(
+            kept,
+            ["rich defi a", "rich defi b", "lone rich payments"],
+            "richness, then the source's order, across categories"
+        );
+    }
+}
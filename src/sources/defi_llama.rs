@@ -1,8 +1,9 @@
 use crate::config::DefiLlamaConfig;
 use crate::error::Result;
 use crate::http::HttpClient;
+use crate::sources::Source;
 use crate::types::{Metric, Signal, SignalSource};
-use chrono::Utc;
+use futures_util::future::BoxFuture;
 use serde::Deserialize;
 use tracing::info;
 
@@ -23,6 +24,32 @@ struct Protocol {
     category: Option<String>,
 }
 
+impl Source for DefiLlamaConfig {
+    fn name(&self) -> &'static str {
+        "DeFiLlama"
+    }
+
+    fn key(&self) -> &'static str {
+        "defi_llama"
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        (self.top_protocols, "top protocols")
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
 pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<Signal>> {
     if !config.enabled {
         return Ok(Vec::new());
@@ -45,19 +72,19 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
             .position(|&t| (t - tvl).abs() < 1.0)
             .map(|r| r + 1);
 
-        signals.push(Signal {
-            source: SignalSource::DeFiLlama,
-            category: "DeFi TVL".into(),
-            title: format!(
+        signals.push(Signal::new(
+            SignalSource::DeFiLlama,
+            "DeFi TVL".into(),
+            format!(
                 "Solana Chain TVL: ${tvl_billions:.2}B{}",
                 rank.map(|r| format!(" (#{r} overall)")).unwrap_or_default()
             ),
-            description: format!(
+            format!(
                 "Total value locked across all Solana DeFi protocols. {}",
                 rank.map(|r| format!("Ranked #{r} among all chains by TVL."))
                     .unwrap_or_default()
             ),
-            metrics: vec![
+            vec![
                 Metric {
                     name: "solana_tvl".into(),
                     value: tvl,
@@ -69,9 +96,8 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
                     unit: "B USD".into(),
                 },
             ],
-            url: Some("https://defillama.com/chain/Solana".into()),
-            timestamp: Utc::now(),
-        });
+            Some("https://defillama.com/chain/Solana".into()),
+        ));
     }
 
     // Fetch protocol data — filter for Solana protocols
@@ -101,16 +127,16 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
 
         let total_solana_tvl: f64 = solana_protocols.iter().filter_map(|p| p.tvl).sum();
 
-        signals.push(Signal {
-            source: SignalSource::DeFiLlama,
-            category: "DeFi TVL".into(),
-            title: format!(
+        signals.push(Signal::new(
+            SignalSource::DeFiLlama,
+            "DeFi TVL".into(),
+            format!(
                 "Top {} Solana DeFi Protocols by TVL ({} total Solana protocols tracked)",
                 top_n,
                 solana_protocols.len()
             ),
-            description: format!("Leading protocols: {}", protocol_list.join(", ")),
-            metrics: vec![
+            format!("Leading protocols: {}", protocol_list.join(", ")),
+            vec![
                 Metric {
                     name: "solana_protocol_count".into(),
                     value: solana_protocols.len() as f64,
@@ -127,9 +153,8 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
                     unit: "USD".into(),
                 },
             ],
-            url: Some("https://defillama.com/chain/Solana".into()),
-            timestamp: Utc::now(),
-        });
+            Some("https://defillama.com/chain/Solana".into()),
+        ));
 
         // Category breakdown
         let mut categories: std::collections::HashMap<String, (f64, usize)> =
@@ -154,15 +179,15 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
             })
             .collect();
 
-        signals.push(Signal {
-            source: SignalSource::DeFiLlama,
-            category: "DeFi TVL".into(),
-            title: format!(
+        signals.push(Signal::new(
+            SignalSource::DeFiLlama,
+            "DeFi TVL".into(),
+            format!(
                 "Solana DeFi Category Breakdown ({} categories)",
                 cat_list.len()
             ),
-            description: format!("Top categories: {}", cat_desc.join(", ")),
-            metrics: cat_list
+            format!("Top categories: {}", cat_desc.join(", ")),
+            cat_list
                 .iter()
                 .take(5)
                 .map(|(cat, tvl, _)| Metric {
@@ -171,9 +196,8 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
                     unit: "USD".into(),
                 })
                 .collect(),
-            url: Some("https://defillama.com/chain/Solana".into()),
-            timestamp: Utc::now(),
-        });
+            Some("https://defillama.com/chain/Solana".into()),
+        ));
     }
 
     info!(
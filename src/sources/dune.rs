@@ -0,0 +1,232 @@
+use crate::config::{DuneConfig, DuneQuery};
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use crate::sources::Source;
+use crate::types::{Metric, Signal, SignalSource};
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+const DUNE_API: &str = "https://api.dune.com/api/v1";
+
+#[derive(Deserialize)]
+struct ExecuteResponse {
+    execution_id: String,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    state: String,
+}
+
+#[derive(Deserialize)]
+struct ResultsResponse {
+    result: Option<QueryResult>,
+}
+
+#[derive(Deserialize)]
+struct QueryResult {
+    #[serde(default)]
+    rows: Vec<serde_json::Map<String, serde_json::Value>>,
+}
+
+impl Source for DuneConfig {
+    fn name(&self) -> &'static str {
+        "Dune"
+    }
+
+    fn key(&self) -> &'static str {
+        "dune"
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled && !self.queries.is_empty()
+    }
+
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn key_env(&self) -> Option<&str> {
+        Some(&self.api_key_env)
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        (self.queries.len(), "queries")
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
+pub async fn collect(config: &DuneConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+    if !config.enabled || config.queries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let api_key = std::env::var(&config.api_key_env).unwrap_or_default();
+    if api_key.is_empty() {
+        return Err(Error::config(format!(
+            "Dune API key not set: ${}",
+            config.api_key_env
+        )));
+    }
+
+    let mut signals = Vec::new();
+
+    for query in &config.queries {
+        match query_signal(config, http, &api_key, query).await {
+            Ok(Some(signal)) => signals.push(signal),
+            Ok(None) => warn!(
+                query = query.id,
+                "Dune query returned no usable rows, skipping"
+            ),
+            Err(e) => warn!(query = query.id, error = %e, "Dune query failed, skipping"),
+        }
+    }
+
+    info!(signal_count = signals.len(), "collected Dune query signals");
+    Ok(signals)
+}
+
+async fn query_signal(
+    config: &DuneConfig,
+    http: &HttpClient,
+    api_key: &str,
+    query: &DuneQuery,
+) -> Result<Option<Signal>> {
+    let headers = [("X-Dune-API-Key", api_key)];
+
+    // Dune executions are asynchronous: trigger, poll until done, then fetch.
+    let body = http
+        .post_json_raw(
+            &format!("{DUNE_API}/query/{}/execute", query.id),
+            "{}",
+            &headers,
+        )
+        .await?;
+    let execution: ExecuteResponse = serde_json::from_str(&body)
+        .map_err(|e| Error::parse(format!("Dune execute response: {e}")))?;
+    debug!(query = query.id, execution = %execution.execution_id, "Dune execution started");
+
+    let mut completed = false;
+    for _ in 0..config.max_polls {
+        let status: StatusResponse = http
+            .get_json_with_headers(
+                &format!("{DUNE_API}/execution/{}/status", execution.execution_id),
+                &headers,
+            )
+            .await?;
+        match status.state.as_str() {
+            "QUERY_STATE_COMPLETED" => {
+                completed = true;
+                break;
+            }
+            "QUERY_STATE_PENDING" | "QUERY_STATE_EXECUTING" => {
+                sleep(Duration::from_secs(config.poll_interval_secs)).await;
+            }
+            other => {
+                return Err(Error::api(
+                    "api.dune.com",
+                    format!("query {} ended in {other}", query.id),
+                ));
+            }
+        }
+    }
+    if !completed {
+        return Err(Error::Timeout {
+            platform: "api.dune.com".into(),
+            after_secs: config.poll_interval_secs * config.max_polls as u64,
+        });
+    }
+
+    let results: ResultsResponse = http
+        .get_json_with_headers(
+            &format!("{DUNE_API}/execution/{}/results", execution.execution_id),
+            &headers,
+        )
+        .await?;
+    let rows = results.result.map(|r| r.rows).unwrap_or_default();
+    let Some(row) = rows.first() else {
+        return Ok(None);
+    };
+
+    let metrics = row_metrics(row, query);
+    if metrics.is_empty() {
+        return Ok(None);
+    }
+
+    let summary: Vec<String> = metrics.iter().map(|m| m.to_string()).collect();
+    Ok(Some(Signal::new(
+        SignalSource::Dune,
+        query.category.clone(),
+        format!("{}: {}", query.name, summary.join(", ")),
+        format!(
+            "Latest row of Dune query {} ({} rows returned).",
+            query.id,
+            rows.len()
+        ),
+        metrics,
+        Some(format!("https://dune.com/queries/{}", query.id)),
+    )))
+}
+
+/// Map the named result columns to metrics; with no columns configured, take
+/// every numeric column. Numbers Dune returns as strings are parsed too.
+fn row_metrics(row: &serde_json::Map<String, serde_json::Value>, query: &DuneQuery) -> Vec<Metric> {
+    let columns: Vec<&String> = if query.columns.is_empty() {
+        row.keys().collect()
+    } else {
+        query.columns.iter().collect()
+    };
+
+    columns
+        .into_iter()
+        .filter_map(|column| {
+            let value = match row.get(column)? {
+                serde_json::Value::Number(n) => n.as_f64()?,
+                serde_json::Value::String(s) => s.parse().ok()?,
+                _ => return None,
+            };
+            Some(Metric {
+                name: column.clone(),
+                value,
+                unit: query.units.get(column).cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_named_and_numeric_columns() {
+        let row: serde_json::Map<String, serde_json::Value> = serde_json::from_str(
+            r#"{"day": "2026-10-14", "volume_usd": 1250000.5, "traders": "8421", "label": "x"}"#,
+        )
+        .unwrap();
+        let mut query = DuneQuery {
+            id: 1,
+            name: "DEX volume".into(),
+            category: "DeFi".into(),
+            columns: vec!["volume_usd".into(), "missing".into()],
+            units: [("volume_usd".to_string(), "USD".to_string())].into(),
+        };
+
+        let metrics = row_metrics(&row, &query);
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].unit, "USD");
+
+        query.columns.clear();
+        let names: Vec<String> = row_metrics(&row, &query)
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(names, ["traders", "volume_usd"]);
+    }
+}
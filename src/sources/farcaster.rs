@@ -0,0 +1,344 @@
+use crate::config::FarcasterConfig;
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use crate::sources::Source;
+use crate::types::{Metric, Signal, SignalSource};
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use std::collections::HashSet;
+use tracing::{info, warn};
+use url::form_urlencoded;
+
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Deserialize)]
+struct FeedResponse {
+    #[serde(default)]
+    casts: Vec<Cast>,
+    next: Option<Cursor>,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    result: FeedResponse,
+}
+
+#[derive(Deserialize)]
+struct Cursor {
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Cast {
+    timestamp: DateTime<Utc>,
+    author: Option<Author>,
+    #[serde(default)]
+    reactions: Reactions,
+    #[serde(default)]
+    replies: Replies,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    fid: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct Reactions {
+    #[serde(default)]
+    likes_count: u64,
+    #[serde(default)]
+    recasts_count: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct Replies {
+    #[serde(default)]
+    count: u64,
+}
+
+/// What a feed is filtered by.
+enum Feed<'a> {
+    Channel(&'a str),
+    Keyword(&'a str),
+}
+
+impl Feed<'_> {
+    fn label(&self) -> String {
+        match self {
+            Feed::Channel(c) => format!("/{c}"),
+            Feed::Keyword(k) => format!("\"{k}\""),
+        }
+    }
+
+    fn url(&self, base_url: &str, cursor: Option<&str>) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        let path = match self {
+            Feed::Channel(c) => {
+                query
+                    .append_pair("channel_ids", c)
+                    .append_pair("with_recasts", "false");
+                "feed/channels"
+            }
+            Feed::Keyword(k) => {
+                query
+                    .append_pair("q", k)
+                    .append_pair("sort_type", "desc_chron");
+                "cast/search"
+            }
+        };
+        query.append_pair("limit", &PAGE_SIZE.to_string());
+        if let Some(cursor) = cursor {
+            query.append_pair("cursor", cursor);
+        }
+        format!("{base_url}/{path}?{}", query.finish())
+    }
+
+    /// The feed on Warpcast, for people.
+    fn web_url(&self) -> String {
+        match self {
+            Feed::Channel(c) => format!("https://warpcast.com/~/channel/{c}"),
+            Feed::Keyword(k) => format!(
+                "https://warpcast.com/~/search/casts?{}",
+                form_urlencoded::Serializer::new(String::new())
+                    .append_pair("q", k)
+                    .finish()
+            ),
+        }
+    }
+}
+
+/// First page of the first configured feed, for `validate` to probe the
+/// endpoint collection actually reads.
+pub fn probe_url(config: &FarcasterConfig) -> Option<String> {
+    let feed = match (config.channels.first(), config.keywords.first()) {
+        (Some(c), _) => Feed::Channel(c),
+        (None, Some(k)) => Feed::Keyword(k),
+        (None, None) => return None,
+    };
+    Some(feed.url(&config.base_url, None))
+}
+
+impl Source for FarcasterConfig {
+    fn name(&self) -> &'static str {
+        "Farcaster"
+    }
+
+    fn key(&self) -> &'static str {
+        "farcaster"
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn key_env(&self) -> Option<&str> {
+        Some(&self.api_key_env)
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        (self.channels.len(), "channels")
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
+pub async fn collect(config: &FarcasterConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+    if !config.enabled {
+        return Ok(Vec::new());
+    }
+
+    let api_key = std::env::var(&config.api_key_env).unwrap_or_default();
+    if api_key.is_empty() {
+        return Err(Error::config(format!(
+            "Farcaster API key not set: ${}",
+            config.api_key_env
+        )));
+    }
+
+    let feeds = config
+        .channels
+        .iter()
+        .map(|c| Feed::Channel(c))
+        .chain(config.keywords.iter().map(|k| Feed::Keyword(k)));
+
+    let mut signals = Vec::new();
+    for feed in feeds {
+        match feed_signal(config, http, &api_key, &feed).await {
+            Ok(signal) => signals.push(signal),
+            Err(e) => {
+                warn!(feed = %feed.label(), error = %e, "failed to fetch Farcaster casts, skipping")
+            }
+        }
+    }
+
+    info!(signal_count = signals.len(), "collected Farcaster signals");
+    Ok(signals)
+}
+
+async fn feed_signal(
+    config: &FarcasterConfig,
+    http: &HttpClient,
+    api_key: &str,
+    feed: &Feed<'_>,
+) -> Result<Signal> {
+    let headers = [("x-api-key", api_key), ("accept", "application/json")];
+    let cutoff = Utc::now() - Duration::hours(24);
+    let mut casts = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut capped = true;
+
+    // Newest first: stop at the first page reaching past 24h, with a hard cap.
+    for _ in 0..config.max_pages {
+        let url = feed.url(&config.base_url, cursor.as_deref());
+        let page = match feed {
+            Feed::Channel(_) => {
+                http.get_json_with_headers::<FeedResponse>(&url, &headers)
+                    .await?
+            }
+            Feed::Keyword(_) => {
+                http.get_json_with_headers::<SearchResponse>(&url, &headers)
+                    .await?
+                    .result
+            }
+        };
+        let reached_cutoff = page.casts.iter().any(|c| c.timestamp < cutoff);
+        casts.extend(page.casts);
+        cursor = page.next.and_then(|n| n.cursor);
+        if reached_cutoff || cursor.is_none() {
+            capped = false;
+            break;
+        }
+    }
+    if capped {
+        warn!(feed = %feed.label(), pages = config.max_pages, "Farcaster pagination cap reached; 24h counts are a lower bound");
+    }
+
+    let activity = Activity::from_casts(&casts, cutoff);
+    let label = feed.label();
+    let url = feed.web_url();
+
+    Ok(Signal::new(
+        SignalSource::Social,
+        "Farcaster".into(),
+        format!(
+            "Farcaster {label}: {} casts in 24h, {} engagements",
+            activity.casts, activity.engagement
+        ),
+        format!(
+            "{} casts from {} distinct authors in the last 24h on {label}, drawing {} likes/recasts/replies ({:.1} per cast).{}",
+            activity.casts,
+            activity.authors,
+            activity.engagement,
+            activity.engagement_per_cast(),
+            if capped {
+                " Pagination cap reached, so counts are a lower bound."
+            } else {
+                ""
+            }
+        ),
+        vec![
+            Metric {
+                name: "cast_count_24h".into(),
+                value: activity.casts as f64,
+                unit: "casts".into(),
+            },
+            Metric {
+                name: "engagement".into(),
+                value: activity.engagement as f64,
+                unit: "reactions".into(),
+            },
+            Metric {
+                name: "unique_authors_24h".into(),
+                value: activity.authors as f64,
+                unit: "authors".into(),
+            },
+        ],
+        Some(url),
+    ))
+}
+
+/// Cast activity within the 24h window.
+struct Activity {
+    casts: usize,
+    authors: usize,
+    engagement: u64,
+}
+
+impl Activity {
+    fn from_casts(casts: &[Cast], cutoff: DateTime<Utc>) -> Self {
+        let recent: Vec<&Cast> = casts.iter().filter(|c| c.timestamp >= cutoff).collect();
+        let authors: HashSet<u64> = recent
+            .iter()
+            .filter_map(|c| c.author.as_ref().map(|a| a.fid))
+            .collect();
+        Self {
+            casts: recent.len(),
+            authors: authors.len(),
+            engagement: recent
+                .iter()
+                .map(|c| c.reactions.likes_count + c.reactions.recasts_count + c.replies.count)
+                .sum(),
+        }
+    }
+
+    fn engagement_per_cast(&self) -> f64 {
+        if self.casts == 0 {
+            0.0
+        } else {
+            self.engagement as f64 / self.casts as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_last_24h() {
+        let now = Utc::now();
+        let page: FeedResponse = serde_json::from_value(serde_json::json!({
+            "casts": [
+                {"timestamp": (now - Duration::hours(1)).to_rfc3339(), "author": {"fid": 1},
+                 "reactions": {"likes_count": 10, "recasts_count": 2}, "replies": {"count": 3}},
+                {"timestamp": (now - Duration::hours(5)).to_rfc3339(), "author": {"fid": 1},
+                 "reactions": {"likes_count": 1, "recasts_count": 0}, "replies": {"count": 0}},
+                {"timestamp": (now - Duration::hours(30)).to_rfc3339(), "author": {"fid": 2},
+                 "reactions": {"likes_count": 99, "recasts_count": 9}, "replies": {"count": 9}}
+            ],
+            "next": {"cursor": null}
+        }))
+        .unwrap();
+
+        let activity = Activity::from_casts(&page.casts, now - Duration::hours(24));
+        assert_eq!(activity.casts, 2);
+        assert_eq!(activity.authors, 1);
+        assert_eq!(activity.engagement, 16);
+    }
+
+    #[test]
+    fn feed_urls_encode_keywords_and_cursors() {
+        let base = "https://api.neynar.com/v2/farcaster";
+        let url = Feed::Keyword("solana & jito #lst").url(base, Some("eyJ0IjoxfQ==&x"));
+        assert_eq!(
+            url,
+            "https://api.neynar.com/v2/farcaster/cast/search?q=solana+%26+jito+%23lst&sort_type=desc_chron&limit=100&cursor=eyJ0IjoxfQ%3D%3D%26x"
+        );
+        assert_eq!(
+            Feed::Channel("solana").url(base, None),
+            "https://api.neynar.com/v2/farcaster/feed/channels?channel_ids=solana&with_recasts=false&limit=100"
+        );
+        assert_eq!(
+            Feed::Keyword("a b").web_url(),
+            "https://warpcast.com/~/search/casts?q=a+b"
+        );
+    }
+}
@@ -1,19 +1,63 @@
 use crate::config::GitHubConfig;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::history::{History, metric_key};
 use crate::http::HttpClient;
+use crate::sources::Source;
+use crate::sources::github_api::{GitHubClient, Resource};
 use crate::types::{Metric, Signal, SignalSource};
 use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
 use serde::Deserialize;
-use tracing::info;
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
 const GITHUB_API: &str = "https://api.github.com";
 
+/// Category of per-repo signals for `tracked_repos`.
+const TRACKED_CATEGORY: &str = "Tracked Repos";
+
+/// Category of issue and discussion activity signals for `flagship_repos`.
+const COMMUNITY_CATEGORY: &str = "Developer Community";
+
+/// GitHub lists at most this many stargazer pages (100 per page).
+const MAX_STARGAZER_PAGE: u64 = 400;
+
+/// Stargazer pages fetched per repo when estimating velocity without history.
+const COLD_START_PAGES: u64 = 3;
+
+/// Results per search page, GitHub's maximum.
+const SEARCH_PAGE: u32 = 100;
+
+/// Stars per day at which a young repo counts as fast-growing.
+const FAST_GROWTH_STARS_PER_DAY: f64 = 5.0;
+
 #[derive(Deserialize)]
+struct Stargazer {
+    starred_at: DateTime<Utc>,
+}
+
+#[derive(Default, Deserialize)]
 struct SearchResponse {
     total_count: u64,
     items: Vec<RepoItem>,
 }
 
+/// Issue search, read for its total only.
+#[derive(Deserialize)]
+struct CountResponse {
+    total_count: u64,
+}
+
+/// Issue and discussion activity of one flagship repo.
+#[derive(Debug, PartialEq)]
+struct Community {
+    open_issues: u64,
+    issues_opened_7d: u64,
+    /// Discussions created or updated in 7 days; `None` when the repo has
+    /// discussions disabled or they couldn't be read.
+    discussion_activity: Option<u64>,
+}
+
 #[derive(Deserialize)]
 #[allow(dead_code)]
 struct RepoItem {
@@ -31,8 +75,49 @@ struct RepoItem {
     watchers_count: u64,
 }
 
+impl Source for GitHubConfig {
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    fn key(&self) -> &'static str {
+        "github"
+    }
+
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn key_env(&self) -> Option<&str> {
+        Some("GITHUB_TOKEN")
+    }
+
+    /// The token may also be set in config.toml.
+    fn key_set(&self) -> bool {
+        !self.token.is_empty()
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        let mut repos: Vec<&String> = self
+            .tracked_repos
+            .iter()
+            .chain(&self.flagship_repos)
+            .collect();
+        repos.sort();
+        repos.dedup();
+        (repos.len(), "repos")
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
 pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+    let github = client(config, http);
     let mut signals = Vec::new();
+    // The first spent budget that cut collection short.
+    let mut exhausted: Option<Error> = None;
 
     // Search for recently created Solana repos
     let cutoff = Utc::now() - chrono::Duration::days(config.lookback_days as i64);
@@ -40,28 +125,36 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<Vec<Sig
 
     for topic in &config.topics {
         let url = format!(
-            "{GITHUB_API}/search/repositories?q=topic:{topic}+created:>{cutoff_str}+stars:>={min_stars}&sort=stars&order=desc&per_page={max}",
+            "{GITHUB_API}/search/repositories?q=topic:{topic}+created:>{cutoff_str}+stars:>={min_stars}&sort=stars&order=desc",
             min_stars = config.min_stars,
-            max = config.max_repos,
         );
 
         info!(topic, "searching GitHub for new repos");
-        let resp: SearchResponse = http.get_json_authed(&url, &config.token).await?;
+        let resp = match search_repos(&github, &url, config.max_repos, config.max_search_pages)
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) if budget_spent(&e) => {
+                warn!(error = %e, "GitHub search budget spent, skipping remaining topic searches");
+                exhausted.get_or_insert(e);
+                break;
+            }
+            Err(e) => return Err(e),
+        };
 
-        signals.push(Signal {
-            source: SignalSource::GitHub,
-            category: format!("New {topic} Repositories"),
-            title: format!("{} new repos with topic '{topic}' in last {} days", resp.total_count, config.lookback_days),
-            description: format!(
+        signals.push(Signal::new(
+SignalSource::GitHub,
+format!("New {topic} Repositories"),
+format!("{} new repos with topic '{topic}' in last {} days", resp.total_count, config.lookback_days),
+format!(
                 "GitHub search found {} repositories created since {cutoff_str} with topic '{topic}' and {}+ stars.",
                 resp.total_count, config.min_stars
             ),
-            metrics: vec![
+vec![
                 Metric { name: "total_new_repos".into(), value: resp.total_count as f64, unit: "repos".into() },
             ],
-            url: Some(format!("https://github.com/topics/{topic}?o=desc&s=stars")),
-            timestamp: Utc::now(),
-        });
+Some(format!("https://github.com/topics/{topic}?o=desc&s=stars")),
+));
 
         // Per-repo signals for the top repos
         let mut categories: std::collections::HashMap<String, Vec<&RepoItem>> =
@@ -80,15 +173,15 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<Vec<Sig
                 .map(|r| format!("{} ({}*)", r.full_name, r.stargazers_count))
                 .collect();
 
-            signals.push(Signal {
-                source: SignalSource::GitHub,
-                category: category.clone(),
-                title: format!(
+            signals.push(Signal::new(
+                SignalSource::GitHub,
+                category.clone(),
+                format!(
                     "{category}: {} new repos, {total_stars} total stars",
                     repos.len()
                 ),
-                description: format!("Top repos: {}", top_repos.join(", ")),
-                metrics: vec![
+                format!("Top repos: {}", top_repos.join(", ")),
+                vec![
                     Metric {
                         name: "repo_count".into(),
                         value: repos.len() as f64,
@@ -105,9 +198,8 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<Vec<Sig
                         unit: "forks".into(),
                     },
                 ],
-                url: None,
-                timestamp: Utc::now(),
-            });
+                None,
+            ));
         }
     }
 
@@ -116,7 +208,15 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<Vec<Sig
         "{GITHUB_API}/search/repositories?q=topic:solana+pushed:>{cutoff_str}&sort=updated&order=desc&per_page=10",
         cutoff_str = (Utc::now() - chrono::Duration::days(7)).format("%Y-%m-%d"),
     );
-    let trending: SearchResponse = http.get_json_authed(&trending_url, &config.token).await?;
+    let trending: SearchResponse = match github.get_json(&trending_url).await {
+        Ok(trending) => trending,
+        Err(e) if budget_spent(&e) => {
+            debug!(error = %e, "GitHub search budget spent, skipping active repos");
+            exhausted.get_or_insert(e);
+            SearchResponse::default()
+        }
+        Err(e) => return Err(e),
+    };
 
     if !trending.items.is_empty() {
         let top: Vec<String> = trending
@@ -133,28 +233,457 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<Vec<Sig
             })
             .collect();
 
-        signals.push(Signal {
-            source: SignalSource::GitHub,
-            category: "Trending Solana Repos".into(),
-            title: format!(
+        signals.push(Signal::new(
+            SignalSource::GitHub,
+            "Trending Solana Repos".into(),
+            format!(
                 "Top {} most active Solana repos this week",
                 trending.items.len()
             ),
-            description: top.join("\n"),
-            metrics: vec![Metric {
+            top.join("\n"),
+            vec![Metric {
                 name: "trending_count".into(),
                 value: trending.items.len() as f64,
                 unit: "repos".into(),
             }],
-            url: Some("https://github.com/topics/solana?o=desc&s=updated".into()),
-            timestamp: Utc::now(),
-        });
+            Some("https://github.com/topics/solana?o=desc&s=updated".into()),
+        ));
+    }
+
+    for repo in &config.tracked_repos {
+        match github
+            .get_json::<RepoItem>(&format!("{GITHUB_API}/repos/{repo}"))
+            .await
+        {
+            Ok(item) => signals.push(tracked_repo_signal(&item)),
+            Err(e) if budget_spent(&e) => {
+                warn!(error = %e, "GitHub core budget spent, skipping remaining tracked repos");
+                exhausted.get_or_insert(e);
+                break;
+            }
+            Err(e) => warn!(repo = %repo, error = %e, "failed to fetch tracked repo, skipping"),
+        }
+    }
+
+    for repo in &config.flagship_repos {
+        match community_activity(&github, repo).await {
+            Ok(activity) => signals.push(community_signal(repo, &activity)),
+            Err(e) if budget_spent(&e) => {
+                warn!(error = %e, "GitHub search budget spent, skipping remaining flagship repos");
+                exhausted.get_or_insert(e);
+                break;
+            }
+            Err(e) => {
+                warn!(repo = %repo, error = %e, "failed to read repo issue activity, skipping")
+            }
+        }
+    }
+
+    if config.trending {
+        match trending_signal(config, &github).await {
+            Ok(Some(signal)) => signals.push(signal),
+            Ok(None) => {}
+            Err(e) => warn!(error = %e, "GitHub trending search failed, skipping"),
+        }
     }
 
+    debug!(
+        core_remaining = ?github.remaining(Resource::Core),
+        search_remaining = ?github.remaining(Resource::Search),
+        graphql_remaining = ?github.remaining(Resource::Graphql),
+        "GitHub rate budget left after collection"
+    );
+    if let Some(e) = exhausted {
+        if signals.is_empty() {
+            return Err(e);
+        }
+        info!(
+            signal_count = signals.len(),
+            "GitHub rate budget ran out, keeping the signals gathered so far"
+        );
+    }
     info!(signal_count = signals.len(), "collected GitHub signals");
     Ok(signals)
 }
 
+/// The GitHub client for `config`'s token and rate-wait limit. Every client
+/// for the same token shares its rate budgets.
+pub fn client<'a>(config: &'a GitHubConfig, http: &'a HttpClient) -> GitHubClient<'a> {
+    GitHubClient::shared(
+        http,
+        &config.token,
+        Duration::from_secs(config.max_rate_wait_secs),
+    )
+}
+
+/// A rate budget ran out and won't refill within `max_rate_wait_secs`, so
+/// further calls of that kind this run would fail the same way.
+fn budget_spent(error: &Error) -> bool {
+    matches!(error, Error::RateLimit { .. })
+}
+
+/// Up to `max_items` results of the repository search `url`, read in pages
+/// of up to 100 until the results, `max_items` or `max_pages` run out. A
+/// later page that fails, say on a rate limit, ends paging with the
+/// results so far.
+async fn search_repos(
+    github: &GitHubClient<'_>,
+    url: &str,
+    max_items: u32,
+    max_pages: u32,
+) -> Result<SearchResponse> {
+    let per_page = max_items.clamp(1, SEARCH_PAGE);
+    let mut total_count = 0;
+    let mut items = Vec::new();
+    for page in 1..=max_pages.max(1) {
+        let page_url = format!("{url}&per_page={per_page}&page={page}");
+        let resp: SearchResponse = match github.get_json(&page_url).await {
+            Ok(resp) => resp,
+            Err(e) if page > 1 => {
+                warn!(page, error = %e, "GitHub search page failed, keeping earlier pages");
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        total_count = resp.total_count;
+        let page_len = resp.items.len();
+        items.extend(resp.items);
+        if page_len < per_page as usize
+            || items.len() >= max_items as usize
+            || items.len() as u64 >= total_count
+        {
+            break;
+        }
+    }
+    items.truncate(max_items as usize);
+    Ok(SearchResponse { total_count, items })
+}
+
+fn tracked_repo_signal(repo: &RepoItem) -> Signal {
+    Signal::new(
+        SignalSource::GitHub,
+        TRACKED_CATEGORY.into(),
+        format!("{}: {} stars", repo.full_name, repo.stargazers_count),
+        format!(
+            "{} total stars, {} forks. {}",
+            repo.stargazers_count,
+            repo.forks_count,
+            repo.description.as_deref().unwrap_or("")
+        ),
+        vec![Metric {
+            name: "stars".into(),
+            value: repo.stargazers_count as f64,
+            unit: "stars".into(),
+        }],
+        Some(repo.html_url.clone()),
+    )
+}
+
+/// Open issues and issues opened this week from the issue search API, plus
+/// discussion activity from GraphQL (REST has no discussion search). Only
+/// the discussion count is optional.
+async fn community_activity(github: &GitHubClient<'_>, repo: &str) -> Result<Community> {
+    let week_ago = (Utc::now() - chrono::Duration::days(7)).format("%Y-%m-%d");
+    let count = |query: String| async move {
+        let url = format!("{GITHUB_API}/search/issues?q={query}&per_page=1");
+        github
+            .get_json::<CountResponse>(&url)
+            .await
+            .map(|r| r.total_count)
+    };
+    let (open_issues, issues_opened_7d) = tokio::try_join!(
+        count(format!("repo:{repo}+is:issue+is:open")),
+        count(format!("repo:{repo}+is:issue+created:>={week_ago}")),
+    )?;
+
+    let discussion_activity = match discussion_activity(github, repo, &week_ago.to_string()).await {
+        Ok(Some(count)) => Some(count),
+        Ok(None) => {
+            debug!(repo = %repo, "discussions disabled, omitting discussion_activity");
+            None
+        }
+        Err(e) => {
+            warn!(repo = %repo, error = %e, "failed to read discussions, omitting discussion_activity");
+            None
+        }
+    };
+    Ok(Community {
+        open_issues,
+        issues_opened_7d,
+        discussion_activity,
+    })
+}
+
+/// Discussions created or updated since `since`; `None` when the repo has
+/// discussions turned off.
+async fn discussion_activity(
+    github: &GitHubClient<'_>,
+    repo: &str,
+    since: &str,
+) -> Result<Option<u64>> {
+    let (owner, name) = repo
+        .split_once('/')
+        .ok_or_else(|| Error::config(format!("flagship repo '{repo}' is not owner/name")))?;
+    let body = serde_json::json!({
+        "query": "query($owner: String!, $name: String!, $q: String!) { \
+            repository(owner: $owner, name: $name) { hasDiscussionsEnabled } \
+            search(type: DISCUSSION, query: $q, first: 0) { discussionCount } }",
+        "variables": {
+            "owner": owner,
+            "name": name,
+            "q": format!("repo:{repo} updated:>={since}"),
+        },
+    });
+    let response = github
+        .post_json(&format!("{GITHUB_API}/graphql"), &body.to_string())
+        .await?;
+    parse_discussion_count(&response)
+}
+
+fn parse_discussion_count(response: &str) -> Result<Option<u64>> {
+    #[derive(Deserialize)]
+    struct Response {
+        data: Option<Data>,
+        #[serde(default)]
+        errors: Vec<GraphQlError>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Data {
+        repository: Option<Repository>,
+        search: Search,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Repository {
+        has_discussions_enabled: bool,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Search {
+        discussion_count: u64,
+    }
+    #[derive(Deserialize)]
+    struct GraphQlError {
+        message: String,
+    }
+
+    let response: Response =
+        serde_json::from_str(response).map_err(|e| Error::parse(format!("GitHub GraphQL: {e}")))?;
+    let Some(data) = response.data else {
+        let messages: Vec<String> = response.errors.into_iter().map(|e| e.message).collect();
+        return Err(Error::api("github", messages.join("; ")));
+    };
+    match data.repository {
+        Some(repo) if repo.has_discussions_enabled => Ok(Some(data.search.discussion_count)),
+        _ => Ok(None),
+    }
+}
+
+fn community_signal(repo: &str, activity: &Community) -> Signal {
+    let mut metrics = vec![
+        Metric {
+            name: "open_issues".into(),
+            value: activity.open_issues as f64,
+            unit: "issues".into(),
+        },
+        Metric {
+            name: "issues_opened_7d".into(),
+            value: activity.issues_opened_7d as f64,
+            unit: "issues".into(),
+        },
+    ];
+    let discussions = match activity.discussion_activity {
+        Some(count) => {
+            metrics.push(Metric {
+                name: "discussion_activity".into(),
+                value: count as f64,
+                unit: "discussions".into(),
+            });
+            format!(" {count} discussions created or updated in the same week.")
+        }
+        None => String::new(),
+    };
+    Signal::new(
+        SignalSource::GitHub,
+        COMMUNITY_CATEGORY.into(),
+        format!("{repo}: {} issues opened in 7d", activity.issues_opened_7d),
+        format!(
+            "{} issues opened in the last 7 days, {} open in total.{discussions} Rising issue and discussion volume tracks developer mindshare.",
+            activity.issues_opened_7d, activity.open_issues
+        ),
+        metrics,
+        Some(format!("https://github.com/{repo}/issues")),
+    )
+}
+
+/// Young Solana repos ranked by stars per day since creation.
+async fn trending_signal(
+    config: &GitHubConfig,
+    github: &GitHubClient<'_>,
+) -> Result<Option<Signal>> {
+    let now = Utc::now();
+    let created_after =
+        (now - chrono::Duration::days(config.trending_days as i64)).format("%Y-%m-%d");
+    let url = format!(
+        "{GITHUB_API}/search/repositories?q=topic:solana+created:>{created_after}&sort=stars&order=desc&per_page=30"
+    );
+    let resp: SearchResponse = github.get_json(&url).await?;
+
+    let mut growth: Vec<(&RepoItem, f64)> = resp
+        .items
+        .iter()
+        .map(|r| {
+            let age_days = ((now - r.created_at).num_hours() as f64 / 24.0).max(1.0);
+            (r, r.stargazers_count as f64 / age_days)
+        })
+        .collect();
+    growth.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let Some(&(_, top_rate)) = growth.first() else {
+        return Ok(None);
+    };
+    let fast = growth
+        .iter()
+        .filter(|(_, rate)| *rate >= FAST_GROWTH_STARS_PER_DAY)
+        .count();
+
+    let listed: Vec<String> = growth
+        .iter()
+        .take(10)
+        .map(|(r, rate)| {
+            format!(
+                "{} (+{rate:.1} stars/day, {} total) - {}",
+                r.full_name,
+                r.stargazers_count,
+                r.description.as_deref().unwrap_or("no description")
+            )
+        })
+        .collect();
+
+    Ok(Some(Signal::new(
+        SignalSource::GitHub,
+        "Fast-Growing Solana Repos".into(),
+        format!(
+            "{fast} Solana repos created in the last {} days gaining {FAST_GROWTH_STARS_PER_DAY}+ stars/day",
+            config.trending_days
+        ),
+        listed.join("\n"),
+        vec![
+            Metric {
+                name: "fast_growing_repos".into(),
+                value: fast as f64,
+                unit: "repos".into(),
+            },
+            Metric {
+                name: "top_stars_per_day".into(),
+                value: top_rate,
+                unit: "stars/day".into(),
+            },
+        ],
+        Some(format!(
+            "https://github.com/search?q=topic%3Asolana+created%3A%3E{created_after}&s=stars&type=repositories"
+        )),
+    )))
+}
+
+/// Add `stars_gained_7d` to tracked-repo signals and lead their text with it,
+/// since absolute stars mostly reflect a repo's age. Uses the history run
+/// closest to a week ago when one exists; otherwise counts recent stargazers.
+pub async fn add_star_velocity(
+    github: &GitHubClient<'_>,
+    history: Option<&History>,
+    signals: &mut [Signal],
+) {
+    let now = Utc::now();
+    let week_ago = now - chrono::Duration::days(7);
+
+    for signal in signals
+        .iter_mut()
+        .filter(|s| s.source == SignalSource::GitHub && s.category == TRACKED_CATEGORY)
+    {
+        let Some(stars) = signal.metrics.iter().find(|m| m.name == "stars") else {
+            continue;
+        };
+        let key = metric_key(signal, stars);
+        let stars = stars.value;
+
+        let from_history = history
+            .and_then(|h| h.value_at(&key, week_ago))
+            .filter(|(at, _)| now - *at <= chrono::Duration::days(14))
+            .map(|(at, past)| {
+                let days = (now - at).num_hours() as f64 / 24.0;
+                ((stars - past) * 7.0 / days, "from history")
+            });
+        let gained = match from_history {
+            Some(gained) => Some(gained),
+            None => {
+                let repo = signal
+                    .url
+                    .as_deref()
+                    .and_then(|u| u.strip_prefix("https://github.com/"))
+                    .unwrap_or_default();
+                match stars_since(github, repo, stars as u64, week_ago).await {
+                    Ok(count) => count.map(|c| (c as f64, "from stargazer timestamps")),
+                    Err(e) => {
+                        warn!(repo = %repo, error = %e, "failed to read stargazers");
+                        None
+                    }
+                }
+            }
+        };
+        let Some((gained, basis)) = gained else {
+            continue;
+        };
+
+        let name = signal
+            .title
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        signal.title = format!("{name}: +{gained:.0} stars in 7d");
+        // The ID follows the title, so it must be derived again.
+        signal.id = signal.compute_id();
+        signal.description = format!(
+            "Gained {gained:.0} stars in the last 7 days ({basis}), {:.1}/day. {}",
+            gained / 7.0,
+            signal.description
+        );
+        signal.metrics.push(Metric {
+            name: "stars_gained_7d".into(),
+            value: gained,
+            unit: "stars".into(),
+        });
+    }
+}
+
+/// Stars given since `since`, read from the newest stargazer pages. `None`
+/// when the repo is too large for the newest stars to be listed.
+async fn stars_since(
+    github: &GitHubClient<'_>,
+    repo: &str,
+    stars: u64,
+    since: DateTime<Utc>,
+) -> Result<Option<u64>> {
+    let last_page = stars.div_ceil(100).max(1);
+    if last_page > MAX_STARGAZER_PAGE {
+        return Ok(None);
+    }
+    let mut count = 0;
+    let first_page = last_page.saturating_sub(COLD_START_PAGES - 1).max(1);
+    for page in (first_page..=last_page).rev() {
+        let url = format!("{GITHUB_API}/repos/{repo}/stargazers?per_page=100&page={page}");
+        let stargazers: Vec<Stargazer> = github
+            .get_json_as(&url, "application/vnd.github.star+json")
+            .await?;
+        count += stargazers.iter().filter(|s| s.starred_at >= since).count() as u64;
+        if stargazers.iter().any(|s| s.starred_at < since) {
+            break;
+        }
+    }
+    Ok(Some(count))
+}
+
 fn categorize_repo(repo: &RepoItem) -> String {
     let topics = repo.topics.as_deref().unwrap_or(&[]);
     let desc = repo.description.as_deref().unwrap_or("").to_lowercase();
@@ -242,3 +771,149 @@ fn categorize_repo(repo: &RepoItem) -> String {
 
     "General Solana".into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, Reply, TempDir};
+
+    #[test]
+    fn disabled_discussions_drop_only_that_metric() {
+        let enabled = r#"{"data":{"repository":{"hasDiscussionsEnabled":true},"search":{"discussionCount":42}}}"#;
+        let disabled = r#"{"data":{"repository":{"hasDiscussionsEnabled":false},"search":{"discussionCount":0}}}"#;
+        let failed = r#"{"data":null,"errors":[{"message":"Bad credentials"}]}"#;
+        assert_eq!(parse_discussion_count(enabled).unwrap(), Some(42));
+        assert_eq!(parse_discussion_count(disabled).unwrap(), None);
+        assert!(
+            parse_discussion_count(failed)
+                .unwrap_err()
+                .to_string()
+                .contains("Bad credentials")
+        );
+
+        let activity = Community {
+            open_issues: 310,
+            issues_opened_7d: 25,
+            discussion_activity: None,
+        };
+        let signal = community_signal("solana-foundation/anchor", &activity);
+        let names: Vec<&str> = signal.metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["open_issues", "issues_opened_7d"]);
+        assert_eq!(signal.category, COMMUNITY_CATEGORY);
+
+        let with_discussions = community_signal(
+            "solana-foundation/anchor",
+            &Community {
+                discussion_activity: Some(12),
+                ..activity
+            },
+        );
+        assert_eq!(with_discussions.metrics[2].name, "discussion_activity");
+        assert_eq!(signal.id, with_discussions.id, "counts don't change the ID");
+    }
+
+    #[tokio::test]
+    async fn star_velocity_uses_week_old_history() {
+        let repo = RepoItem {
+            full_name: "anza-xyz/agave".into(),
+            description: None,
+            html_url: "https://github.com/anza-xyz/agave".into(),
+            stargazers_count: 1100,
+            forks_count: 0,
+            open_issues_count: 0,
+            language: None,
+            topics: None,
+            created_at: Utc::now(),
+            pushed_at: Utc::now(),
+            watchers_count: 0,
+        };
+        let dir = TempDir::new("stars");
+        let path = dir.join("history.json");
+        let week_old = serde_json::json!([{
+            "started_at": (Utc::now() - chrono::Duration::days(7)).to_rfc3339(),
+            "metrics": {"GitHub|Tracked Repos|https://github.com/anza-xyz/agave|stars": 1000.0}
+        }]);
+        std::fs::write(&path, week_old.to_string()).unwrap();
+        let history = History::load(&path).unwrap();
+
+        let mut signals = vec![tracked_repo_signal(&repo)];
+        let id = signals[0].id.clone();
+        let http = HttpClient::new(&Default::default()).unwrap();
+        let github = GitHubClient::new(&http, "", Duration::from_secs(60));
+        add_star_velocity(&github, Some(&history), &mut signals).await;
+
+        let gained = signals[0]
+            .metrics
+            .iter()
+            .find(|m| m.name == "stars_gained_7d")
+            .unwrap();
+        assert!((gained.value - 100.0).abs() < 1.0);
+        assert!(signals[0].title.contains("+100 stars in 7d"));
+        assert!(signals[0].description.starts_with("Gained 100 stars"));
+        assert_ne!(signals[0].id, id);
+        assert_eq!(
+            signals[0].id,
+            signals[0].compute_id(),
+            "ID follows the title"
+        );
+    }
+
+    #[tokio::test]
+    async fn search_pages_until_results_or_page_cap_run_out() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let addr = test_support::mock_http(move |request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            // 250 results in all, 100 per page.
+            let page: usize = request
+                .path
+                .split("&page=")
+                .nth(1)
+                .and_then(|rest| rest.split('&').next())
+                .and_then(|p| p.parse().ok())
+                .unwrap();
+            let items: Vec<serde_json::Value> = (0..250)
+                .skip((page - 1) * 100)
+                .take(100)
+                .map(|i| {
+                    serde_json::json!({
+                        "full_name": format!("dev/repo-{i}"),
+                        "description": null,
+                        "html_url": format!("https://github.com/dev/repo-{i}"),
+                        "stargazers_count": 10,
+                        "forks_count": 0,
+                        "open_issues_count": 0,
+                        "language": null,
+                        "topics": null,
+                        "created_at": "2026-01-01T00:00:00Z",
+                        "pushed_at": "2026-01-01T00:00:00Z"
+                    })
+                })
+                .collect();
+            Reply::json(serde_json::json!({"total_count": 250, "items": items}).to_string())
+        })
+        .await;
+        let http = HttpClient::new(&Default::default()).unwrap();
+        let github = GitHubClient::new(&http, "", Duration::from_secs(60));
+        let url = format!("http://{addr}/search/repositories?q=topic:solana");
+
+        let all = search_repos(&github, &url, 1000, 5).await.unwrap();
+        assert_eq!(all.items.len(), 250);
+        assert_eq!(
+            requests.swap(0, Ordering::SeqCst),
+            3,
+            "stops at the short page"
+        );
+
+        let capped = search_repos(&github, &url, 1000, 2).await.unwrap();
+        assert_eq!(capped.items.len(), 200);
+        assert_eq!(capped.total_count, 250);
+        assert_eq!(requests.swap(0, Ordering::SeqCst), 2);
+
+        let few = search_repos(&github, &url, 30, 5).await.unwrap();
+        assert_eq!(few.items.len(), 30);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
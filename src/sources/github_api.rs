@@ -0,0 +1,479 @@
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use crate::http_cache::CachedPage;
+use chrono::Utc;
+use reqwest::Method;
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{debug, warn};
+
+/// Tries per call when the failure is transient: a timeout, a dropped
+/// connection, a 5xx, or a rate limit that resets within `max_wait`.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the second try of a transiently failed call; doubled after.
+const BASE_DELAY: Duration = Duration::from_secs(1);
+
+const ACCEPT_JSON: &str = "application/vnd.github+json";
+
+/// GitHub's separately metered rate-limit buckets. Search allows 30
+/// requests a minute; core allows 5,000 an hour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Core,
+    Search,
+    Graphql,
+}
+
+impl Resource {
+    /// The bucket a request to `url` is charged to, judged by its path.
+    pub fn of(url: &str) -> Self {
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let path = rest.find('/').map_or("", |i| &rest[i..]);
+        if path.starts_with("/search/") {
+            Self::Search
+        } else if path.starts_with("/graphql") {
+            Self::Graphql
+        } else {
+            Self::Core
+        }
+    }
+
+    /// The bucket GitHub names in `X-RateLimit-Resource`, when it's one
+    /// this client tracks.
+    fn from_header(name: &str) -> Option<Self> {
+        match name {
+            "core" => Some(Self::Core),
+            "search" => Some(Self::Search),
+            "graphql" => Some(Self::Graphql),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Core => "core",
+            Self::Search => "search",
+            Self::Graphql => "graphql",
+        }
+    }
+}
+
+impl std::fmt::Display for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What's left of one bucket, as of the last response charged to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Budget {
+    remaining: u64,
+    limit: u64,
+    /// Unix time the bucket refills.
+    reset: i64,
+}
+
+impl Budget {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let number = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<i64>().ok())
+        };
+        Some(Self {
+            remaining: number("x-ratelimit-remaining")?.max(0) as u64,
+            limit: number("x-ratelimit-limit").unwrap_or(0).max(0) as u64,
+            reset: number("x-ratelimit-reset")?,
+        })
+    }
+
+    /// Seconds until the bucket refills, counted from `now`.
+    fn resets_in(&self, now: i64) -> u64 {
+        (self.reset - now).max(0) as u64
+    }
+}
+
+/// GitHub API client that reads the rate-limit headers of every response
+/// and meters core, search and GraphQL calls apart. A call to a spent
+/// bucket waits for the reset if it's within `max_wait`, and otherwise
+/// fails with [`Error::RateLimit`] without spending a request. GETs
+/// outside search are sent with the `ETag` from `http.cache_path`, and a 304
+/// (which GitHub doesn't charge for) reuses the stored body.
+pub struct GitHubClient<'a> {
+    http: &'a HttpClient,
+    token: &'a str,
+    max_wait: Duration,
+    budgets: Budgets,
+}
+
+type Budgets = Arc<Mutex<HashMap<Resource, Budget>>>;
+
+/// The rate budgets of every [`GitHubClient::shared`] client for `token`.
+fn shared_budgets(token: &str) -> Budgets {
+    static BUDGETS: OnceLock<Mutex<HashMap<String, Budgets>>> = OnceLock::new();
+    BUDGETS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(token.to_string())
+        .or_default()
+        .clone()
+}
+
+impl<'a> GitHubClient<'a> {
+    pub fn new(http: &'a HttpClient, token: &'a str, max_wait: Duration) -> Self {
+        Self {
+            http,
+            token,
+            max_wait,
+            budgets: Budgets::default(),
+        }
+    }
+
+    /// A client that shares its rate budgets with every other one built
+    /// this way for `token`, so a later stage of a run knows what
+    /// collection already spent.
+    pub fn shared(http: &'a HttpClient, token: &'a str, max_wait: Duration) -> Self {
+        Self {
+            budgets: shared_budgets(token),
+            ..Self::new(http, token, max_wait)
+        }
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        self.get_json_as(url, ACCEPT_JSON).await
+    }
+
+    /// GET with a custom media type, such as `application/vnd.github.star+json`.
+    pub async fn get_json_as<T: DeserializeOwned>(&self, url: &str, accept: &str) -> Result<T> {
+        let body = self.send(Method::GET, url, accept, None).await?;
+        serde_json::from_str(&body).map_err(|e| Error::parse(format!("JSON parse: {e}")))
+    }
+
+    pub async fn post_json(&self, url: &str, body: &str) -> Result<String> {
+        self.send(Method::POST, url, ACCEPT_JSON, Some(body)).await
+    }
+
+    /// Requests left in `resource`'s bucket, if a response has reported it.
+    pub fn remaining(&self, resource: Resource) -> Option<u64> {
+        self.budgets
+            .lock()
+            .unwrap()
+            .get(&resource)
+            .map(|b| b.remaining)
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        accept: &str,
+        body: Option<&str>,
+    ) -> Result<String> {
+        let resource = Resource::of(url);
+        // Search queries carry a date, so their URLs change daily and
+        // stored copies would only pile up in the cache.
+        let cacheable = method == Method::GET && resource != Resource::Search;
+        let cached = if cacheable {
+            self.http.cached_page(url)
+        } else {
+            None
+        };
+        let auth = format!("Bearer {}", self.token);
+        let mut delay = BASE_DELAY;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            self.wait_for_budget(resource).await?;
+            let mut headers = vec![
+                ("Authorization", auth.as_str()),
+                ("Accept", accept),
+                ("X-GitHub-Api-Version", "2022-11-28"),
+            ];
+            if let Some(etag) = cached.as_ref().and_then(|page| page.etag.as_deref()) {
+                headers.push(("If-None-Match", etag));
+            }
+            let last = attempt == MAX_ATTEMPTS;
+            let resp = match self
+                .http
+                .request_raw(method.clone(), url, &headers, body)
+                .await
+            {
+                Ok(resp) => resp,
+                Err(e) if e.is_retryable() && !last => {
+                    warn!(attempt, error = %e, "GitHub request failed, will retry");
+                    self.http.spend_retry()?;
+                    sleep(delay).await;
+                    delay *= 2;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let budget = self.record(resource, &resp.headers);
+
+            match resp.status {
+                200..=299 => {
+                    if cacheable {
+                        let etag = resp
+                            .headers
+                            .get("etag")
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_string);
+                        self.http.cache_page(
+                            url,
+                            CachedPage {
+                                etag,
+                                last_modified: None,
+                                body: resp.text.clone(),
+                            },
+                        );
+                    }
+                    return Ok(resp.text);
+                }
+                304 => {
+                    let page = cached.ok_or_else(|| {
+                        Error::api_with_status("github", "304 for an uncached page", 304)
+                    })?;
+                    debug!(url, "GitHub resource unchanged, reusing cached body");
+                    return Ok(page.body);
+                }
+                403 | 429 if is_rate_limited(&resp.headers, budget) => {
+                    let now = Utc::now().timestamp();
+                    let wait = retry_after(&resp.headers)
+                        .or_else(|| budget.map(|b| b.resets_in(now)))
+                        .unwrap_or(60);
+                    if last || Duration::from_secs(wait) > self.max_wait {
+                        return Err(exhausted(resource, wait));
+                    }
+                    warn!(%resource, wait_secs = wait, "GitHub rate limit hit, waiting for reset");
+                    sleep(Duration::from_secs(wait)).await;
+                }
+                500..=599 if !last => {
+                    warn!(
+                        attempt,
+                        status = resp.status,
+                        "GitHub server error, will retry"
+                    );
+                    self.http.spend_retry()?;
+                    sleep(delay).await;
+                    delay *= 2;
+                }
+                status => return Err(Error::api_with_status("github", resp.text, status)),
+            }
+        }
+        Err(exhausted(resource, 0))
+    }
+
+    /// Hold a call to a spent bucket until it refills, or refuse it when
+    /// that's further off than `max_wait`.
+    async fn wait_for_budget(&self, resource: Resource) -> Result<()> {
+        let Some(budget) = self.budgets.lock().unwrap().get(&resource).copied() else {
+            return Ok(());
+        };
+        let wait = budget.resets_in(Utc::now().timestamp());
+        if budget.remaining > 0 || wait == 0 {
+            return Ok(());
+        }
+        if Duration::from_secs(wait) > self.max_wait {
+            return Err(exhausted(resource, wait));
+        }
+        debug!(%resource, wait_secs = wait, "GitHub budget spent, waiting for reset");
+        sleep(Duration::from_secs(wait)).await;
+        Ok(())
+    }
+
+    /// Note the budget a response reports, under the bucket GitHub names
+    /// when it names one.
+    fn record(&self, resource: Resource, headers: &HeaderMap) -> Option<Budget> {
+        let budget = Budget::from_headers(headers)?;
+        let resource = headers
+            .get("x-ratelimit-resource")
+            .and_then(|v| v.to_str().ok())
+            .and_then(Resource::from_header)
+            .unwrap_or(resource);
+        debug!(
+            %resource,
+            remaining = budget.remaining,
+            limit = budget.limit,
+            resets_in_secs = budget.resets_in(Utc::now().timestamp()),
+            "GitHub rate budget"
+        );
+        self.budgets.lock().unwrap().insert(resource, budget);
+        Some(budget)
+    }
+}
+
+/// A 403 or 429 is a rate limit, not a permission error, when the bucket
+/// is empty or GitHub asks for a pause (its secondary limits).
+fn is_rate_limited(headers: &HeaderMap, budget: Option<Budget>) -> bool {
+    budget.is_some_and(|b| b.remaining == 0) || retry_after(headers).is_some()
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+fn exhausted(resource: Resource, wait: u64) -> Error {
+    Error::RateLimit {
+        platform: format!("GitHub ({resource} budget)"),
+        retry_after_secs: Some(wait),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, Reply, TempDir};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn requests_are_charged_to_their_bucket() {
+        assert_eq!(
+            Resource::of("https://api.github.com/search/repositories?q=x"),
+            Resource::Search
+        );
+        assert_eq!(
+            Resource::of("https://api.github.com/repos/anza-xyz/agave"),
+            Resource::Core
+        );
+        assert_eq!(
+            Resource::of("https://api.github.com/graphql"),
+            Resource::Graphql
+        );
+        assert_eq!(
+            Resource::of("http://127.0.0.1:9/search/issues"),
+            Resource::Search
+        );
+    }
+
+    /// Serves `/search/issues` normally, the rest of `/search/*` from an
+    /// already-spent bucket resetting in an hour, and everything else with
+    /// an `ETag`, answering 304 when it's sent back.
+    async fn mock_github() -> (std::net::SocketAddr, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let addr = test_support::mock_http(move |request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let reset = (Utc::now().timestamp() + 3600).to_string();
+            let bucket = |reply: Reply, resource: &str, limit: &str, remaining: &str| {
+                reply
+                    .header("x-ratelimit-resource", resource)
+                    .header("x-ratelimit-limit", limit)
+                    .header("x-ratelimit-remaining", remaining)
+                    .header("x-ratelimit-reset", reset.clone())
+            };
+            if request.path.starts_with("/search/issues") {
+                bucket(
+                    Reply::json(r#"{"total_count":3}"#).header("etag", "\"s1\""),
+                    "search",
+                    "30",
+                    "29",
+                )
+            } else if request.path.starts_with("/search/") {
+                bucket(
+                    Reply::status(403).body(r#"{"message":"API rate limit exceeded"}"#),
+                    "search",
+                    "30",
+                    "0",
+                )
+            } else if request.header("if-none-match") == Some("\"v1\"") {
+                bucket(Reply::status(304), "core", "5000", "4999")
+            } else {
+                bucket(
+                    Reply::ok(r#"{"stars":7}"#).header("etag", "\"v1\""),
+                    "core",
+                    "5000",
+                    "4998",
+                )
+            }
+        })
+        .await;
+        (addr, requests)
+    }
+
+    #[tokio::test]
+    async fn spent_search_budget_fails_fast_without_touching_core() {
+        let (addr, requests) = mock_github().await;
+        let http = HttpClient::new(&Default::default()).unwrap();
+        let github = GitHubClient::new(&http, "", Duration::from_secs(60));
+
+        let search = format!("http://{addr}/search/repositories?q=topic:solana");
+        let err = github
+            .get_json::<serde_json::Value>(&search)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::RateLimit { .. }), "{err}");
+        assert_eq!(github.remaining(Resource::Search), Some(0));
+        assert_eq!(requests.swap(0, Ordering::SeqCst), 1);
+
+        // The next search is refused before it's sent.
+        assert!(github.get_json::<serde_json::Value>(&search).await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        // Core has its own bucket.
+        let repo: serde_json::Value = github
+            .get_json(&format!("http://{addr}/repos/anza-xyz/agave"))
+            .await
+            .unwrap();
+        assert_eq!(repo["stars"], 7);
+        assert_eq!(github.remaining(Resource::Core), Some(4998));
+    }
+
+    #[tokio::test]
+    async fn unchanged_resources_are_revalidated_with_their_etag() {
+        let (addr, requests) = mock_github().await;
+        let dir = TempDir::new("gh-etag");
+        let path = dir.join("http.json");
+        let config = crate::config::HttpConfig {
+            cache_path: Some(path.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        let http = HttpClient::new(&config).unwrap();
+        let github = GitHubClient::new(&http, "", Duration::from_secs(60));
+        let url = format!("http://{addr}/repos/anza-xyz/agave");
+
+        let first: serde_json::Value = github.get_json(&url).await.unwrap();
+        let second: serde_json::Value = github.get_json(&url).await.unwrap();
+        assert_eq!(first, second, "a 304 reuses the stored body");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(github.remaining(Resource::Core), Some(4999));
+
+        let search = format!("http://{addr}/search/issues?q=created:>=2026-10-08");
+        let _: serde_json::Value = github.get_json(&search).await.unwrap();
+        assert!(
+            http.cached_page(&search).is_none(),
+            "dated searches aren't kept"
+        );
+        assert!(http.cached_page(&url).is_some());
+    }
+
+    #[test]
+    fn shared_clients_share_rate_budgets() {
+        let http = HttpClient::new(&Default::default()).unwrap();
+        let wait = Duration::from_secs(60);
+        let collection = GitHubClient::shared(&http, "ghp_shared", wait);
+        collection.budgets.lock().unwrap().insert(
+            Resource::Core,
+            Budget {
+                remaining: 12,
+                limit: 5000,
+                reset: 0,
+            },
+        );
+        let later = GitHubClient::shared(&http, "ghp_shared", wait);
+        assert_eq!(later.remaining(Resource::Core), Some(12));
+        assert_eq!(
+            GitHubClient::new(&http, "ghp_shared", wait).remaining(Resource::Core),
+            None
+        );
+    }
+}
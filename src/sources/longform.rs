@@ -0,0 +1,354 @@
+use crate::analysis::sentiment;
+use crate::config::SocialSource;
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use crate::types::{Metric, Signal, SignalSource};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Url;
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Titles listed in each publication's signal.
+const TITLES_LISTED: usize = 5;
+
+/// Long-form platforms with a feed whose layout is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Substack,
+    Mirror,
+}
+
+impl Platform {
+    /// From `source_type`, or else the URL's host. Custom-domain
+    /// publications need an explicit `source_type`.
+    pub fn detect(source: &SocialSource) -> Option<Self> {
+        match source.source_type.to_lowercase().as_str() {
+            "substack" => return Some(Self::Substack),
+            "mirror" => return Some(Self::Mirror),
+            _ => {}
+        }
+        let url = Url::parse(&source.url).ok()?;
+        let host = url.host_str()?;
+        if host == "substack.com" || host.ends_with(".substack.com") {
+            Some(Self::Substack)
+        } else if host == "mirror.xyz" || host.ends_with(".mirror.xyz") {
+            Some(Self::Mirror)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Substack => "Substack",
+            Self::Mirror => "Mirror",
+        }
+    }
+
+    /// Substack serves RSS at `/feed`; Mirror serves Atom at `/feed/atom`.
+    fn feed_url(self, url: &str) -> String {
+        let base = url.trim_end_matches('/');
+        match self {
+            Self::Substack => format!("{base}/feed"),
+            Self::Mirror => format!("{base}/feed/atom"),
+        }
+    }
+}
+
+/// One post read from a feed.
+#[derive(Debug, PartialEq)]
+struct Post {
+    title: String,
+    author: Option<String>,
+    published: DateTime<Utc>,
+}
+
+/// A publication's feed: its own title and posts, newest first.
+#[derive(Debug)]
+struct Feed {
+    title: Option<String>,
+    posts: Vec<Post>,
+}
+
+impl Feed {
+    /// Posts published in the 7 days before `now`.
+    fn recent(&self, now: DateTime<Utc>) -> impl Iterator<Item = &Post> {
+        self.posts
+            .iter()
+            .filter(move |p| p.published >= now - Duration::days(7))
+    }
+}
+
+/// Activity signal for one Substack or Mirror publication, read from its
+/// feed. Errors when the feed is missing or isn't RSS/Atom, so the caller
+/// can fall back to scraping the page. With `sentiment`, this week's titles
+/// are scored too.
+pub async fn collect(
+    http: &HttpClient,
+    platform: Platform,
+    source: &SocialSource,
+    sentiment: bool,
+) -> Result<Signal> {
+    let feed_url = platform.feed_url(&source.url);
+    let feed = parse_feed(&http.get_text(&feed_url).await?).ok_or_else(|| {
+        Error::parse(format!(
+            "{feed_url} is not an RSS or Atom feed with dated posts"
+        ))
+    })?;
+    info!(
+        source = %source.name,
+        platform = platform.label(),
+        posts = feed.posts.len(),
+        "read long-form feed"
+    );
+    let now = Utc::now();
+    let mut signal = publication_signal(platform, source, &feed, now);
+    if sentiment {
+        let titles: Vec<&str> = feed.recent(now).map(|p| p.title.as_str()).collect();
+        sentiment::add_keyword_score(&mut signal, &titles);
+    }
+    Ok(signal)
+}
+
+fn publication_signal(
+    platform: Platform,
+    source: &SocialSource,
+    feed: &Feed,
+    now: DateTime<Utc>,
+) -> Signal {
+    let name = feed.title.as_deref().unwrap_or(&source.name);
+    let recent: Vec<&Post> = feed.recent(now).collect();
+
+    let mut authors: BTreeMap<&str, usize> = BTreeMap::new();
+    for post in &recent {
+        if let Some(author) = &post.author {
+            *authors.entry(author.as_str()).or_default() += 1;
+        }
+    }
+    let mut authors: Vec<(&str, usize)> = authors.into_iter().collect();
+    authors.sort_by_key(|(_, posts)| std::cmp::Reverse(*posts));
+
+    let mut description = String::new();
+    if !authors.is_empty() {
+        let listed: Vec<String> = authors
+            .iter()
+            .map(|(author, posts)| format!("{author} ({posts})"))
+            .collect();
+        description.push_str(&format!("Authors this week: {}. ", listed.join(", ")));
+    }
+    let titles: Vec<String> = feed
+        .posts
+        .iter()
+        .take(TITLES_LISTED)
+        .map(|p| format!("{} ({})", p.title, p.published.format("%Y-%m-%d")))
+        .collect();
+    if !titles.is_empty() {
+        description.push_str(&format!("Latest: {}", titles.join("; ")));
+    }
+
+    let mut metrics = vec![Metric {
+        name: "posts_7d".into(),
+        value: recent.len() as f64,
+        unit: "posts".into(),
+    }];
+    if !authors.is_empty() {
+        metrics.push(Metric {
+            name: "authors_7d".into(),
+            value: authors.len() as f64,
+            unit: "authors".into(),
+        });
+    }
+
+    let mut signal = Signal::new(
+        SignalSource::Social,
+        format!("{}: {}", platform.label(), source.name),
+        format!(
+            "{name}: {} posts in 7 days on {}",
+            recent.len(),
+            platform.label()
+        ),
+        description.trim_end().to_string(),
+        metrics,
+        Some(source.url.clone()),
+    );
+    signal.content_timestamp = feed.posts.first().map(|p| p.published);
+    signal
+}
+
+/// Read RSS 2.0 (`<item>`, `pubDate`, `dc:creator`) or Atom (`<entry>`,
+/// `published`, `<author><name>`). `None` when the document is neither.
+/// Posts without a parseable date are skipped.
+fn parse_feed(xml: &str) -> Option<Feed> {
+    let (item_tag, head_end) = if xml.contains("<rss") {
+        ("item", xml.find("<item"))
+    } else if xml.contains("<feed") {
+        ("entry", xml.find("<entry"))
+    } else {
+        return None;
+    };
+    let head = &xml[..head_end.unwrap_or(xml.len())];
+
+    let mut posts: Vec<Post> = elements(xml, item_tag)
+        .filter_map(|item| {
+            let title = text(item, "title")?;
+            let published = if item_tag == "item" {
+                text(item, "pubDate").and_then(|d| DateTime::parse_from_rfc2822(&d).ok())
+            } else {
+                text(item, "published")
+                    .or_else(|| text(item, "updated"))
+                    .and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
+            }?;
+            let author = text(item, "dc:creator")
+                .or_else(|| {
+                    elements(item, "author")
+                        .next()
+                        .and_then(|a| text(a, "name"))
+                })
+                .or_else(|| text(item, "author"));
+            Some(Post {
+                title,
+                author,
+                published: published.with_timezone(&Utc),
+            })
+        })
+        .collect();
+    posts.sort_by_key(|p| std::cmp::Reverse(p.published));
+
+    Some(Feed {
+        title: text(head, "title"),
+        posts,
+    })
+}
+
+/// Inner markup of each `<tag ...>...</tag>` in `xml`, in order.
+fn elements<'a>(xml: &'a str, tag: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{tag}");
+    let close = format!("</{tag}>");
+    let mut rest = xml;
+    std::iter::from_fn(move || {
+        loop {
+            let start = rest.find(&open)?;
+            let after = &rest[start + open.len()..];
+            // `<item` must not match `<itemized>`.
+            if !after.starts_with(['>', ' ', '\t', '\r', '\n']) {
+                rest = after;
+                continue;
+            }
+            let body_start = after.find('>')? + 1;
+            let body = &after[body_start..];
+            let end = body.find(&close)?;
+            rest = &body[end + close.len()..];
+            return Some(&body[..end]);
+        }
+    })
+}
+
+/// Text of the first `<tag>`, with CDATA unwrapped and entities decoded.
+/// `None` when missing or blank.
+fn text(xml: &str, tag: &str) -> Option<String> {
+    let raw = elements(xml, tag).next()?.trim();
+    let raw = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|r| r.strip_suffix("]]>"))
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            raw.replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&#39;", "'")
+                .replace("&amp;", "&")
+        });
+    let text = raw.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(url: &str, source_type: &str) -> SocialSource {
+        SocialSource {
+            name: "Test".into(),
+            url: url.into(),
+            source_type: source_type.into(),
+        }
+    }
+
+    #[test]
+    fn platform_from_host_or_type() {
+        let detect = |url, kind| Platform::detect(&source(url, kind));
+        assert_eq!(
+            detect("https://thedefiant.substack.com", "blog"),
+            Some(Platform::Substack)
+        );
+        assert_eq!(
+            detect("https://mirror.xyz/dao.eth", "blog"),
+            Some(Platform::Mirror)
+        );
+        assert_eq!(
+            detect("https://newsletter.example.com", "substack"),
+            Some(Platform::Substack)
+        );
+        assert_eq!(detect("https://www.helius.dev/blog", "blog"), None);
+    }
+
+    #[test]
+    fn substack_rss_counts_posts_and_authors() {
+        let rss = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/"><channel>
+<title><![CDATA[Solana Weekly]]></title>
+<item><title><![CDATA[Firedancer & the client race]]></title><dc:creator><![CDATA[Ana Lyst]]></dc:creator>
+<pubDate>Wed, 14 Oct 2026 09:00:00 GMT</pubDate></item>
+<item><title>Restaking yields</title><dc:creator>Ana Lyst</dc:creator>
+<pubDate>Mon, 12 Oct 2026 09:00:00 GMT</pubDate></item>
+<item><title>Old news</title><dc:creator>Bo Writer</dc:creator>
+<pubDate>Tue, 01 Sep 2026 09:00:00 GMT</pubDate></item>
+<item><title>Undated</title></item>
+</channel></rss>"#;
+        let feed = parse_feed(rss).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Solana Weekly"));
+        assert_eq!(feed.posts.len(), 3);
+        assert_eq!(feed.posts[0].title, "Firedancer & the client race");
+
+        let now = "2026-10-15T00:00:00Z".parse().unwrap();
+        let signal = publication_signal(
+            Platform::Substack,
+            &source("https://solanaweekly.substack.com", "blog"),
+            &feed,
+            now,
+        );
+        assert_eq!(signal.category, "Substack: Test");
+        assert_eq!(signal.metrics[0].name, "posts_7d");
+        assert_eq!(signal.metrics[0].value, 2.0);
+        assert_eq!(signal.metrics[1].value, 1.0);
+        assert!(
+            signal.description.contains("Ana Lyst (2)"),
+            "{}",
+            signal.description
+        );
+        assert!(signal.description.contains("(2026-10-14)"));
+        assert_eq!(
+            signal.content_timestamp,
+            Some("2026-10-14T09:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn mirror_atom_entries_and_unknown_documents() {
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>dao.eth</title>
+<entry><title type="html">Governance notes</title><published>2026-10-13T12:00:00Z</published>
+<author><name>dao.eth</name></author><link href="https://mirror.xyz/dao.eth/abc"/></entry>
+</feed>"#;
+        let feed = parse_feed(atom).unwrap();
+        assert_eq!(
+            feed.posts,
+            [Post {
+                title: "Governance notes".into(),
+                author: Some("dao.eth".into()),
+                published: "2026-10-13T12:00:00Z".parse().unwrap(),
+            }]
+        );
+        assert!(parse_feed("<html><body><h2>Blog</h2></body></html>").is_none());
+    }
+}
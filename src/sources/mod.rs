@@ -1,4 +1,120 @@
 pub mod defi_llama;
+pub mod dune;
+pub mod farcaster;
 pub mod github;
+pub mod github_api;
+pub mod longform;
+pub mod nft;
+pub mod programs;
 pub mod social;
 pub mod solana_rpc;
+pub mod telegram;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::http::HttpClient;
+use crate::types::Signal;
+use futures_util::future::BoxFuture;
+
+/// A signal source. Each source's config section implements this, so adding
+/// a source means one impl plus one line in [`registry`].
+pub trait Source: Send + Sync {
+    /// Display name used in logs, e.g. "Solana RPC".
+    fn name(&self) -> &'static str;
+
+    /// Key for per-source settings such as `[http.timeouts]` and `[http.proxies]`.
+    fn key(&self) -> &'static str;
+
+    /// Whether the source is configured to run at all.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    /// Cap on signals kept from this source per run.
+    fn max_signals(&self) -> Option<usize>;
+
+    /// Env var the source reads its API key or token from, if it needs one.
+    fn key_env(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the credential named by [`Source::key_env`] is available.
+    fn key_set(&self) -> bool {
+        self.key_env()
+            .is_some_and(|env| std::env::var(env).is_ok_and(|v| !v.is_empty()))
+    }
+
+    /// How many items the source follows and what they are, e.g. `(5, "repos")`.
+    fn tracked(&self) -> (usize, &'static str);
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>>;
+}
+
+/// Every source, in collection order.
+pub fn registry(cfg: &Config) -> Vec<&dyn Source> {
+    vec![
+        &cfg.github,
+        &cfg.solana,
+        &cfg.social,
+        &cfg.defi_llama,
+        &cfg.nft,
+        &cfg.dune,
+        &cfg.farcaster,
+        &cfg.telegram,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn registry_keys_are_unique_and_opt_in_sources_start_disabled() {
+        let cfg: Config = toml::from_str("[github]\n[solana]\n[llm]\n").unwrap();
+        let registry = registry(&cfg);
+
+        let keys: HashSet<&str> = registry.iter().map(|s| s.key()).collect();
+        assert_eq!(keys.len(), registry.len());
+
+        let disabled: Vec<&str> = registry
+            .iter()
+            .filter(|s| !s.enabled())
+            .map(|s| s.name())
+            .collect();
+        assert_eq!(disabled, ["NFT", "Dune", "Farcaster", "Telegram"]);
+    }
+
+    #[test]
+    fn sources_report_tracked_items_and_key_env() {
+        let cfg: Config = toml::from_str(
+            r#"
+[github]
+token = "ghp_test"
+tracked_repos = ["a/b", "c/d"]
+flagship_repos = ["a/b"]
+
+[solana]
+tracked_programs = [{ program = "jupiter" }]
+
+[llm]
+
+[dune]
+api_key_env = "ST_NARRATIVE_TEST_UNSET_DUNE_KEY"
+"#,
+        )
+        .unwrap();
+        let registry = registry(&cfg);
+        let find = |key: &str| *registry.iter().find(|s| s.key() == key).unwrap();
+
+        let github = find("github");
+        assert_eq!(github.tracked(), (2, "repos"), "overlap counted once");
+        assert!(github.key_set(), "token from config counts");
+        assert_eq!(find("solana").tracked(), (1, "programs, mints and pools"));
+        assert_eq!(find("solana").key_env(), None);
+
+        let dune = find("dune");
+        assert_eq!(dune.key_env(), Some("ST_NARRATIVE_TEST_UNSET_DUNE_KEY"));
+        assert!(!dune.key_set());
+    }
+}
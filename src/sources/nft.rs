@@ -0,0 +1,281 @@
+use crate::config::NftConfig;
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use crate::sources::Source;
+use crate::types::{Metric, Signal, SignalSource};
+use chrono::Utc;
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const DAY_SECS: i64 = 24 * 3600;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectionStats {
+    /// Lamports.
+    floor_price: Option<u64>,
+    listed_count: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Activity {
+    #[serde(rename = "type")]
+    kind: String,
+    /// SOL.
+    #[serde(default)]
+    price: f64,
+    block_time: Option<i64>,
+}
+
+impl Source for NftConfig {
+    fn name(&self) -> &'static str {
+        "NFT"
+    }
+
+    fn key(&self) -> &'static str {
+        "nft"
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled && !self.collections.is_empty()
+    }
+
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        (self.collections.len(), "collections")
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
+pub async fn collect(config: &NftConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+    if !config.enabled {
+        return Ok(Vec::new());
+    }
+
+    let mut signals = Vec::new();
+
+    for symbol in &config.collections {
+        match collection_signal(config, http, symbol).await {
+            Ok(signal) => signals.push(signal),
+            Err(Error::Api {
+                status_code: Some(404),
+                ..
+            }) => {
+                warn!(collection = %symbol, "collection not found (delisted?), skipping");
+            }
+            Err(e) => {
+                warn!(collection = %symbol, error = %e, "failed to fetch collection stats, skipping");
+            }
+        }
+    }
+
+    info!(signal_count = signals.len(), "collected NFT market signals");
+    Ok(signals)
+}
+
+async fn collection_signal(config: &NftConfig, http: &HttpClient, symbol: &str) -> Result<Signal> {
+    let stats: CollectionStats = http
+        .get_json(&format!("{}/collections/{symbol}/stats", config.base_url))
+        .await?;
+    let activities: Vec<Activity> = http
+        .get_json(&format!(
+            "{}/collections/{symbol}/activities?offset=0&limit=500",
+            config.base_url
+        ))
+        .await?;
+
+    let window = SalesWindow::from_activities(&activities, Utc::now().timestamp());
+    let floor = stats.floor_price.unwrap_or(0) as f64 / LAMPORTS_PER_SOL;
+    let listed = stats.listed_count.unwrap_or(0);
+
+    let mut metrics = vec![
+        Metric {
+            name: "floor_price".into(),
+            value: floor,
+            unit: "SOL".into(),
+        },
+        Metric {
+            name: "volume_24h".into(),
+            value: window.volume_24h,
+            unit: "SOL".into(),
+        },
+        Metric {
+            name: "listed_count".into(),
+            value: listed as f64,
+            unit: "listings".into(),
+        },
+        Metric {
+            name: "sales_24h".into(),
+            value: window.sales_24h as f64,
+            unit: "sales".into(),
+        },
+    ];
+    if let Some(change) = window.volume_change_pct() {
+        metrics.push(Metric {
+            name: "volume_change_24h".into(),
+            value: change,
+            unit: "%".into(),
+        });
+    }
+    if let Some(change) = window.floor_change_pct() {
+        metrics.push(Metric {
+            name: "floor_change_24h".into(),
+            value: change,
+            unit: "%".into(),
+        });
+    }
+
+    let trend = match (window.volume_change_pct(), window.floor_change_pct()) {
+        (Some(v), Some(f)) if v > 0.0 && f > 0.0 => {
+            " Volume and floor both rising — accumulation pattern."
+        }
+        (Some(v), Some(f)) if v > 0.0 && f < 0.0 => {
+            " Volume rising while floor falls — distribution/selling pressure."
+        }
+        _ => "",
+    };
+
+    Ok(Signal::new(
+        SignalSource::Nft,
+        "NFT".into(),
+        format!(
+            "{symbol}: floor {floor:.2} SOL, {:.0} SOL 24h volume",
+            window.volume_24h
+        ),
+        format!(
+            "Magic Eden collection {symbol}: {listed} listed, {} sales in the last 24h ({} the day before).{trend}",
+            window.sales_24h, window.sales_prev_24h
+        ),
+        metrics,
+        Some(format!("https://magiceden.io/marketplace/{symbol}")),
+    ))
+}
+
+/// Sales split into the last 24h and the 24h before it. Magic Eden's public API
+/// has no historical floor, so the floor change compares the lowest sale price
+/// in each window.
+struct SalesWindow {
+    volume_24h: f64,
+    volume_prev_24h: f64,
+    sales_24h: usize,
+    sales_prev_24h: usize,
+    low_24h: Option<f64>,
+    low_prev_24h: Option<f64>,
+}
+
+impl SalesWindow {
+    fn from_activities(activities: &[Activity], now: i64) -> Self {
+        let mut window = Self {
+            volume_24h: 0.0,
+            volume_prev_24h: 0.0,
+            sales_24h: 0,
+            sales_prev_24h: 0,
+            low_24h: None,
+            low_prev_24h: None,
+        };
+        for a in activities.iter().filter(|a| a.kind == "buyNow") {
+            let Some(t) = a.block_time else { continue };
+            let age = now - t;
+            let (volume, sales, low) = if age < DAY_SECS {
+                (
+                    &mut window.volume_24h,
+                    &mut window.sales_24h,
+                    &mut window.low_24h,
+                )
+            } else if age < 2 * DAY_SECS {
+                (
+                    &mut window.volume_prev_24h,
+                    &mut window.sales_prev_24h,
+                    &mut window.low_prev_24h,
+                )
+            } else {
+                continue;
+            };
+            *volume += a.price;
+            *sales += 1;
+            *low = Some(low.map_or(a.price, |l: f64| l.min(a.price)));
+        }
+        window
+    }
+
+    fn volume_change_pct(&self) -> Option<f64> {
+        (self.volume_prev_24h > 0.0)
+            .then(|| (self.volume_24h - self.volume_prev_24h) / self.volume_prev_24h * 100.0)
+    }
+
+    fn floor_change_pct(&self) -> Option<f64> {
+        match (self.low_24h, self.low_prev_24h) {
+            (Some(now), Some(prev)) if prev > 0.0 => Some((now - prev) / prev * 100.0),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_800_000_000;
+
+    fn sale(price: f64, age: i64) -> Activity {
+        Activity {
+            kind: "buyNow".into(),
+            price,
+            block_time: Some(NOW - age),
+        }
+    }
+
+    #[test]
+    fn window_boundaries_are_half_open() {
+        let activities = vec![
+            sale(1.0, 0),
+            sale(2.0, DAY_SECS - 1),
+            // Exactly one day old falls into the previous window.
+            sale(4.0, DAY_SECS),
+            sale(8.0, 2 * DAY_SECS - 1),
+            // Exactly two days old is dropped.
+            sale(16.0, 2 * DAY_SECS),
+            Activity {
+                kind: "list".into(),
+                price: 32.0,
+                block_time: Some(NOW),
+            },
+            Activity {
+                kind: "buyNow".into(),
+                price: 64.0,
+                block_time: None,
+            },
+        ];
+
+        let window = SalesWindow::from_activities(&activities, NOW);
+        assert_eq!(window.sales_24h, 2);
+        assert_eq!(window.volume_24h, 3.0);
+        assert_eq!(window.low_24h, Some(1.0));
+        assert_eq!(window.sales_prev_24h, 2);
+        assert_eq!(window.volume_prev_24h, 12.0);
+        assert_eq!(window.low_prev_24h, Some(4.0));
+        assert_eq!(window.volume_change_pct(), Some(-75.0));
+        assert_eq!(window.floor_change_pct(), Some(-75.0));
+    }
+
+    #[test]
+    fn changes_need_a_previous_window() {
+        let window = SalesWindow::from_activities(&[sale(5.0, 60)], NOW);
+        assert_eq!(window.sales_24h, 1);
+        assert_eq!(window.volume_change_pct(), None);
+        assert_eq!(window.floor_change_pct(), None);
+
+        let window = SalesWindow::from_activities(&[], NOW);
+        assert_eq!(window.volume_24h, 0.0);
+        assert_eq!(window.low_24h, None);
+    }
+}
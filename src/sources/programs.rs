@@ -0,0 +1,133 @@
+use crate::config::TrackedProgram;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// One well-known program from `data/programs.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KnownProgram {
+    pub name: String,
+    pub address: String,
+    pub category: String,
+}
+
+/// The bundled registry, keyed by short name such as "jupiter".
+pub fn registry() -> &'static BTreeMap<String, KnownProgram> {
+    static REGISTRY: OnceLock<BTreeMap<String, KnownProgram>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        toml::from_str(include_str!("../../data/programs.toml"))
+            .expect("data/programs.toml is a valid program registry")
+    })
+}
+
+pub fn by_address(address: &str) -> Option<&'static KnownProgram> {
+    registry().values().find(|p| p.address == address)
+}
+
+/// A `[[solana.tracked_programs]]` entry as written: a registry key or an
+/// address, with name and category optional when the registry knows them.
+#[derive(Debug, Deserialize)]
+pub struct ProgramEntry {
+    /// Registry key, e.g. "jupiter", instead of an address.
+    program: Option<String>,
+    address: Option<String>,
+    name: Option<String>,
+    category: Option<String>,
+    #[serde(default)]
+    count_accounts: bool,
+}
+
+impl TryFrom<ProgramEntry> for TrackedProgram {
+    type Error = String;
+
+    /// Explicit `name` and `category` win over the registry's.
+    fn try_from(entry: ProgramEntry) -> Result<Self, String> {
+        let (address, known) = match (entry.program, entry.address) {
+            (Some(_), Some(_)) => {
+                return Err("set either `program` or `address`, not both".into());
+            }
+            (Some(key), None) => {
+                let known = registry().get(&key).ok_or_else(|| {
+                    let keys: Vec<&str> = registry().keys().map(String::as_str).collect();
+                    format!("unknown program '{key}'; known: {}", keys.join(", "))
+                })?;
+                (known.address.clone(), Some(known))
+            }
+            (None, Some(address)) => {
+                let known = by_address(&address);
+                (address, known)
+            }
+            (None, None) => return Err("tracked program needs `program` or `address`".into()),
+        };
+        let missing =
+            |field: &str| format!("program {address} is not in the registry; set its `{field}`");
+        let name = match entry.name.or_else(|| known.map(|k| k.name.clone())) {
+            Some(name) => name,
+            None => return Err(missing("name")),
+        };
+        let category = match entry.category.or_else(|| known.map(|k| k.category.clone())) {
+            Some(category) => category,
+            None => return Err(missing("category")),
+        };
+        Ok(TrackedProgram {
+            name,
+            address,
+            category,
+            count_accounts: entry.count_accounts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(entry: &str) -> Result<TrackedProgram, String> {
+        let entry: ProgramEntry = toml::from_str(entry).map_err(|e| e.to_string())?;
+        TrackedProgram::try_from(entry)
+    }
+
+    #[test]
+    fn bundled_registry_is_valid_and_unique() {
+        let registry = registry();
+        assert!(registry.contains_key("jupiter"));
+        let mut addresses: Vec<&str> = registry.values().map(|p| p.address.as_str()).collect();
+        addresses.sort();
+        addresses.dedup();
+        assert_eq!(addresses.len(), registry.len(), "addresses are unique");
+    }
+
+    #[test]
+    fn entries_resolve_by_key_or_address_with_overrides() {
+        let by_key = parse(r#"program = "orca""#).unwrap();
+        assert_eq!(by_key.name, "Orca Whirlpools");
+        assert_eq!(
+            by_key.address,
+            "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"
+        );
+
+        let by_address = parse(
+            r#"address = "MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD"
+category = "LSTs""#,
+        )
+        .unwrap();
+        assert_eq!(by_address.name, "Marinade Finance");
+        assert_eq!(by_address.category, "LSTs", "explicit config wins");
+
+        let unknown = parse(r#"address = "11111111111111111111111111111111""#).unwrap_err();
+        assert!(unknown.contains("set its `name`"), "{unknown}");
+        assert!(
+            parse(r#"program = "jupitr""#)
+                .unwrap_err()
+                .contains("known: ")
+        );
+        assert!(
+            parse(
+                r#"program = "jupiter"
+address = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4""#
+            )
+            .is_err()
+        );
+    }
+}
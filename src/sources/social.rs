@@ -1,28 +1,129 @@
-use crate::config::SocialConfig;
+use crate::analysis::sentiment;
+use crate::config::{SentimentMode, SocialConfig, SocialSource};
 use crate::error::Result;
 use crate::http::HttpClient;
+use crate::language;
+use crate::links;
+use crate::sources::{Source, longform};
 use crate::types::{Metric, Signal, SignalSource};
-use chrono::Utc;
+use crate::util;
+use futures_util::future::BoxFuture;
+use futures_util::stream::{self, StreamExt};
 use scraper::{Html, Selector};
+use std::time::Duration;
+use tokio::time::sleep;
 use tracing::{info, warn};
 
+impl Source for SocialConfig {
+    fn name(&self) -> &'static str {
+        "Social"
+    }
+
+    fn key(&self) -> &'static str {
+        "social"
+    }
+
+    fn enabled(&self) -> bool {
+        !self.sources.is_empty()
+    }
+
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        (self.sources.len(), "blogs and publications")
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
 pub async fn collect(config: &SocialConfig, http: &HttpClient) -> Result<Vec<Signal>> {
-    let mut signals = Vec::new();
+    let scrapes: Vec<_> = config
+        .sources
+        .iter()
+        .map(|source| scrape_or_skip(http, config, source))
+        .collect();
+    let mut scraped: Vec<(&str, Vec<Signal>)> = stream::iter(scrapes)
+        .buffer_unordered(config.max_concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
 
-    for source in &config.sources {
-        match scrape_source(http, &source.name, &source.url).await {
-            Ok(mut source_signals) => signals.append(&mut source_signals),
-            Err(e) => {
-                warn!(source = %source.name, url = %source.url, error = %e, "failed to scrape, skipping");
+    // Completion order varies run to run; keep reports stable.
+    scraped.sort_by(|a, b| a.0.cmp(b.0));
+    let signals: Vec<Signal> = scraped.into_iter().flat_map(|(_, s)| s).collect();
+
+    info!(signal_count = signals.len(), "collected social signals");
+    Ok(signals)
+}
+
+/// Pause before retrying a blog, plus up to as much again in jitter so
+/// sources that failed together don't retry in lockstep.
+const RETRY_PAUSE: Duration = Duration::from_millis(500);
+
+/// One blog's signals, tagged with its name. Substack and Mirror
+/// publications are read from their feeds, falling back to page scraping when
+/// the feed isn't recognized. A 5xx is retried up to `scrape_retries` times;
+/// timeouts and refused connections aren't, since the HTTP client already
+/// retried them. What still fails is logged and skipped so one broken blog
+/// doesn't sink the rest.
+async fn scrape_or_skip<'a>(
+    http: &HttpClient,
+    config: &SocialConfig,
+    source: &'a SocialSource,
+) -> Option<(&'a str, Vec<Signal>)> {
+    let mut retries = 0;
+    let result = loop {
+        match scrape(http, config, source).await {
+            Err(e) if e.is_server_error() && retries < config.scrape_retries => {
+                if let Err(spent) = http.spend_retry() {
+                    break Err(spent);
+                }
+                retries += 1;
+                warn!(source = %source.name, error = %e, retry = retries, "blog server error, retrying");
+                sleep(RETRY_PAUSE + util::jitter(RETRY_PAUSE)).await;
             }
+            result => break result,
+        }
+    };
+    match result {
+        Ok(signals) => Some((source.name.as_str(), signals)),
+        Err(e) => {
+            warn!(source = %source.name, url = %source.url, error = %e, "failed to scrape, skipping");
+            None
         }
     }
+}
 
-    info!(signal_count = signals.len(), "collected social signals");
-    Ok(signals)
+async fn scrape(
+    http: &HttpClient,
+    config: &SocialConfig,
+    source: &SocialSource,
+) -> Result<Vec<Signal>> {
+    let sentiment = config.sentiment != SentimentMode::Off;
+    match longform::Platform::detect(source) {
+        Some(platform) => match longform::collect(http, platform, source, sentiment).await {
+            Ok(signal) => Ok(vec![signal]),
+            Err(e) => {
+                warn!(source = %source.name, error = %e, "long-form feed not recognized, scraping the page instead");
+                scrape_source(http, config, &source.name, &source.url).await
+            }
+        },
+        None => scrape_source(http, config, &source.name, &source.url).await,
+    }
 }
 
-async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<Signal>> {
+async fn scrape_source(
+    http: &HttpClient,
+    config: &SocialConfig,
+    name: &str,
+    url: &str,
+) -> Result<Vec<Signal>> {
     let html_text = http.get_text(url).await?;
     let document = Html::parse_document(&html_text);
 
@@ -43,7 +144,14 @@ async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<S
         if let Ok(selector) = Selector::parse(sel_str) {
             for element in document.select(&selector) {
                 let title = element.text().collect::<String>().trim().to_string();
-                let href = element.value().attr("href").unwrap_or("").to_string();
+                // Anchors and script links are navigation, not articles
+                let Some(href) = element
+                    .value()
+                    .attr("href")
+                    .and_then(|href| links::resolve(url, href))
+                else {
+                    continue;
+                };
 
                 if !title.is_empty() && title.len() > 5 {
                     articles.push((title, href));
@@ -55,52 +163,81 @@ async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<S
         }
     }
 
-    // Deduplicate by title
+    Ok(blog_signal(config, name, url, articles)
+        .into_iter()
+        .collect())
+}
+
+/// The summary signal for one blog's scraped `(title, link)` pairs, or
+/// `None` when nothing is left after dropping duplicates and spam. Each
+/// title is tagged with its language and matched on the keywords for it.
+fn blog_signal(
+    config: &SocialConfig,
+    name: &str,
+    url: &str,
+    mut articles: Vec<(String, String)>,
+) -> Option<Signal> {
+    // Deduplicate by title, dropping spam outright
     articles.sort_by(|a, b| a.0.cmp(&b.0));
     articles.dedup_by(|a, b| a.0 == b.0);
+    articles.retain(|(title, _)| !matches_any(title, &config.exclude_keywords));
+    if articles.is_empty() {
+        return None;
+    }
 
+    let languages: Vec<Option<&str>> = articles
+        .iter()
+        .map(|(title, _)| language::detect(title))
+        .collect();
     let solana_articles: Vec<&(String, String)> = articles
         .iter()
-        .filter(|(title, _)| {
-            let lower = title.to_lowercase();
-            lower.contains("solana")
-                || lower.contains("sol")
-                || lower.contains("defi")
-                || lower.contains("depin")
-                || lower.contains("token")
-                || lower.contains("validator")
-                || lower.contains("staking")
-                || lower.contains("nft")
-                || lower.contains("web3")
-                || lower.contains("blockchain")
-                || lower.contains("crypto")
+        .zip(&languages)
+        .filter(|((title, _), lang)| {
+            matches_any(title, &config.relevance_keywords)
+                || lang
+                    .and_then(|l| config.language_keywords.get(l))
+                    .is_some_and(|keywords| matches_any(title, keywords))
         })
+        .map(|(article, _)| article)
         .collect();
 
-    if solana_articles.is_empty() && articles.is_empty() {
-        return Ok(Vec::new());
+    let listed = if solana_articles.is_empty() {
+        articles.len()
+    } else {
+        solana_articles.len()
+    };
+    if listed > config.max_titles {
+        info!(
+            source = %name,
+            articles = listed,
+            kept = config.max_titles,
+            "article titles truncated by max_titles"
+        );
     }
-
     let titles: Vec<String> = if solana_articles.is_empty() {
-        articles.iter().take(10).map(|(t, _)| t.clone()).collect()
+        articles
+            .iter()
+            .take(config.max_titles)
+            .map(|(t, _)| t.clone())
+            .collect()
     } else {
         solana_articles
             .iter()
-            .take(10)
+            .take(config.max_titles)
             .map(|(t, _)| t.clone())
             .collect()
     };
 
-    Ok(vec![Signal {
-        source: SignalSource::Social,
-        category: format!("Blog: {name}"),
-        title: format!(
+    let mut signal = Signal::new(
+        SignalSource::Social,
+        format!("Blog: {name}"),
+        format!(
             "{name}: {} recent articles ({} Solana-related)",
             articles.len(),
             solana_articles.len()
         ),
-        description: format!("Recent topics: {}", titles.join("; ")),
-        metrics: vec![
+        format!("Recent topics: {}", titles.join("; ")),
+        vec![
             Metric {
                 name: "total_articles".into(),
                 value: articles.len() as f64,
@@ -112,7 +249,247 @@ async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<S
                 unit: "articles".into(),
             },
         ],
-        url: Some(url.to_string()),
-        timestamp: Utc::now(),
-    }])
+        Some(url.to_string()),
+    );
+    add_languages(&mut signal, config, &languages);
+    if config.sentiment != SentimentMode::Off {
+        // Every listed article is scored, not only the titles shown.
+        let scored: Vec<&str> = if solana_articles.is_empty() {
+            articles.iter().map(|(t, _)| t.as_str()).collect()
+        } else {
+            solana_articles.iter().map(|(t, _)| t.as_str()).collect()
+        };
+        sentiment::add_keyword_score(&mut signal, &scored);
+    }
+    Some(signal)
+}
+
+/// Tag a blog signal with its articles' languages, most common first, and
+/// note the mix when it isn't all English. Articles in a language with no
+/// keyword list stay counted but are flagged, since only the base keywords
+/// could match them.
+fn add_languages(signal: &mut Signal, config: &SocialConfig, languages: &[Option<&str>]) {
+    let mut counts: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+    for lang in languages.iter().flatten() {
+        *counts.entry(lang).or_default() += 1;
+    }
+    let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+    ranked.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    signal.languages = ranked.iter().map(|(lang, _)| lang.to_string()).collect();
+    if ranked
+        .iter()
+        .all(|(lang, _)| *lang == language::BASE_LANGUAGE)
+    {
+        return;
+    }
+
+    let mix: Vec<String> = ranked
+        .iter()
+        .map(|(lang, count)| format!("{lang} {count}"))
+        .collect();
+    signal
+        .description
+        .push_str(&format!(" Languages: {}.", mix.join(", ")));
+
+    let unsupported: Vec<(&str, usize)> = ranked
+        .into_iter()
+        .filter(|(lang, _)| {
+            *lang != language::BASE_LANGUAGE && !config.language_keywords.contains_key(*lang)
+        })
+        .collect();
+    let flagged: usize = unsupported.iter().map(|(_, count)| count).sum();
+    if flagged == 0 {
+        return;
+    }
+    let codes: Vec<&str> = unsupported.iter().map(|(lang, _)| *lang).collect();
+    info!(
+        source = %signal.category,
+        articles = flagged,
+        languages = %codes.join(", "),
+        "articles in languages without relevance keywords"
+    );
+    signal.description.push_str(&format!(
+        " {flagged} articles in languages without keywords ({}) are counted but matched on the base keywords only.",
+        codes.join(", ")
+    ));
+    signal.metrics.push(Metric {
+        name: "unsupported_language_articles".into(),
+        value: flagged as f64,
+        unit: "articles".into(),
+    });
+}
+
+/// Case-insensitive substring match against any keyword.
+fn matches_any(title: &str, keywords: &[String]) -> bool {
+    let lower = title.to_lowercase();
+    keywords
+        .iter()
+        .any(|k| !k.is_empty() && lower.contains(&k.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_relevance_keywords;
+    use crate::test_support::{self, Reply};
+
+    #[test]
+    fn custom_keyword_surfaces_missed_article() {
+        let title = "Perps open interest hits record on Drift";
+        let defaults = default_relevance_keywords();
+        assert!(!matches_any(title, &defaults));
+
+        let mut custom = defaults;
+        custom.push("Perps".into());
+        assert!(matches_any(title, &custom));
+    }
+
+    #[test]
+    fn exclude_keywords_match_case_insensitively() {
+        let exclude = vec!["airdrop giveaway".to_string()];
+        assert!(matches_any(
+            "HUGE Airdrop Giveaway for SOL holders",
+            &exclude
+        ));
+        assert!(!matches_any("Airdrop mechanics explained", &exclude));
+    }
+
+    #[test]
+    fn articles_are_matched_and_tagged_by_language() {
+        let articles = [
+            "Firedancer is coming to mainnet",
+            "Solana validators upgrade to v2",
+            "索拉纳质押收益的变化",
+            "솔라나 스테이킹 가이드",
+            "Обзор рынка за неделю",
+        ]
+        .iter()
+        .map(|title| {
+            (
+                title.to_string(),
+                format!("https://blog.example/{}", title.len()),
+            )
+        })
+        .collect();
+        let config = SocialConfig {
+            sentiment: SentimentMode::Off,
+            ..SocialConfig::default()
+        };
+        let signal = blog_signal(&config, "Mixed", "https://blog.example", articles).unwrap();
+
+        let metric = |name: &str| {
+            signal
+                .metrics
+                .iter()
+                .find(|m| m.name == name)
+                .map(|m| m.value)
+        };
+        assert_eq!(metric("total_articles"), Some(5.0));
+        // The Chinese and Korean titles match their own keyword lists.
+        assert_eq!(metric("solana_relevant"), Some(3.0));
+        assert_eq!(metric("unsupported_language_articles"), Some(1.0));
+        assert_eq!(signal.languages, ["en", "ko", "ru", "zh"]);
+        assert!(
+            signal
+                .description
+                .contains("Languages: en 2, ko 1, ru 1, zh 1."),
+            "{}",
+            signal.description
+        );
+        assert!(signal.description.contains("(ru)"));
+
+        let english = vec![(
+            "Solana validators upgrade to v2".to_string(),
+            "https://blog.example/a".to_string(),
+        )];
+        let signal = blog_signal(&config, "English", "https://blog.example", english).unwrap();
+        assert_eq!(signal.languages, ["en"]);
+        assert!(!signal.description.contains("Languages:"));
+        assert!(
+            signal
+                .metrics
+                .iter()
+                .all(|m| m.name != "unsupported_language_articles")
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_scrape_is_sorted_and_skips_failures() {
+        let addr = test_support::mock_http(|request| {
+            if request.path.starts_with("/missing") {
+                Reply::status(404)
+            } else {
+                Reply::ok(
+                    "<article><h2><a href=\"/post\">Solana validators upgrade</a></h2></article>",
+                )
+            }
+        })
+        .await;
+
+        let source = |name: &str, path: &str| SocialSource {
+            name: name.into(),
+            url: format!("http://{addr}{path}"),
+            source_type: "blog".into(),
+        };
+        let config = SocialConfig {
+            sources: vec![
+                source("Zeta Blog", "/zeta"),
+                source("Broken Blog", "/missing"),
+                source("Alpha Blog", "/alpha"),
+            ],
+            max_concurrency: 2,
+            ..SocialConfig::default()
+        };
+        let http = HttpClient::new(&Default::default()).unwrap();
+
+        let signals = collect(&config, &http).await.unwrap();
+        let categories: Vec<&str> = signals.iter().map(|s| s.category.as_str()).collect();
+        assert_eq!(categories, ["Blog: Alpha Blog", "Blog: Zeta Blog"]);
+    }
+
+    #[tokio::test]
+    async fn server_error_is_retried_once_and_404_is_not() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let flaky_hits = Arc::new(AtomicUsize::new(0));
+        let missing_hits = Arc::new(AtomicUsize::new(0));
+        let (flaky, missing) = (flaky_hits.clone(), missing_hits.clone());
+        let addr = test_support::mock_http(move |request| {
+            if request.path.starts_with("/missing") {
+                missing.fetch_add(1, Ordering::SeqCst);
+                Reply::status(404)
+            } else if flaky.fetch_add(1, Ordering::SeqCst) == 0 {
+                Reply::status(503)
+            } else {
+                Reply::ok(
+                    "<article><h2><a href=\"/post\">Solana validators upgrade</a></h2></article>",
+                )
+            }
+        })
+        .await;
+
+        let source = |name: &str, path: &str| SocialSource {
+            name: name.into(),
+            url: format!("http://{addr}{path}"),
+            source_type: "blog".into(),
+        };
+        let mut config = SocialConfig {
+            sources: vec![
+                source("Shaky Blog", "/shaky"),
+                source("Gone Blog", "/missing"),
+            ],
+            ..SocialConfig::default()
+        };
+        let http = HttpClient::new(&Default::default()).unwrap();
+
+        let signals = collect(&config, &http).await.unwrap();
+        assert_eq!(signals.len(), 1, "recovered after one 503");
+        assert_eq!(flaky_hits.load(Ordering::SeqCst), 2);
+        assert_eq!(missing_hits.load(Ordering::SeqCst), 1, "404 is not retried");
+
+        flaky_hits.store(0, Ordering::SeqCst);
+        config.scrape_retries = 0;
+        assert!(collect(&config, &http).await.unwrap().is_empty());
+        assert_eq!(flaky_hits.load(Ordering::SeqCst), 1);
+    }
 }
@@ -1,10 +1,51 @@
-use crate::config::SolanaConfig;
+use crate::config::{
+    AmmLayout, Commitment, SolanaConfig, TrackedMint, TrackedPool, TrackedProgram,
+};
 use crate::error::{Error, Result};
+use crate::history::{History, metric_key};
 use crate::http::HttpClient;
+use crate::sources::Source;
 use crate::types::{Metric, Signal, SignalSource};
+use crate::util::{metric_value, safe_div};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use chrono::Utc;
+use futures_util::future::BoxFuture;
+use futures_util::future::join_all;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
+
+/// Window `transfer_volume_24h` and `unique_senders` cover.
+const TRANSFER_WINDOW_SECS: i64 = 24 * 3600;
+
+/// SPL Token-2022, the token program with mint and account extensions.
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VveNdGXD9s5tEVuEVXxU6mkeGGf1sz";
+
+/// Size of a Token-2022 mint with no extensions, same as an SPL Token mint.
+const MINT_LEN: u64 = 82;
+
+/// Offset of the account-type byte in an extended Token-2022 account; `1`
+/// marks a mint. Matched as base58, in which byte 0x01 is "2".
+const ACCOUNT_TYPE_OFFSET: u64 = 165;
+
+/// USDC and USDT: a pool holding either is valued in US dollars.
+const USD_STABLECOINS: [&str; 2] = [
+    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+];
+
+/// Wrapped SOL: a pool holding it and no stablecoin is valued in SOL.
+const WRAPPED_SOL: &str = "So11111111111111111111111111111111111111112";
+
+/// Accounts per `getMultipleAccounts` call, the RPC maximum.
+const MULTIPLE_ACCOUNTS_MAX: usize = 100;
+
+/// Methods whose params have no config object to carry a commitment.
+const NO_COMMITMENT: [&str; 1] = ["getRecentPerformanceSamples"];
+
+/// Methods that reject `processed`; they are sent `confirmed` instead.
+const CONFIRMED_AT_LEAST: [&str; 2] = ["getSignaturesForAddress", "getTransaction"];
 
 #[derive(Serialize)]
 struct RpcRequest<'a> {
@@ -64,76 +105,154 @@ struct SupplyValue {
     non_circulating: u64,
 }
 
-pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Signal>> {
-    let mut signals = Vec::new();
+impl Source for SolanaConfig {
+    fn name(&self) -> &'static str {
+        "Solana RPC"
+    }
+
+    fn key(&self) -> &'static str {
+        "solana"
+    }
 
-    // Get recent performance samples (TPS data)
-    let perf_samples = rpc_call::<Vec<PerformanceSample>>(
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        (
+            self.tracked_programs.len() + self.tracked_mints.len() + self.tracked_pools.len(),
+            "programs, mints and pools",
+        )
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
+pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+    let rpc = RpcClient::new(
         &config.rpc_url,
         http,
-        "getRecentPerformanceSamples",
-        serde_json::json!([10]),
-    )
-    .await?;
+        config.max_concurrency,
+        config.commitment,
+    );
+    let consistency = format!(" Read at {} commitment.", config.commitment);
+    let mut signals = Vec::new();
 
-    if !perf_samples.is_empty() {
-        let avg_tps: f64 = perf_samples
-            .iter()
-            .map(|s| s.num_transactions as f64 / s.sample_period_secs as f64)
-            .sum::<f64>()
-            / perf_samples.len() as f64;
+    // Fire the network-level calls and every program's activity query at once;
+    // the semaphore in `RpcClient` bounds how many are actually in flight.
+    let now = Utc::now().timestamp();
+    let (perf_samples, epoch, supply, activities, account_counts, volumes, token2022, pools) = tokio::join!(
+        rpc.call::<Vec<PerformanceSample>>("getRecentPerformanceSamples", serde_json::json!([10])),
+        rpc.call::<EpochInfo>("getEpochInfo", serde_json::json!([])),
+        rpc.call::<Supply>("getSupply", serde_json::json!([])),
+        join_all(
+            config
+                .tracked_programs
+                .iter()
+                .map(|program| get_program_activity(
+                    &rpc,
+                    &program.address,
+                    now,
+                    config.activity_window_hours,
+                    config.max_signature_pages,
+                )),
+        ),
+        join_all(config.tracked_programs.iter().map(|program| async {
+            if program.count_accounts {
+                Some(get_account_count(&rpc, &program.address).await)
+            } else {
+                None
+            }
+        })),
+        join_all(
+            config
+                .tracked_mints
+                .iter()
+                .map(|mint| get_transfer_volume(&rpc, mint, now)),
+        ),
+        async {
+            if config.track_token2022 {
+                Some(get_token2022_stats(&rpc, config.token2022_sample).await)
+            } else {
+                None
+            }
+        },
+        async {
+            if config.tracked_pools.is_empty() {
+                Ok(Vec::new())
+            } else {
+                get_pool_tvls(&rpc, &config.tracked_pools).await
+            }
+        },
+    );
+    let perf_samples = perf_samples?;
+    let epoch = epoch?;
+    let supply = supply?;
 
-        let non_vote_tps_values: Vec<f64> = perf_samples
-            .iter()
-            .filter_map(|s| {
-                s.num_non_vote_transactions
-                    .map(|nv| nv as f64 / s.sample_period_secs as f64)
-            })
-            .collect();
-        let avg_non_vote_tps: f64 = if non_vote_tps_values.is_empty() {
-            0.0
-        } else {
-            non_vote_tps_values.iter().sum::<f64>() / non_vote_tps_values.len() as f64
+    if let Some(tps) = TpsStats::from_samples(&perf_samples) {
+        let trend = match tps.trend_pct {
+            Some(pct) => format!(" Recent half of the window vs older half: {pct:+.1}%."),
+            None => String::new(),
         };
-
-        signals.push(Signal {
-            source: SignalSource::SolanaOnchain,
-            category: "Network Performance".into(),
-            title: format!("Solana TPS: {avg_tps:.0} total, {avg_non_vote_tps:.0} non-vote"),
-            description: format!(
-                "Average over {} recent samples. Non-vote TPS indicates real user activity vs consensus overhead.",
-                perf_samples.len()
+        let mut metrics = vec![
+            Metric {
+                name: "avg_tps".into(),
+                value: tps.avg_tps,
+                unit: "tx/s".into(),
+            },
+            Metric {
+                name: "avg_non_vote_tps".into(),
+                value: tps.avg_non_vote_tps,
+                unit: "tx/s".into(),
+            },
+        ];
+        if let Some(pct) = tps.trend_pct {
+            metrics.push(Metric {
+                name: "tps_trend_pct".into(),
+                value: pct,
+                unit: "%".into(),
+            });
+        }
+        signals.push(Signal::new(
+            SignalSource::SolanaOnchain,
+            "Network Performance".into(),
+            format!(
+                "Solana TPS: {:.0} total, {:.0} non-vote",
+                tps.avg_tps, tps.avg_non_vote_tps
             ),
-            metrics: vec![
-                Metric { name: "avg_tps".into(), value: avg_tps, unit: "tx/s".into() },
-                Metric { name: "avg_non_vote_tps".into(), value: avg_non_vote_tps, unit: "tx/s".into() },
-            ],
-            url: Some("https://explorer.solana.com/".into()),
-            timestamp: Utc::now(),
-        });
+            format!(
+                "Average over {} recent samples. Non-vote TPS indicates real user activity vs consensus overhead.{trend}",
+                tps.samples
+            ),
+            metrics,
+            Some("https://explorer.solana.com/".into()),
+        ));
     }
 
-    // Get epoch info
-    let epoch: EpochInfo =
-        rpc_call(&config.rpc_url, http, "getEpochInfo", serde_json::json!([])).await?;
-
-    let epoch_progress = epoch.slot_index as f64 / epoch.slots_in_epoch as f64 * 100.0;
+    // Epoch info
+    let epoch_progress = safe_div(
+        epoch.slot_index as f64,
+        epoch.slots_in_epoch as f64,
+        "epoch_progress",
+    ) * 100.0;
 
-    signals.push(Signal {
-        source: SignalSource::SolanaOnchain,
-        category: "Network State".into(),
-        title: format!("Epoch {} — {epoch_progress:.1}% complete", epoch.epoch),
-        description: format!(
-            "Slot {}/{}, absolute slot {}. {}",
+    signals.push(Signal::new(
+        SignalSource::SolanaOnchain,
+        "Network State".into(),
+        format!("Epoch {} — {epoch_progress:.1}% complete", epoch.epoch),
+        format!(
+            "Slot {}/{}, absolute slot {}.{}{consistency}",
             epoch.slot_index,
             epoch.slots_in_epoch,
             epoch.absolute_slot,
             epoch
                 .transaction_count
-                .map(|tc| format!("Total transactions: {tc}"))
+                .map(|tc| format!(" Total transactions: {tc}."))
                 .unwrap_or_default()
         ),
-        metrics: vec![
+        vec![
             Metric {
                 name: "epoch".into(),
                 value: epoch.epoch as f64,
@@ -150,30 +269,30 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
                 unit: "slot".into(),
             },
         ],
-        url: Some("https://explorer.solana.com/".into()),
-        timestamp: Utc::now(),
-    });
-
-    // Get SOL supply
-    let supply: Supply =
-        rpc_call(&config.rpc_url, http, "getSupply", serde_json::json!([])).await?;
+        Some("https://explorer.solana.com/".into()),
+    ));
 
-    let circulating_pct = supply.value.circulating as f64 / supply.value.total as f64 * 100.0;
+    // SOL supply
+    let circulating_pct = safe_div(
+        supply.value.circulating as f64,
+        supply.value.total as f64,
+        "circulating_pct",
+    ) * 100.0;
 
-    signals.push(Signal {
-        source: SignalSource::SolanaOnchain,
-        category: "Token Economics".into(),
-        title: format!(
+    signals.push(Signal::new(
+        SignalSource::SolanaOnchain,
+        "Token Economics".into(),
+        format!(
             "SOL Supply: {:.1}M circulating ({circulating_pct:.1}%)",
             supply.value.circulating as f64 / 1_000_000_000.0 / 1_000_000.0,
         ),
-        description: format!(
-            "Total: {:.1}M SOL, Circulating: {:.1}M SOL, Non-circulating: {:.1}M SOL",
+        format!(
+            "Total: {:.1}M SOL, Circulating: {:.1}M SOL, Non-circulating: {:.1}M SOL.{consistency}",
             supply.value.total as f64 / 1e15,
             supply.value.circulating as f64 / 1e15,
             supply.value.non_circulating as f64 / 1e15,
         ),
-        metrics: vec![
+        vec![
             Metric {
                 name: "circulating_sol".into(),
                 value: supply.value.circulating as f64 / 1e9,
@@ -185,52 +304,21 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
                 unit: "%".into(),
             },
         ],
-        url: None,
-        timestamp: Utc::now(),
-    });
+        None,
+    ));
 
-    // Get signature counts for tracked programs (paginated for real counts)
-    for program in &config.tracked_programs {
-        match get_program_activity(&config.rpc_url, http, &program.address).await {
+    // Signature counts for tracked programs (paginated for real counts)
+    for ((program, activity), account_count) in config
+        .tracked_programs
+        .iter()
+        .zip(activities)
+        .zip(account_counts)
+    {
+        match activity {
             Ok(activity) => {
-                let title = if activity.tx_per_hour > 0.0 {
-                    let time_str = if activity.time_span_hours < 1.0 {
-                        format!("{:.0}m", activity.time_span_hours * 60.0)
-                    } else {
-                        format!("{:.1}h", activity.time_span_hours)
-                    };
-                    format!(
-                        "{}: {:.0} tx/hr ({} txs over {})",
-                        program.name, activity.tx_per_hour, activity.tx_count, time_str
-                    )
-                } else {
-                    format!(
-                        "{}: {} recent transactions",
-                        program.name, activity.tx_count
-                    )
-                };
-                let description = format!(
-                    "Program {} ({}) — {} transactions sampled. {}",
-                    program.name,
-                    program.address,
-                    activity.tx_count,
-                    if activity.tx_per_hour > 0.0 {
-                        if activity.time_span_hours < 1.0 {
-                            format!(
-                                "Rate: {:.0} tx/hr over {:.0} minutes.",
-                                activity.tx_per_hour,
-                                activity.time_span_hours * 60.0
-                            )
-                        } else {
-                            format!(
-                                "Rate: {:.0} tx/hr over {:.1} hours.",
-                                activity.tx_per_hour, activity.time_span_hours
-                            )
-                        }
-                    } else {
-                        "Insufficient data for rate calculation.".into()
-                    }
-                );
+                let (title, description) =
+                    program_activity_text(program, &activity, config.activity_window_hours);
+                let description = format!("{description}{consistency}");
                 let mut metrics = vec![Metric {
                     name: "recent_tx_count".into(),
                     value: activity.tx_count as f64,
@@ -256,25 +344,86 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
                         });
                     }
                 }
-                signals.push(Signal {
-                    source: SignalSource::SolanaOnchain,
-                    category: program.category.clone(),
+                match account_count {
+                    Some(Ok(count)) => metrics.push(Metric {
+                        name: "account_count".into(),
+                        value: count as f64,
+                        unit: "accounts".into(),
+                    }),
+                    Some(Err(e)) if is_method_unavailable(&e) => {
+                        info!(program = %program.name, "getProgramAccounts unavailable on this RPC node, skipping account count");
+                    }
+                    Some(Err(e)) => {
+                        warn!(program = %program.name, error = %e, "failed to count program accounts");
+                    }
+                    None => {}
+                }
+                signals.push(Signal::new(
+                    SignalSource::SolanaOnchain,
+                    program.category.clone(),
                     title,
                     description,
                     metrics,
-                    url: Some(format!(
+                    Some(format!(
                         "https://explorer.solana.com/address/{}",
                         program.address
                     )),
-                    timestamp: Utc::now(),
-                });
+                ));
+            }
+            Err(e) => {
+                warn!(program = %program.name, error = %e, "failed to get program activity");
+            }
+        }
+    }
+
+    for (mint, volume) in config.tracked_mints.iter().zip(volumes) {
+        match volume {
+            Ok(volume) => {
+                let mut signal = mint_signal(mint, &volume);
+                signal.description.push_str(&consistency);
+                signals.push(signal);
             }
             Err(e) => {
-                tracing::warn!(program = %program.name, error = %e, "failed to get program activity");
+                warn!(mint = %mint.name, error = %e, "failed to measure token transfer volume, skipping");
             }
         }
     }
 
+    match pools {
+        Ok(pools) => {
+            for (pool, tvl) in config.tracked_pools.iter().zip(pools) {
+                match tvl {
+                    Ok(tvl) => {
+                        let mut signal = pool_signal(pool, &tvl);
+                        signal.description.push_str(&consistency);
+                        signals.push(signal);
+                    }
+                    Err(e) => {
+                        warn!(pool = %pool.name, error = %e, "failed to read pool TVL, skipping");
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            warn!(error = %e, "failed to read pool accounts, skipping pool TVL");
+        }
+    }
+
+    match token2022 {
+        Some(Ok(stats)) => {
+            let mut signal = token2022_signal(&stats);
+            signal.description.push_str(&consistency);
+            signals.push(signal);
+        }
+        Some(Err(e)) if is_method_unavailable(&e) => {
+            info!("getProgramAccounts unavailable on this RPC node, skipping Token-2022 tracking");
+        }
+        Some(Err(e)) => {
+            warn!(error = %e, "failed to scan Token-2022 mints, skipping");
+        }
+        None => {}
+    }
+
     info!(
         signal_count = signals.len(),
         "collected Solana onchain signals"
@@ -282,16 +431,145 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
     Ok(signals)
 }
 
+/// Throughput over the sampled window, plus its direction within it.
+struct TpsStats {
+    samples: usize,
+    avg_tps: f64,
+    avg_non_vote_tps: f64,
+    /// Newer half of the window against the older half, in percent.
+    trend_pct: Option<f64>,
+}
+
+impl TpsStats {
+    /// Samples arrive newest first. Ones with a zero-length period carry no
+    /// rate and are skipped.
+    fn from_samples(samples: &[PerformanceSample]) -> Option<Self> {
+        let usable: Vec<&PerformanceSample> = samples
+            .iter()
+            .filter(|s| s.sample_period_secs > 0)
+            .collect();
+        if usable.is_empty() {
+            return None;
+        }
+        let tps = |s: &&PerformanceSample| s.num_transactions as f64 / s.sample_period_secs as f64;
+        let mean = |values: &[f64]| {
+            if values.is_empty() {
+                0.0
+            } else {
+                values.iter().sum::<f64>() / values.len() as f64
+            }
+        };
+
+        let rates: Vec<f64> = usable.iter().map(tps).collect();
+        let non_vote: Vec<f64> = usable
+            .iter()
+            .filter_map(|s| {
+                s.num_non_vote_transactions
+                    .map(|nv| nv as f64 / s.sample_period_secs as f64)
+            })
+            .collect();
+
+        let (recent, older) = rates.split_at(rates.len() / 2);
+        let trend_pct = (!recent.is_empty() && mean(older) > 0.0)
+            .then(|| (mean(recent) - mean(older)) / mean(older) * 100.0);
+
+        Some(Self {
+            samples: usable.len(),
+            avg_tps: mean(&rates),
+            avg_non_vote_tps: mean(&non_vote),
+            trend_pct,
+        })
+    }
+}
+
+/// Signatures per `getSignaturesForAddress` page, the RPC maximum.
+const SIGNATURE_PAGE: usize = 1000;
+
+#[derive(Debug, PartialEq)]
 struct ProgramActivity {
+    /// Transactions in the window, or in the part of it read before
+    /// `max_signature_pages` ran out.
     tx_count: usize,
     tx_per_hour: f64,
+    /// Hours the count covers: the whole window when complete.
     time_span_hours: f64,
+    /// False when the page cap was reached before the window boundary, so
+    /// `tx_count` is a lower bound.
+    complete: bool,
+}
+
+/// Counts signatures page by page, newest first, up to the window boundary.
+struct SignatureWindow {
+    now: i64,
+    since: i64,
+    count: usize,
+    oldest: Option<i64>,
+    complete: bool,
 }
 
+impl SignatureWindow {
+    fn new(now: i64, window_hours: u32) -> Self {
+        Self {
+            now,
+            since: now - i64::from(window_hours) * 3600,
+            count: 0,
+            oldest: None,
+            complete: false,
+        }
+    }
+
+    /// Count one page of block times. Returns whether the next page is
+    /// needed: false once a signature predates the window or the program's
+    /// history runs out.
+    fn add_page(&mut self, block_times: &[Option<i64>]) -> bool {
+        for time in block_times {
+            match *time {
+                Some(t) if t < self.since => {
+                    self.complete = true;
+                    return false;
+                }
+                Some(t) => {
+                    self.count += 1;
+                    self.oldest = Some(t);
+                }
+                None => self.count += 1,
+            }
+        }
+        if block_times.len() < SIGNATURE_PAGE {
+            self.complete = true;
+        }
+        !self.complete
+    }
+
+    fn finish(self) -> ProgramActivity {
+        let span_secs = match (self.complete, self.oldest) {
+            (true, _) => self.now - self.since,
+            (false, Some(oldest)) => (self.now - oldest).max(1),
+            (false, None) => 0,
+        };
+        let time_span_hours = span_secs as f64 / 3600.0;
+        let tx_per_hour = if self.count == 0 {
+            0.0
+        } else {
+            safe_div(self.count as f64, time_span_hours, "tx_per_hour")
+        };
+        ProgramActivity {
+            tx_count: self.count,
+            tx_per_hour,
+            time_span_hours,
+            complete: self.complete,
+        }
+    }
+}
+
+/// Transactions signed by `address` in the last `window_hours`, paging back
+/// with the `before` cursor until the window boundary or `max_pages`.
 async fn get_program_activity(
-    rpc_url: &str,
-    http: &HttpClient,
+    rpc: &RpcClient<'_>,
     address: &str,
+    now: i64,
+    window_hours: u32,
+    max_pages: usize,
 ) -> Result<ProgramActivity> {
     #[derive(Deserialize)]
     struct SigInfo {
@@ -300,76 +578,1446 @@ async fn get_program_activity(
         block_time: Option<i64>,
     }
 
-    let mut all_sigs = Vec::new();
+    let mut window = SignatureWindow::new(now, window_hours);
     let mut before: Option<String> = None;
+    for _ in 0..max_pages {
+        let mut options = serde_json::json!({"limit": SIGNATURE_PAGE});
+        if let Some(cursor) = &before {
+            options["before"] = cursor.clone().into();
+        }
+        let sigs: Vec<SigInfo> = rpc
+            .call(
+                "getSignaturesForAddress",
+                serde_json::json!([address, options]),
+            )
+            .await?;
+        before = sigs.last().map(|s| s.signature.clone());
+        let times: Vec<Option<i64>> = sigs.iter().map(|s| s.block_time).collect();
+        if !window.add_page(&times) {
+            break;
+        }
+    }
+    Ok(window.finish())
+}
 
-    // Paginate: up to 10 pages (1000 sigs max) using `before` cursor
-    for _ in 0..10 {
-        let params = if let Some(ref cursor) = before {
-            serde_json::json!([address, {"limit": 100, "before": cursor}])
-        } else {
-            serde_json::json!([address, {"limit": 100}])
-        };
+/// Title and description of a program's activity signal. A count cut short
+/// by `max_signature_pages` is stated as a lower bound over the span read.
+fn program_activity_text(
+    program: &TrackedProgram,
+    activity: &ProgramActivity,
+    window_hours: u32,
+) -> (String, String) {
+    let span = if activity.time_span_hours < 1.0 {
+        format!("{:.0}m", activity.time_span_hours * 60.0)
+    } else {
+        format!("{:.1}h", activity.time_span_hours)
+    };
+    if activity.tx_count == 0 {
+        return (
+            format!("{}: no transactions in {window_hours}h", program.name),
+            format!(
+                "Program {} ({}) signed no transactions in the last {window_hours} hours.",
+                program.name, program.address
+            ),
+        );
+    }
+    if activity.complete {
+        (
+            format!(
+                "{}: {:.0} tx/hr ({} txs in {window_hours}h)",
+                program.name, activity.tx_per_hour, activity.tx_count
+            ),
+            format!(
+                "Program {} ({}) — {} transactions in the last {window_hours} hours. Rate: {:.0} tx/hr.",
+                program.name, program.address, activity.tx_count, activity.tx_per_hour
+            ),
+        )
+    } else {
+        (
+            format!(
+                "{}: {:.0} tx/hr ({}+ txs over {span})",
+                program.name, activity.tx_per_hour, activity.tx_count
+            ),
+            format!(
+                "Program {} ({}) — the newest {} transactions span only {span} of the {window_hours}h window; max_signature_pages was reached first, so the count is a lower bound. Rate: {:.0} tx/hr over {span}.",
+                program.name, program.address, activity.tx_count, activity.tx_per_hour
+            ),
+        )
+    }
+}
 
-        let sigs: Vec<SigInfo> = rpc_call(rpc_url, http, "getSignaturesForAddress", params).await?;
+/// Tokens moved between holders of one mint over the recent window.
+#[derive(Debug, Default, PartialEq)]
+struct TransferVolume {
+    volume: f64,
+    unique_senders: usize,
+    transactions: usize,
+    /// Hours between the oldest and newest transaction read.
+    window_hours: f64,
+    /// False when `max_transactions` ran out before the window was covered.
+    complete: bool,
+}
 
-        let batch_len = sigs.len();
-        if let Some(last) = sigs.last() {
-            before = Some(last.signature.clone());
-        }
-        all_sigs.extend(sigs);
+/// Transfer volume for a mint from plain RPC, no indexer: the mint's recent
+/// signatures, then each transaction's token balances before and after.
+/// Balance diffs catch transfers made inside swaps and other programs, not
+/// just top-level transfer instructions. Transfers that never reference the
+/// mint account (legacy `Transfer` rather than `TransferChecked`) don't show
+/// up in its signatures, so the volume is a lower bound.
+async fn get_transfer_volume(
+    rpc: &RpcClient<'_>,
+    mint: &TrackedMint,
+    now: i64,
+) -> Result<TransferVolume> {
+    #[derive(Deserialize)]
+    struct SigInfo {
+        signature: String,
+        #[serde(rename = "blockTime")]
+        block_time: Option<i64>,
+        err: Option<serde_json::Value>,
+    }
 
-        if batch_len < 100 {
+    let since = now - TRANSFER_WINDOW_SECS;
+    let mut in_window: Vec<(String, i64)> = Vec::new();
+    let mut before: Option<String> = None;
+    let mut complete = false;
+    while in_window.len() < mint.max_transactions {
+        let limit = (mint.max_transactions - in_window.len()).min(1000);
+        let mut options = serde_json::json!({"limit": limit});
+        if let Some(cursor) = &before {
+            options["before"] = cursor.clone().into();
+        }
+        let sigs: Vec<SigInfo> = rpc
+            .call(
+                "getSignaturesForAddress",
+                serde_json::json!([mint.mint, options]),
+            )
+            .await?;
+        let page_len = sigs.len();
+        before = sigs.last().map(|s| s.signature.clone());
+        for sig in sigs {
+            match sig.block_time {
+                Some(t) if t < since => complete = true,
+                Some(t) if sig.err.is_none() => in_window.push((sig.signature, t)),
+                _ => {}
+            }
+        }
+        if complete || page_len < limit {
+            complete = true;
             break;
         }
     }
 
-    let tx_count = all_sigs.len();
+    let transactions = join_all(in_window.iter().map(|(signature, _)| {
+        rpc.call::<Option<TokenTransaction>>(
+            "getTransaction",
+            serde_json::json!([
+                signature,
+                {"encoding": "jsonParsed", "maxSupportedTransactionVersion": 0}
+            ]),
+        )
+    }))
+    .await;
+
+    let mut volume = 0.0;
+    let mut senders = std::collections::HashSet::new();
+    let mut read = 0;
+    for tx in transactions {
+        let meta = match tx {
+            Ok(Some(TokenTransaction { meta: Some(meta) })) => meta,
+            Ok(_) => continue,
+            Err(e) => {
+                debug!(mint = %mint.name, error = %e, "skipping unreadable transaction");
+                continue;
+            }
+        };
+        read += 1;
+        let (moved, from) = balance_flows(&meta, &mint.mint);
+        volume += moved;
+        senders.extend(from);
+    }
+
+    let times = in_window.iter().map(|(_, t)| *t);
+    let window_hours = match (times.clone().max(), times.min()) {
+        (Some(newest), Some(oldest)) => (newest - oldest) as f64 / 3600.0,
+        _ => 0.0,
+    };
+    Ok(TransferVolume {
+        volume,
+        unique_senders: senders.len(),
+        transactions: read,
+        window_hours,
+        complete,
+    })
+}
+
+#[derive(Deserialize)]
+struct TokenTransaction {
+    meta: Option<TokenMeta>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenMeta {
+    #[serde(default)]
+    pre_token_balances: Vec<TokenBalance>,
+    #[serde(default)]
+    post_token_balances: Vec<TokenBalance>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenBalance {
+    account_index: u32,
+    mint: String,
+    owner: Option<String>,
+    ui_token_amount: TokenAmount,
+}
+
+#[derive(Deserialize)]
+struct TokenAmount {
+    /// Raw integer amount; `uiAmount` can be null, this can't.
+    amount: String,
+    decimals: u32,
+}
+
+impl TokenAmount {
+    fn value(&self) -> f64 {
+        self.amount.parse::<f64>().unwrap_or(0.0) / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// Tokens of `mint` that changed hands in one transaction, and the owners
+/// they left. Moved is the lesser of total inflow and total outflow, so
+/// mints and burns don't count as transfers.
+fn balance_flows(meta: &TokenMeta, mint: &str) -> (f64, Vec<String>) {
+    let mut accounts: std::collections::BTreeMap<u32, (Option<&str>, f64, f64)> =
+        std::collections::BTreeMap::new();
+    for b in meta.pre_token_balances.iter().filter(|b| b.mint == mint) {
+        let entry = accounts.entry(b.account_index).or_default();
+        entry.0 = b.owner.as_deref();
+        entry.1 = b.ui_token_amount.value();
+    }
+    for b in meta.post_token_balances.iter().filter(|b| b.mint == mint) {
+        let entry = accounts.entry(b.account_index).or_default();
+        entry.0 = entry.0.or(b.owner.as_deref());
+        entry.2 = b.ui_token_amount.value();
+    }
 
-    // Compute rate from block timestamps (results ordered newest-first)
-    let timestamps: Vec<i64> = all_sigs.iter().filter_map(|s| s.block_time).collect();
-    let (tx_per_hour, time_span_hours) = if timestamps.len() >= 2 {
-        let newest = timestamps[0];
-        let oldest = timestamps[timestamps.len() - 1];
-        let span_secs = (newest - oldest).max(1) as f64;
-        let span_hours = span_secs / 3600.0;
-        (tx_count as f64 / span_hours, span_hours)
+    let (mut inflow, mut outflow) = (0.0, 0.0);
+    let mut senders = Vec::new();
+    for (owner, pre, post) in accounts.into_values() {
+        let delta = post - pre;
+        if delta > 0.0 {
+            inflow += delta;
+        } else if delta < 0.0 {
+            outflow -= delta;
+            if let Some(owner) = owner {
+                senders.push(owner.to_string());
+            }
+        }
+    }
+    (f64::min(inflow, outflow), senders)
+}
+
+fn mint_signal(mint: &TrackedMint, volume: &TransferVolume) -> Signal {
+    let coverage = if volume.complete {
+        format!("{} transactions in the last 24h.", volume.transactions)
     } else {
-        (0.0, 0.0)
+        format!(
+            "Sample of the latest {} transactions, spanning {:.1}h of the 24h window (max_transactions reached).",
+            volume.transactions, volume.window_hours
+        )
     };
+    let mut metrics = vec![
+        Metric {
+            name: "transfer_volume_24h".into(),
+            value: volume.volume,
+            unit: "tokens".into(),
+        },
+        Metric {
+            name: "unique_senders".into(),
+            value: volume.unique_senders as f64,
+            unit: "wallets".into(),
+        },
+        Metric {
+            name: "sampled_transactions".into(),
+            value: volume.transactions as f64,
+            unit: "txs".into(),
+        },
+    ];
+    if !volume.complete {
+        metrics.push(Metric {
+            name: "sample_window".into(),
+            value: volume.window_hours,
+            unit: "hours".into(),
+        });
+    }
+    Signal::new(
+        SignalSource::SolanaOnchain,
+        mint.category.clone(),
+        format!(
+            "{}: {:.0} tokens transferred by {} senders",
+            mint.name, volume.volume, volume.unique_senders
+        ),
+        format!(
+            "Mint {} — {coverage} Volume is summed from token balance changes; unique senders separate broad use from a few wallets cycling tokens.",
+            mint.mint
+        ),
+        metrics,
+        Some(format!("https://explorer.solana.com/address/{}", mint.mint)),
+    )
+}
+
+/// Where a pool's state account keeps its vault addresses and, for
+/// concentrated liquidity, its price. Supporting another AMM takes one more
+/// entry in [`PoolLayout::of`].
+struct PoolLayout {
+    /// Byte offsets of the base and quote vault public keys.
+    vaults: (usize, usize),
+    /// Byte offset of the Q64.64 square root of the base price in quote.
+    /// Without one the price is the reserve ratio, exact for constant product.
+    sqrt_price: Option<usize>,
+}
+
+/// Vault addresses and price read from a pool's state account.
+#[derive(Debug, PartialEq)]
+struct PoolState {
+    base_vault: String,
+    quote_vault: String,
+    sqrt_price: Option<u128>,
+}
+
+impl PoolLayout {
+    fn of(amm: AmmLayout) -> Self {
+        match amm {
+            // LIQUIDITY_STATE_LAYOUT_V4: 32 u64 parameters and the swap
+            // totals, then base_vault and quote_vault.
+            AmmLayout::RaydiumV4 => Self {
+                vaults: (336, 368),
+                sqrt_price: None,
+            },
+            // Discriminator, config, bump, tick spacing, fees and liquidity
+            // precede sqrt_price; token_vault_a follows mint_a, token_vault_b
+            // follows mint_b.
+            AmmLayout::OrcaWhirlpool => Self {
+                vaults: (133, 213),
+                sqrt_price: Some(65),
+            },
+        }
+    }
+
+    /// `None` when `data` is too short to hold the fields.
+    fn read(&self, data: &[u8]) -> Option<PoolState> {
+        let key = |offset: usize| data.get(offset..offset + 32).map(base58);
+        let sqrt_price = match self.sqrt_price {
+            Some(offset) => Some(u128::from_le_bytes(
+                data.get(offset..offset + 16)?.try_into().ok()?,
+            )),
+            None => None,
+        };
+        Some(PoolState {
+            base_vault: key(self.vaults.0)?,
+            quote_vault: key(self.vaults.1)?,
+            sqrt_price,
+        })
+    }
+}
+
+/// Base58 with the Bitcoin alphabet, as Solana writes public keys.
+fn base58(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    // Base58 digits, least significant first.
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = u32::from(byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char))
+        .collect()
+}
+
+/// One vault's token balance.
+#[derive(Debug, PartialEq)]
+struct VaultBalance {
+    mint: String,
+    amount: f64,
+    decimals: u32,
+}
+
+/// The balance of a `jsonParsed` token account; `None` for anything else.
+fn vault_balance(account: &serde_json::Value) -> Option<VaultBalance> {
+    let info = &account["data"]["parsed"]["info"];
+    let amount: TokenAmount = serde_json::from_value(info["tokenAmount"].clone()).ok()?;
+    Some(VaultBalance {
+        mint: info["mint"].as_str()?.to_string(),
+        amount: amount.value(),
+        decimals: amount.decimals,
+    })
+}
+
+/// A pool's reserves, valued together in one of its two tokens.
+#[derive(Debug, PartialEq)]
+struct PoolTvl {
+    base: f64,
+    quote: f64,
+    tvl: f64,
+    /// "USD", "SOL" or "tokens", from the mint the TVL is counted in.
+    unit: &'static str,
+    denomination: String,
+}
 
-    Ok(ProgramActivity {
-        tx_count,
-        tx_per_hour,
-        time_span_hours,
+/// Unit of an amount of `mint`.
+fn mint_unit(mint: &str) -> &'static str {
+    if USD_STABLECOINS.contains(&mint) {
+        "USD"
+    } else if mint == WRAPPED_SOL {
+        "SOL"
+    } else {
+        "tokens"
+    }
+}
+
+/// Value both reserves at the pool's own price: the square-root price when
+/// the layout has one, else the reserve ratio. The TVL is counted in the
+/// quote token, or in the base token when only that one is USD or SOL.
+/// `None` without a price, e.g. when a constant-product vault is empty.
+fn value_pool(
+    base: &VaultBalance,
+    quote: &VaultBalance,
+    sqrt_price: Option<u128>,
+) -> Option<PoolTvl> {
+    let price = match sqrt_price {
+        Some(sqrt) => {
+            let root = sqrt as f64 / 2f64.powi(64);
+            root * root * 10f64.powi(base.decimals as i32 - quote.decimals as i32)
+        }
+        None => quote.amount / base.amount,
+    };
+    if !(price.is_finite() && price > 0.0) {
+        return None;
+    }
+    let in_quote = base.amount * price + quote.amount;
+    let rank = |mint: &str| match mint_unit(mint) {
+        "USD" => 2,
+        "SOL" => 1,
+        _ => 0,
+    };
+    let (tvl, denomination) = if rank(&base.mint) > rank(&quote.mint) {
+        (in_quote / price, &base.mint)
+    } else {
+        (in_quote, &quote.mint)
+    };
+    Some(PoolTvl {
+        base: base.amount,
+        quote: quote.amount,
+        tvl,
+        unit: mint_unit(denomination),
+        denomination: denomination.clone(),
     })
 }
 
-async fn rpc_call<T: serde::de::DeserializeOwned>(
-    rpc_url: &str,
+#[derive(Deserialize)]
+struct AccountsPage {
+    value: Vec<serde_json::Value>,
+}
+
+/// `addresses` in order, `null` where an account doesn't exist, read in
+/// calls of up to [`MULTIPLE_ACCOUNTS_MAX`].
+async fn get_multiple_accounts(
+    rpc: &RpcClient<'_>,
+    addresses: &[&str],
+    encoding: &str,
+) -> Result<Vec<serde_json::Value>> {
+    let mut accounts = Vec::with_capacity(addresses.len());
+    for chunk in addresses.chunks(MULTIPLE_ACCOUNTS_MAX) {
+        let page: AccountsPage = rpc
+            .call(
+                "getMultipleAccounts",
+                serde_json::json!([chunk, {"encoding": encoding}]),
+            )
+            .await?;
+        accounts.extend(page.value);
+    }
+    Ok(accounts)
+}
+
+/// TVL of each pool from plain RPC, no price API: the state accounts give
+/// the vault addresses, the vaults' parsed token accounts give the reserves.
+/// Fails as a whole only when an RPC call does.
+async fn get_pool_tvls(rpc: &RpcClient<'_>, pools: &[TrackedPool]) -> Result<Vec<Result<PoolTvl>>> {
+    let addresses: Vec<&str> = pools.iter().map(|p| p.address.as_str()).collect();
+    let accounts = get_multiple_accounts(rpc, &addresses, "base64").await?;
+    let states: Vec<Result<PoolState>> = pools
+        .iter()
+        .zip(&accounts)
+        .map(|(pool, account)| {
+            let data = account["data"][0]
+                .as_str()
+                .and_then(|b64| BASE64.decode(b64).ok())
+                .ok_or_else(|| Error::parse(format!("pool account {} not found", pool.address)))?;
+            PoolLayout::of(pool.amm).read(&data).ok_or_else(|| {
+                Error::parse(format!(
+                    "pool account {} is too short for a {} pool",
+                    pool.address,
+                    pool.amm.label()
+                ))
+            })
+        })
+        .collect();
+
+    let vaults: Vec<&str> = states
+        .iter()
+        .flatten()
+        .flat_map(|s| [s.base_vault.as_str(), s.quote_vault.as_str()])
+        .collect();
+    let balances = get_multiple_accounts(rpc, &vaults, "jsonParsed").await?;
+    let mut balances = balances.chunks(2);
+    Ok(pools
+        .iter()
+        .zip(states)
+        .map(|(pool, state)| {
+            let state = state?;
+            let Some([base, quote]) = balances.next() else {
+                return Err(Error::parse("missing vault accounts"));
+            };
+            let (Some(base), Some(quote)) = (vault_balance(base), vault_balance(quote)) else {
+                return Err(Error::parse(format!(
+                    "vaults {} and {} are not token accounts; is amm = {:?} right?",
+                    state.base_vault, state.quote_vault, pool.amm
+                )));
+            };
+            value_pool(&base, &quote, state.sqrt_price)
+                .ok_or_else(|| Error::parse("pool has an empty vault, so no price"))
+        })
+        .collect())
+}
+
+fn pool_url(address: &str) -> String {
+    format!("https://explorer.solana.com/address/{address}")
+}
+
+/// `value` in `unit` for a title: dollars in millions, anything else whole.
+fn amount_text(value: f64, unit: &str) -> String {
+    if unit == "USD" {
+        format!("${:.2}M", value / 1_000_000.0)
+    } else {
+        format!("{} {unit}", metric_value(value, Some(0)))
+    }
+}
+
+fn pool_signal(pool: &TrackedPool, tvl: &PoolTvl) -> Signal {
+    Signal::new(
+        SignalSource::SolanaOnchain,
+        pool.category.clone(),
+        format!(
+            "{}: {} TVL onchain",
+            pool.name,
+            amount_text(tvl.tvl, tvl.unit)
+        ),
+        format!(
+            "{} pool {} holds {} base and {} quote tokens in its vaults, valued at the pool's own price in {}. Read from vault balances over RPC, without a price API.",
+            pool.amm.label(),
+            pool.address,
+            metric_value(tvl.base, None),
+            metric_value(tvl.quote, None),
+            tvl.denomination
+        ),
+        vec![
+            Metric {
+                name: "pool_tvl".into(),
+                value: tvl.tvl,
+                unit: tvl.unit.into(),
+            },
+            Metric {
+                name: "base_reserve".into(),
+                value: tvl.base,
+                unit: "tokens".into(),
+            },
+            Metric {
+                name: "quote_reserve".into(),
+                value: tvl.quote,
+                unit: "tokens".into(),
+            },
+        ],
+        Some(pool_url(&pool.address)),
+    )
+}
+
+#[derive(Deserialize)]
+struct LlamaPoolChart {
+    /// Oldest first.
+    data: Vec<LlamaPoolPoint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LlamaPoolPoint {
+    tvl_usd: Option<f64>,
+}
+
+/// Compare the onchain TVL of every pool with a `defillama_pool` against
+/// DeFiLlama's latest figure for it. Pools not valued in USD, directly or
+/// through `pool_tvl_usd`, are skipped. Returns how many pools differ by
+/// more than `pool_tvl_tolerance_pct`.
+pub async fn cross_check_pool_tvl(
+    config: &SolanaConfig,
     http: &HttpClient,
+    signals: &mut [Signal],
+) -> usize {
+    let mut flagged = 0;
+    for pool in &config.tracked_pools {
+        let Some(id) = &pool.defillama_pool else {
+            continue;
+        };
+        let url = pool_url(&pool.address);
+        let Some(signal) = signals
+            .iter_mut()
+            .find(|s| s.source == SignalSource::SolanaOnchain && s.url.as_ref() == Some(&url))
+        else {
+            continue;
+        };
+        let Some(onchain) = onchain_usd(signal) else {
+            debug!(pool = %pool.name, "pool TVL not in USD, skipping DeFiLlama cross-check");
+            continue;
+        };
+        let chart: LlamaPoolChart = match http
+            .get_json(&format!("https://yields.llama.fi/chart/{id}"))
+            .await
+        {
+            Ok(chart) => chart,
+            Err(e) => {
+                warn!(pool = %pool.name, error = %e, "DeFiLlama pool TVL unavailable, skipping cross-check");
+                continue;
+            }
+        };
+        let Some(llama) = chart.data.iter().rev().find_map(|p| p.tvl_usd) else {
+            continue;
+        };
+        if flag_tvl_gap(signal, onchain, llama, config.pool_tvl_tolerance_pct) {
+            warn!(pool = %pool.name, onchain, defillama = llama, "pool TVL differs from DeFiLlama's");
+            flagged += 1;
+        }
+    }
+    flagged
+}
+
+fn onchain_usd(signal: &Signal) -> Option<f64> {
+    signal
+        .metrics
+        .iter()
+        .find(|m| (m.name == "pool_tvl" && m.unit == "USD") || m.name == "pool_tvl_usd")
+        .map(|m| m.value)
+}
+
+/// Add DeFiLlama's TVL and the gap to it to a pool signal, and say so in
+/// the description when the gap exceeds `tolerance_pct`. Returns whether it
+/// did.
+fn flag_tvl_gap(signal: &mut Signal, onchain: f64, llama: f64, tolerance_pct: f64) -> bool {
+    signal.metrics.push(Metric {
+        name: "defillama_tvl".into(),
+        value: llama,
+        unit: "USD".into(),
+    });
+    if llama <= 0.0 {
+        return false;
+    }
+    let gap = (onchain - llama) / llama * 100.0;
+    signal.metrics.push(Metric {
+        name: "tvl_discrepancy_pct".into(),
+        value: gap,
+        unit: "%".into(),
+    });
+    if gap.abs() <= tolerance_pct {
+        return false;
+    }
+    signal.description.push_str(&format!(
+        " Onchain TVL is {gap:+.0}% off DeFiLlama's {} for this pool, beyond the {tolerance_pct:.0}% tolerance: one of the two misprices or miscounts it.",
+        amount_text(llama, "USD")
+    ));
+    true
+}
+
+/// Number of accounts owned by `address`. `dataSlice` with zero length keeps
+/// the response to pubkeys and account headers, not account data.
+async fn get_account_count(rpc: &RpcClient<'_>, address: &str) -> Result<usize> {
+    let accounts: Vec<serde::de::IgnoredAny> = rpc
+        .call(
+            "getProgramAccounts",
+            serde_json::json!([
+                address,
+                {"encoding": "base64", "dataSlice": {"offset": 0, "length": 0}}
+            ]),
+        )
+        .await?;
+    Ok(accounts.len())
+}
+
+/// Mints under the Token-2022 program, and how often each extension appears
+/// among the extended mints that were read in full.
+#[derive(Debug, Default, PartialEq)]
+struct Token2022Stats {
+    plain_mints: usize,
+    extended_mints: usize,
+    /// Extended mints read in full for the breakdown.
+    sampled: usize,
+    /// Sampled mints the node returned as raw bytes rather than parsed.
+    unparsed: usize,
+    /// Extension name as the node spells it (`transferHook`) and its count
+    /// in the sample.
+    extensions: std::collections::BTreeMap<String, usize>,
+}
+
+impl Token2022Stats {
+    /// `count` in the sample, scaled up to all extended mints.
+    fn estimated(&self, count: usize) -> usize {
+        if self.sampled == 0 || self.sampled >= self.extended_mints {
+            return count;
+        }
+        (count as f64 * self.extended_mints as f64 / self.sampled as f64).round() as usize
+    }
+}
+
+#[derive(Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+}
+
+/// Count Token-2022 mints by size and account type without their data,
+/// then read up to `sample` of the extended ones with `jsonParsed` for their
+/// extensions, so the scan stays small however many mints there are. Both
+/// counts have to succeed; half a count would read as a drop.
+async fn get_token2022_stats(rpc: &RpcClient<'_>, sample: usize) -> Result<Token2022Stats> {
+    let keys_only = |filter: serde_json::Value| {
+        serde_json::json!([
+            TOKEN_2022_PROGRAM,
+            {
+                "encoding": "base64",
+                "dataSlice": {"offset": 0, "length": 0},
+                "filters": [filter]
+            }
+        ])
+    };
+    let (plain, extended) = tokio::join!(
+        rpc.call::<Vec<serde::de::IgnoredAny>>(
+            "getProgramAccounts",
+            keys_only(serde_json::json!({"dataSize": MINT_LEN})),
+        ),
+        rpc.call::<Vec<KeyedAccount>>(
+            "getProgramAccounts",
+            keys_only(serde_json::json!({"memcmp": {"offset": ACCOUNT_TYPE_OFFSET, "bytes": "2"}})),
+        ),
+    );
+    let (plain, extended) = (plain?, extended?);
+    let sampled = sample_evenly(&extended, sample);
+    let accounts = get_multiple_accounts(rpc, &sampled, "jsonParsed").await?;
+    let mut stats = extension_breakdown(&accounts);
+    stats.plain_mints = plain.len();
+    stats.extended_mints = extended.len();
+    Ok(stats)
+}
+
+/// Up to `limit` of `accounts`' keys, spread across the whole list.
+fn sample_evenly(accounts: &[KeyedAccount], limit: usize) -> Vec<&str> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let step = accounts.len().div_ceil(limit).max(1);
+    accounts
+        .iter()
+        .step_by(step)
+        .map(|a| a.pubkey.as_str())
+        .collect()
+}
+
+/// Tally the extensions of accounts read with `jsonParsed`. An account whose
+/// data isn't a parsed mint (the node fell back to base64, or parsed it as
+/// something else) is counted as unparsed and adds nothing to the breakdown.
+fn extension_breakdown(accounts: &[serde_json::Value]) -> Token2022Stats {
+    let mut stats = Token2022Stats {
+        sampled: accounts.len(),
+        ..Default::default()
+    };
+    for account in accounts {
+        let parsed = &account["data"]["parsed"];
+        if parsed["type"] != "mint" {
+            stats.unparsed += 1;
+            continue;
+        }
+        let Some(extensions) = parsed["info"]["extensions"].as_array() else {
+            continue;
+        };
+        for name in extensions.iter().filter_map(|e| e["extension"].as_str()) {
+            *stats.extensions.entry(name.to_string()).or_default() += 1;
+        }
+    }
+    stats
+}
+
+/// `transferHook` -> `transfer_hook`, for metric names.
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if !out.is_empty() {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn token2022_signal(stats: &Token2022Stats) -> Signal {
+    let with_extensions = stats.extended_mints;
+    let total = stats.plain_mints + with_extensions;
+    let mut metrics = vec![
+        Metric {
+            name: "token2022_mints".into(),
+            value: total as f64,
+            unit: "mints".into(),
+        },
+        Metric {
+            name: "token2022_mints_with_extensions".into(),
+            value: with_extensions as f64,
+            unit: "mints".into(),
+        },
+    ];
+    let mut ranked: Vec<(&String, &usize)> = stats.extensions.iter().collect();
+    ranked.sort_by_key(|&(_, count)| std::cmp::Reverse(*count));
+    for (name, count) in &ranked {
+        metrics.push(Metric {
+            name: format!("token2022_ext_{}", snake_case(name)),
+            value: stats.estimated(**count) as f64,
+            unit: "mints".into(),
+        });
+    }
+
+    let breakdown = if ranked.is_empty() {
+        "No extension breakdown: the node returned no parsed mint data.".to_string()
+    } else {
+        let top = ranked
+            .iter()
+            .take(5)
+            .map(|(name, count)| format!("{name} {}", stats.estimated(**count)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let skipped = if stats.unparsed > 0 {
+            format!(
+                " {} mints came back unparsed and aren't broken down.",
+                stats.unparsed
+            )
+        } else {
+            String::new()
+        };
+        let sampled = if stats.sampled < stats.extended_mints {
+            format!(
+                " Extension counts are estimated from a sample of {} of the {} mints.",
+                stats.sampled, stats.extended_mints
+            )
+        } else {
+            String::new()
+        };
+        format!("Most used extensions: {top}.{skipped}{sampled}")
+    };
+    Signal::new(
+        SignalSource::SolanaOnchain,
+        "Token Extensions".into(),
+        format!("Token-2022: {total} mints, {with_extensions} with extensions"),
+        format!(
+            "Mints under the Token-2022 program, separate from SPL Token. Extensions such as transfer hooks and confidential transfers mark tokens built for compliance, payments and programmable transfers. {breakdown}"
+        ),
+        metrics,
+        Some(format!(
+            "https://explorer.solana.com/address/{TOKEN_2022_PROGRAM}"
+        )),
+    )
+}
+
+/// Whether the node refused `getProgramAccounts` outright: many public
+/// endpoints disable it or exclude large programs from their indexes.
+fn is_method_unavailable(error: &Error) -> bool {
+    match error {
+        Error::Api {
+            message,
+            status_code,
+            ..
+        } => {
+            let message = message.to_lowercase();
+            matches!(status_code, Some(403 | 405 | 410))
+                || ["method not found", "disabled", "excluded", "not available"]
+                    .iter()
+                    .any(|needle| message.contains(needle))
+        }
+        _ => false,
+    }
+}
+
+/// Counts whose run-over-run change is added as a growth metric.
+const GROWTH_METRICS: [(&str, &str); 2] = [
+    ("account_count", "account_growth_pct"),
+    ("unique_senders", "unique_senders_growth_pct"),
+];
+
+/// Add `account_growth_pct` next to each `account_count`, and
+/// `unique_senders_growth_pct` next to each `unique_senders`, relative to the
+/// most recent run in `history` that recorded the same program or mint.
+pub fn add_growth(signals: &mut [Signal], history: &History) {
+    for signal in signals.iter_mut() {
+        for (count_name, growth_name) in GROWTH_METRICS {
+            let Some(count) = signal.metrics.iter().find(|m| m.name == count_name) else {
+                continue;
+            };
+            let key = metric_key(signal, count);
+            let Some(&previous) = history.series(&key, usize::MAX).last() else {
+                continue;
+            };
+            if previous <= 0.0 {
+                continue;
+            }
+            let growth = (count.value - previous) / previous * 100.0;
+            signal.metrics.push(Metric {
+                name: growth_name.into(),
+                value: growth,
+                unit: "%".into(),
+            });
+        }
+    }
+}
+
+/// JSON-RPC client that caps the number of in-flight requests to one
+/// endpoint and sends its commitment with every call.
+struct RpcClient<'a> {
+    url: &'a str,
+    http: &'a HttpClient,
+    permits: Semaphore,
+    commitment: Commitment,
+}
+
+impl<'a> RpcClient<'a> {
+    fn new(
+        url: &'a str,
+        http: &'a HttpClient,
+        max_concurrency: usize,
+        commitment: Commitment,
+    ) -> Self {
+        Self {
+            url,
+            http,
+            permits: Semaphore::new(max_concurrency.max(1)),
+            commitment,
+        }
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|e| Error::http(e.to_string()))?;
+
+        let request = RpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params: with_commitment(method, params, self.commitment),
+        };
+
+        let body =
+            serde_json::to_string(&request).map_err(|e| Error::parse(format!("serialize: {e}")))?;
+
+        let resp_text = self.http.post_json_raw(self.url, &body, &[]).await?;
+
+        let resp: RpcResponse<T> = serde_json::from_str(&resp_text)
+            .map_err(|e| Error::parse(format!("parse RPC: {e}")))?;
+
+        if let Some(err) = resp.error {
+            return Err(Error::api("solana-rpc", err.message));
+        }
+
+        resp.result
+            .ok_or_else(|| Error::parse("RPC response missing result"))
+    }
+}
+
+/// Add `commitment` to the call's trailing config object, appending one if
+/// the params end in a positional argument. An explicit commitment in
+/// `params` is kept.
+fn with_commitment(
     method: &str,
-    params: serde_json::Value,
-) -> Result<T> {
-    let request = RpcRequest {
-        jsonrpc: "2.0",
-        id: 1,
-        method,
-        params,
+    mut params: serde_json::Value,
+    commitment: Commitment,
+) -> serde_json::Value {
+    if NO_COMMITMENT.contains(&method) {
+        return params;
+    }
+    let commitment = match commitment {
+        Commitment::Processed if CONFIRMED_AT_LEAST.contains(&method) => Commitment::Confirmed,
+        c => c,
+    };
+    let Some(args) = params.as_array_mut() else {
+        return params;
     };
+    match args.last_mut().and_then(|last| last.as_object_mut()) {
+        Some(config) => {
+            config
+                .entry("commitment")
+                .or_insert_with(|| commitment.as_str().into());
+        }
+        None => args.push(serde_json::json!({"commitment": commitment.as_str()})),
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, TempDir};
+
+    #[test]
+    fn commitment_merges_without_clobbering_params() {
+        let finalized = Commitment::Finalized;
+        assert_eq!(
+            with_commitment("getEpochInfo", serde_json::json!([]), finalized),
+            serde_json::json!([{"commitment": "finalized"}])
+        );
+        assert_eq!(
+            with_commitment(
+                "getRecentPerformanceSamples",
+                serde_json::json!([10]),
+                finalized
+            ),
+            serde_json::json!([10]),
+            "the sample limit takes no config object"
+        );
+        assert_eq!(
+            with_commitment(
+                "getSignaturesForAddress",
+                serde_json::json!(["Addr", {"limit": 100, "before": "sig"}]),
+                Commitment::Processed,
+            ),
+            serde_json::json!(["Addr", {"limit": 100, "before": "sig", "commitment": "confirmed"}])
+        );
+        assert_eq!(
+            with_commitment(
+                "getProgramAccounts",
+                serde_json::json!(["Addr"]),
+                Commitment::Processed,
+            ),
+            serde_json::json!(["Addr", {"commitment": "processed"}])
+        );
+        assert_eq!(
+            with_commitment(
+                "getSupply",
+                serde_json::json!([{"commitment": "confirmed"}]),
+                finalized,
+            ),
+            serde_json::json!([{"commitment": "confirmed"}]),
+            "explicit commitment wins"
+        );
+    }
+
+    #[test]
+    fn account_growth_compares_with_last_recorded_count() {
+        let dir = TempDir::new("accounts");
+        let program = |count: f64| {
+            test_support::metric_signal("DeFi", "Program", "account_count", count, "accounts")
+        };
+
+        let mut history = History::load(&dir.join("history.json")).unwrap();
+        let mut first = vec![program(1000.0)];
+        add_growth(&mut first, &history);
+        assert_eq!(first[0].metrics.len(), 1, "no history, no growth metric");
+
+        history.record(&first, None);
+        let mut second = vec![program(1100.0)];
+        add_growth(&mut second, &history);
+        let growth = second[0]
+            .metrics
+            .iter()
+            .find(|m| m.name == "account_growth_pct")
+            .unwrap();
+        assert!((growth.value - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn balance_diffs_count_transfers_not_mints() {
+        const MINT: &str = "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN";
+        let balance = |index: u32, owner: &str, amount: &str, mint: &str| {
+            serde_json::json!({
+                "accountIndex": index,
+                "mint": mint,
+                "owner": owner,
+                "uiTokenAmount": {"amount": amount, "decimals": 6, "uiAmount": null}
+            })
+        };
+        // Alice sends 2.5 to Bob; an unrelated mint moves in the same tx.
+        let transfer: TokenMeta = serde_json::from_value(serde_json::json!({
+            "preTokenBalances": [
+                balance(1, "alice", "10000000", MINT),
+                balance(2, "bob", "0", MINT),
+                balance(3, "carol", "5", "So11111111111111111111111111111111111111112"),
+            ],
+            "postTokenBalances": [
+                balance(1, "alice", "7500000", MINT),
+                balance(2, "bob", "2500000", MINT),
+                balance(3, "carol", "0", "So11111111111111111111111111111111111111112"),
+            ],
+        }))
+        .unwrap();
+        let (moved, senders) = balance_flows(&transfer, MINT);
+        assert!((moved - 2.5).abs() < 1e-9);
+        assert_eq!(senders, ["alice"]);
+
+        // A new account created by minting: inflow only, nothing transferred.
+        let mint_to: TokenMeta = serde_json::from_value(serde_json::json!({
+            "preTokenBalances": [],
+            "postTokenBalances": [balance(1, "dave", "1000000", MINT)],
+        }))
+        .unwrap();
+        assert_eq!(balance_flows(&mint_to, MINT), (0.0, Vec::<String>::new()));
+    }
+
+    #[test]
+    fn partial_sample_states_its_window() {
+        let mint = TrackedMint {
+            name: "JUP".into(),
+            mint: "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN".into(),
+            category: "DeFi".into(),
+            max_transactions: 200,
+        };
+        let volume = TransferVolume {
+            volume: 125_000.0,
+            unique_senders: 87,
+            transactions: 200,
+            window_hours: 3.5,
+            complete: false,
+        };
+        let signal = mint_signal(&mint, &volume);
+        let metric = |name: &str| {
+            signal
+                .metrics
+                .iter()
+                .find(|m| m.name == name)
+                .unwrap()
+                .value
+        };
+        assert_eq!(metric("transfer_volume_24h"), 125_000.0);
+        assert_eq!(metric("unique_senders"), 87.0);
+        assert_eq!(metric("sample_window"), 3.5);
+        assert!(signal.description.contains("spanning 3.5h"));
 
-    let body =
-        serde_json::to_string(&request).map_err(|e| Error::parse(format!("serialize: {e}")))?;
+        let complete = mint_signal(
+            &mint,
+            &TransferVolume {
+                complete: true,
+                ..volume
+            },
+        );
+        assert!(!complete.metrics.iter().any(|m| m.name == "sample_window"));
+    }
 
-    let resp_text = http.post_json_raw(rpc_url, &body, &[]).await?;
+    fn sample(num_transactions: u64, sample_period_secs: u64) -> PerformanceSample {
+        PerformanceSample {
+            num_transactions,
+            num_non_vote_transactions: Some(num_transactions / 4),
+            num_slots: 150,
+            sample_period_secs,
+        }
+    }
 
-    let resp: RpcResponse<T> =
-        serde_json::from_str(&resp_text).map_err(|e| Error::parse(format!("parse RPC: {e}")))?;
+    #[test]
+    fn tps_trend_compares_recent_half_with_older_half() {
+        // Newest first: 3000 tx/s recently, 2000 tx/s before.
+        let samples = [
+            sample(180_000, 60),
+            sample(180_000, 60),
+            sample(120_000, 60),
+            sample(120_000, 60),
+        ];
+        let stats = TpsStats::from_samples(&samples).unwrap();
+        assert!((stats.avg_tps - 2500.0).abs() < 1e-9);
+        assert!((stats.trend_pct.unwrap() - 50.0).abs() < 1e-9);
+    }
 
-    if let Some(err) = resp.error {
-        return Err(Error::api("solana-rpc", err.message));
+    #[test]
+    fn zero_period_samples_are_skipped() {
+        let stats = TpsStats::from_samples(&[sample(1000, 0), sample(60_000, 60)]).unwrap();
+        assert_eq!(stats.samples, 1);
+        assert!(stats.avg_tps.is_finite());
+        assert_eq!(stats.trend_pct, None);
+        assert!(TpsStats::from_samples(&[sample(1000, 0)]).is_none());
     }
 
-    resp.result
-        .ok_or_else(|| Error::parse("RPC response missing result"))
+    #[test]
+    fn disabled_method_is_recognised() {
+        assert!(is_method_unavailable(&Error::api(
+            "solana-rpc",
+            "Method getProgramAccounts is disabled"
+        )));
+        assert!(!is_method_unavailable(&Error::api(
+            "solana-rpc",
+            "Invalid param: WrongSize"
+        )));
+    }
+
+    #[test]
+    fn signature_window_counts_to_the_boundary_or_page_cap() {
+        let now = 1_700_000_000;
+        let full_page = |newest: i64| -> Vec<Option<i64>> {
+            (0..SIGNATURE_PAGE as i64)
+                .map(|i| Some(newest - i))
+                .collect()
+        };
+
+        // 1500 transactions in the last 24h: the second page crosses the boundary.
+        let mut window = SignatureWindow::new(now, 24);
+        assert!(window.add_page(&full_page(now)));
+        let mut second = full_page(now - 1000)[..500].to_vec();
+        second.push(Some(now - 25 * 3600));
+        assert!(!window.add_page(&second));
+        let activity = window.finish();
+        assert_eq!(activity.tx_count, 1500);
+        assert!(activity.complete);
+        assert_eq!(activity.time_span_hours, 24.0);
+        assert!((activity.tx_per_hour - 62.5).abs() < 1e-9);
+
+        // A quiet program's history runs out inside the window.
+        let mut quiet = SignatureWindow::new(now, 24);
+        assert!(!quiet.add_page(&[Some(now - 60), None]));
+        let activity = quiet.finish();
+        assert_eq!(activity.tx_count, 2);
+        assert!(activity.complete);
+
+        // A busy program fills the page cap first: a lower bound over the span read.
+        let mut busy = SignatureWindow::new(now, 24);
+        assert!(busy.add_page(&full_page(now)));
+        let activity = busy.finish();
+        assert!(!activity.complete);
+        assert_eq!(activity.tx_count, SIGNATURE_PAGE);
+        assert!((activity.time_span_hours - 999.0 / 3600.0).abs() < 1e-9);
+
+        let program = TrackedProgram {
+            name: "Jupiter".into(),
+            address: "JUP".into(),
+            category: "DeFi".into(),
+            count_accounts: false,
+        };
+        let (title, description) = program_activity_text(&program, &activity, 24);
+        assert!(title.contains("1000+ txs over 17m"), "{title}");
+        assert!(description.contains("lower bound"), "{description}");
+    }
+
+    #[test]
+    fn token2022_extensions_are_tallied_best_effort() {
+        let mint = |extensions: serde_json::Value| {
+            serde_json::json!({
+                "data": {"program": "spl-token-2022", "parsed": {
+                    "type": "mint",
+                    "info": {"decimals": 6, "extensions": extensions}
+                }}
+            })
+        };
+        let accounts = vec![
+            mint(serde_json::json!([
+                {"extension": "transferHook", "state": {}},
+                {"extension": "metadataPointer", "state": {}}
+            ])),
+            mint(serde_json::json!([{"extension": "transferHook", "state": {}}])),
+            // Parsed, but without an extensions list.
+            serde_json::json!({"data": {"parsed": {"type": "mint", "info": {}}}}),
+            // The node fell back to raw bytes.
+            serde_json::json!({"data": ["AQAAAA==", "base64"]}),
+        ];
+        let mut stats = extension_breakdown(&accounts);
+        assert_eq!(stats.sampled, 4);
+        assert_eq!(stats.unparsed, 1);
+        assert_eq!(stats.extensions["transferHook"], 2);
+        assert_eq!(stats.extensions["metadataPointer"], 1);
+
+        stats.plain_mints = 10;
+        stats.extended_mints = 4;
+        let signal = token2022_signal(&stats);
+        let metric = |name: &str| {
+            signal
+                .metrics
+                .iter()
+                .find(|m| m.name == name)
+                .map(|m| m.value)
+        };
+        assert_eq!(metric("token2022_mints"), Some(14.0));
+        assert_eq!(metric("token2022_mints_with_extensions"), Some(4.0));
+        assert_eq!(metric("token2022_ext_transfer_hook"), Some(2.0));
+        assert_eq!(metric("token2022_ext_metadata_pointer"), Some(1.0));
+        assert!(
+            signal.description.contains("transferHook 2"),
+            "{}",
+            signal.description
+        );
+        assert!(signal.description.contains("1 mints came back unparsed"));
+        assert!(!signal.description.contains("sample"));
+
+        // Four of 400 read: the breakdown is scaled to all of them.
+        stats.extended_mints = 400;
+        let signal = token2022_signal(&stats);
+        let hooks = signal
+            .metrics
+            .iter()
+            .find(|m| m.name == "token2022_ext_transfer_hook")
+            .unwrap();
+        assert_eq!(hooks.value, 200.0);
+        assert!(
+            signal.description.contains("sample of 4 of the 400 mints"),
+            "{}",
+            signal.description
+        );
+
+        // Nothing parsed: the counts stand, the breakdown is left out.
+        let mut raw = extension_breakdown(&accounts[3..]);
+        raw.extended_mints = 1;
+        let signal = token2022_signal(&raw);
+        assert_eq!(signal.metrics.len(), 2);
+        assert!(signal.description.contains("No extension breakdown"));
+    }
+
+    #[test]
+    fn token2022_sample_spreads_over_the_scan() {
+        let accounts: Vec<KeyedAccount> = (0..10)
+            .map(|i| KeyedAccount {
+                pubkey: format!("M{i}"),
+            })
+            .collect();
+        assert_eq!(sample_evenly(&accounts, 3), ["M0", "M4", "M8"]);
+        assert_eq!(sample_evenly(&accounts, 50).len(), 10);
+        assert!(sample_evenly(&accounts, 0).is_empty());
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn pool_layouts_read_vaults_and_price() {
+        let wsol = hex("069b8857feab8184fb687f634618c035dac439dc1aeb3b5598a0f00000000001");
+        let token = hex("06ddf6e1d765a193d9cbe146ceeb79ac1cb485ed5f5b37913a8cf5857eff00a9");
+        assert_eq!(base58(&wsol), WRAPPED_SOL);
+        assert_eq!(
+            base58(&token),
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        );
+        assert_eq!(base58(&[0; 32]), "11111111111111111111111111111111");
+
+        let mut data = vec![0u8; 653];
+        data[65..81].copy_from_slice(&(7u128 << 64).to_le_bytes());
+        data[133..165].copy_from_slice(&wsol);
+        data[213..245].copy_from_slice(&token);
+        assert_eq!(
+            PoolLayout::of(AmmLayout::OrcaWhirlpool).read(&data),
+            Some(PoolState {
+                base_vault: WRAPPED_SOL.into(),
+                quote_vault: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".into(),
+                sqrt_price: Some(7 << 64),
+            })
+        );
+        assert_eq!(
+            PoolLayout::of(AmmLayout::RaydiumV4).read(&data[..380]),
+            None,
+            "quote vault cut off"
+        );
+    }
+
+    #[test]
+    fn pools_are_valued_at_their_own_price_in_the_best_known_token() {
+        let balance = |mint: &str, amount: f64, decimals: u32| VaultBalance {
+            mint: mint.into(),
+            amount,
+            decimals,
+        };
+        let usdc = USD_STABLECOINS[0];
+
+        // SOL/USDC whirlpool at 150 USDC per SOL, in raw units 0.15.
+        let sqrt = (0.15f64.sqrt() * 2f64.powi(64)) as u128;
+        let tvl = value_pool(
+            &balance(WRAPPED_SOL, 1000.0, 9),
+            &balance(usdc, 50_000.0, 6),
+            Some(sqrt),
+        )
+        .unwrap();
+        assert!((tvl.tvl - 200_000.0).abs() < 0.01, "{tvl:?}");
+        assert_eq!(tvl.unit, "USD");
+
+        // Constant product with USDC as base: counted in USDC, not the quote.
+        let tvl = value_pool(
+            &balance(usdc, 1000.0, 6),
+            &balance("TKNmint", 5000.0, 9),
+            None,
+        )
+        .unwrap();
+        assert_eq!((tvl.tvl, tvl.unit), (2000.0, "USD"));
+        assert_eq!(tvl.denomination, usdc);
+
+        assert_eq!(
+            value_pool(&balance("A", 0.0, 6), &balance(WRAPPED_SOL, 0.0, 9), None),
+            None,
+            "empty constant-product pool has no price"
+        );
+    }
+
+    #[test]
+    fn pool_tvl_far_from_defillama_is_flagged() {
+        let pool = TrackedPool {
+            name: "SOL-USDC".into(),
+            address: WRAPPED_SOL.into(),
+            amm: AmmLayout::RaydiumV4,
+            category: "DEX Liquidity".into(),
+            defillama_pool: Some("llama-id".into()),
+        };
+        let tvl = PoolTvl {
+            base: 1000.0,
+            quote: 150_000.0,
+            tvl: 300_000.0,
+            unit: "USD",
+            denomination: USD_STABLECOINS[0].into(),
+        };
+        let mut signal = pool_signal(&pool, &tvl);
+        assert_eq!(signal.title, "SOL-USDC: $0.30M TVL onchain");
+        assert_eq!(onchain_usd(&signal), Some(300_000.0));
+
+        assert!(!flag_tvl_gap(
+            &mut signal.clone(),
+            300_000.0,
+            280_000.0,
+            25.0
+        ));
+        assert!(flag_tvl_gap(&mut signal, 300_000.0, 200_000.0, 25.0));
+        let gap = signal
+            .metrics
+            .iter()
+            .find(|m| m.name == "tvl_discrepancy_pct");
+        assert_eq!(gap.map(|m| m.value), Some(50.0));
+        assert!(
+            signal.description.contains("+50% off DeFiLlama's $0.20M"),
+            "{}",
+            signal.description
+        );
+    }
 }
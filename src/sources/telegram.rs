@@ -0,0 +1,366 @@
+use crate::config::TelegramConfig;
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use crate::sources::Source;
+use crate::types::{Metric, Signal, SignalSource};
+use chrono::{Duration, Utc};
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+const PAGE_SIZE: usize = 100;
+
+/// Pause between Bot API calls. Telegram answers bursts with 429s.
+const REQUEST_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    result: T,
+}
+
+#[derive(Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+    channel_post: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    /// Unix seconds.
+    date: i64,
+    chat: Chat,
+    forward_origin: Option<ForwardOrigin>,
+}
+
+#[derive(Deserialize)]
+struct ForwardOrigin {
+    /// Set when the original came from a channel.
+    chat: Option<Chat>,
+}
+
+#[derive(Deserialize)]
+struct Chat {
+    id: i64,
+    title: Option<String>,
+    username: Option<String>,
+}
+
+impl Source for TelegramConfig {
+    fn name(&self) -> &'static str {
+        "Telegram"
+    }
+
+    fn key(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled && !self.channels.is_empty()
+    }
+
+    fn max_signals(&self) -> Option<usize> {
+        self.max_signals
+    }
+
+    fn key_env(&self) -> Option<&str> {
+        Some(&self.bot_token_env)
+    }
+
+    fn tracked(&self) -> (usize, &'static str) {
+        (self.channels.len(), "channels")
+    }
+
+    fn collect<'a>(&'a self, http: &'a HttpClient) -> BoxFuture<'a, Result<Vec<Signal>>> {
+        Box::pin(collect(self, http))
+    }
+}
+
+pub async fn collect(config: &TelegramConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+    if !config.enabled || config.channels.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let token = std::env::var(&config.bot_token_env).unwrap_or_default();
+    if token.is_empty() {
+        return Err(Error::config(format!(
+            "Telegram bot token not set: ${}",
+            config.bot_token_env
+        )));
+    }
+    let api = BotApi {
+        http,
+        base: format!("{}/bot{token}", config.base_url),
+        token: &token,
+    };
+
+    let updates = api.updates(config.max_pages).await?;
+    let cutoff = (Utc::now() - Duration::hours(24)).timestamp();
+
+    let mut signals = Vec::new();
+    for channel in &config.channels {
+        sleep(REQUEST_INTERVAL).await;
+        let chat: Chat = match api.call(&format!("getChat?chat_id={channel}")).await {
+            Ok(chat) => chat,
+            Err(e) if is_not_member(&e) => {
+                warn!(
+                    channel = %channel,
+                    "Telegram bot is not a member of this channel; add it as an admin to track it. Skipping"
+                );
+                continue;
+            }
+            Err(e) => {
+                warn!(channel = %channel, error = %e, "failed to look up Telegram channel, skipping");
+                continue;
+            }
+        };
+
+        sleep(REQUEST_INTERVAL).await;
+        let subscribers: Option<u64> = api
+            .call(&format!("getChatMemberCount?chat_id={}", chat.id))
+            .await
+            .inspect_err(|e| warn!(channel = %channel, error = %e, "failed to read Telegram subscriber count"))
+            .ok();
+
+        signals.push(channel_signal(
+            channel,
+            &chat,
+            Activity::count(&updates, chat.id, cutoff),
+            subscribers,
+        ));
+    }
+
+    info!(
+        signal_count = signals.len(),
+        updates = updates.len(),
+        "collected Telegram signals"
+    );
+    Ok(signals)
+}
+
+struct BotApi<'a> {
+    http: &'a HttpClient,
+    base: String,
+    token: &'a str,
+}
+
+impl BotApi<'_> {
+    async fn call<T: DeserializeOwned>(&self, method: &str) -> Result<T> {
+        self.http
+            .get_json::<Envelope<T>>(&format!("{}/{method}", self.base))
+            .await
+            .map(|envelope| envelope.result)
+            .map_err(|e| redact(e, self.token))
+    }
+
+    /// Pending updates, oldest first. Telegram keeps unconfirmed updates for
+    /// 24 hours, which is exactly the window counted. Paging past the first
+    /// batch confirms it, so the bot shouldn't be shared with another
+    /// consumer of `getUpdates`.
+    async fn updates(&self, max_pages: u32) -> Result<Vec<Update>> {
+        let mut updates: Vec<Update> = Vec::new();
+        for page in 0..max_pages {
+            if page > 0 {
+                sleep(REQUEST_INTERVAL).await;
+            }
+            let offset = updates.last().map_or(0, |u| u.update_id + 1);
+            let batch: Vec<Update> = self
+                .call(&format!("getUpdates?offset={offset}&limit={PAGE_SIZE}&timeout=0"))
+                .await
+                .map_err(|e| match e {
+                    Error::Api {
+                        status_code: Some(409),
+                        ..
+                    } => Error::config(
+                        "Telegram bot has a webhook set; getUpdates is unavailable until it is removed (deleteWebhook)",
+                    ),
+                    e => e,
+                })?;
+            let done = batch.len() < PAGE_SIZE;
+            updates.extend(batch);
+            if done {
+                return Ok(updates);
+            }
+        }
+        warn!(
+            pages = max_pages,
+            "Telegram getUpdates cap reached; 24h counts are a lower bound"
+        );
+        Ok(updates)
+    }
+}
+
+/// The bot can't read the channel: it was never added, was removed, or the
+/// name is wrong.
+fn is_not_member(error: &Error) -> bool {
+    match error {
+        Error::Api {
+            message,
+            status_code: Some(400 | 403),
+            ..
+        } => {
+            let message = message.to_lowercase();
+            [
+                "not a member",
+                "chat not found",
+                "kicked",
+                "not enough rights",
+            ]
+            .iter()
+            .any(|m| message.contains(m))
+        }
+        _ => false,
+    }
+}
+
+/// The bot token is part of every Bot API URL, and transport errors echo the
+/// URL. Keep it out of logs.
+fn redact(error: Error, token: &str) -> Error {
+    let scrub = |s: String| s.replace(token, "<token>");
+    match error {
+        Error::Http(message) => Error::Http(scrub(message)),
+        Error::Api {
+            platform,
+            message,
+            status_code,
+        } => Error::Api {
+            platform,
+            message: scrub(message),
+            status_code,
+        },
+        e => e,
+    }
+}
+
+/// Channel activity within the 24h window.
+#[derive(Debug, PartialEq)]
+struct Activity {
+    posts: usize,
+    forwards: usize,
+}
+
+impl Activity {
+    /// Posts in channel `chat_id`, and forwards of its posts into chats the
+    /// bot can see, since `cutoff` (Unix seconds).
+    fn count(updates: &[Update], chat_id: i64, cutoff: i64) -> Self {
+        let recent = |m: &&Message| m.date >= cutoff;
+        let posts = updates
+            .iter()
+            .filter_map(|u| u.channel_post.as_ref())
+            .filter(recent)
+            .filter(|m| m.chat.id == chat_id)
+            .count();
+        let forwards = updates
+            .iter()
+            .flat_map(|u| [u.message.as_ref(), u.channel_post.as_ref()])
+            .flatten()
+            .filter(recent)
+            .filter(|m| {
+                m.forward_origin
+                    .as_ref()
+                    .and_then(|o| o.chat.as_ref())
+                    .is_some_and(|c| c.id == chat_id)
+            })
+            .count();
+        Self { posts, forwards }
+    }
+}
+
+fn channel_signal(
+    channel: &str,
+    chat: &Chat,
+    activity: Activity,
+    subscribers: Option<u64>,
+) -> Signal {
+    let name = chat.title.as_deref().unwrap_or(channel);
+    let mut metrics = vec![
+        Metric {
+            name: "messages_24h".into(),
+            value: activity.posts as f64,
+            unit: "posts".into(),
+        },
+        Metric {
+            name: "forwards_24h".into(),
+            value: activity.forwards as f64,
+            unit: "forwards".into(),
+        },
+    ];
+    if let Some(subscribers) = subscribers {
+        metrics.push(Metric {
+            name: "subscribers".into(),
+            value: subscribers as f64,
+            unit: "members".into(),
+        });
+    }
+
+    Signal::new(
+        SignalSource::Social,
+        "Telegram".into(),
+        format!("Telegram {name}: {} posts in 24h", activity.posts),
+        format!(
+            "{} posts in the last 24h on {name}{}, forwarded {} times into chats the bot can see.",
+            activity.posts,
+            subscribers
+                .map(|s| format!(" ({s} subscribers)"))
+                .unwrap_or_default(),
+            activity.forwards,
+        ),
+        metrics,
+        chat.username
+            .as_ref()
+            .map(|username| format!("https://t.me/{username}")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_recent_posts_and_forwards_for_one_channel() {
+        let now = Utc::now().timestamp();
+        let updates: Vec<Update> = serde_json::from_value(serde_json::json!([
+            {"update_id": 1, "channel_post": {"date": now - 3600, "chat": {"id": -100, "type": "channel"}}},
+            {"update_id": 2, "channel_post": {"date": now - 90_000, "chat": {"id": -100, "type": "channel"}}},
+            {"update_id": 3, "channel_post": {"date": now - 60, "chat": {"id": -200, "type": "channel"}}},
+            {"update_id": 4, "message": {"date": now - 120, "chat": {"id": 42, "type": "group"},
+             "forward_origin": {"type": "channel", "date": now - 3600, "message_id": 7,
+                                "chat": {"id": -100, "type": "channel"}}}}
+        ]))
+        .unwrap();
+
+        let activity = Activity::count(&updates, -100, now - 86_400);
+        assert_eq!(
+            activity,
+            Activity {
+                posts: 1,
+                forwards: 1
+            }
+        );
+    }
+
+    #[test]
+    fn membership_errors_are_recognised_and_token_is_redacted() {
+        let body = r#"{"ok":false,"error_code":403,"description":"Forbidden: bot is not a member of the channel chat"}"#;
+        assert!(is_not_member(&Error::api_with_status(
+            "api.telegram.org",
+            body,
+            403
+        )));
+        assert!(!is_not_member(&Error::api_with_status(
+            "api.telegram.org",
+            "Bad Request: wrong parameter",
+            400
+        )));
+
+        let err = redact(
+            Error::http(
+                "error sending request for url (https://api.telegram.org/bot123:ABC/getChat)",
+            ),
+            "123:ABC",
+        );
+        assert!(!err.to_string().contains("123:ABC"));
+    }
+}
@@ -0,0 +1,245 @@
+//! Fixtures shared by the unit tests: scratch directories, signals and a
+//! local server that answers HTTP requests with canned replies.
+
+use crate::types::{Metric, Narrative, Signal, SignalSource, TrendDirection};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A fresh directory under the system temp dir, named for the test and this
+/// process, and removed when dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("st-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// An onchain signal carrying a single metric, linked to an explorer page
+/// named after its title.
+pub fn metric_signal(category: &str, title: &str, metric: &str, value: f64, unit: &str) -> Signal {
+    Signal::new(
+        SignalSource::SolanaOnchain,
+        category.into(),
+        title.into(),
+        String::new(),
+        vec![Metric {
+            name: metric.into(),
+            value,
+            unit: unit.into(),
+        }],
+        Some(format!("https://explorer.solana.com/address/{title}")),
+    )
+}
+
+/// An accelerating narrative with `confidence` and nothing else filled in;
+/// tests set the fields they care about with struct update syntax.
+pub fn narrative(title: &str, confidence: f64) -> Narrative {
+    Narrative {
+        id: Narrative::id_for(title),
+        title: title.into(),
+        summary: String::new(),
+        raw_confidence: confidence,
+        adjusted_confidence: None,
+        confidence_note: String::new(),
+        supporting_signals: Vec::new(),
+        trend: TrendDirection::Accelerating,
+        key_metrics: Vec::new(),
+        relationships: Vec::new(),
+        continuity: None,
+        recurrence: None,
+    }
+}
+
+/// A listener on a free local port.
+pub async fn listener() -> (TcpListener, SocketAddr) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    (listener, addr)
+}
+
+/// A local address nothing listens on, so connecting to it is refused.
+pub fn unused_addr() -> SocketAddr {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+/// Accept connections on a free local port, each handled in its own task.
+pub async fn serve<F, Fut>(handle: F) -> SocketAddr
+where
+    F: Fn(TcpStream) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (listener, addr) = listener().await;
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(handle(socket));
+        }
+    });
+    addr
+}
+
+/// Serve HTTP, answering every request with `handler`'s reply.
+pub async fn mock_http<F>(handler: F) -> SocketAddr
+where
+    F: Fn(&Request) -> Reply + Send + Sync + 'static,
+{
+    mock_http_async(move |request| std::future::ready(handler(&request))).await
+}
+
+/// [`mock_http`] for handlers that need to wait before answering.
+pub async fn mock_http_async<F, Fut>(handler: F) -> SocketAddr
+where
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Reply> + Send + 'static,
+{
+    let handler = Arc::new(handler);
+    serve(move |socket| {
+        let handler = handler.clone();
+        async move {
+            let mut stream = BufReader::new(socket);
+            let Some(request) = Request::read(&mut stream).await else {
+                return;
+            };
+            let reply = handler(request).await;
+            let _ = stream.get_mut().write_all(&reply.to_bytes()).await;
+        }
+    })
+    .await
+}
+
+/// A request as the mock server received it.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// Path and query, as sent.
+    pub path: String,
+    /// Names lowercased.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    async fn read(stream: &mut BufReader<TcpStream>) -> Option<Self> {
+        let mut line = String::new();
+        stream.read_line(&mut line).await.ok()?;
+        let path = line.split_whitespace().nth(1)?.to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            if stream.read_line(&mut line).await.ok()? == 0 {
+                break;
+            }
+            let Some((name, value)) = line.trim_end().split_once(':') else {
+                break;
+            };
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+
+        let mut request = Self {
+            path,
+            headers,
+            body: String::new(),
+        };
+        let length: usize = request
+            .header("content-length")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).await.ok()?;
+        request.body = String::from_utf8_lossy(&body).into_owned();
+        Some(request)
+    }
+
+    /// A header's value, by case-insensitive name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The body parsed as JSON.
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+}
+
+/// A canned response. The connection is closed after it is sent.
+#[derive(Debug, Clone)]
+pub struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Reply {
+    /// An empty response with `status`.
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// 200 with `body`.
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::status(200).body(body)
+    }
+
+    /// 200 with a JSON `body`.
+    pub fn json(body: impl Into<String>) -> Self {
+        Self::ok(body).header("content-type", "application/json")
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = reqwest::StatusCode::from_u16(self.status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("");
+        let mut head = format!("HTTP/1.1 {} {reason}\r\n", self.status);
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str(&format!(
+            "content-length: {}\r\nconnection: close\r\n\r\n",
+            self.body.len()
+        ));
+        [head.into_bytes(), self.body.clone().into_bytes()].concat()
+    }
+}
@@ -0,0 +1,281 @@
+use crate::pipeline::Analysis;
+use crate::types::{BuildIdea, Signal};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{self, IsTerminal, Write};
+
+/// What the user asked for when leaving the dashboard.
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Quit,
+    Rerun,
+}
+
+/// Browse an analysis interactively: narratives on the left, the selected
+/// narrative's evidence and build ideas on the right. Falls back to a plain
+/// text dump when stdin/stdout aren't a terminal.
+pub fn browse(analysis: &Analysis) -> io::Result<Action> {
+    if io::stdin().is_terminal() && io::stdout().is_terminal() {
+        return interactive(analysis);
+    }
+    print_plain(analysis, &mut io::stdout().lock())?;
+    Ok(Action::Quit)
+}
+
+/// Run the dashboard in raw mode on the alternate screen. The terminal is
+/// restored on the way out, and by the panic hook `ratatui::init` installs.
+fn interactive(analysis: &Analysis) -> io::Result<Action> {
+    let mut terminal = ratatui::init();
+    let action = event_loop(&mut terminal, analysis);
+    ratatui::restore();
+    action
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, analysis: &Analysis) -> io::Result<Action> {
+    let count = analysis.narratives.len();
+    let mut list = ListState::default().with_selected((count > 0).then_some(0));
+    loop {
+        terminal.draw(|frame| draw(frame, analysis, &mut list))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        let selected = list.selected().unwrap_or(0);
+        match step(key) {
+            Some(Step::Up) if count > 0 => list.select(Some(selected.saturating_sub(1))),
+            Some(Step::Down) if selected + 1 < count => list.select(Some(selected + 1)),
+            Some(Step::Leave(action)) => return Ok(action),
+            _ => {}
+        }
+    }
+}
+
+/// What a key press does in the dashboard.
+#[derive(Debug, PartialEq)]
+enum Step {
+    Up,
+    Down,
+    Leave(Action),
+}
+
+/// Raw mode delivers Ctrl-C as a key rather than a signal, so it quits here
+/// like `q` does.
+fn step(key: KeyEvent) -> Option<Step> {
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Step::Leave(Action::Quit))
+        }
+        KeyCode::Up | KeyCode::Char('k') => Some(Step::Up),
+        KeyCode::Down | KeyCode::Char('j') => Some(Step::Down),
+        KeyCode::Char('q' | 'Q') => Some(Step::Leave(Action::Quit)),
+        KeyCode::Char('r' | 'R') => Some(Step::Leave(Action::Rerun)),
+        _ => None,
+    }
+}
+
+/// Draw the dashboard. The list scrolls to keep the selection in view.
+fn draw(frame: &mut Frame, analysis: &Analysis, list: &mut ListState) {
+    let [header, body] =
+        Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(frame.area());
+    let title = Line::from(vec![
+        Span::styled("SolScout", Style::new().add_modifier(Modifier::BOLD)),
+        Span::raw(format!(
+            "  {} narratives · {} signals · {} ideas    ",
+            analysis.narratives.len(),
+            analysis.signals.len(),
+            analysis.build_ideas.len()
+        )),
+        Span::styled(
+            "↑/↓ select · r rerun · q quit",
+            Style::new().add_modifier(Modifier::DIM),
+        ),
+    ]);
+    frame.render_widget(
+        Paragraph::new(title).block(Block::new().borders(Borders::BOTTOM)),
+        header,
+    );
+
+    let [left, right] =
+        Layout::horizontal([Constraint::Ratio(1, 3), Constraint::Ratio(2, 3)]).areas(body);
+    let items: Vec<String> = if analysis.narratives.is_empty() {
+        vec!["(no narratives)".into()]
+    } else {
+        analysis
+            .narratives
+            .iter()
+            .map(|n| format!("{:>3}% {}", (n.confidence() * 100.0) as u32, n.title))
+            .collect()
+    };
+    frame.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .block(Block::new().borders(Borders::RIGHT)),
+        left,
+        list,
+    );
+
+    let selected = list.selected().unwrap_or(0);
+    let lines: Vec<Line> = detail_lines(analysis, selected, right.width.into())
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::new().borders(Borders::LEFT))
+            .wrap(Wrap { trim: false }),
+        right,
+    );
+}
+
+fn detail_lines(analysis: &Analysis, selected: usize, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let Some(narrative) = analysis.narratives.get(selected) else {
+        lines.push(
+            analysis
+                .analysis_skipped
+                .as_deref()
+                .map(|r| format!("LLM analysis skipped: {r}"))
+                .unwrap_or_else(|| "No narratives identified.".into()),
+        );
+        return lines;
+    };
+
+    lines.push(format!("{} ({})", narrative.title, narrative.trend));
+    lines.extend(wrap(&narrative.summary, width));
+    lines.push(String::new());
+    lines.push("Supporting signals".into());
+    for id in &narrative.supporting_signals {
+        match analysis.signals.iter().find(|s| &s.id == id) {
+            Some(s) => lines.push(signal_line(s)),
+            None => lines.push(format!("  {id}  (unknown signal)")),
+        }
+    }
+    lines.push(String::new());
+    lines.push("Build ideas".into());
+    let ideas: Vec<&BuildIdea> = analysis
+        .build_ideas
+        .iter()
+        .filter(|i| i.narrative_id == narrative.id)
+        .collect();
+    if ideas.is_empty() {
+        lines.push("  (none)".into());
+    }
+    for idea in ideas {
+        lines.push(format!("  • {}", idea.title));
+        lines.extend(
+            wrap(&idea.description, width.saturating_sub(4))
+                .into_iter()
+                .map(|l| format!("    {l}")),
+        );
+    }
+    lines
+}
+
+fn signal_line(s: &Signal) -> String {
+    let metrics: Vec<String> = s.metrics.iter().map(|m| m.to_string()).collect();
+    format!("  [{}] {} — {}", s.source, s.title, metrics.join(", "))
+}
+
+fn print_plain(analysis: &Analysis, out: &mut impl Write) -> io::Result<()> {
+    if let Some(reason) = &analysis.analysis_skipped {
+        writeln!(out, "LLM analysis skipped: {reason}")?;
+    }
+    for i in 0..analysis.narratives.len() {
+        writeln!(out)?;
+        for line in detail_lines(analysis, i, 100) {
+            writeln!(out, "{line}")?;
+        }
+    }
+    if analysis.narratives.is_empty() {
+        writeln!(out, "Signals")?;
+        for s in &analysis.signals {
+            writeln!(out, "{}", signal_line(s))?;
+        }
+    }
+    Ok(())
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(10);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + word.chars().count() + 1 > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::narrative;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn analysis(titles: &[String]) -> Analysis {
+        serde_json::from_value(serde_json::json!({
+            "signals": [],
+            "narratives": titles
+                .iter()
+                .map(|t| narrative(t, 0.5))
+                .collect::<Vec<_>>(),
+            "build_ideas": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn ctrl_c_and_q_quit_and_other_keys_navigate() {
+        let key = |code, modifiers| step(KeyEvent::new(code, modifiers));
+        let ctrl_c = key(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(ctrl_c, Some(Step::Leave(Action::Quit)));
+        assert_eq!(key(KeyCode::Char('c'), KeyModifiers::NONE), None);
+        assert_eq!(
+            key(KeyCode::Char('q'), KeyModifiers::NONE),
+            Some(Step::Leave(Action::Quit))
+        );
+        assert_eq!(
+            key(KeyCode::Char('r'), KeyModifiers::NONE),
+            Some(Step::Leave(Action::Rerun))
+        );
+        assert_eq!(key(KeyCode::Down, KeyModifiers::NONE), Some(Step::Down));
+        assert_eq!(key(KeyCode::Char('k'), KeyModifiers::NONE), Some(Step::Up));
+        // A lone Escape is just an ignored key, not the start of a read.
+        assert_eq!(key(KeyCode::Esc, KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn list_scrolls_to_keep_the_selection_visible() {
+        let titles: Vec<String> = (0..30).map(|i| format!("Narrative {i}")).collect();
+        let analysis = analysis(&titles);
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        let mut list = ListState::default().with_selected(Some(25));
+        terminal
+            .draw(|frame| draw(frame, &analysis, &mut list))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("50% Narrative 25"), "{screen}");
+        assert!(!screen.contains("50% Narrative 0 "), "{screen}");
+    }
+}
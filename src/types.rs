@@ -3,13 +3,131 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signal {
+    /// Stable identifier, see [`Signal::compute_id`]. Empty in snapshots
+    /// written before IDs existed; call [`fill_missing_ids`] after loading.
+    #[serde(default)]
+    pub id: String,
     pub source: SignalSource,
     pub category: String,
     pub title: String,
     pub description: String,
     pub metrics: Vec<Metric>,
     pub url: Option<String>,
+    /// When the signal was collected.
     pub timestamp: DateTime<Utc>,
+    /// When the content behind it was published or last changed, for
+    /// sources that know; the report ages signals by this when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_timestamp: Option<DateTime<Utc>>,
+    /// Analyst override of the source's baseline quality, from annotations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f64>,
+    /// Analyst note from annotations, passed to the LLM with the signal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Where a signal merged from outside came from, e.g. "external";
+    /// none for signals this tool collected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// Languages of the articles behind a blog signal, as ISO 639-1 codes,
+    /// most common first; empty for other sources.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    /// How much the signal matters to the story, 0.0-1.0, from its novelty,
+    /// quality and source-diversity contribution. Set at collection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importance: Option<f64>,
+}
+
+impl Signal {
+    pub fn new(
+        source: SignalSource,
+        category: String,
+        title: String,
+        description: String,
+        metrics: Vec<Metric>,
+        url: Option<String>,
+    ) -> Self {
+        let mut signal = Self {
+            id: String::new(),
+            source,
+            category,
+            title,
+            description,
+            metrics,
+            url,
+            timestamp: Utc::now(),
+            content_timestamp: None,
+            quality: None,
+            note: None,
+            origin: None,
+            languages: Vec::new(),
+            importance: None,
+        };
+        signal.id = signal.compute_id();
+        signal
+    }
+
+    /// Trust in this signal, 0.0-1.0: the annotated quality if any, else
+    /// its source's baseline.
+    pub fn quality(&self) -> f64 {
+        self.quality.unwrap_or_else(|| self.source.quality())
+    }
+
+    /// Hash of (source, category, title) with digit runs in the title masked,
+    /// so "Solana TPS: 4012 total" keeps the same ID when the number changes.
+    pub fn compute_id(&self) -> String {
+        let key = format!(
+            "{}\u{1f}{}\u{1f}{}",
+            self.source,
+            self.category,
+            mask_numbers(&self.title)
+        );
+        format!("s{:08x}", fnv1a(&key) as u32)
+    }
+}
+
+/// Assign IDs to signals loaded without one and disambiguate any collisions,
+/// so every ID in the slice is unique.
+pub fn fill_missing_ids(signals: &mut [Signal]) {
+    let mut seen = std::collections::HashMap::new();
+    for s in signals.iter_mut() {
+        if s.id.is_empty() {
+            s.id = s.compute_id();
+        }
+        let count = seen.entry(s.id.clone()).or_insert(0u32);
+        *count += 1;
+        if *count > 1 {
+            s.id = format!("{}-{count}", s.id);
+        }
+    }
+}
+
+fn mask_numbers(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            if !out.ends_with('#') {
+                out.push('#');
+            }
+        } else if matches!(c, '.' | ',')
+            && out.ends_with('#')
+            && chars.peek().is_some_and(|n| n.is_ascii_digit())
+        {
+            // decimal point or thousands separator inside a number
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, stable across Rust releases.
+pub fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -18,6 +136,22 @@ pub enum SignalSource {
     SolanaOnchain,
     Social,
     DeFiLlama,
+    Nft,
+    Dune,
+}
+
+impl SignalSource {
+    /// Baseline trust in a source's data, 0.0-1.0. Direct chain and TVL data
+    /// beats repo activity, which beats scraped blog headlines.
+    pub fn quality(&self) -> f64 {
+        match self {
+            Self::SolanaOnchain => 1.0,
+            Self::DeFiLlama | Self::Dune => 0.9,
+            Self::GitHub => 0.8,
+            Self::Nft => 0.7,
+            Self::Social => 0.5,
+        }
+    }
 }
 
 impl std::fmt::Display for SignalSource {
@@ -27,6 +161,8 @@ impl std::fmt::Display for SignalSource {
             Self::SolanaOnchain => write!(f, "Solana Onchain"),
             Self::Social => write!(f, "Social"),
             Self::DeFiLlama => write!(f, "DeFiLlama"),
+            Self::Nft => write!(f, "NFT Markets"),
+            Self::Dune => write!(f, "Dune"),
         }
     }
 }
@@ -38,24 +174,144 @@ pub struct Metric {
     pub unit: String,
 }
 
+/// Canonical metric units and the spellings sources use for them, matched
+/// case-insensitively. Add a synonym here when a source spells a unit its
+/// own way, so its metrics group with everyone else's.
+const UNIT_SYNONYMS: &[(&str, &[&str])] = &[
+    ("txs", &["tx", "txn", "txns", "transaction", "transactions"]),
+    (
+        "tx/s",
+        &["tps", "txs/s", "tx/sec", "txs/sec", "transactions/s"],
+    ),
+    (
+        "tx/hr",
+        &["txs/hr", "tx/h", "tx/hour", "txs/hour", "transactions/hour"],
+    ),
+    ("%", &["percent", "pct", "percentage"]),
+    ("USD", &["usd", "$", "dollars", "us$"]),
+    ("SOL", &["sol"]),
+    ("lamports", &["lamport"]),
+    ("stars", &["star"]),
+    ("forks", &["fork"]),
+    ("repos", &["repo", "repositories", "repository"]),
+    ("wallets", &["wallet"]),
+    ("hours", &["hour", "hr", "hrs", "h"]),
+    ("minutes", &["minute", "min", "mins"]),
+];
+
+/// The canonical spelling of `unit`, e.g. "transactions" → "txs" and
+/// "percent" → "%". Units not in the table pass through unchanged.
+pub fn canonicalize_unit(unit: &str) -> &str {
+    let trimmed = unit.trim();
+    UNIT_SYNONYMS
+        .iter()
+        .find(|(canonical, synonyms)| {
+            canonical.eq_ignore_ascii_case(trimmed)
+                || synonyms.iter().any(|s| s.eq_ignore_ascii_case(trimmed))
+        })
+        .map_or(unit, |(canonical, _)| canonical)
+}
+
+/// Rewrite every metric unit in `signals` to its canonical spelling.
+/// Returns how many units changed.
+pub fn canonicalize_units(signals: &mut [Signal]) -> usize {
+    let mut changed = 0;
+    for metric in signals.iter_mut().flat_map(|s| s.metrics.iter_mut()) {
+        let canonical = canonicalize_unit(&metric.unit);
+        if canonical != metric.unit {
+            metric.unit = canonical.to_string();
+            changed += 1;
+        }
+    }
+    changed
+}
+
 impl std::fmt::Display for Metric {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = crate::util::metric_value(self.value, None);
         if self.unit.is_empty() {
-            write!(f, "{}: {:.1}", self.name, self.value)
+            write!(f, "{}: {value}", self.name)
         } else {
-            write!(f, "{}: {:.1} {}", self.name, self.value, self.unit)
+            write!(f, "{}: {value} {}", self.name, self.unit)
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Narrative {
+    /// Stable identifier derived from the title.
+    pub id: String,
     pub title: String,
     pub summary: String,
-    pub confidence: f64,
-    pub supporting_signals: Vec<usize>,
+    /// The model's self-reported confidence, 0.0-1.0.
+    #[serde(alias = "confidence")]
+    pub raw_confidence: f64,
+    /// Confidence after recalibration against the supporting evidence.
+    #[serde(default)]
+    pub adjusted_confidence: Option<f64>,
+    /// Human-readable reason for the adjustment.
+    #[serde(default)]
+    pub confidence_note: String,
+    /// IDs of the signals backing this narrative.
+    pub supporting_signals: Vec<String>,
     pub trend: TrendDirection,
     pub key_metrics: Vec<Metric>,
+    /// Outgoing edges to other narratives in the same run.
+    #[serde(default)]
+    pub relationships: Vec<Relationship>,
+    /// How this narrative has carried across runs, once tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuity: Option<Continuity>,
+    /// How many of an ensemble's synthesis runs found this narrative.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+}
+
+/// A narrative's agreement across the synthesis runs of one ensemble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recurrence {
+    /// Runs that identified the narrative.
+    pub appeared: usize,
+    /// Runs that completed.
+    pub runs: usize,
+}
+
+/// A narrative's record across runs, from `history.narratives_path`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Continuity {
+    /// Consecutive runs, ending with this one, that identified the narrative.
+    pub streak: u32,
+    pub first_seen: DateTime<Utc>,
+    /// Confidence in each run it appeared in, oldest first, at most the last 10.
+    pub confidence_trajectory: Vec<f64>,
+}
+
+/// A directed edge from the owning narrative to another one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Relationship {
+    pub kind: RelationKind,
+    /// ID of the narrative at the other end.
+    pub target: String,
+    #[serde(default)]
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    Enables,
+    CompetesWith,
+    DependsOn,
+}
+
+impl std::fmt::Display for RelationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Enables => write!(f, "enables"),
+            Self::CompetesWith => write!(f, "competes with"),
+            Self::DependsOn => write!(f, "depends on"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -78,12 +334,47 @@ impl std::fmt::Display for TrendDirection {
 }
 
 impl TrendDirection {
-    pub fn css_class(&self) -> &'static str {
+    pub fn css_class(&self, theme: Theme) -> &'static str {
+        match (theme, self) {
+            (Theme::Dark, Self::Accelerating) => "text-green-400",
+            (Theme::Dark, Self::Stable) => "text-blue-400",
+            (Theme::Dark, Self::Decelerating) => "text-red-400",
+            (Theme::Dark, Self::Emerging) => "text-yellow-400",
+            (Theme::Light, Self::Accelerating) => "text-green-700",
+            (Theme::Light, Self::Stable) => "text-blue-700",
+            (Theme::Light, Self::Decelerating) => "text-red-700",
+            (Theme::Light, Self::Emerging) => "text-amber-600",
+        }
+    }
+}
+
+/// Color palette for the HTML report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    /// Class placed on `<html>`; Tailwind's `dark:` variants key off it.
+    pub fn html_class(&self) -> &'static str {
         match self {
-            Self::Accelerating => "text-green-400",
-            Self::Stable => "text-blue-400",
-            Self::Decelerating => "text-red-400",
-            Self::Emerging => "text-yellow-400",
+            Self::Dark => "dark",
+            Self::Light => "light",
+        }
+    }
+}
+
+impl std::str::FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dark" => Ok(Self::Dark),
+            "light" => Ok(Self::Light),
+            other => Err(format!("unknown theme '{other}' (expected dark or light)")),
         }
     }
 }
@@ -96,5 +387,113 @@ pub struct BuildIdea {
     pub mvp_scope: String,
     pub competitive_landscape: String,
     pub timing_rationale: String,
-    pub narrative_index: usize,
+    /// ID of the narrative this idea builds on.
+    pub narrative_id: String,
+    /// Reused from the previous run because its narrative had not changed.
+    #[serde(default)]
+    pub carried_over: bool,
+}
+
+/// What produced a `run`, so an old report can be read in context and two
+/// runs told comparable or not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetadata {
+    /// `Config::fingerprint`: equal for the same effective settings.
+    pub config_hash: String,
+    /// Configured LLM provider and model, CLI overrides applied. The one
+    /// that actually answered is the run's `produced_by`.
+    pub provider: String,
+    pub model: String,
+    /// Sources the run collected from, sorted.
+    pub source_set: Vec<String>,
+    pub tool_version: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+}
+
+impl Narrative {
+    /// The confidence to show and rank by: adjusted if recalibrated.
+    pub fn confidence(&self) -> f64 {
+        self.adjusted_confidence.unwrap_or(self.raw_confidence)
+    }
+
+    pub fn id_for(title: &str) -> String {
+        format!("n{:08x}", fnv1a(&title.trim().to_lowercase()) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tps(title: &str) -> Signal {
+        Signal::new(
+            SignalSource::SolanaOnchain,
+            "Network Performance".into(),
+            title.into(),
+            String::new(),
+            Vec::new(),
+            None,
+        )
+    }
+
+    #[test]
+    fn id_ignores_changing_numbers() {
+        assert_eq!(
+            tps("Solana TPS: 4012 total, 1,203.5 non-vote").id,
+            tps("Solana TPS: 3980 total, 998 non-vote").id
+        );
+        assert_ne!(tps("Solana TPS: 4012 total").id, tps("Epoch 812").id);
+    }
+
+    #[test]
+    fn fill_missing_ids_disambiguates() {
+        let mut signals = vec![tps("a"), tps("a")];
+        signals[0].id.clear();
+        fill_missing_ids(&mut signals);
+        assert_eq!(signals[1].id, format!("{}-2", signals[0].id));
+    }
+
+    #[test]
+    fn unit_synonyms_map_to_one_spelling() {
+        for (unit, canonical) in [
+            ("txs", "txs"),
+            ("tx", "txs"),
+            ("Transactions", "txs"),
+            ("txns", "txs"),
+            ("TPS", "tx/s"),
+            ("tx/sec", "tx/s"),
+            ("txs/hour", "tx/hr"),
+            ("%", "%"),
+            ("percent", "%"),
+            (" pct ", "%"),
+            ("usd", "USD"),
+            ("$", "USD"),
+            ("sol", "SOL"),
+            ("star", "stars"),
+            ("hrs", "hours"),
+        ] {
+            assert_eq!(canonicalize_unit(unit), canonical, "{unit:?}");
+        }
+        for custom in ["B USD", "stars/day", "casts", ""] {
+            assert_eq!(canonicalize_unit(custom), custom);
+        }
+
+        let mut signal = tps("a");
+        signal.metrics = vec![
+            Metric {
+                name: "tx_count".into(),
+                value: 1.0,
+                unit: "transactions".into(),
+            },
+            Metric {
+                name: "share".into(),
+                value: 2.0,
+                unit: "%".into(),
+            },
+        ];
+        let mut signals = vec![signal];
+        assert_eq!(canonicalize_units(&mut signals), 1);
+        assert_eq!(signals[0].metrics[0].unit, "txs");
+    }
 }
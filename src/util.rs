@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// `numerator / denominator`, or 0.0 with a warning when the denominator is
+/// zero, so a degenerate RPC response can't put NaN or infinity into a
+/// metric. `what` names the quantity for the log line.
+pub fn safe_div(numerator: f64, denominator: f64, what: &str) -> f64 {
+    if denominator == 0.0 {
+        warn!(metric = what, "zero denominator, reporting 0");
+        return 0.0;
+    }
+    numerator / denominator
+}
+
+/// A random duration from zero up to `max`, to spread out retries.
+pub fn jitter(max: Duration) -> Duration {
+    use std::hash::BuildHasher;
+    let random = std::collections::hash_map::RandomState::new().hash_one(Instant::now());
+    max.mul_f64((random % 1000) as f64 / 1000.0)
+}
+
+/// 32-44 base58 characters, the shape of a Solana public key.
+pub fn is_pubkey(s: &str) -> bool {
+    (32..=44).contains(&s.len())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+/// Significant figures kept for metric values between 0 and 1.
+const SMALL_VALUE_SIG_FIGS: i32 = 3;
+
+/// `value` for display, without thousands grouping. `decimals` fixes the
+/// precision; otherwise it follows magnitude: none from 1,000 up, one from 1
+/// up, three significant figures below 1 (so 0.03 stays 0.03), and
+/// scientific notation below one millionth.
+pub fn metric_value(value: f64, decimals: Option<usize>) -> String {
+    if let Some(decimals) = decimals {
+        return format!("{value:.decimals$}");
+    }
+    let abs = value.abs();
+    if !value.is_finite() || abs == 0.0 {
+        return format!("{value:.1}");
+    }
+    if abs >= 1000.0 {
+        return format!("{value:.0}");
+    }
+    if abs >= 1.0 {
+        return format!("{value:.1}");
+    }
+    if abs < 1e-6 {
+        return format!("{value:.2e}");
+    }
+    let decimals = (SMALL_VALUE_SIG_FIGS - 1 - abs.log10().floor() as i32).max(1) as usize;
+    let fixed = format!("{value:.decimals$}");
+    let trimmed = fixed.trim_end_matches('0');
+    if trimmed.ends_with('.') {
+        format!("{trimmed}0")
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metric_precision_follows_magnitude() {
+        assert_eq!(metric_value(0.03, None), "0.03");
+        assert_eq!(metric_value(0.123456, None), "0.123");
+        assert_eq!(metric_value(-0.000_456_78, None), "-0.000457");
+        assert_eq!(metric_value(0.000_000_012_3, None), "1.23e-8");
+        assert_eq!(metric_value(0.0, None), "0.0");
+        assert_eq!(metric_value(42.26, None), "42.3");
+        assert_eq!(metric_value(581_234_567.25, None), "581234567");
+        assert_eq!(metric_value(581_234_567.25, Some(2)), "581234567.25");
+        assert_eq!(metric_value(0.03, Some(0)), "0");
+    }
+
+    #[test]
+    fn zero_denominator_yields_zero() {
+        assert_eq!(safe_div(3.0, 4.0, "ratio"), 0.75);
+        assert_eq!(safe_div(5.0, 0.0, "ratio"), 0.0);
+        assert_eq!(safe_div(0.0, 0.0, "ratio"), 0.0);
+        assert_eq!(safe_div(-1.0, -0.0, "ratio"), 0.0);
+    }
+}